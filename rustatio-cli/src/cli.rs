@@ -92,6 +92,10 @@ pub enum Commands {
         #[arg(long, default_value = "20.0", value_name = "PERCENT")]
         random_range: f64,
 
+        /// How randomized rates move between updates
+        #[arg(long, value_enum, default_value = "uniform")]
+        randomize_mode: RandomizeModeArg,
+
         /// Randomize the stop ratio target within a percentage range
         #[arg(long)]
         randomize_ratio: bool,
@@ -120,6 +124,10 @@ pub enum Commands {
         #[arg(long, default_value = "1.0", value_name = "HOURS")]
         progressive_duration: f64,
 
+        /// Shape of the ramp towards the target rates
+        #[arg(long, value_enum, default_value = "linear")]
+        progressive_curve: ProgressiveCurveArg,
+
         /// Path to config file
         #[arg(long, value_name = "FILE")]
         config: Option<PathBuf>,
@@ -252,6 +260,9 @@ pub enum ClientArg {
     Deluge,
     Bittorrent,
     Rtorrent,
+    Vuze,
+    Biglybt,
+    Libtorrent,
 }
 
 impl From<ClientArg> for rustatio_core::ClientType {
@@ -263,6 +274,9 @@ impl From<ClientArg> for rustatio_core::ClientType {
             ClientArg::Deluge => Self::Deluge,
             ClientArg::Bittorrent => Self::BitTorrent,
             ClientArg::Rtorrent => Self::RTorrent,
+            ClientArg::Vuze => Self::Vuze,
+            ClientArg::Biglybt => Self::BiglyBT,
+            ClientArg::Libtorrent => Self::Libtorrent,
         }
     }
 }
@@ -287,6 +301,46 @@ impl From<PostStopActionArg> for rustatio_core::PostStopAction {
     }
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum RandomizeModeArg {
+    /// Independent jitter on every update (default)
+    Uniform,
+    /// Drift gradually across the range
+    Smoothed,
+}
+
+impl From<RandomizeModeArg> for rustatio_core::RandomizeMode {
+    fn from(mode: RandomizeModeArg) -> Self {
+        match mode {
+            RandomizeModeArg::Uniform => Self::Uniform,
+            RandomizeModeArg::Smoothed => Self::Smoothed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ProgressiveCurveArg {
+    /// Constant climb from start to target (default)
+    Linear,
+    /// Start slowly and accelerate towards the target
+    EaseIn,
+    /// Climb quickly and settle into the target
+    EaseOut,
+    /// Steep early climb that flattens out
+    Logarithmic,
+}
+
+impl From<ProgressiveCurveArg> for rustatio_core::ProgressiveCurve {
+    fn from(curve: ProgressiveCurveArg) -> Self {
+        match curve {
+            ProgressiveCurveArg::Linear => Self::Linear,
+            ProgressiveCurveArg::EaseIn => Self::EaseIn,
+            ProgressiveCurveArg::EaseOut => Self::EaseOut,
+            ProgressiveCurveArg::Logarithmic => Self::Logarithmic,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum ShellArg {
     Bash,
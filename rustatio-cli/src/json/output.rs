@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+pub use rustatio_core::format::{format_bytes, format_duration};
 use rustatio_core::{ClientType, FakerState, FakerStats, TorrentInfo};
 use serde::Serialize;
 
@@ -52,6 +53,7 @@ pub struct TorrentLoadedEvent {
     pub num_pieces: usize,
     pub piece_length: u64,
     pub is_single_file: bool,
+    pub is_private: bool,
     pub file_count: usize,
     pub timestamp: DateTime<Utc>,
 }
@@ -66,6 +68,7 @@ impl From<&TorrentInfo> for TorrentLoadedEvent {
             num_pieces: torrent.num_pieces,
             piece_length: torrent.piece_length,
             is_single_file: torrent.is_single_file,
+            is_private: torrent.is_private,
             file_count: if torrent.file_count > 0 {
                 torrent.file_count
             } else {
@@ -283,6 +286,7 @@ pub struct TorrentInfoOutput {
     pub piece_length: u64,
     pub piece_length_human: String,
     pub is_single_file: bool,
+    pub is_private: bool,
     pub files: Vec<FileOutput>,
     pub creation_date: Option<String>,
     pub created_by: Option<String>,
@@ -309,6 +313,7 @@ impl From<&TorrentInfo> for TorrentInfoOutput {
             piece_length: torrent.piece_length,
             piece_length_human: format_bytes(torrent.piece_length),
             is_single_file: torrent.is_single_file,
+            is_private: torrent.is_private,
             files: torrent
                 .files
                 .iter()
@@ -319,7 +324,7 @@ impl From<&TorrentInfo> for TorrentInfoOutput {
                 })
                 .collect(),
             creation_date: torrent.creation_date.map(|ts| {
-                DateTime::from_timestamp(ts, 0).map_or_else(
+                i64::try_from(ts).ok().and_then(|ts| DateTime::from_timestamp(ts, 0)).map_or_else(
                     || ts.to_string(),
                     |dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
                 )
@@ -371,38 +376,3 @@ impl Default for ClientsOutput {
         Self::new()
     }
 }
-
-/// Format bytes to human readable string
-pub fn format_bytes(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-    const TB: u64 = GB * 1024;
-
-    if bytes >= TB {
-        format!("{:.2} TB", bytes as f64 / TB as f64)
-    } else if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{bytes} B")
-    }
-}
-
-/// Format duration to human readable string
-pub fn format_duration(secs: u64) -> String {
-    let hours = secs / 3600;
-    let minutes = (secs % 3600) / 60;
-    let seconds = secs % 60;
-
-    if hours > 0 {
-        format!("{hours}h {minutes:02}m {seconds:02}s")
-    } else if minutes > 0 {
-        format!("{minutes}m {seconds:02}s")
-    } else {
-        format!("{seconds}s")
-    }
-}
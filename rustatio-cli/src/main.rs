@@ -40,6 +40,7 @@ async fn main() -> Result<()> {
             idle_when_no_seeders,
             no_randomize,
             random_range,
+            randomize_mode,
             randomize_ratio,
             random_ratio_range,
             post_stop_action,
@@ -47,6 +48,7 @@ async fn main() -> Result<()> {
             target_upload,
             target_download,
             progressive_duration,
+            progressive_curve,
             config: config_file,
             json,
             interval,
@@ -127,12 +129,14 @@ async fn main() -> Result<()> {
                 idle_when_no_seeders,
                 no_randomize,
                 random_range,
+                randomize_mode,
                 randomize_ratio,
                 random_ratio_range,
                 progressive,
                 target_upload,
                 target_download,
                 progressive_duration,
+                progressive_curve,
                 post_stop_action,
                 json_mode: json,
                 stats_interval: interval,
@@ -209,6 +213,9 @@ async fn main() -> Result<()> {
                 "deluge" => cli::ClientArg::Deluge,
                 "bittorrent" => cli::ClientArg::Bittorrent,
                 "rtorrent" => cli::ClientArg::Rtorrent,
+                "vuze" => cli::ClientArg::Vuze,
+                "biglybt" => cli::ClientArg::Biglybt,
+                "libtorrent" => cli::ClientArg::Libtorrent,
                 _ => cli::ClientArg::Qbittorrent,
             };
 
@@ -231,12 +238,14 @@ async fn main() -> Result<()> {
                 post_stop_action: cli::PostStopActionArg::Idle,
                 no_randomize: false,
                 random_range: 20.0,
+                randomize_mode: cli::RandomizeModeArg::Uniform,
                 randomize_ratio: false,
                 random_ratio_range: 10.0,
                 progressive: false,
                 target_upload: None,
                 target_download: None,
                 progressive_duration: 1.0,
+                progressive_curve: cli::ProgressiveCurveArg::Linear,
                 json_mode: json,
                 stats_interval: interval,
                 save_session: !no_save_session,
@@ -519,6 +528,9 @@ fn print_torrent_info(torrent: &rustatio_core::TorrentInfo) {
     println!("Name:        {}", torrent.name);
     println!("Size:        {}", format_bytes(torrent.total_size));
     println!("Info Hash:   {}", torrent.info_hash_hex());
+    if torrent.is_private {
+        println!("Private:     yes");
+    }
     println!();
     println!("Tracker:     {}", torrent.announce);
 
@@ -537,7 +549,9 @@ fn print_torrent_info(torrent: &rustatio_core::TorrentInfo) {
     println!("Pieces:      {} x {}", torrent.num_pieces, format_bytes(torrent.piece_length));
 
     if let Some(date) = torrent.creation_date {
-        if let Some(dt) = chrono::DateTime::from_timestamp(date, 0) {
+        if let Some(dt) =
+            i64::try_from(date).ok().and_then(|date| chrono::DateTime::from_timestamp(date, 0))
+        {
             println!("Created:     {}", dt.format("%Y-%m-%d %H:%M:%S UTC"));
         }
     }
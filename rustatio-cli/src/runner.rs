@@ -35,12 +35,14 @@ pub struct RunnerConfig {
     pub post_stop_action: crate::cli::PostStopActionArg,
     pub no_randomize: bool,
     pub random_range: f64,
+    pub randomize_mode: crate::cli::RandomizeModeArg,
     pub randomize_ratio: bool,
     pub random_ratio_range: f64,
     pub progressive: bool,
     pub target_upload: Option<f64>,
     pub target_download: Option<f64>,
     pub progressive_duration: f64,
+    pub progressive_curve: crate::cli::ProgressiveCurveArg,
     pub json_mode: bool,
     pub stats_interval: u64,
     pub save_session: bool,
@@ -173,14 +175,14 @@ pub async fn run_json_mode(config: RunnerConfig) -> Result<()> {
             Some(cmd) = cmd_rx.recv() => {
                 match cmd {
                     RunnerCommand::Pause => {
-                        if let Err(e) = faker.pause() {
+                        if let Err(e) = faker.pause().await {
                             OutputEvent::error(format!("Pause error: {e}")).emit();
                         } else {
                             OutputEvent::paused().emit();
                         }
                     }
                     RunnerCommand::Resume => {
-                        if let Err(e) = faker.resume() {
+                        if let Err(e) = faker.resume().await {
                             OutputEvent::error(format!("Resume error: {e}")).emit();
                         } else {
                             OutputEvent::resumed().emit();
@@ -281,14 +283,20 @@ pub fn create_faker_config(config: &RunnerConfig) -> FakerConfig {
         download_rate: config.download_rate,
         port: config.port,
         vpn_port_sync: false,
+        accept_connections: true,
         client_type: config.client.into(),
         client_version: config.client_version.clone(),
+        custom_user_agent: None,
+        peer_id_override: None,
         initial_uploaded: config.initial_uploaded,
         initial_downloaded: config.initial_downloaded,
         completion_percent: config.completion,
-        num_want: 50,
+        selected_files: None,
+        num_want: None,
+        compact: true,
         randomize_rates: !config.no_randomize,
         random_range_percent: config.random_range,
+        randomize_mode: config.randomize_mode.into(),
         randomize_ratio: config.randomize_ratio,
         random_ratio_range_percent: config.random_ratio_range,
         stop_at_ratio: config.stop_ratio,
@@ -296,14 +304,45 @@ pub fn create_faker_config(config: &RunnerConfig) -> FakerConfig {
         stop_at_uploaded: config.stop_uploaded.map(|gb| (gb * 1024.0 * 1024.0 * 1024.0) as u64),
         stop_at_downloaded: config.stop_downloaded.map(|gb| (gb * 1024.0 * 1024.0 * 1024.0) as u64),
         stop_at_seed_time: config.stop_time.map(|hours| (hours * 3600.0) as u64),
+        stop_at_timestamp: None,
+        hnr_min_seed_time: None,
+        hnr_min_ratio: None,
         idle_when_no_leechers: config.idle_when_no_leechers,
         idle_when_no_seeders: config.idle_when_no_seeders,
+        announce_ip: None,
+        announce_ipv6: None,
+        auto_announce_vpn_ip: false,
+        proxy_url: None,
+        seed_only: false,
+        seed_only_after_complete: false,
+        max_upload_before_complete_ratio: None,
+        max_plausible_upload_kbps: FakerConfig::default().max_plausible_upload_kbps,
         scrape_interval: 60,
+        scrape_enabled: true,
+        idle_announce_interval: None,
+        min_announce_interval: None,
+        max_announce_interval: None,
+        announce_jitter_secs: 30,
+        regenerate_identity_on_start: true,
+        rate_schedules: Vec::new(),
+        rate_schedule_utc_offset: 0,
+        announce_on_first_leecher: false,
+        announce_on_pause: false,
+        announce_on_resume: true,
+        retry_ipv6_only_trackers: true,
+        dry_run: false,
+        record_last_announce: false,
+        announce_v2_hash: false,
         post_stop_action: config.post_stop_action.into(),
         progressive_rates: config.progressive,
         target_upload_rate: config.target_upload,
         target_download_rate: config.target_download,
         progressive_duration: (config.progressive_duration * 3600.0) as u64,
+        progressive_curve: config.progressive_curve.into(),
+        history_length: 60,
+        history_archive_secs: 0,
+        history_archive_resolution_secs: 300,
+        bonus_formula: None,
     }
 }
 
@@ -172,7 +172,7 @@ pub async fn run_tui_mode(config: RunnerConfig) -> Result<()> {
                     let is_running =
                         app.stats.as_ref().is_some_and(|s| matches!(s.state, FakerState::Running));
                     if is_running {
-                        if let Err(e) = faker.pause() {
+                        if let Err(e) = faker.pause().await {
                             app.set_status(format!("Pause failed: {e}"));
                         } else {
                             app.set_status("Paused - press [r] to resume");
@@ -183,7 +183,7 @@ pub async fn run_tui_mode(config: RunnerConfig) -> Result<()> {
                     let is_paused =
                         app.stats.as_ref().is_some_and(|s| matches!(s.state, FakerState::Paused));
                     if is_paused {
-                        if let Err(e) = faker.resume() {
+                        if let Err(e) = faker.resume().await {
                             app.set_status(format!("Resume failed: {e}"));
                         } else {
                             app.set_status("Resumed");
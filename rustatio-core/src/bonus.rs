@@ -0,0 +1,368 @@
+//! Seeding bonus estimates from simple tracker formulas.
+//!
+//! A formula is either the name of a built-in tracker formula or an arithmetic
+//! expression over `size` (GiB), `seeders` and `seed_time` (hours). Expressions
+//! support `+ - * / ^`, parentheses and `sqrt`, `ln`, `log10`, `exp`, `abs`,
+//! `min` and `max`.
+
+use thiserror::Error;
+
+/// Built-in formulas, selectable by name instead of an expression.
+pub const BUILTIN_BONUS_FORMULAS: &[(&str, &str)] = &[
+    // Gazelle hourly rate (seed time in days) multiplied by hours seeded
+    (
+        "gazelle",
+        "size * (0.025 + 0.6 * ln(1 + seed_time / 24) / max(seeders, 1) ^ 0.6) * seed_time",
+    ),
+    // One point per GiB per hour seeded
+    ("linear", "size * seed_time"),
+];
+
+/// Longest formula accepted, which also bounds how deep the parsed expression can get.
+const MAX_FORMULA_LEN: usize = 1024;
+
+/// Deepest nesting of parentheses, calls and unary minus the parser follows.
+const MAX_NESTING_DEPTH: usize = 32;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Invalid bonus formula: {0}")]
+pub struct BonusFormulaError(String);
+
+/// Values a bonus formula is evaluated against.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BonusInputs {
+    pub size_gib: f64,
+    pub seeders: f64,
+    pub seed_time_hours: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BonusFormula {
+    expr: Expr,
+}
+
+impl BonusFormula {
+    /// Parse a built-in formula name or a custom expression.
+    pub fn parse(source: &str) -> Result<Self, BonusFormulaError> {
+        let source = source.trim();
+        let source = BUILTIN_BONUS_FORMULAS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(source))
+            .map_or(source, |(_, expr)| expr);
+        if source.len() > MAX_FORMULA_LEN {
+            return Err(BonusFormulaError(format!(
+                "formula is longer than {MAX_FORMULA_LEN} characters"
+            )));
+        }
+
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens: &tokens, pos: 0, depth: 0 };
+        let expr = parser.expr()?;
+        if parser.pos != tokens.len() {
+            return Err(BonusFormulaError(format!("unexpected token at position {}", parser.pos)));
+        }
+        Ok(Self { expr })
+    }
+
+    pub fn evaluate(&self, inputs: &BonusInputs) -> f64 {
+        self.expr.eval(inputs)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Op(char),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Var {
+    Size,
+    Seeders,
+    SeedTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Func {
+    Sqrt,
+    Ln,
+    Log10,
+    Exp,
+    Abs,
+    Min,
+    Max,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "sqrt" => Self::Sqrt,
+            "ln" => Self::Ln,
+            "log10" => Self::Log10,
+            "exp" => Self::Exp,
+            "abs" => Self::Abs,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            _ => return None,
+        })
+    }
+
+    const fn arity(self) -> usize {
+        match self {
+            Self::Min | Self::Max => 2,
+            _ => 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Num(f64),
+    Var(Var),
+    Neg(Box<Self>),
+    Binary(char, Box<Self>, Box<Self>),
+    Call(Func, Vec<Self>),
+}
+
+impl Expr {
+    fn eval(&self, inputs: &BonusInputs) -> f64 {
+        match self {
+            Self::Num(value) => *value,
+            Self::Var(Var::Size) => inputs.size_gib,
+            Self::Var(Var::Seeders) => inputs.seeders,
+            Self::Var(Var::SeedTime) => inputs.seed_time_hours,
+            Self::Neg(inner) => -inner.eval(inputs),
+            Self::Binary(op, lhs, rhs) => {
+                let (lhs, rhs) = (lhs.eval(inputs), rhs.eval(inputs));
+                match op {
+                    '+' => lhs + rhs,
+                    '-' => lhs - rhs,
+                    '*' => lhs * rhs,
+                    '/' => lhs / rhs,
+                    _ => lhs.powf(rhs),
+                }
+            }
+            Self::Call(func, args) => {
+                let arg = |idx: usize| args.get(idx).map_or(f64::NAN, |arg| arg.eval(inputs));
+                match func {
+                    Func::Sqrt => arg(0).sqrt(),
+                    Func::Ln => arg(0).ln(),
+                    Func::Log10 => arg(0).log10(),
+                    Func::Exp => arg(0).exp(),
+                    Func::Abs => arg(0).abs(),
+                    Func::Min => arg(0).min(arg(1)),
+                    Func::Max => arg(0).max(arg(1)),
+                }
+            }
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, BonusFormulaError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+
+    while idx < chars.len() {
+        let c = chars[idx];
+        if c.is_whitespace() {
+            idx += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = idx;
+            while idx < chars.len() && (chars[idx].is_ascii_digit() || chars[idx] == '.') {
+                idx += 1;
+            }
+            let text: String = chars[start..idx].iter().collect();
+            let value = text
+                .parse::<f64>()
+                .map_err(|_| BonusFormulaError(format!("invalid number '{text}'")))?;
+            tokens.push(Token::Num(value));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = idx;
+            while idx < chars.len() && (chars[idx].is_ascii_alphanumeric() || chars[idx] == '_') {
+                idx += 1;
+            }
+            let text: String = chars[start..idx].iter().collect();
+            tokens.push(Token::Ident(text.to_ascii_lowercase()));
+        } else if "+-*/^(),".contains(c) {
+            tokens.push(Token::Op(c));
+            idx += 1;
+        } else {
+            return Err(BonusFormulaError(format!("unexpected character '{c}'")));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek_op(&self) -> Option<char> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(op)) => Some(*op),
+            _ => None,
+        }
+    }
+
+    fn expect_op(&mut self, expected: char) -> Result<(), BonusFormulaError> {
+        if self.peek_op() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(BonusFormulaError(format!("expected '{expected}' at position {}", self.pos)))
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, BonusFormulaError> {
+        let mut lhs = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek_op() {
+            self.pos += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Expr, BonusFormulaError> {
+        let mut lhs = self.unary()?;
+        while let Some(op @ ('*' | '/')) = self.peek_op() {
+            self.pos += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    /// Every nested sub-expression goes through here, so this is where depth is capped.
+    fn unary(&mut self) -> Result<Expr, BonusFormulaError> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(BonusFormulaError(format!(
+                "formula is nested deeper than {MAX_NESTING_DEPTH} levels"
+            )));
+        }
+        self.depth += 1;
+        let result = if self.peek_op() == Some('-') {
+            self.pos += 1;
+            self.unary().map(|inner| Expr::Neg(Box::new(inner)))
+        } else {
+            self.power()
+        };
+        self.depth -= 1;
+        result
+    }
+
+    fn power(&mut self) -> Result<Expr, BonusFormulaError> {
+        let base = self.primary()?;
+        if self.peek_op() == Some('^') {
+            self.pos += 1;
+            return Ok(Expr::Binary('^', Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Expr, BonusFormulaError> {
+        let Some(token) = self.tokens.get(self.pos) else {
+            return Err(BonusFormulaError("unexpected end of formula".to_string()));
+        };
+        self.pos += 1;
+
+        match token {
+            Token::Num(value) => Ok(Expr::Num(*value)),
+            Token::Op('(') => {
+                let inner = self.expr()?;
+                self.expect_op(')')?;
+                Ok(inner)
+            }
+            Token::Ident(name) => match name.as_str() {
+                "size" => Ok(Expr::Var(Var::Size)),
+                "seeders" => Ok(Expr::Var(Var::Seeders)),
+                "seed_time" => Ok(Expr::Var(Var::SeedTime)),
+                name => {
+                    let func = Func::from_name(name)
+                        .ok_or_else(|| BonusFormulaError(format!("unknown name '{name}'")))?;
+                    self.call(func)
+                }
+            },
+            Token::Op(op) => {
+                Err(BonusFormulaError(format!("unexpected '{op}' at position {}", self.pos - 1)))
+            }
+        }
+    }
+
+    fn call(&mut self, func: Func) -> Result<Expr, BonusFormulaError> {
+        self.expect_op('(')?;
+        let mut args = vec![self.expr()?];
+        while self.peek_op() == Some(',') {
+            self.pos += 1;
+            args.push(self.expr()?);
+        }
+        self.expect_op(')')?;
+
+        if args.len() != func.arity() {
+            return Err(BonusFormulaError(format!(
+                "{func:?} expects {} argument(s), got {}",
+                func.arity(),
+                args.len()
+            )));
+        }
+        Ok(Expr::Call(func, args))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(source: &str, inputs: &BonusInputs) -> f64 {
+        let formula = BonusFormula::parse(source);
+        assert!(formula.is_ok(), "failed to parse {source}: {formula:?}");
+        formula.map_or(f64::NAN, |formula| formula.evaluate(inputs))
+    }
+
+    #[test]
+    fn evaluates_operator_precedence_and_variables() {
+        let inputs = BonusInputs { size_gib: 4.0, seeders: 3.0, seed_time_hours: 10.0 };
+
+        assert_eq!(eval("1 + 2 * 3", &inputs), 7.0);
+        assert_eq!(eval("(1 + 2) * 3", &inputs), 9.0);
+        assert_eq!(eval("-2 ^ 2", &inputs), -4.0);
+        assert_eq!(eval("2 ^ 3 ^ 2", &inputs), 512.0);
+        assert_eq!(eval("size * seed_time / max(seeders, 1)", &inputs), 40.0 / 3.0);
+        assert_eq!(eval("sqrt(size) + min(seeders, 1)", &inputs), 3.0);
+    }
+
+    #[test]
+    fn builtin_formulas_parse_by_name() {
+        let inputs = BonusInputs { size_gib: 2.0, seeders: 1.0, seed_time_hours: 24.0 };
+
+        assert_eq!(eval("LINEAR", &inputs), 48.0);
+        let gazelle = eval("gazelle", &inputs);
+        let expected = 2.0 * 0.6f64.mul_add(2f64.ln(), 0.025) * 24.0;
+        assert!((gazelle - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_invalid_formulas() {
+        for source in ["", "size +", "foo * 2", "max(1)", "size $ 2", "(size", "size size"] {
+            assert!(BonusFormula::parse(source).is_err(), "{source} should be rejected");
+        }
+    }
+
+    #[test]
+    fn rejects_deeply_nested_and_oversized_formulas() {
+        let nested =
+            format!("{}size{}", "(".repeat(MAX_NESTING_DEPTH), ")".repeat(MAX_NESTING_DEPTH));
+        assert!(BonusFormula::parse(&nested).is_err());
+        assert!(BonusFormula::parse(&"-".repeat(10_000)).is_err());
+        assert!(
+            BonusFormula::parse("1 + ".repeat(MAX_FORMULA_LEN).trim_end_matches(" + ")).is_err()
+        );
+
+        let shallow = format!("{}size{}", "(".repeat(8), ")".repeat(8));
+        assert!(BonusFormula::parse(&shallow).is_ok());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::faker::PostStopAction;
+use crate::faker::{PostStopAction, ProgressiveCurve, RandomizeMode};
 use crate::torrent::ClientType;
 use serde::{Deserialize, Serialize};
 use std::fs;
@@ -57,6 +57,8 @@ pub struct InstanceConfig {
     pub randomize_rates: bool,
     pub random_range_percent: f64,
     #[serde(default)]
+    pub randomize_mode: RandomizeMode,
+    #[serde(default)]
     pub randomize_ratio: bool,
     #[serde(default = "default_random_ratio_range_percent")]
     pub random_ratio_range_percent: f64,
@@ -79,6 +81,8 @@ pub struct InstanceConfig {
     pub target_upload_rate: f64,
     pub target_download_rate: f64,
     pub progressive_duration_hours: f64,
+    #[serde(default)]
+    pub progressive_curve: ProgressiveCurve,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,6 +1,10 @@
+use crate::bonus::{BonusFormula, BonusInputs};
+use crate::clock::{Clock, SystemClock};
 use crate::protocol::{
-    AnnounceRequest, AnnounceResponse, TrackerClient, TrackerError, TrackerEvent,
+    AnnounceExchange, AnnounceRequest, AnnounceResponse, TrackerClient, TrackerError, TrackerEvent,
+    TrackerTiers, IPV6_ONLY_TRACKER_ERROR,
 };
+use crate::schedule::{active_rate_schedule, minute_of_day, parse_datetime, RateSchedule};
 use crate::torrent::{ClientConfig, ClientType, TorrentInfo};
 use crate::{log_debug, log_info, log_trace, log_warn};
 #[cfg(not(target_arch = "wasm32"))]
@@ -8,6 +12,7 @@ use crate::{peer_listener::handle_is_connectable, protocol::peer_id_to_array};
 use instant::Instant;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv6Addr};
 use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
@@ -45,23 +50,48 @@ pub struct FakerConfig {
     #[serde(default)]
     pub vpn_port_sync: bool,
 
+    /// Answer inbound peer connections on the announced port, so trackers that probe it
+    /// see a connectable peer (default: true). Off leaves the port closed.
+    #[serde(default = "default_accept_connections")]
+    pub accept_connections: bool,
+
     /// Client to emulate
     pub client_type: ClientType,
 
     /// Client version (optional, uses default if None)
     pub client_version: Option<String>,
 
+    /// Exact User-Agent to send instead of the one derived from the client (advanced).
+    /// The peer ID still uses the client's prefix.
+    #[serde(default, alias = "user_agent_override")]
+    pub custom_user_agent: Option<String>,
+
+    /// Exact 20-byte peer ID to announce instead of a generated one (advanced). Kept
+    /// across restarts, even with `regenerate_identity_on_start`.
+    #[serde(default)]
+    pub peer_id_override: Option<String>,
+
     /// Initial uploaded amount in bytes
     pub initial_uploaded: u64,
 
     /// Initial downloaded amount in bytes
     pub initial_downloaded: u64,
 
-    /// Percentage already downloaded (0-100)
+    /// Percentage already downloaded (0-100), [`DEFAULT_COMPLETION_PERCENT`] when unset
     pub completion_percent: f64,
 
-    /// Number of peers to request
-    pub num_want: u32,
+    /// Indices of the files being downloaded; `left` and completion only count these.
+    /// `None` selects the whole torrent.
+    #[serde(default)]
+    pub selected_files: Option<Vec<usize>>,
+
+    /// Number of peers to request; defaults to the emulated client's own value
+    #[serde(default)]
+    pub num_want: Option<u32>,
+
+    /// Ask for compact peer lists (default: true). Turn off for trackers that reject them.
+    #[serde(default = "default_compact")]
+    pub compact: bool,
 
     /// Enable randomization of rates
     #[serde(default = "default_randomize_rates")]
@@ -71,6 +101,10 @@ pub struct FakerConfig {
     #[serde(default = "default_random_range")]
     pub random_range_percent: f64,
 
+    /// How the randomized rate moves within its range between updates (default uniform)
+    #[serde(default)]
+    pub randomize_mode: RandomizeMode,
+
     /// Enable randomization of the stop ratio target
     #[serde(default)]
     pub randomize_ratio: bool,
@@ -96,6 +130,19 @@ pub struct FakerConfig {
     /// Stop after seeding for this many seconds (optional)
     pub stop_at_seed_time: Option<u64>,
 
+    /// Stop at this wall-clock time in unix seconds, however long the session ran (optional)
+    #[serde(default)]
+    pub stop_at_timestamp: Option<u64>,
+
+    // Hit-and-run protection (either requirement being met lifts the protection)
+    /// Refuse to stop or pause before seeding this many seconds (optional)
+    #[serde(default)]
+    pub hnr_min_seed_time: Option<u64>,
+
+    /// Refuse to stop or pause before reaching this ratio (optional)
+    #[serde(default)]
+    pub hnr_min_ratio: Option<f64>,
+
     /// Idle (0 KB/s upload) when there are no leechers - stays connected for bonus points (optional, default false)
     #[serde(default)]
     pub idle_when_no_leechers: bool,
@@ -104,10 +151,115 @@ pub struct FakerConfig {
     #[serde(default)]
     pub idle_when_no_seeders: bool,
 
+    /// IP address sent to trackers as `ip=` (optional)
+    #[serde(default)]
+    pub announce_ip: Option<IpAddr>,
+
+    /// IPv6 address sent to trackers as `ipv6=` (optional)
+    #[serde(default)]
+    pub announce_ipv6: Option<Ipv6Addr>,
+
+    /// Announce the VPN's public IP detected through Gluetun (server only, default false)
+    #[serde(default)]
+    pub auto_announce_vpn_ip: bool,
+
+    /// HTTP or SOCKS5 proxy for this instance's announces and scrapes (optional)
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+
+    /// Seed an already downloaded torrent: forces `completion_percent` to 100,
+    /// `download_rate` to 0 and `idle_when_no_seeders` off (default false)
+    #[serde(default)]
+    pub seed_only: bool,
+
+    /// Don't upload anything until the torrent is complete (optional, default false)
+    #[serde(default)]
+    pub seed_only_after_complete: bool,
+
+    /// While incomplete, cap session upload at session download times this ratio (optional)
+    #[serde(default)]
+    pub max_upload_before_complete_ratio: Option<f64>,
+
+    /// Ceiling in KB/s for the upload rate, whatever the configured, scheduled or
+    /// progressive rate (default: 125000, about 1 Gbit/s; 0 disables)
+    #[serde(default = "default_max_plausible_upload_kbps")]
+    pub max_plausible_upload_kbps: f64,
+
     /// Interval in seconds between scrape requests for peer count updates (default: 60)
     #[serde(default = "default_scrape_interval")]
     pub scrape_interval: u64,
 
+    /// Scrape the tracker periodically (default: true). Disable for trackers that frown
+    /// on scraping; peer counts then come from announce responses only.
+    #[serde(default = "default_scrape_enabled")]
+    pub scrape_enabled: bool,
+
+    /// Announce interval in seconds used while idling (optional, floored at the tracker's min interval)
+    #[serde(default)]
+    pub idle_announce_interval: Option<u64>,
+
+    /// Lower bound in seconds for the tracker's announce interval (optional)
+    #[serde(default)]
+    pub min_announce_interval: Option<u64>,
+
+    /// Upper bound in seconds for the tracker's announce interval, never below the
+    /// tracker's min interval (optional)
+    #[serde(default)]
+    pub max_announce_interval: Option<u64>,
+
+    /// Spread announces by up to this many seconds either way, using an offset derived
+    /// from the peer ID so it is stable per instance (default: 30, 0 disables)
+    #[serde(default = "default_announce_jitter")]
+    pub announce_jitter_secs: u64,
+
+    /// Generate a fresh peer ID and key on every start, like real clients do
+    /// (default: true). Disable to keep one identity for the faker's lifetime.
+    #[serde(default = "default_regenerate_identity")]
+    pub regenerate_identity_on_start: bool,
+
+    /// Time-of-day windows overriding the upload/download rates (first match wins)
+    #[serde(default)]
+    pub rate_schedules: Vec<RateSchedule>,
+
+    /// Offset in minutes from UTC that `rate_schedules` times are expressed in
+    #[serde(default)]
+    pub rate_schedule_utc_offset: i32,
+
+    /// Announce immediately when a scrape shows the first leecher while idling (default false).
+    /// Has no effect with `scrape_enabled` off.
+    #[serde(default)]
+    pub announce_on_first_leecher: bool,
+
+    /// Send `event=stopped` with the current totals when pausing, as real clients do, so
+    /// the tracker does not list the paused peer as active (default false)
+    #[serde(default)]
+    pub announce_on_pause: bool,
+
+    /// Send `event=started` when resuming a paused instance, so a tracker that dropped the
+    /// peer during the pause lists it again (default true)
+    #[serde(default = "default_announce_on_resume")]
+    pub announce_on_resume: bool,
+
+    /// Keep retrying with backoff when a tracker is IPv6-only and this host has no IPv6
+    /// route, in case connectivity shows up later; off stops the instance instead (default true)
+    #[serde(default = "default_retry_ipv6_only_trackers")]
+    pub retry_ipv6_only_trackers: bool,
+
+    /// Never contact the tracker: announces and scrapes are answered locally with a
+    /// made-up swarm while stats, stop conditions and scheduling run as usual (default false)
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Keep the last announce URL and raw tracker response for debugging (default false)
+    #[serde(default)]
+    pub record_last_announce: bool,
+
+    /// Also announce a hybrid (v1 + v2) torrent under its truncated v2 hash, as BEP 52
+    /// clients do to join the v2 swarm. Never done for private torrents, where a tracker
+    /// indexing both hashes would credit the upload twice (default false)
+    #[serde(default)]
+    pub announce_v2_hash: bool,
+
     // Progressive rate adjustment
     /// Enable progressive rate adjustment
     #[serde(default)]
@@ -123,9 +275,31 @@ pub struct FakerConfig {
     #[serde(default = "default_progressive_duration")]
     pub progressive_duration: u64,
 
+    /// Shape of the ramp from the base rates to the target rates (default linear)
+    #[serde(default)]
+    pub progressive_curve: ProgressiveCurve,
+
     /// What to do when stop conditions are met
     #[serde(default)]
     pub post_stop_action: PostStopAction,
+
+    /// Graph points kept at full resolution, one per update (default: 60, max: 3600)
+    #[serde(default = "default_history_length")]
+    pub history_length: usize,
+
+    /// Also keep older graph points for this many seconds, thinned to one per
+    /// `history_archive_resolution_secs` (default: 0, off; 86400 keeps a day)
+    #[serde(default)]
+    pub history_archive_secs: u64,
+
+    /// Spacing in seconds between the older graph points (default: 300)
+    #[serde(default = "default_history_archive_resolution")]
+    pub history_archive_resolution_secs: u64,
+
+    /// Bonus point formula: a built-in name (e.g. "gazelle") or an expression over
+    /// `size` (GiB), `seeders` and `seed_time` (hours)
+    #[serde(default)]
+    pub bonus_formula: Option<String>,
 }
 
 /// UI-friendly preset settings format (matches frontend)
@@ -137,11 +311,15 @@ pub struct PresetSettings {
     pub download_rate: Option<f64>,
     pub port: Option<u16>,
     pub vpn_port_sync: Option<bool>,
+    pub accept_connections: Option<bool>,
     pub selected_client: Option<ClientType>,
     pub selected_client_version: Option<String>,
+    #[serde(alias = "userAgentOverride")]
+    pub custom_user_agent: Option<String>,
     pub completion_percent: Option<f64>,
     pub randomize_rates: Option<bool>,
     pub random_range_percent: Option<f64>,
+    pub randomize_mode: Option<RandomizeMode>,
     pub randomize_ratio: Option<bool>,
     pub random_ratio_range_percent: Option<f64>,
     // Stop conditions with enabled flags
@@ -153,71 +331,252 @@ pub struct PresetSettings {
     pub stop_at_downloaded_gb: Option<f64>,
     pub stop_at_seed_time_enabled: Option<bool>,
     pub stop_at_seed_time_hours: Option<f64>,
+    pub stop_at_datetime_enabled: Option<bool>,
+    /// `YYYY-MM-DDTHH:MM[:SS]` with optional `Z`/`±HH:MM` offset, UTC when omitted
+    pub stop_at_datetime: Option<String>,
+    pub hnr_protection_enabled: Option<bool>,
+    pub hnr_min_seed_time_hours: Option<f64>,
+    pub hnr_min_ratio: Option<f64>,
     pub idle_when_no_leechers: Option<bool>,
     pub idle_when_no_seeders: Option<bool>,
+    pub seed_only: Option<bool>,
+    pub seed_only_after_complete: Option<bool>,
+    pub max_upload_before_complete_ratio: Option<f64>,
+    pub max_plausible_upload_kbps: Option<f64>,
+    pub announce_ip: Option<String>,
+    pub announce_ipv6: Option<String>,
+    pub auto_announce_vpn_ip: Option<bool>,
+    pub proxy_url: Option<String>,
+    pub announce_on_first_leecher: Option<bool>,
+    pub scrape_enabled: Option<bool>,
+    pub announce_on_pause: Option<bool>,
+    pub announce_on_resume: Option<bool>,
+    pub retry_ipv6_only_trackers: Option<bool>,
+    pub dry_run: Option<bool>,
     pub post_stop_action: Option<String>,
     // Progressive rates
     pub progressive_rates_enabled: Option<bool>,
     pub target_upload_rate: Option<f64>,
     pub target_download_rate: Option<f64>,
     pub progressive_duration_hours: Option<f64>,
+    pub progressive_curve: Option<ProgressiveCurve>,
+    pub history_length: Option<usize>,
+    pub bonus_formula: Option<String>,
 }
 
-impl From<PresetSettings> for FakerConfig {
-    fn from(p: PresetSettings) -> Self {
-        let stop_at_ratio =
-            if p.stop_at_ratio_enabled.unwrap_or(false) { p.stop_at_ratio } else { None };
+impl PresetSettings {
+    /// Reject values that converting to a [`FakerConfig`] would otherwise drop silently.
+    pub fn validate(&self) -> Result<()> {
+        if self.stop_at_datetime_enabled.unwrap_or(false)
+            && self.stop_at_datetime.as_deref().and_then(parse_datetime).is_none()
+        {
+            return Err(FakerError::ConfigError(format!(
+                "stop_at_datetime must look like 2025-01-31T18:00Z (got {:?})",
+                self.stop_at_datetime.as_deref().unwrap_or_default()
+            )));
+        }
+        if let Some(ip) = Self::given(self.announce_ip.as_deref()) {
+            ip.parse::<IpAddr>().map_err(|_| {
+                FakerError::ConfigError(format!("announce_ip is not an IP address (got {ip:?})"))
+            })?;
+        }
+        if let Some(ip) = Self::given(self.announce_ipv6.as_deref()) {
+            ip.parse::<Ipv6Addr>().map_err(|_| {
+                FakerError::ConfigError(format!(
+                    "announce_ipv6 is not an IPv6 address (got {ip:?})"
+                ))
+            })?;
+        }
+        Ok(())
+    }
 
-        let stop_at_uploaded = if p.stop_at_uploaded_enabled.unwrap_or(false) {
-            p.stop_at_uploaded_gb.map(|gb| (gb * 1024.0 * 1024.0 * 1024.0) as u64)
-        } else {
-            None
-        };
+    /// Overwrite the settings this preset gives in `config`, leaving the rest as they
+    /// are. An enabled flag that is present decides whether its limit applies.
+    pub fn apply_to(self, config: &mut FakerConfig) {
+        let p = self;
+        let gib = |gb: f64| (gb * 1024.0 * 1024.0 * 1024.0) as u64;
+        let hours = |h: f64| (h * 3600.0) as u64;
 
-        let stop_at_downloaded = if p.stop_at_downloaded_enabled.unwrap_or(false) {
-            p.stop_at_downloaded_gb.map(|gb| (gb * 1024.0 * 1024.0 * 1024.0) as u64)
-        } else {
-            None
-        };
+        if let Some(rate) = p.upload_rate {
+            config.upload_rate = rate;
+        }
+        if let Some(rate) = p.download_rate {
+            config.download_rate = rate;
+        }
+        if let Some(port) = p.port {
+            config.port = port;
+        }
+        if let Some(enabled) = p.vpn_port_sync {
+            config.vpn_port_sync = enabled;
+        }
+        if let Some(enabled) = p.accept_connections {
+            config.accept_connections = enabled;
+        }
+        if let Some(client) = p.selected_client {
+            config.client_type = client;
+        }
+        if p.selected_client_version.is_some() {
+            config.client_version = p.selected_client_version;
+        }
+        if let Some(agent) = p.custom_user_agent {
+            config.custom_user_agent = Some(agent).filter(|agent| !agent.trim().is_empty());
+        }
+        if let Some(percent) = p.completion_percent {
+            config.completion_percent = percent;
+        }
+        if let Some(enabled) = p.randomize_rates {
+            config.randomize_rates = enabled;
+        }
+        if let Some(percent) = p.random_range_percent {
+            config.random_range_percent = percent;
+        }
+        if let Some(mode) = p.randomize_mode {
+            config.randomize_mode = mode;
+        }
+        if let Some(enabled) = p.randomize_ratio {
+            config.randomize_ratio = enabled;
+        }
+        if let Some(percent) = p.random_ratio_range_percent {
+            config.random_ratio_range_percent = percent;
+        }
 
-        let stop_at_seed_time = if p.stop_at_seed_time_enabled.unwrap_or(false) {
-            p.stop_at_seed_time_hours.map(|h| (h * 3600.0) as u64)
-        } else {
-            None
-        };
+        if let Some(enabled) = p.stop_at_ratio_enabled {
+            config.stop_at_ratio = p.stop_at_ratio.filter(|_| enabled);
+        }
+        if let Some(enabled) = p.stop_at_uploaded_enabled {
+            config.stop_at_uploaded = p.stop_at_uploaded_gb.filter(|_| enabled).map(gib);
+        }
+        if let Some(enabled) = p.stop_at_downloaded_enabled {
+            config.stop_at_downloaded = p.stop_at_downloaded_gb.filter(|_| enabled).map(gib);
+        }
+        if let Some(enabled) = p.stop_at_seed_time_enabled {
+            config.stop_at_seed_time = p.stop_at_seed_time_hours.filter(|_| enabled).map(hours);
+        }
+        if let Some(enabled) = p.stop_at_datetime_enabled {
+            config.stop_at_timestamp =
+                p.stop_at_datetime.as_deref().filter(|_| enabled).and_then(parse_datetime);
+        }
+        if let Some(enabled) = p.hnr_protection_enabled {
+            config.hnr_min_seed_time =
+                p.hnr_min_seed_time_hours.filter(|_| enabled).map(hours).filter(|secs| *secs > 0);
+            config.hnr_min_ratio = p.hnr_min_ratio.filter(|ratio| enabled && *ratio > 0.0);
+        }
 
-        Self {
-            upload_rate: p.upload_rate.unwrap_or(50.0),
-            download_rate: p.download_rate.unwrap_or(100.0),
-            port: p.port.unwrap_or(6881),
-            vpn_port_sync: p.vpn_port_sync.unwrap_or(false),
-            client_type: p.selected_client.unwrap_or(ClientType::QBittorrent),
-            client_version: p.selected_client_version,
-            initial_uploaded: 0,
-            initial_downloaded: 0,
-            completion_percent: p.completion_percent.unwrap_or(100.0),
-            num_want: 50,
-            randomize_rates: p.randomize_rates.unwrap_or(true),
-            random_range_percent: p.random_range_percent.unwrap_or(20.0),
-            randomize_ratio: p.randomize_ratio.unwrap_or(false),
-            random_ratio_range_percent: p.random_ratio_range_percent.unwrap_or(10.0),
-            stop_at_ratio,
-            effective_stop_at_ratio: None,
-            stop_at_uploaded,
-            stop_at_downloaded,
-            stop_at_seed_time,
-            idle_when_no_leechers: p.idle_when_no_leechers.unwrap_or(false),
-            idle_when_no_seeders: p.idle_when_no_seeders.unwrap_or(false),
-            scrape_interval: 60,
-            post_stop_action: match p.post_stop_action.as_deref() {
-                Some("stop_seeding") => PostStopAction::StopSeeding,
-                Some("delete_instance") => PostStopAction::DeleteInstance,
+        if let Some(enabled) = p.idle_when_no_leechers {
+            config.idle_when_no_leechers = enabled;
+        }
+        if let Some(enabled) = p.idle_when_no_seeders {
+            config.idle_when_no_seeders = enabled;
+        }
+        if let Some(enabled) = p.seed_only {
+            config.seed_only = enabled;
+        }
+        if let Some(enabled) = p.seed_only_after_complete {
+            config.seed_only_after_complete = enabled;
+        }
+        if p.max_upload_before_complete_ratio.is_some() {
+            config.max_upload_before_complete_ratio = p.max_upload_before_complete_ratio;
+        }
+        if let Some(ceiling) = p.max_plausible_upload_kbps {
+            config.max_plausible_upload_kbps = ceiling;
+        }
+        if p.announce_ip.is_some() {
+            config.announce_ip =
+                FakerConfig::announce_ip_from_preset("announce_ip", p.announce_ip.as_deref());
+        }
+        if p.announce_ipv6.is_some() {
+            config.announce_ipv6 =
+                FakerConfig::announce_ip_from_preset("announce_ipv6", p.announce_ipv6.as_deref());
+        }
+        if let Some(enabled) = p.auto_announce_vpn_ip {
+            config.auto_announce_vpn_ip = enabled;
+        }
+        if let Some(url) = p.proxy_url {
+            config.proxy_url = Some(url).filter(|url| !url.trim().is_empty());
+        }
+        if let Some(enabled) = p.announce_on_first_leecher {
+            config.announce_on_first_leecher = enabled;
+        }
+        if let Some(enabled) = p.scrape_enabled {
+            config.scrape_enabled = enabled;
+        }
+        if let Some(enabled) = p.announce_on_pause {
+            config.announce_on_pause = enabled;
+        }
+        if let Some(enabled) = p.announce_on_resume {
+            config.announce_on_resume = enabled;
+        }
+        if let Some(enabled) = p.retry_ipv6_only_trackers {
+            config.retry_ipv6_only_trackers = enabled;
+        }
+        if let Some(enabled) = p.dry_run {
+            config.dry_run = enabled;
+        }
+        if let Some(action) = p.post_stop_action {
+            config.post_stop_action = match action.as_str() {
+                "stop_seeding" => PostStopAction::StopSeeding,
+                "delete_instance" => PostStopAction::DeleteInstance,
                 _ => PostStopAction::Idle,
-            },
-            progressive_rates: p.progressive_rates_enabled.unwrap_or(false),
-            target_upload_rate: p.target_upload_rate,
-            target_download_rate: p.target_download_rate,
-            progressive_duration: (p.progressive_duration_hours.unwrap_or(1.0) * 3600.0) as u64,
+            };
+        }
+
+        if let Some(enabled) = p.progressive_rates_enabled {
+            config.progressive_rates = enabled;
+        }
+        if p.target_upload_rate.is_some() {
+            config.target_upload_rate = p.target_upload_rate;
+        }
+        if p.target_download_rate.is_some() {
+            config.target_download_rate = p.target_download_rate;
+        }
+        if let Some(duration) = p.progressive_duration_hours {
+            config.progressive_duration = hours(duration);
+        }
+        if let Some(curve) = p.progressive_curve {
+            config.progressive_curve = curve;
+        }
+        if let Some(length) = p.history_length {
+            config.history_length = length;
+        }
+        if let Some(formula) = p.bonus_formula {
+            config.bonus_formula = Some(formula).filter(|formula| !formula.trim().is_empty());
+        }
+        config.apply_seed_only();
+    }
+
+    /// A text setting with surrounding whitespace trimmed, `None` when blank.
+    fn given(value: Option<&str>) -> Option<&str> {
+        value.map(str::trim).filter(|value| !value.is_empty())
+    }
+}
+
+impl From<PresetSettings> for FakerConfig {
+    fn from(p: PresetSettings) -> Self {
+        let mut config = Self::default();
+        p.apply_to(&mut config);
+        config
+    }
+}
+
+impl FakerConfig {
+    /// Parse an announce IP from a preset, logging one that cannot be read. A blank
+    /// value means none.
+    fn announce_ip_from_preset<T: std::str::FromStr>(name: &str, value: Option<&str>) -> Option<T> {
+        let value = PresetSettings::given(value)?;
+        let ip = value.parse().ok();
+        if ip.is_none() {
+            log_warn!("Ignoring preset {} {:?}: not an IP address", name, value);
+        }
+        ip
+    }
+
+    /// Overwrite the fields `seed_only` derives, so they can't disagree with it
+    pub const fn apply_seed_only(&mut self) {
+        if self.seed_only {
+            self.completion_percent = 100.0;
+            self.download_rate = 0.0;
+            self.target_download_rate = None;
+            self.idle_when_no_seeders = false;
         }
     }
 }
@@ -238,10 +597,63 @@ const fn default_random_ratio_range() -> f64 {
     10.0
 }
 
+const fn default_compact() -> bool {
+    true
+}
+
 const fn default_scrape_interval() -> u64 {
     60 // 60 seconds
 }
 
+const fn default_scrape_enabled() -> bool {
+    true
+}
+
+const fn default_accept_connections() -> bool {
+    true
+}
+
+const fn default_history_length() -> usize {
+    60
+}
+
+const fn default_history_archive_resolution() -> u64 {
+    300 // 5 minutes
+}
+
+/// About 1 Gbit/s, more than any home connection uploads
+const DEFAULT_MAX_PLAUSIBLE_UPLOAD_KBPS: f64 = 125_000.0;
+
+/// About 100 Mbit/s; configured upload rates above this are warned about
+const REALISTIC_HOME_UPLOAD_KBPS: f64 = 12_500.0;
+
+/// Completion used when a config does not set one.
+///
+/// New instances start with nothing downloaded, whether they come from
+/// `FakerConfig::default`, a preset without a completion or a watch folder without a
+/// default config.
+pub const DEFAULT_COMPLETION_PERCENT: f64 = 0.0;
+
+const fn default_max_plausible_upload_kbps() -> f64 {
+    DEFAULT_MAX_PLAUSIBLE_UPLOAD_KBPS
+}
+
+const fn default_announce_jitter() -> u64 {
+    30 // ±30 seconds
+}
+
+const fn default_regenerate_identity() -> bool {
+    true
+}
+
+const fn default_announce_on_resume() -> bool {
+    true
+}
+
+const fn default_retry_ipv6_only_trackers() -> bool {
+    true
+}
+
 impl Default for FakerConfig {
     fn default() -> Self {
         Self {
@@ -249,14 +661,20 @@ impl Default for FakerConfig {
             download_rate: 100.0, // 100 KB/s
             port: 6881,
             vpn_port_sync: false,
+            accept_connections: true,
             client_type: ClientType::QBittorrent,
             client_version: None,
+            custom_user_agent: None,
+            peer_id_override: None,
             initial_uploaded: 0,
             initial_downloaded: 0,
-            completion_percent: 0.0,
-            num_want: 50,
+            completion_percent: DEFAULT_COMPLETION_PERCENT,
+            selected_files: None,
+            num_want: None,
+            compact: true,
             randomize_rates: true,
             random_range_percent: 20.0,
+            randomize_mode: RandomizeMode::Uniform,
             randomize_ratio: false,
             random_ratio_range_percent: 10.0,
             stop_at_ratio: None,
@@ -264,14 +682,45 @@ impl Default for FakerConfig {
             stop_at_uploaded: None,
             stop_at_downloaded: None,
             stop_at_seed_time: None,
+            stop_at_timestamp: None,
+            hnr_min_seed_time: None,
+            hnr_min_ratio: None,
             idle_when_no_leechers: false,
             idle_when_no_seeders: false,
+            announce_ip: None,
+            announce_ipv6: None,
+            auto_announce_vpn_ip: false,
+            proxy_url: None,
+            seed_only: false,
+            seed_only_after_complete: false,
+            max_upload_before_complete_ratio: None,
+            max_plausible_upload_kbps: DEFAULT_MAX_PLAUSIBLE_UPLOAD_KBPS,
             scrape_interval: 60,
+            scrape_enabled: true,
+            idle_announce_interval: None,
+            min_announce_interval: None,
+            max_announce_interval: None,
+            announce_jitter_secs: 30,
+            regenerate_identity_on_start: true,
+            rate_schedules: Vec::new(),
+            rate_schedule_utc_offset: 0,
+            announce_on_first_leecher: false,
+            announce_on_pause: false,
+            announce_on_resume: true,
+            retry_ipv6_only_trackers: true,
+            dry_run: false,
+            record_last_announce: false,
+            announce_v2_hash: false,
             progressive_rates: false,
             target_upload_rate: None,
             target_download_rate: None,
             progressive_duration: 3600,
+            progressive_curve: ProgressiveCurve::Linear,
             post_stop_action: PostStopAction::Idle,
+            history_length: 60,
+            history_archive_secs: 0,
+            history_archive_resolution_secs: 300,
+            bonus_formula: None,
         }
     }
 }
@@ -285,6 +734,55 @@ pub enum PostStopAction {
     DeleteInstance,
 }
 
+/// How randomized rates move within `random_range_percent` from one update to the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RandomizeMode {
+    /// Independent jitter on every update
+    #[default]
+    Uniform,
+    /// Random walk that drifts gradually across the range, like real bandwidth
+    Smoothed,
+}
+
+/// Shape of the progressive ramp between the base and target rates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressiveCurve {
+    #[default]
+    Linear,
+    /// Starts slowly and accelerates towards the target
+    EaseIn,
+    /// Climbs quickly and settles into the target
+    EaseOut,
+    /// Steep early climb that flattens out logarithmically
+    Logarithmic,
+}
+
+impl ProgressiveCurve {
+    /// Map the elapsed fraction of the ramp (0.0..=1.0) to the fraction of the rate change
+    #[must_use]
+    pub fn apply(self, progress: f64) -> f64 {
+        let p = progress.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => p,
+            Self::EaseIn => p * p,
+            Self::EaseOut => (1.0 - p).mul_add(p - 1.0, 1.0),
+            Self::Logarithmic => 9.0f64.mul_add(p, 1.0).log10(),
+        }
+    }
+}
+
+/// Why a faker ended up in the `Stopped` state
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StopReason {
+    UserRequested,
+    StopConditionMet { which: String },
+    Error { msg: String },
+    Shutdown,
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum FakerState {
     Idle,
@@ -320,6 +818,12 @@ pub struct FakerStats {
     pub tracker_retry_attempt: u32,
     #[serde(default)]
     pub tracker_retry_at_ms: Option<u64>,
+    /// Exact `failure reason` from the tracker's last failed announce
+    #[serde(default)]
+    pub tracker_failure_reason: Option<String>,
+    /// `warning message` from the tracker's last successful announce
+    #[serde(default)]
+    pub tracker_warning: Option<String>,
 
     // === SESSION STATS (current session only) ===
     pub session_uploaded: u64,   // Uploaded in current session
@@ -359,6 +863,25 @@ pub struct FakerStats {
     pub stop_condition_met: bool,
     #[serde(default)]
     pub post_stop_action: PostStopAction,
+    #[serde(default)]
+    pub stop_reason: Option<StopReason>,
+
+    // Estimated bonus points from the configured bonus formula
+    #[serde(default)]
+    pub estimated_bonus: Option<f64>,
+
+    // True while hit-and-run protection blocks stopping and pausing
+    #[serde(default)]
+    pub hnr_protected: bool,
+
+    // Tracker from the announce-list currently answering announces
+    #[serde(default)]
+    pub active_tracker: Option<String>,
+
+    // True once the tracker accepted a `completed` announce for this torrent, which is
+    // sent once in its lifetime, not again in later sessions
+    #[serde(default)]
+    pub completed_already_sent: bool,
 
     // === INTERNAL ===
     #[serde(skip)]
@@ -368,10 +891,60 @@ pub struct FakerStats {
     pub announce_count: u32,
 }
 
+/// Round `value` to `decimals` places (capped at 15) to drop floating-point noise.
+pub fn round_to_decimals(value: f64, decimals: u32) -> f64 {
+    if !value.is_finite() {
+        return value;
+    }
+    let factor = 10f64.powi(decimals.min(15).cast_signed());
+    (value * factor).round() / factor
+}
+
+impl FakerStats {
+    /// Round ratio, rate and progress fields to `decimals` places for API output.
+    pub fn round_floats(&mut self, decimals: u32) {
+        let round = |value: &mut f64| *value = round_to_decimals(*value, decimals);
+
+        for value in [
+            &mut self.ratio,
+            &mut self.torrent_completion,
+            &mut self.session_ratio,
+            &mut self.current_upload_rate,
+            &mut self.current_download_rate,
+            &mut self.average_upload_rate,
+            &mut self.average_download_rate,
+            &mut self.upload_progress,
+            &mut self.download_progress,
+            &mut self.ratio_progress,
+            &mut self.seed_time_progress,
+        ] {
+            round(value);
+        }
+        self.effective_stop_at_ratio.iter_mut().for_each(round);
+        self.estimated_bonus.iter_mut().for_each(round);
+        self.upload_rate_history.iter_mut().for_each(round);
+        self.download_rate_history.iter_mut().for_each(round);
+        self.ratio_history.iter_mut().for_each(round);
+    }
+
+    /// Drop the rate/ratio history vectors to keep API payloads small.
+    pub fn clear_history(&mut self) {
+        self.upload_rate_history = Vec::new();
+        self.download_rate_history = Vec::new();
+        self.ratio_history = Vec::new();
+        self.history_timestamps = Vec::new();
+    }
+}
+
 pub struct RatioFaker {
     torrent: Arc<TorrentInfo>,
     config: FakerConfig,
+    // Bytes of the selected files, which `left` and completion are measured against
+    wanted_size: u64,
     tracker_client: Arc<TrackerClient>,
+    trackers: Arc<TrackerTiers>,
+    // `config.bonus_formula`, parsed once instead of on every tick
+    bonus_formula: Option<BonusFormula>,
 
     // Runtime state
     stats: FakerStats,
@@ -385,16 +958,43 @@ pub struct RatioFaker {
     start_time: Instant,
     last_update: Instant,
     announce_interval: Duration,
+    min_announce_interval: Option<Duration>,
+    /// Failed announces in a row while running, driving the retry backoff
+    announce_failures: u32,
+    /// Whether the tracker accepted a `started` announce this session. Until it does,
+    /// every announce is sent as `started`, since some trackers reject others.
+    started_acknowledged: bool,
+    /// Last announce sent, filled when `record_last_announce` is set
+    last_announce: AnnounceRecorder,
+    /// Whether this session already warned about uploading more than a home connection could
+    implausible_upload_warned: bool,
 
     // Scrape
     last_scrape: Instant,
-    scrape_supported: bool,
+    scrape_consecutive_failures: u32,
+    /// Set after a failed scrape: the next attempt waits until then instead of the interval
+    scrape_retry_at: Option<Instant>,
+
+    // Per-instance multiplier applied to configured rates (fleet de-correlation)
+    rate_scale: f64,
+
+    // Server-wide throttle keeping the combined rate of all instances under a cap
+    upload_throttle: f64,
+    download_throttle: f64,
+
+    // Where each rate sits within the randomization range (-1.0 to 1.0), kept across
+    // updates for the smoothed mode
+    upload_noise: f64,
+    download_noise: f64,
+
+    clock: Arc<dyn Clock>,
 }
 
 #[derive(Debug, Clone, Copy)]
 struct UpdateOutcome {
     completed: bool,
-    stop: bool,
+    /// Name of the stop condition that was reached this tick, if any
+    stop: Option<&'static str>,
     scrape_due: bool,
     announce_due: bool,
 }
@@ -411,31 +1011,167 @@ struct TickInputs {
     config: FakerConfig,
 }
 
-const TRACKER_RETRY_SCHEDULE_SECS: [u64; 4] = [30, 60, 120, 300];
+/// First retry after a failed announce; each further failure doubles it, up to the
+/// tracker's announce interval and never beyond `ANNOUNCE_BACKOFF_MAX_SECS`.
+const ANNOUNCE_BACKOFF_BASE_SECS: u64 = 30;
+const ANNOUNCE_BACKOFF_MAX_SECS: u64 = 1800;
+
+/// Slower retries after the tracker answered with a `failure reason`, so private
+/// trackers are not hammered.
+const TRACKER_FAILURE_RETRY_SCHEDULE_SECS: [u64; 4] = [300, 900, 1800, 3600];
+
+/// Largest random move of a smoothed rate per update, as a fraction of its half-range.
+/// Set to sqrt(1 - (1 - pull)^2) so the walk settles to the same spread as uniform mode
+/// (a standard deviation of about 0.58 of the half-range) and covers the whole band,
+/// rather than hovering within about ±0.28 of it as a step of 0.15 would.
+const SMOOTHED_NOISE_STEP: f64 = 0.31;
+
+/// Share of a smoothed rate's offset given back each update, so it drifts back towards the
+/// base rate instead of sticking to an edge of the range
+const SMOOTHED_NOISE_PULL: f64 = 0.05;
+
+/// Retry delays after consecutive scrape failures. Once they are used up, scraping
+/// pauses for `SCRAPE_COOLDOWN_SECS` and peer counts come from announces only.
+const SCRAPE_RETRY_SCHEDULE_SECS: [u64; 3] = [30, 120, 600];
+const SCRAPE_COOLDOWN_SECS: u64 = 3600;
+
+/// Larger swarm counts than this are treated as tracker garbage.
+const MAX_PLAUSIBLE_PEER_COUNT: i64 = 10_000_000;
+
+/// Largest accepted `announce_jitter_secs`: one hour either way.
+const MAX_ANNOUNCE_JITTER_SECS: u64 = 3600;
+
+/// Largest accepted `history_length`: an hour of one-second updates.
+const MAX_HISTORY_LENGTH: usize = 3600;
+
+/// Announce interval a dry run pretends the tracker asked for.
+const DRY_RUN_ANNOUNCE_INTERVAL_SECS: i64 = 1800;
+
+/// Seeders and leechers a dry run reports: made up from the info hash, so every
+/// announce for a torrent sees the same swarm.
+fn simulated_swarm(info_hash: &[u8; 20]) -> (i64, i64) {
+    let seed = info_hash.iter().take(8).fold(0i64, |acc, byte| (acc << 8) | i64::from(*byte));
+    let seed = seed.rem_euclid(1 << 32);
+    (5 + seed % 60, 1 + (seed / 60) % 25)
+}
+
+/// The last announce sent to a tracker, as the tracker saw it
+#[derive(Debug, Clone)]
+pub struct LastAnnounce {
+    /// When it was sent, in Unix milliseconds
+    pub sent_at_ms: u64,
+    pub exchange: AnnounceExchange,
+}
+
+type AnnounceRecorder = Arc<std::sync::Mutex<Option<LastAnnounce>>>;
 
 struct AnnouncePlan {
     tracker_client: Arc<TrackerClient>,
-    tracker_url: String,
+    trackers: Arc<TrackerTiers>,
     request: AnnounceRequest,
+    /// Truncated v2 hash of a hybrid torrent, announced after each v1 announce when
+    /// `announce_v2_hash` is set
+    info_hash_v2: Option<[u8; 20]>,
+    dry_run: bool,
+    /// Where to keep each announce sent, when recording is on
+    recorder: Option<AnnounceRecorder>,
 }
 
 impl AnnouncePlan {
+    /// Announce to the active tracker, falling back through the announce-list tiers.
+    /// When every tracker fails, the first tracker's error is returned.
     async fn execute(&self) -> Result<AnnounceResponse> {
-        self.tracker_client
-            .announce(&self.tracker_url, &self.request)
-            .await
-            .map_err(FakerError::from)
+        if self.dry_run {
+            let (complete, incomplete) = simulated_swarm(&self.request.info_hash);
+            log_debug!("Dry run: not announcing {:?} to the tracker", self.request.event);
+            return Ok(AnnounceResponse {
+                interval: DRY_RUN_ANNOUNCE_INTERVAL_SECS,
+                min_interval: None,
+                tracker_id: None,
+                complete,
+                incomplete,
+                warning: None,
+            });
+        }
+
+        let mut first_error = None;
+        let mut urls = self.trackers.announce_order().into_iter().peekable();
+        while let Some(url) = urls.next() {
+            match self.announce(&url, &self.request).await {
+                Ok(response) => {
+                    self.trackers.mark_working(&url);
+                    self.announce_v2(&url).await;
+                    return Ok(response);
+                }
+                Err(e) => {
+                    if urls.peek().is_some() {
+                        log_warn!("Announce to {} failed, trying next tracker: {}", url, e);
+                    }
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        Err(first_error.map_or_else(
+            || FakerError::ConfigError("Torrent has no tracker URL".to_string()),
+            FakerError::from,
+        ))
     }
+
+    async fn announce(
+        &self,
+        url: &str,
+        request: &AnnounceRequest,
+    ) -> std::result::Result<AnnounceResponse, TrackerError> {
+        let Some(recorder) = &self.recorder else {
+            return self.tracker_client.announce(url, request).await;
+        };
+        let sent_at_ms = RatioFaker::current_timestamp_millis();
+        let (result, exchange) = self.tracker_client.announce_recorded(url, request).await;
+        let last = LastAnnounce { sent_at_ms, exchange };
+        *recorder.lock().unwrap_or_else(std::sync::PoisonError::into_inner) = Some(last);
+        result
+    }
+
+    /// Join the v2 swarm of a hybrid torrent on a tracker that accepted the v1 announce,
+    /// as BEP 52 clients do. The v1 swarm drives stats, so this answer is only logged.
+    async fn announce_v2(&self, url: &str) {
+        let Some(info_hash) = self.info_hash_v2 else {
+            return;
+        };
+        let request = AnnounceRequest { info_hash, ..self.request.clone() };
+        if let Err(e) = self.announce(url, &request).await {
+            log_warn!("Announce of the v2 info hash to {} failed: {}", url, e);
+        }
+    }
+}
+
+/// A due scrape: what a batched (multi-hash) scrape needs to include this torrent
+#[cfg(not(target_arch = "wasm32"))]
+pub struct ScrapeTarget {
+    pub tracker_client: Arc<TrackerClient>,
+    pub tracker_url: String,
+    pub info_hash: [u8; 20],
 }
 
 struct ScrapePlan {
     tracker_client: Arc<TrackerClient>,
     tracker_url: String,
     info_hash: [u8; 20],
+    dry_run: bool,
 }
 
 impl ScrapePlan {
     async fn execute(&self) -> Result<crate::protocol::ScrapeResponse> {
+        if self.dry_run {
+            let (complete, incomplete) = simulated_swarm(&self.info_hash);
+            return Ok(crate::protocol::ScrapeResponse {
+                complete,
+                incomplete,
+                downloaded: complete * 3,
+                name: None,
+            });
+        }
         self.tracker_client
             .scrape(&self.tracker_url, &self.info_hash)
             .await
@@ -456,23 +1192,295 @@ impl RatioFaker {
         }
     }
 
-    fn tracker_error_is_retryable(message: &str) -> bool {
-        message == "Tracker unavailable"
+    /// Bytes left at `completion_percent`, rounded to the nearest byte so that a
+    /// completion derived from `left` (and persisted) maps back to the same `left`.
+    fn left_for_completion(total_size: u64, completion_percent: f64) -> u64 {
+        let completion = completion_percent.clamp(0.0, 100.0) / 100.0;
+        let downloaded = (total_size as f64 * completion).round() as u64;
+        total_size.saturating_sub(downloaded)
     }
 
-    fn tracker_retry_delay_secs(attempt: u32) -> u64 {
-        let idx = attempt.saturating_sub(1) as usize;
-        TRACKER_RETRY_SCHEDULE_SECS
-            .get(idx)
-            .copied()
-            .unwrap_or_else(|| *TRACKER_RETRY_SCHEDULE_SECS.last().unwrap_or(&300))
-    }
+    fn completion_for_left(total_size: u64, left: u64) -> f64 {
+        if total_size > 0 {
+            (total_size.saturating_sub(left) as f64 / total_size as f64) * 100.0
+        } else {
+            100.0
+        }
+    }
+
+    /// Client identity for `config`, with the custom User-Agent applied when set.
+    fn client_config_for(config: &FakerConfig) -> Result<ClientConfig> {
+        let mut client_config =
+            ClientConfig::get(config.client_type, config.client_version.clone());
+        if let Some(agent) = config.custom_user_agent.as_deref() {
+            let agent = crate::validation::validate_user_agent(agent)
+                .map_err(|e| FakerError::ConfigError(e.to_string()))?;
+            client_config.user_agent = agent.to_string();
+        }
+        client_config.proxy_url = config
+            .proxy_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(str::to_string);
+        Ok(client_config)
+    }
+
+    /// Peer ID to announce with: the override when set, otherwise a fresh one for the client.
+    fn peer_id_for(config: &FakerConfig, client_config: &ClientConfig) -> Result<String> {
+        config.peer_id_override.as_deref().map_or_else(
+            || Ok(client_config.generate_peer_id()),
+            |peer_id| {
+                crate::validation::validate_peer_id(peer_id)
+                    .map(str::to_string)
+                    .map_err(|e| FakerError::ConfigError(e.to_string()))
+            },
+        )
+    }
+
+    /// Reject `config` for the same reasons `new` and `update_config` would, without
+    /// needing a torrent.
+    pub fn validate_config(config: &FakerConfig) -> Result<()> {
+        let client_config = Self::client_config_for(config)?;
+        Self::peer_id_for(config, &client_config)?;
+        Self::parse_bonus_formula(config)?;
+        Self::validate_announce_interval_bounds(config)?;
+        Self::validate_rate_schedules(config)?;
+        Self::validate_upload_before_complete_ratio(config)?;
+        Self::validate_max_plausible_upload(config)?;
+        Self::validate_history_length(config)
+    }
+
+    fn validate_history_length(config: &FakerConfig) -> Result<()> {
+        if config.history_length > MAX_HISTORY_LENGTH {
+            return Err(FakerError::ConfigError(format!(
+                "history_length must be at most {MAX_HISTORY_LENGTH} (got {})",
+                config.history_length
+            )));
+        }
+        Ok(())
+    }
+
+    fn parse_bonus_formula(config: &FakerConfig) -> Result<Option<BonusFormula>> {
+        config
+            .bonus_formula
+            .as_deref()
+            .map(|formula| {
+                BonusFormula::parse(formula).map_err(|e| FakerError::ConfigError(e.to_string()))
+            })
+            .transpose()
+    }
+
+    fn validate_announce_interval_bounds(config: &FakerConfig) -> Result<()> {
+        if config.max_announce_interval == Some(0) {
+            return Err(FakerError::ConfigError(
+                "max_announce_interval must be greater than 0".to_string(),
+            ));
+        }
+        if config.announce_jitter_secs > MAX_ANNOUNCE_JITTER_SECS {
+            return Err(FakerError::ConfigError(format!(
+                "announce_jitter_secs must be at most {MAX_ANNOUNCE_JITTER_SECS} (got {})",
+                config.announce_jitter_secs
+            )));
+        }
+        if let (Some(min), Some(max)) = (config.min_announce_interval, config.max_announce_interval)
+        {
+            if min > max {
+                return Err(FakerError::ConfigError(format!(
+                    "min_announce_interval ({min}s) exceeds max_announce_interval ({max}s)"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_rate_schedules(config: &FakerConfig) -> Result<()> {
+        for schedule in &config.rate_schedules {
+            schedule.validate().map_err(FakerError::ConfigError)?;
+        }
+        Ok(())
+    }
+
+    /// Size of the selected files, or of the whole torrent when nothing is selected
+    fn wanted_size(torrent: &TorrentInfo, config: &FakerConfig) -> Result<u64> {
+        let Some(indices) = config.selected_files.as_deref() else {
+            return Ok(torrent.total_size);
+        };
+        if indices.is_empty() {
+            return Err(FakerError::ConfigError("selected_files must not be empty".into()));
+        }
+        torrent.selected_size(indices).ok_or_else(|| {
+            FakerError::ConfigError(format!(
+                "selected_files must be indices below the torrent's {} files",
+                torrent.file_lengths.len()
+            ))
+        })
+    }
+
+    fn validate_upload_before_complete_ratio(config: &FakerConfig) -> Result<()> {
+        match config.max_upload_before_complete_ratio {
+            Some(ratio) if !ratio.is_finite() || ratio < 0.0 => {
+                Err(FakerError::ConfigError(format!(
+                    "max_upload_before_complete_ratio must be finite and not negative (got {ratio})"
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn validate_max_plausible_upload(config: &FakerConfig) -> Result<()> {
+        let ceiling = config.max_plausible_upload_kbps;
+        if ceiling.is_finite() && ceiling >= 0.0 {
+            Ok(())
+        } else {
+            Err(FakerError::ConfigError(format!(
+                "max_plausible_upload_kbps must be finite and not negative (got {ceiling})"
+            )))
+        }
+    }
+
+    /// A warning when the configured upload rate is more than a home connection could
+    /// sustain, put in terms of how many copies of the torrent that uploads per hour.
+    fn implausible_upload_warning(config: &FakerConfig, torrent_size: u64) -> Option<String> {
+        let rate = config.upload_rate.max(config.target_upload_rate.unwrap_or(0.0));
+        if rate <= REALISTIC_HOME_UPLOAD_KBPS {
+            return None;
+        }
+        let copies_per_hour = rate * 1024.0 * 3600.0 / torrent_size.max(1) as f64;
+        Some(format!(
+            "Upload rate {rate:.0} KB/s is more than a home connection uploads (about \
+             {REALISTIC_HOME_UPLOAD_KBPS:.0} KB/s): {copies_per_hour:.0} copies of this \
+             torrent per hour, which trackers can flag as cheating"
+        ))
+    }
+
+    /// A warning once this session has uploaded more than a home connection could in the
+    /// time it has run, put in terms of copies of the torrent. The first minute is left
+    /// alone, as a burst that short says little.
+    fn implausible_session_warning(stats: &FakerStats, torrent_size: u64) -> Option<String> {
+        let elapsed_secs = stats.elapsed_time.as_secs_f64();
+        let uploaded = stats.session_uploaded as f64;
+        if elapsed_secs < 60.0 || uploaded <= REALISTIC_HOME_UPLOAD_KBPS * 1024.0 * elapsed_secs {
+            return None;
+        }
+        let gib = uploaded / (1024.0 * 1024.0 * 1024.0);
+        let minutes = elapsed_secs / 60.0;
+        let copies = uploaded / torrent_size.max(1) as f64;
+        Some(format!(
+            "Uploaded {gib:.1} GiB in {minutes:.0} min, more than a home connection could \
+             (about {REALISTIC_HOME_UPLOAD_KBPS:.0} KB/s): {copies:.0} copies of this torrent, \
+             which trackers can flag as cheating"
+        ))
+    }
+
+    /// Clamp the upload rate to `max_plausible_upload_kbps`.
+    fn clamp_to_plausible_upload(config: &FakerConfig, upload_rate: f64) -> f64 {
+        let ceiling = config.max_plausible_upload_kbps;
+        if ceiling > 0.0 && upload_rate > ceiling {
+            log_debug!("Clamping upload rate {:.0} KB/s to {:.0} KB/s", upload_rate, ceiling);
+            ceiling
+        } else {
+            upload_rate
+        }
+    }
+
+    fn estimate_bonus(
+        formula: Option<&BonusFormula>,
+        stats: &FakerStats,
+        torrent_size: u64,
+    ) -> Option<f64> {
+        let formula = formula?;
+        let inputs = BonusInputs {
+            size_gib: torrent_size as f64 / (1024.0 * 1024.0 * 1024.0),
+            seeders: stats.seeders.max(0) as f64,
+            seed_time_hours: stats.elapsed_time.as_secs_f64() / 3600.0,
+        };
+        Some(formula.evaluate(&inputs)).filter(|bonus| bonus.is_finite())
+    }
+
+    /// Clamp a tracker-reported peer count to a sane range before displaying it.
+    fn sanitize_peer_count(value: i64, field: &str) -> i64 {
+        if value < 0 {
+            log_warn!("Tracker reported negative {} count ({}), using 0", field, value);
+            0
+        } else if value > MAX_PLAUSIBLE_PEER_COUNT {
+            log_warn!(
+                "Tracker reported implausible {} count ({}), capping at {}",
+                field,
+                value,
+                MAX_PLAUSIBLE_PEER_COUNT
+            );
+            MAX_PLAUSIBLE_PEER_COUNT
+        } else {
+            value
+        }
+    }
+
+    fn set_swarm_counts(&mut self, complete: i64, incomplete: i64) {
+        self.stats.seeders = Self::sanitize_peer_count(complete, "seeder");
+        self.stats.leechers = Self::sanitize_peer_count(incomplete, "leecher");
+    }
+
+    /// Record swarm counts and the tracker's warning from a successful announce.
+    fn apply_announce_response(&mut self, response: &AnnounceResponse) {
+        self.set_swarm_counts(response.complete, response.incomplete);
+        self.stats.tracker_warning.clone_from(&response.warning);
+    }
+
+    fn tracker_error_is_retryable(&self, message: &str) -> bool {
+        message == "Tracker unavailable"
+            || (self.config.retry_ipv6_only_trackers && message == IPV6_ONLY_TRACKER_ERROR)
+    }
+
+    fn tracker_retry_delay_secs(
+        attempt: u32,
+        tracker_failure: bool,
+        announce_interval: Duration,
+    ) -> u64 {
+        if !tracker_failure {
+            return Self::announce_backoff_secs(attempt, announce_interval);
+        }
+        let schedule = &TRACKER_FAILURE_RETRY_SCHEDULE_SECS;
+        let idx = attempt.saturating_sub(1) as usize;
+        schedule.get(idx).copied().unwrap_or_else(|| *schedule.last().unwrap_or(&3600))
+    }
+
+    fn announce_backoff_secs(failures: u32, announce_interval: Duration) -> u64 {
+        let cap = announce_interval
+            .as_secs()
+            .clamp(ANNOUNCE_BACKOFF_BASE_SECS, ANNOUNCE_BACKOFF_MAX_SECS);
+        let doublings = failures.saturating_sub(1).min(16);
+        (ANNOUNCE_BACKOFF_BASE_SECS << doublings).min(cap)
+    }
+
+    /// Record a failed announce and schedule the retry with exponential backoff.
+    fn schedule_announce_retry(&mut self) -> u64 {
+        self.announce_failures = self.announce_failures.saturating_add(1);
+        let delay_secs =
+            Self::announce_backoff_secs(self.announce_failures, self.announce_interval);
+        self.stats.next_announce = Some(self.clock.now() + Duration::from_secs(delay_secs));
+        delay_secs
+    }
+
+    fn scrape_retry_delay_secs(failures: u32) -> u64 {
+        let idx = failures.saturating_sub(1) as usize;
+        SCRAPE_RETRY_SCHEDULE_SECS.get(idx).copied().unwrap_or(SCRAPE_COOLDOWN_SECS)
+    }
 
     fn arm_tracker_retry(&mut self) {
         self.stats.tracker_retry_attempt = self.stats.tracker_retry_attempt.saturating_add(1);
-        let delay_secs = Self::tracker_retry_delay_secs(self.stats.tracker_retry_attempt);
+        let delay_secs = Self::tracker_retry_delay_secs(
+            self.stats.tracker_retry_attempt,
+            self.stats.tracker_failure_reason.is_some(),
+            self.announce_interval,
+        );
         self.stats.tracker_retry_at_ms =
             Some(Self::current_timestamp_millis().saturating_add(delay_secs.saturating_mul(1000)));
+        log_info!(
+            "Tracker retry {} scheduled in {}s",
+            self.stats.tracker_retry_attempt,
+            delay_secs
+        );
     }
 
     const fn clear_tracker_retry(&mut self) {
@@ -482,7 +1490,11 @@ impl RatioFaker {
 
     fn seed_tracker_retry_if_needed(&mut self) {
         if self.stats.tracker_retry_at_ms.is_none()
-            && self.stats.tracker_error.as_deref().is_some_and(Self::tracker_error_is_retryable)
+            && self
+                .stats
+                .tracker_error
+                .as_deref()
+                .is_some_and(|message| self.tracker_error_is_retryable(message))
         {
             self.arm_tracker_retry();
         }
@@ -501,6 +1513,7 @@ impl RatioFaker {
                     "Tracker unavailable"
                 }
             }
+            TrackerError::Ipv6Unreachable(_) => IPV6_ONLY_TRACKER_ERROR,
             TrackerError::HttpError(_)
             | TrackerError::BencodeError(_)
             | TrackerError::UrlError(_) => "Tracker unavailable",
@@ -519,14 +1532,25 @@ impl RatioFaker {
             || message.contains("torrent does not exist")
     }
 
+    /// Tracker failures that will not go away on retry: the torrent is unknown to the
+    /// tracker or the announce URL's credentials are rejected.
+    fn is_fatal_tracker_failure(reason: &str) -> bool {
+        let lower = reason.to_ascii_lowercase();
+        Self::is_missing_torrent_message(reason)
+            || lower.contains("passkey")
+            || lower.contains("not authorized")
+            || lower.contains("unauthorized")
+    }
+
     fn mark_tracker_invalid(&mut self, message: &str) {
         self.stats.tracker_error = Some(message.to_string());
-        if Self::tracker_error_is_retryable(message) {
+        if self.tracker_error_is_retryable(message) {
             self.arm_tracker_retry();
         } else {
             self.clear_tracker_retry();
         }
         self.stats.state = FakerState::Stopped;
+        self.stats.stop_reason = Some(StopReason::Error { msg: message.to_string() });
         self.stats.is_idling = false;
         self.stats.idling_reason = None;
         self.stats.current_upload_rate = 0.0;
@@ -538,6 +1562,13 @@ impl RatioFaker {
 
     fn apply_tracker_error(&mut self, error: &FakerError) {
         if let FakerError::TrackerError(tracker_error) = error {
+            self.stats.tracker_failure_reason = match tracker_error {
+                TrackerError::TrackerFailure(reason) => {
+                    log_warn!("Tracker failure reason: {}", reason);
+                    Some(reason.clone())
+                }
+                _ => None,
+            };
             let message = Self::tracker_error_message(tracker_error);
             self.mark_tracker_invalid(&message);
         }
@@ -545,6 +1576,7 @@ impl RatioFaker {
 
     fn clear_tracker_error(&mut self) {
         self.stats.tracker_error = None;
+        self.stats.tracker_failure_reason = None;
         self.clear_tracker_retry();
     }
 
@@ -586,6 +1618,18 @@ impl RatioFaker {
         config: FakerConfig,
         http_client: Option<reqwest::Client>,
     ) -> Result<Self> {
+        Self::with_clock(torrent, config, http_client, Arc::new(SystemClock))
+    }
+
+    /// Like [`RatioFaker::new`], reading time from `clock` instead of the system clock.
+    pub fn with_clock(
+        torrent: Arc<TorrentInfo>,
+        config: FakerConfig,
+        http_client: Option<reqwest::Client>,
+        clock: Arc<dyn Clock>,
+    ) -> Result<Self> {
+        let mut config = config;
+        config.apply_seed_only();
         log_debug!(
             "Creating RatioFaker for '{}' (size: {} bytes)",
             torrent.name,
@@ -599,10 +1643,15 @@ impl RatioFaker {
         );
 
         // Create client configuration
-        let client_config = ClientConfig::get(config.client_type, config.client_version.clone());
+        Self::validate_config(&config)?;
+        let client_config = Self::client_config_for(&config)?;
+        let bonus_formula = Self::parse_bonus_formula(&config)?;
+        if let Some(warning) = Self::implausible_upload_warning(&config, torrent.total_size) {
+            log_warn!("{}", warning);
+        }
 
         // Generate session identifiers
-        let peer_id = client_config.generate_peer_id();
+        let peer_id = Self::peer_id_for(&config, &client_config)?;
         let key = ClientConfig::generate_key();
 
         log_trace!("Generated peer_id: {}, key: {}", peer_id, key);
@@ -611,13 +1660,11 @@ impl RatioFaker {
         let tracker_client = TrackerClient::new(client_config, http_client)
             .map_err(|e| FakerError::ConfigError(e.to_string()))?;
 
-        let mut config = config;
         Self::resolve_stop_ratio(&mut config);
 
-        // Calculate how much of THIS torrent is already downloaded
-        let completion = config.completion_percent.clamp(0.0, 100.0) / 100.0;
-        let torrent_downloaded = (torrent.total_size as f64 * completion) as u64;
-        let left = torrent.total_size.saturating_sub(torrent_downloaded);
+        // Calculate how much of THIS torrent (or its selected files) is already downloaded
+        let wanted_size = Self::wanted_size(&torrent, &config)?;
+        let left = Self::left_for_completion(wanted_size, config.completion_percent);
 
         let stats = FakerStats {
             // Cumulative stats from previous sessions
@@ -631,11 +1678,7 @@ impl RatioFaker {
 
             // Torrent state
             left,
-            torrent_completion: if torrent.total_size > 0 {
-                ((torrent.total_size - left) as f64 / torrent.total_size as f64) * 100.0
-            } else {
-                100.0
-            },
+            torrent_completion: Self::completion_for_left(wanted_size, left),
             seeders: 0,
             leechers: 0,
             state: FakerState::Stopped,
@@ -646,6 +1689,8 @@ impl RatioFaker {
             tracker_error: None,
             tracker_retry_attempt: 0,
             tracker_retry_at_ms: None,
+            tracker_failure_reason: None,
+            tracker_warning: None,
 
             // Session stats (starts fresh at 0)
             session_uploaded: 0,
@@ -687,21 +1732,44 @@ impl RatioFaker {
 
             stop_condition_met: false,
             post_stop_action: config.post_stop_action,
+            stop_reason: None,
+            estimated_bonus: None,
+            hnr_protected: false,
+            active_tracker: None,
+            completed_already_sent: false,
         };
 
+        let trackers = Arc::new(TrackerTiers::new(torrent.tracker_tiers()));
+
+        let now = clock.now();
         Ok(Self {
             torrent,
             config,
+            wanted_size,
             tracker_client: Arc::new(tracker_client),
+            trackers,
+            bonus_formula,
             stats,
             peer_id,
             key,
             tracker_id: None,
-            start_time: Instant::now(),
-            last_update: Instant::now(),
+            start_time: now,
+            last_update: now,
             announce_interval: Duration::from_mins(30), // Default 30 minutes
-            last_scrape: Instant::now(),
-            scrape_supported: true,
+            min_announce_interval: None,
+            announce_failures: 0,
+            started_acknowledged: false,
+            last_announce: AnnounceRecorder::default(),
+            last_scrape: now,
+            scrape_consecutive_failures: 0,
+            scrape_retry_at: None,
+            rate_scale: 1.0,
+            upload_throttle: 1.0,
+            download_throttle: 1.0,
+            upload_noise: 0.0,
+            download_noise: 0.0,
+            implausible_upload_warned: false,
+            clock,
         })
     }
 
@@ -720,18 +1788,40 @@ impl RatioFaker {
         }
 
         log_info!("Starting ratio faker for torrent: {}", self.torrent.name);
+        if self.config.dry_run {
+            log_info!("Dry run: {} will not contact its tracker", self.torrent.name);
+        }
+        if self.torrent.is_private {
+            log_warn!(
+                "{} is a private torrent: announcing only to the trackers listed in it",
+                self.torrent.name
+            );
+        }
 
+        if self.config.regenerate_identity_on_start {
+            self.regenerate_identity();
+        }
         self.reset_session_state_for_start(true);
-        self.start_time = Instant::now();
-        self.last_update = Instant::now();
+        let now = self.clock.now();
+        self.start_time = now;
+        self.last_update = now;
 
-        let request = self.build_announce_request(TrackerEvent::Started);
+        Some(self.announce_plan(TrackerEvent::Started))
+    }
 
-        Some(AnnouncePlan {
-            tracker_client: Arc::clone(&self.tracker_client),
-            tracker_url: self.torrent.get_tracker_url().to_string(),
-            request,
-        })
+    /// New peer ID and key for the emulated client. The tracker ID belonged to the
+    /// old peer, so it is dropped as well. A peer ID override is kept as is.
+    fn regenerate_identity(&mut self) {
+        let client_config =
+            ClientConfig::get(self.config.client_type, self.config.client_version.clone());
+        if let Some(peer_id) = self.config.peer_id_override.clone() {
+            self.peer_id = peer_id;
+        } else {
+            self.peer_id = client_config.generate_peer_id();
+        }
+        self.key = ClientConfig::generate_key();
+        self.tracker_id = None;
+        log_trace!("Regenerated peer_id: {}, key: {}", self.peer_id, self.key);
     }
 
     fn reset_session_state_for_start(&mut self, clear_tracker_retry: bool) {
@@ -762,6 +1852,11 @@ impl RatioFaker {
         self.stats.is_idling = false;
         self.stats.idling_reason = None;
         self.stats.tracker_error = None;
+        self.stats.tracker_failure_reason = None;
+        self.stats.stop_reason = None;
+        self.announce_failures = 0;
+        self.started_acknowledged = false;
+        self.implausible_upload_warned = false;
         if clear_tracker_retry {
             self.clear_tracker_retry();
         }
@@ -774,7 +1869,7 @@ impl RatioFaker {
     }
 
     pub fn restore_runtime(&mut self, mut stats: FakerStats) {
-        let now = Instant::now();
+        let now = self.clock.now();
 
         stats.post_stop_action = self.config.post_stop_action;
         stats.last_announce = None;
@@ -789,36 +1884,38 @@ impl RatioFaker {
         self.rebase_timers_from_elapsed(now);
         self.tracker_id = None;
         self.announce_interval = Duration::from_mins(30);
+        self.min_announce_interval = None;
+        self.announce_failures = 0;
+        self.started_acknowledged = false;
         self.last_scrape = now;
-        self.scrape_supported = true;
+        self.scrape_consecutive_failures = 0;
+        self.scrape_retry_at = None;
     }
 
     #[cfg(not(target_arch = "wasm32"))]
     fn begin_restore_running(&mut self) -> AnnouncePlan {
         log_info!("Restoring active ratio faker for torrent: {}", self.torrent.name);
 
-        self.rebase_timers_from_elapsed(Instant::now());
+        self.rebase_timers_from_elapsed(self.clock.now());
         self.stats.state = FakerState::Running;
+        self.started_acknowledged = false;
 
-        AnnouncePlan {
-            tracker_client: Arc::clone(&self.tracker_client),
-            tracker_url: self.torrent.get_tracker_url().to_string(),
-            request: self.build_announce_request(TrackerEvent::Started),
-        }
+        self.announce_plan(TrackerEvent::Started)
     }
 
     fn apply_start_result(&mut self, result: Result<AnnounceResponse>) {
         match result {
             Ok(response) => {
                 self.clear_tracker_error();
-                self.announce_interval = Duration::from_secs(response.interval as u64);
+                self.set_announce_intervals(&response);
+                self.apply_announce_response(&response);
                 self.tracker_id = response.tracker_id;
-
-                self.stats.seeders = response.complete;
-                self.stats.leechers = response.incomplete;
-                self.stats.last_announce = Some(Instant::now());
-                self.stats.next_announce = Some(Instant::now() + self.announce_interval);
+                let now = self.clock.now();
+                self.stats.last_announce = Some(now);
+                self.stats.next_announce = Some(now + self.jittered_announce_delay());
                 self.stats.announce_count += 1;
+                self.announce_failures = 0;
+                self.started_acknowledged = true;
 
                 log_info!(
                     "Started successfully. Seeders: {}, Leechers: {}, Interval: {}s",
@@ -832,8 +1929,8 @@ impl RatioFaker {
                 if matches!(self.stats.state, FakerState::Stopped) {
                     log_warn!("Initial announce failed, stopping faker: {}", e);
                 } else {
-                    log_warn!("Initial announce failed, will retry on next cycle: {}", e);
-                    self.stats.next_announce = Some(Instant::now() + Duration::from_secs(30));
+                    let delay_secs = self.schedule_announce_retry();
+                    log_warn!("Initial announce failed, retrying in {}s: {}", delay_secs, e);
                 }
             }
         }
@@ -845,14 +1942,19 @@ impl RatioFaker {
 
     /// Stop the ratio faking session
     pub async fn stop(&mut self) -> Result<()> {
-        if let Some(plan) = self.begin_stop() {
+        self.stop_with_reason(StopReason::UserRequested).await
+    }
+
+    /// Stop the ratio faking session, recording why it was stopped
+    pub async fn stop_with_reason(&mut self, reason: StopReason) -> Result<()> {
+        if let Some(plan) = self.begin_stop(reason) {
             let result = plan.execute().await;
             self.apply_stop_result(result);
         }
         Ok(())
     }
 
-    fn begin_stop(&mut self) -> Option<AnnouncePlan> {
+    fn begin_stop(&mut self, reason: StopReason) -> Option<AnnouncePlan> {
         if matches!(self.stats.state, FakerState::Stopped) {
             log_debug!("Already stopped, skipping stop");
             return None;
@@ -861,12 +1963,9 @@ impl RatioFaker {
         log_info!("Stopping ratio faker");
 
         self.stats.state = FakerState::Stopping;
+        self.stats.stop_reason = Some(reason);
 
-        Some(AnnouncePlan {
-            tracker_client: Arc::clone(&self.tracker_client),
-            tracker_url: self.torrent.get_tracker_url().to_string(),
-            request: self.build_announce_request(TrackerEvent::Stopped),
-        })
+        Some(self.announce_plan(TrackerEvent::Stopped))
     }
 
     fn apply_stop_result(&mut self, result: Result<AnnounceResponse>) {
@@ -887,7 +1986,7 @@ impl RatioFaker {
         self.stats.current_download_rate = 0.0;
     }
 
-    async fn apply_post_stop_action(&mut self) -> Result<()> {
+    async fn apply_post_stop_action(&mut self, which: &str) -> Result<()> {
         self.stats.stop_condition_met = true;
         match self.config.post_stop_action {
             PostStopAction::Idle => {
@@ -899,7 +1998,8 @@ impl RatioFaker {
             }
             PostStopAction::StopSeeding | PostStopAction::DeleteInstance => {
                 log_info!("Stop condition met, stopping faker");
-                self.stop().await?;
+                self.stop_with_reason(StopReason::StopConditionMet { which: which.to_string() })
+                    .await?;
             }
         }
         Ok(())
@@ -907,41 +2007,29 @@ impl RatioFaker {
 
     /// Update the fake stats (call this periodically)
     pub async fn update(&mut self) -> Result<()> {
-        let now = Instant::now();
+        let now = self.clock.now();
         let outcome = self.tick(now);
 
-        if outcome.completed {
-            let plan = AnnouncePlan {
-                tracker_client: Arc::clone(&self.tracker_client),
-                tracker_url: self.torrent.get_tracker_url().to_string(),
-                request: self.build_announce_request(TrackerEvent::Completed),
-            };
-            match plan.execute().await {
-                Ok(response) => {
-                    self.stats.seeders = response.complete;
-                    self.stats.leechers = response.incomplete;
-                    self.stats.announce_count += 1;
-                }
-                Err(e) => {
-                    log_warn!("Completion announce failed, continuing: {}", e);
-                }
-            }
+        if let Some(plan) = self.completion_announce_plan(outcome.completed) {
+            let result = plan.execute().await;
+            self.apply_completion_result(result);
         }
 
+        let mut announce_due = outcome.announce_due;
         if outcome.scrape_due {
             let plan = self.build_scrape_plan();
             let result = plan.execute().await;
-            self.apply_scrape_result(&result, now);
+            announce_due |= self.apply_scrape_result(&result, now);
         }
 
-        if outcome.announce_due {
+        if announce_due {
             let plan = self.build_periodic_announce_plan();
             let result = plan.execute().await;
             self.apply_periodic_announce_result(result);
         }
 
-        if outcome.stop {
-            self.apply_post_stop_action().await?;
+        if let Some(which) = outcome.stop {
+            self.apply_post_stop_action(which).await?;
         }
 
         Ok(())
@@ -951,32 +2039,44 @@ impl RatioFaker {
         let elapsed = now.duration_since(self.last_update);
         self.last_update = now;
 
+        // Paused time is not seeding time: elapsed time and averages stay frozen until
+        // `resume` rebases the timers, even if a frontend keeps polling meanwhile
+        if matches!(self.stats.state, FakerState::Paused) {
+            return UpdateOutcome {
+                completed: false,
+                stop: None,
+                scrape_due: false,
+                announce_due: false,
+            };
+        }
+
         if self.stats.tracker_error.is_some() {
             self.stats.current_upload_rate = 0.0;
             self.stats.current_download_rate = 0.0;
             return UpdateOutcome {
                 completed: false,
-                stop: false,
+                stop: None,
                 scrape_due: false,
                 announce_due: false,
             };
         }
 
-        if self.check_stop_conditions(&self.stats) {
+        if let Some(which) = self.check_stop_conditions(&self.stats) {
             self.stats.current_upload_rate = 0.0;
             self.stats.current_download_rate = 0.0;
             return UpdateOutcome {
                 completed: false,
-                stop: true,
+                stop: Some(which),
                 scrape_due: false,
                 announce_due: false,
             };
         }
 
-        let inputs = self.build_tick_inputs(elapsed);
-        let (base_upload_rate, base_download_rate) = self.calc_base_rates(&inputs);
+        let inputs = self.build_tick_inputs(elapsed, now);
+        let (base_upload_rate, base_download_rate) = Self::calc_base_rates(&inputs);
         let (upload_rate, download_rate) =
             self.apply_randomized_rates(base_upload_rate, base_download_rate, inputs.left);
+        let upload_rate = Self::clamp_to_plausible_upload(&inputs.config, upload_rate);
         let (mut upload_rate, mut download_rate, is_idling, idling_reason) =
             Self::apply_idling_rules(&inputs, upload_rate, download_rate);
 
@@ -993,6 +2093,15 @@ impl RatioFaker {
 
         self.stats.is_idling = is_idling;
         self.stats.idling_reason = idling_reason;
+        self.pull_idle_announce_forward();
+
+        let upload_rate = Self::cap_upload_before_complete(
+            &self.stats,
+            &inputs.config,
+            upload_rate,
+            download_rate,
+            inputs.elapsed,
+        );
 
         let completed = Self::apply_rate_and_transfer_updates(
             &mut self.stats,
@@ -1002,13 +2111,25 @@ impl RatioFaker {
         );
 
         Self::apply_derived_updates(&mut self.stats, now, &inputs);
+        self.stats.estimated_bonus =
+            Self::estimate_bonus(self.bonus_formula.as_ref(), &self.stats, inputs.torrent_size);
+        if !self.implausible_upload_warned {
+            if let Some(warning) =
+                Self::implausible_session_warning(&self.stats, inputs.torrent_size)
+            {
+                log_warn!("{}", warning);
+                self.implausible_upload_warned = true;
+            }
+        }
 
         self.compute_tick_outcome(&self.stats, now, &inputs, completed)
     }
 
-    fn build_tick_inputs(&self, elapsed: Duration) -> TickInputs {
+    /// Progressive rates interpolate on the time since start as of `now`, not as of the
+    /// previous tick, so the rate does not depend on how often a frontend polls.
+    fn build_tick_inputs(&self, elapsed: Duration, now: Instant) -> TickInputs {
         let stats = &self.stats;
-        let elapsed_secs = stats.elapsed_time.as_secs();
+        let elapsed_secs = now.duration_since(self.start_time).as_secs();
 
         TickInputs {
             elapsed,
@@ -1017,49 +2138,64 @@ impl RatioFaker {
             seeders: stats.seeders,
             leechers: stats.leechers,
             announce_count: stats.announce_count,
-            torrent_size: self.torrent.total_size,
+            torrent_size: self.wanted_size,
             start_time: self.start_time,
             config: self.config.clone(),
         }
     }
 
-    fn calc_base_rates(&self, inputs: &TickInputs) -> (f64, f64) {
+    /// Base rates before randomization. An active rate schedule window overrides the
+    /// configured (or progressive) rate it sets.
+    fn calc_base_rates(inputs: &TickInputs) -> (f64, f64) {
         let config = &inputs.config;
+        let window = active_rate_schedule(
+            &config.rate_schedules,
+            minute_of_day(Self::current_timestamp_millis(), config.rate_schedule_utc_offset),
+        );
 
-        let base_upload_rate = if config.progressive_rates {
-            self.calculate_progressive_rate(
+        let base_upload_rate = match window.and_then(|w| w.upload_rate) {
+            Some(rate) => rate,
+            None if config.progressive_rates => Self::calculate_progressive_rate(
                 config.upload_rate,
                 config.target_upload_rate.unwrap_or(config.upload_rate),
                 inputs.elapsed_secs,
                 config.progressive_duration,
-            )
-        } else {
-            config.upload_rate
+                config.progressive_curve,
+            ),
+            None => config.upload_rate,
         };
 
-        let base_download_rate = if config.progressive_rates {
-            self.calculate_progressive_rate(
+        let base_download_rate = match window.and_then(|w| w.download_rate) {
+            Some(rate) => rate,
+            None if config.progressive_rates => Self::calculate_progressive_rate(
                 config.download_rate,
                 config.target_download_rate.unwrap_or(config.download_rate),
                 inputs.elapsed_secs,
                 config.progressive_duration,
-            )
-        } else {
-            config.download_rate
+                config.progressive_curve,
+            ),
+            None => config.download_rate,
         };
 
         (base_upload_rate, base_download_rate)
     }
 
     fn apply_randomized_rates(
-        &self,
+        &mut self,
         base_upload_rate: f64,
         base_download_rate: f64,
         left: u64,
     ) -> (f64, f64) {
-        let upload_rate = self.apply_randomization(base_upload_rate);
-        let download_rate =
-            if left == 0 { 0.0 } else { self.apply_randomization(base_download_rate) };
+        self.upload_noise = self.next_rate_noise(self.upload_noise);
+        let upload_rate =
+            self.apply_randomization(base_upload_rate, self.upload_noise) * self.upload_throttle;
+        let download_rate = if left == 0 {
+            0.0
+        } else {
+            self.download_noise = self.next_rate_noise(self.download_noise);
+            self.apply_randomization(base_download_rate, self.download_noise)
+                * self.download_throttle
+        };
 
         (upload_rate, download_rate)
     }
@@ -1108,6 +2244,36 @@ impl RatioFaker {
         (upload, download, is_idling, idling_reason)
     }
 
+    /// Keep an incomplete torrent from uploading more than a real leecher could:
+    /// nothing with `seed_only_after_complete`, else at most
+    /// `max_upload_before_complete_ratio` times what the session downloaded so far.
+    fn cap_upload_before_complete(
+        stats: &FakerStats,
+        config: &FakerConfig,
+        upload_rate: f64,
+        download_rate: f64,
+        elapsed: Duration,
+    ) -> f64 {
+        if stats.left == 0 {
+            return upload_rate;
+        }
+        if config.seed_only_after_complete {
+            return 0.0;
+        }
+        let Some(ratio) = config.max_upload_before_complete_ratio else {
+            return upload_rate;
+        };
+
+        let secs = elapsed.as_secs_f64();
+        if secs <= 0.0 {
+            return upload_rate;
+        }
+        let tick_download = (download_rate * 1024.0 * secs).min(stats.left as f64);
+        let downloaded_by_tick_end = stats.session_downloaded as f64 + tick_download;
+        let budget = downloaded_by_tick_end.mul_add(ratio, -(stats.session_uploaded as f64));
+        upload_rate.min((budget / 1024.0 / secs).max(0.0))
+    }
+
     fn apply_rate_and_transfer_updates(
         stats: &mut FakerStats,
         upload_rate: f64,
@@ -1138,6 +2304,7 @@ impl RatioFaker {
             &inputs.config,
             inputs.start_time,
         );
+        Self::record_history(stats, &inputs.config, Self::current_timestamp_millis());
     }
 
     fn compute_tick_outcome(
@@ -1149,51 +2316,150 @@ impl RatioFaker {
     ) -> UpdateOutcome {
         let stop = self.check_stop_conditions(stats);
 
-        let scrape_due = self.scrape_supported
-            && now.duration_since(self.last_scrape).as_secs() >= inputs.config.scrape_interval;
+        let scrape_due = self.scrape_due(&inputs.config, now);
 
         let announce_due = stats.next_announce.is_some_and(|next_announce| now >= next_announce);
 
         UpdateOutcome { completed, stop, scrape_due, announce_due }
     }
 
+    /// The regular announce, sent as `started` while the tracker has not accepted one
+    /// yet (e.g. after a failed start), whatever the timer says.
     fn build_periodic_announce_plan(&self) -> AnnouncePlan {
+        if self.started_acknowledged {
+            self.announce_plan(TrackerEvent::None)
+        } else {
+            log_debug!("Tracker has not accepted a started announce yet, sending started");
+            self.announce_plan(TrackerEvent::Started)
+        }
+    }
+
+    /// The `completed` announce to send when the download finished this tick, unless
+    /// one was already sent for this torrent
+    fn completion_announce_plan(&self, completed: bool) -> Option<AnnouncePlan> {
+        if !completed {
+            return None;
+        }
+        if self.stats.completed_already_sent {
+            log_debug!("Download finished again, completion was already announced");
+            return None;
+        }
+        Some(self.announce_plan(TrackerEvent::Completed))
+    }
+
+    fn apply_completion_result(&mut self, result: Result<AnnounceResponse>) {
+        match result {
+            Ok(response) => {
+                self.apply_announce_response(&response);
+                self.stats.announce_count += 1;
+                self.stats.completed_already_sent = true;
+            }
+            Err(e) => {
+                log_warn!("Completion announce failed, continuing: {}", e);
+            }
+        }
+    }
+
+    fn announce_plan(&self, event: TrackerEvent) -> AnnouncePlan {
         AnnouncePlan {
             tracker_client: Arc::clone(&self.tracker_client),
-            tracker_url: self.torrent.get_tracker_url().to_string(),
-            request: self.build_announce_request(TrackerEvent::None),
+            trackers: Arc::clone(&self.trackers),
+            request: self.build_announce_request(event),
+            info_hash_v2: (self.config.announce_v2_hash && !self.torrent.is_private)
+                .then(|| self.torrent.hybrid_info_hash_v2())
+                .flatten(),
+            dry_run: self.config.dry_run,
+            recorder: self.config.record_last_announce.then(|| Arc::clone(&self.last_announce)),
+        }
+    }
+
+    /// The last announce sent while `record_last_announce` was set
+    pub fn last_announce(&self) -> Option<LastAnnounce> {
+        if !self.config.record_last_announce {
+            return None;
         }
+        self.last_announce.lock().unwrap_or_else(std::sync::PoisonError::into_inner).clone()
+    }
+
+    fn scrape_due(&self, config: &FakerConfig, now: Instant) -> bool {
+        config.scrape_enabled
+            && self.scrape_retry_at.map_or_else(
+                || now.duration_since(self.last_scrape).as_secs() >= config.scrape_interval,
+                |retry_at| now >= retry_at,
+            )
+    }
+
+    /// The scrape the next update would send, for a caller that wants to send it
+    /// together with others. Dry runs never reach the tracker, so they have none.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn due_scrape(&self, now: Instant) -> Option<ScrapeTarget> {
+        let runnable = matches!(self.stats.state, FakerState::Running)
+            && self.stats.tracker_error.is_none()
+            && self.check_stop_conditions(&self.stats).is_none()
+            && !self.config.dry_run;
+        (runnable && self.scrape_due(&self.config, now)).then(|| ScrapeTarget {
+            tracker_client: Arc::clone(&self.tracker_client),
+            tracker_url: self.trackers.active_url(),
+            info_hash: self.torrent.info_hash,
+        })
     }
 
     fn build_scrape_plan(&self) -> ScrapePlan {
         ScrapePlan {
             tracker_client: Arc::clone(&self.tracker_client),
-            tracker_url: self.torrent.get_tracker_url().to_string(),
+            tracker_url: self.trackers.active_url(),
             info_hash: self.torrent.info_hash,
+            dry_run: self.config.dry_run,
         }
     }
 
+    /// Apply a scrape result. Returns true when `announce_on_first_leecher` is set and
+    /// the scrape shows the first leecher while idling, so an announce should go out now.
     fn apply_scrape_result(
         &mut self,
         result: &Result<crate::protocol::ScrapeResponse>,
         now: Instant,
-    ) {
+    ) -> bool {
         match result {
             Ok(scrape_response) => {
+                let leecher_appeared = self.config.announce_on_first_leecher
+                    && self.stats.is_idling
+                    && self.stats.next_announce.is_some()
+                    && self.stats.leechers == 0
+                    && scrape_response.incomplete > 0;
+
                 self.clear_tracker_error();
-                self.stats.seeders = scrape_response.complete;
-                self.stats.leechers = scrape_response.incomplete;
+                self.set_swarm_counts(scrape_response.complete, scrape_response.incomplete);
                 self.last_scrape = now;
+                self.scrape_consecutive_failures = 0;
+                self.scrape_retry_at = None;
                 log_debug!(
                     "Scrape updated peer counts: seeders={}, leechers={}",
                     scrape_response.complete,
                     scrape_response.incomplete
                 );
+                if leecher_appeared {
+                    log_info!("Leecher appeared while idling, announcing now");
+                }
+                leecher_appeared
             }
+            // A failed scrape leaves the instance running; announces keep the counts fresh
             Err(e) => {
-                self.apply_tracker_error(e);
-                log_warn!("Scrape failed, disabling periodic scrape: {}", e);
-                self.scrape_supported = false;
+                self.scrape_consecutive_failures =
+                    self.scrape_consecutive_failures.saturating_add(1);
+                let delay_secs = Self::scrape_retry_delay_secs(self.scrape_consecutive_failures);
+                self.scrape_retry_at = Some(now + Duration::from_secs(delay_secs));
+                if delay_secs == SCRAPE_COOLDOWN_SECS {
+                    log_warn!(
+                        "Scrape failed {} times in a row, using announce peer counts for {}s: {}",
+                        self.scrape_consecutive_failures,
+                        delay_secs,
+                        e
+                    );
+                } else {
+                    log_warn!("Scrape failed, retrying in {}s: {}", delay_secs, e);
+                }
+                false
             }
         }
     }
@@ -1202,12 +2468,14 @@ impl RatioFaker {
         match result {
             Ok(response) => {
                 self.clear_tracker_error();
-                self.announce_interval = Duration::from_secs(response.interval as u64);
-                self.stats.seeders = response.complete;
-                self.stats.leechers = response.incomplete;
-                self.stats.last_announce = Some(Instant::now());
-                self.stats.next_announce = Some(Instant::now() + self.announce_interval);
+                self.set_announce_intervals(&response);
+                self.apply_announce_response(&response);
+                let now = self.clock.now();
+                self.stats.last_announce = Some(now);
+                self.stats.next_announce = Some(now + self.jittered_announce_delay());
                 self.stats.announce_count += 1;
+                self.announce_failures = 0;
+                self.started_acknowledged = true;
 
                 log_info!(
                     "Periodic announce complete. Seeders: {}, Leechers: {}",
@@ -1217,11 +2485,77 @@ impl RatioFaker {
             }
             Err(e) => {
                 self.apply_tracker_error(&e);
-                log_warn!("Periodic announce failed, will retry next cycle: {}", e);
-                if !matches!(self.stats.state, FakerState::Stopped) {
-                    self.stats.next_announce = Some(Instant::now() + Duration::from_secs(30));
+                if matches!(self.stats.state, FakerState::Stopped) {
+                    log_warn!("Periodic announce failed: {}", e);
+                } else {
+                    let delay_secs = self.schedule_announce_retry();
+                    log_warn!(
+                        "Periodic announce failed {} time(s) in a row, retrying in {}s: {}",
+                        self.announce_failures,
+                        delay_secs,
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    fn set_announce_intervals(&mut self, response: &AnnounceResponse) {
+        self.announce_interval = Duration::from_secs(response.interval as u64);
+        self.min_announce_interval =
+            response.min_interval.map(|secs| Duration::from_secs(secs.max(0) as u64));
+    }
+
+    /// Delay until the next regular announce. The tracker interval is clamped to the
+    /// configured bounds; while idling, `idle_announce_interval` replaces it. Neither
+    /// goes below the tracker's `min interval`.
+    fn next_announce_delay(&self) -> Duration {
+        let delay = match self.config.idle_announce_interval {
+            Some(secs) if self.stats.is_idling => Duration::from_secs(secs),
+            _ => {
+                let mut interval = self.announce_interval;
+                if let Some(min) = self.config.min_announce_interval {
+                    interval = interval.max(Duration::from_secs(min));
                 }
+                if let Some(max) = self.config.max_announce_interval {
+                    interval = interval.min(Duration::from_secs(max));
+                }
+                interval
             }
+        };
+        delay.max(self.min_announce_interval.unwrap_or_default())
+    }
+
+    /// `next_announce_delay` shifted by this instance's jitter offset, kept within
+    /// `max_announce_interval` (outside idling) and never below the tracker's `min interval`.
+    fn jittered_announce_delay(&self) -> Duration {
+        let delay = self.next_announce_delay();
+        let offset = announce_jitter_offset_secs(&self.peer_id, self.config.announce_jitter_secs);
+        let offset_duration = Duration::from_secs(offset.unsigned_abs());
+        let mut shifted = if offset < 0 {
+            delay.saturating_sub(offset_duration)
+        } else {
+            delay.saturating_add(offset_duration)
+        };
+        let idle_override = self.stats.is_idling && self.config.idle_announce_interval.is_some();
+        if let Some(max) = self.config.max_announce_interval.filter(|_| !idle_override) {
+            shifted = shifted.min(Duration::from_secs(max));
+        }
+        shifted.max(self.min_announce_interval.unwrap_or_default()).max(Duration::from_secs(1))
+    }
+
+    /// When an instance starts idling mid-interval, bring the pending announce
+    /// forward so the idle cadence applies without waiting for the full interval.
+    fn pull_idle_announce_forward(&mut self) {
+        if !self.stats.is_idling || self.config.idle_announce_interval.is_none() {
+            return;
+        }
+        let (Some(last), Some(next)) = (self.stats.last_announce, self.stats.next_announce) else {
+            return;
+        };
+        let idle_next = last + self.next_announce_delay();
+        if idle_next < next {
+            self.stats.next_announce = Some(idle_next);
         }
     }
 
@@ -1235,7 +2569,11 @@ impl RatioFaker {
 
     pub fn can_retry_tracker(&self) -> bool {
         matches!(self.stats.state, FakerState::Stopped)
-            && self.stats.tracker_error.as_deref().is_some_and(Self::tracker_error_is_retryable)
+            && self
+                .stats
+                .tracker_error
+                .as_deref()
+                .is_some_and(|message| self.tracker_error_is_retryable(message))
     }
 
     pub fn recover_tracker(&mut self) -> Result<()> {
@@ -1244,42 +2582,37 @@ impl RatioFaker {
         }
 
         self.reset_session_state_for_start(false);
-        self.start_time = Instant::now();
-        self.last_update = Instant::now();
+        let now = self.clock.now();
+        self.start_time = now;
+        self.last_update = now;
         Ok(())
     }
 
-    /// Update only the stats without announcing to tracker (for live updates)
+    /// Update only the stats without announcing to tracker (for live updates).
+    ///
+    /// Rates, progressive interpolation and the scrape timer depend only on the clock,
+    /// so every frontend sees the same behaviour whatever its polling cadence.
     pub async fn update_stats_only(&mut self) -> Result<()> {
-        let now = Instant::now();
+        let now = self.clock.now();
         let outcome = self.tick(now);
 
-        if outcome.completed {
-            let plan = AnnouncePlan {
-                tracker_client: Arc::clone(&self.tracker_client),
-                tracker_url: self.torrent.get_tracker_url().to_string(),
-                request: self.build_announce_request(TrackerEvent::Completed),
-            };
-            match plan.execute().await {
-                Ok(response) => {
-                    self.stats.seeders = response.complete;
-                    self.stats.leechers = response.incomplete;
-                    self.stats.announce_count += 1;
-                }
-                Err(e) => {
-                    log_warn!("Completion announce failed, continuing: {}", e);
-                }
-            }
+        if let Some(plan) = self.completion_announce_plan(outcome.completed) {
+            let result = plan.execute().await;
+            self.apply_completion_result(result);
         }
 
         if outcome.scrape_due {
             let plan = self.build_scrape_plan();
             let result = plan.execute().await;
-            self.apply_scrape_result(&result, now);
+            if self.apply_scrape_result(&result, now) {
+                let plan = self.build_periodic_announce_plan();
+                let result = plan.execute().await;
+                self.apply_periodic_announce_result(result);
+            }
         }
 
-        if outcome.stop {
-            self.apply_post_stop_action().await?;
+        if let Some(which) = outcome.stop {
+            self.apply_post_stop_action(which).await?;
         }
 
         Ok(())
@@ -1310,6 +2643,8 @@ impl RatioFaker {
             tracker_error: None,
             tracker_retry_attempt: 0,
             tracker_retry_at_ms: None,
+            tracker_failure_reason: None,
+            tracker_warning: None,
             session_uploaded: 0,
             session_downloaded: 0,
             session_ratio: 0.0,
@@ -1336,12 +2671,19 @@ impl RatioFaker {
             announce_count: 0,
             stop_condition_met: false,
             post_stop_action: config.post_stop_action,
+            stop_reason: None,
+            estimated_bonus: None,
+            hnr_protected: false,
+            active_tracker: None,
+            completed_already_sent: false,
         }
     }
 
     /// Non-async stats snapshot (for synchronous exit-save contexts)
     pub fn stats_snapshot(&self) -> FakerStats {
-        self.stats.clone()
+        let mut stats = self.stats.clone();
+        stats.active_tracker = Some(self.trackers.active_url());
+        stats
     }
 
     pub fn peer_id(&self) -> &str {
@@ -1352,6 +2694,27 @@ impl RatioFaker {
         self.torrent.info_hash
     }
 
+    /// Scale configured rates by `scale` so instances sharing an IP don't move in lockstep.
+    pub fn set_rate_scale(&mut self, scale: f64) {
+        self.rate_scale = if scale.is_finite() && scale > 0.0 { scale } else { 1.0 };
+    }
+
+    /// Throttle upload/download rates by factors in `[0, 1]` to respect a combined cap.
+    pub fn set_throttle(&mut self, upload: f64, download: f64) {
+        let clamp = |factor: f64| if factor.is_finite() { factor.clamp(0.0, 1.0) } else { 1.0 };
+        self.upload_throttle = clamp(upload);
+        self.download_throttle = clamp(download);
+    }
+
+    /// Push the pending announce back to `at`. Never moves an announce earlier.
+    pub fn defer_next_announce(&mut self, at: Instant) {
+        if let Some(next) = self.stats.next_announce {
+            if at > next {
+                self.stats.next_announce = Some(at);
+            }
+        }
+    }
+
     pub const fn port(&self) -> u16 {
         self.config.port
     }
@@ -1364,21 +2727,31 @@ impl RatioFaker {
     /// Update the faker's configuration in-place without recreating the entire struct.
     ///
     /// This avoids re-allocating the `reqwest::Client` and other internal state.
-    /// Only recreates the `TrackerClient` if the `client_type` changed (which changes peer ID / User-Agent).
+    /// Only recreates the `TrackerClient` if the client identity or custom User-Agent changed.
     pub fn update_config(
         &mut self,
         config: FakerConfig,
         http_client: Option<reqwest::Client>,
     ) -> Result<()> {
         let mut config = config;
+        config.apply_seed_only();
         let client_type_changed = config.client_type != self.config.client_type
             || config.client_version != self.config.client_version;
+        let user_agent_changed = config.custom_user_agent != self.config.custom_user_agent;
+        let proxy_changed = config.proxy_url != self.config.proxy_url;
+        let peer_id_changed = config.peer_id_override != self.config.peer_id_override;
+        Self::validate_config(&config)?;
+        let bonus_formula = Self::parse_bonus_formula(&config)?;
+        if let Some(warning) = Self::implausible_upload_warning(&config, self.torrent.total_size) {
+            log_warn!("{}", warning);
+        }
 
-        if client_type_changed {
-            let client_config =
-                ClientConfig::get(config.client_type, config.client_version.clone());
-            self.peer_id = client_config.generate_peer_id();
-            self.key = ClientConfig::generate_key();
+        if client_type_changed || user_agent_changed || proxy_changed || peer_id_changed {
+            let client_config = Self::client_config_for(&config)?;
+            if client_type_changed || peer_id_changed {
+                self.peer_id = Self::peer_id_for(&config, &client_config)?;
+                self.key = ClientConfig::generate_key();
+            }
             self.tracker_client = Arc::new(
                 TrackerClient::new(client_config, http_client)
                     .map_err(|e| FakerError::ConfigError(e.to_string()))?,
@@ -1388,21 +2761,16 @@ impl RatioFaker {
         // Recompute left/torrent_completion from the new completion_percent.
         // This ensures that changing completion_percent in the UI takes effect
         // without having to recreate the faker.
-        let completion = config.completion_percent.clamp(0.0, 100.0) / 100.0;
-        let torrent_downloaded = (self.torrent.total_size as f64 * completion) as u64;
-        let new_left = self.torrent.total_size.saturating_sub(torrent_downloaded);
-        let new_torrent_completion = if self.torrent.total_size > 0 {
-            ((self.torrent.total_size - new_left) as f64 / self.torrent.total_size as f64) * 100.0
-        } else {
-            100.0
-        };
-
+        let wanted_size = Self::wanted_size(&self.torrent, &config)?;
+        let new_left = Self::left_for_completion(wanted_size, config.completion_percent);
+        self.wanted_size = wanted_size;
         self.stats.left = new_left;
-        self.stats.torrent_completion = new_torrent_completion;
+        self.stats.torrent_completion = Self::completion_for_left(wanted_size, new_left);
 
         Self::resolve_stop_ratio(&mut config);
         self.stats.effective_stop_at_ratio = config.stop_at_ratio;
 
+        self.bonus_formula = bonus_formula;
         self.config = config;
         Ok(())
     }
@@ -1416,6 +2784,7 @@ impl RatioFaker {
             self.stats.left
         );
 
+        let client = self.tracker_client.client_config();
         AnnounceRequest {
             info_hash: self.torrent.info_hash,
             peer_id: self.peer_id.clone(),
@@ -1423,16 +2792,49 @@ impl RatioFaker {
             uploaded: self.stats.uploaded,
             downloaded: self.stats.downloaded,
             left: self.stats.left,
-            compact: true,
-            no_peer_id: false,
+            compact: self.config.compact && client.supports_compact,
+            no_peer_id: client.sends_no_peer_id,
             event,
-            ip: None,
-            numwant: Some(self.config.num_want),
+            ip: self.config.announce_ip.map(|ip| ip.to_string()),
+            ipv6: self.config.announce_ipv6.map(|ip| ip.to_string()),
+            numwant: Some(self.config.num_want.unwrap_or(client.num_want)),
             key: Some(self.key.clone()),
             tracker_id: self.tracker_id.clone(),
         }
     }
 
+    /// Announce `started` then `stopped` without touching session state. Fails only
+    /// when the tracker rejects the torrent outright; transient failures are logged.
+    pub async fn validate_with_tracker(&self) -> Result<()> {
+        let (started, stopped) = self.build_validation_plans();
+        Self::run_validation(started, stopped).await
+    }
+
+    fn build_validation_plans(&self) -> (AnnouncePlan, AnnouncePlan) {
+        (self.announce_plan(TrackerEvent::Started), self.announce_plan(TrackerEvent::Stopped))
+    }
+
+    async fn run_validation(started: AnnouncePlan, stopped: AnnouncePlan) -> Result<()> {
+        match started.execute().await {
+            Ok(_) => {}
+            Err(FakerError::TrackerError(TrackerError::TrackerFailure(reason)))
+                if Self::is_fatal_tracker_failure(&reason) =>
+            {
+                log_warn!("Tracker rejected torrent during validation: {}", reason);
+                return Err(FakerError::TrackerError(TrackerError::TrackerFailure(reason)));
+            }
+            Err(e) => {
+                log_warn!("Tracker validation inconclusive, continuing: {}", e);
+                return Ok(());
+            }
+        }
+
+        if let Err(e) = stopped.execute().await {
+            log_warn!("Validation stopped announce failed: {}", e);
+        }
+        Ok(())
+    }
+
     /// Scrape the tracker for stats
     pub async fn scrape(&self) -> Result<crate::protocol::ScrapeResponse> {
         let plan = self.build_scrape_plan();
@@ -1447,51 +2849,122 @@ impl RatioFaker {
     }
 
     /// Pause the faker
-    pub fn pause(&mut self) -> Result<()> {
+    pub async fn pause(&mut self) -> Result<()> {
+        if let Some(plan) = self.begin_pause() {
+            let result = plan.execute().await;
+            self.apply_pause_result(&result);
+        }
+        Ok(())
+    }
+
+    /// Pause without contacting the tracker, even when `announce_on_pause` is set.
+    /// Used when the network path must not be trusted, such as a dropped VPN.
+    pub fn pause_silently(&mut self) {
+        let _ = self.begin_pause();
+    }
+
+    /// Enter the paused state. Returns the `stopped` announce to send when
+    /// `announce_on_pause` is set and the tracker currently lists this peer.
+    fn begin_pause(&mut self) -> Option<AnnouncePlan> {
         log_info!("Pausing ratio faker");
+        let was_announced =
+            matches!(self.stats.state, FakerState::Running) && self.stats.announce_count > 0;
         self.stats.state = FakerState::Paused;
         self.stats.is_idling = false;
         self.stats.idling_reason = None;
         self.stats.current_upload_rate = 0.0;
         self.stats.current_download_rate = 0.0;
-        Ok(())
+
+        if !(self.config.announce_on_pause && was_announced) {
+            return None;
+        }
+        Some(self.announce_plan(TrackerEvent::Stopped))
+    }
+
+    fn apply_pause_result(&mut self, result: &Result<AnnounceResponse>) {
+        match result {
+            Ok(_) => {
+                self.stats.announce_count += 1;
+                // The tracker dropped this peer, so announce again as soon as it resumes
+                self.stats.last_announce = Some(self.clock.now());
+                self.stats.next_announce = None;
+                // ... as `started`, whether or not `announce_on_resume` sends one itself
+                self.started_acknowledged = false;
+                log_info!("Pause announced to tracker");
+            }
+            Err(e) => {
+                log_warn!("Pause announce failed (tracker will time out peer): {}", e);
+            }
+        }
     }
 
     /// Resume the faker
-    pub fn resume(&mut self) -> Result<()> {
+    pub async fn resume(&mut self) -> Result<()> {
+        if let Some(plan) = self.begin_resume() {
+            let result = plan.execute().await;
+            self.apply_resume_result(result);
+        }
+        Ok(())
+    }
+
+    /// Leave the paused state. Returns the `started` announce that re-registers this
+    /// peer when `announce_on_resume` is set.
+    fn begin_resume(&mut self) -> Option<AnnouncePlan> {
         log_info!("Resuming ratio faker");
-        let now = Instant::now();
+        let was_paused = matches!(self.stats.state, FakerState::Paused);
+        let now = self.clock.now();
         self.stats.state = FakerState::Running;
         self.rebase_timers_from_elapsed(now);
-        if self.stats.next_announce.is_none() {
-            self.stats.next_announce = Some(now);
+
+        if !(self.config.announce_on_resume && was_paused) {
+            if self.stats.next_announce.is_none() {
+                self.stats.next_announce = Some(now);
+            }
+            return None;
+        }
+        // Until one succeeds, retries are sent as `started` too
+        self.started_acknowledged = false;
+        Some(self.announce_plan(TrackerEvent::Started))
+    }
+
+    fn apply_resume_result(&mut self, result: Result<AnnounceResponse>) {
+        match result {
+            Ok(response) => self.apply_start_result(Ok(response)),
+            Err(e) => {
+                // Stay running rather than stopping as a failed start would
+                let delay_secs = self.schedule_announce_retry();
+                log_warn!("Resume announce failed, retrying in {}s: {}", delay_secs, e);
+            }
+        }
+    }
+
+    /// Next position of a rate within its randomization range, from -1.0 to 1.0. Uniform
+    /// mode draws a fresh one every update; smoothed mode steps from the previous one.
+    fn next_rate_noise(&self, previous: f64) -> f64 {
+        let sample = rand::rng().random::<f64>().mul_add(2.0, -1.0);
+        match self.config.randomize_mode {
+            RandomizeMode::Uniform => sample,
+            RandomizeMode::Smoothed => SMOOTHED_NOISE_STEP
+                .mul_add(sample, previous * (1.0 - SMOOTHED_NOISE_PULL))
+                .clamp(-1.0, 1.0),
         }
-        Ok(())
     }
 
     /// Apply randomization to a rate if enabled
-    fn apply_randomization(&self, base_rate: f64) -> f64 {
+    fn apply_randomization(&self, base_rate: f64, noise: f64) -> f64 {
+        let base_rate = base_rate * self.rate_scale;
         if self.config.randomize_rates {
-            let mut rng = rand::rng();
             let range = self.config.random_range_percent / 100.0;
-            let variation = 1.0 + rng.random::<f64>().mul_add(range * 2.0, -range);
-            base_rate * variation
+            base_rate * noise.mul_add(range, 1.0)
         } else {
             base_rate
         }
     }
 
-    /// Update rate statistics and history
-    fn update_rate_stats(stats: &mut FakerStats, upload_rate: f64, download_rate: f64) {
+    /// Update rate statistics
+    const fn update_rate_stats(stats: &mut FakerStats, upload_rate: f64, download_rate: f64) {
         stats.current_upload_rate = upload_rate;
         stats.current_download_rate = download_rate;
-
-        // Record timestamp for this data point (Unix millis)
-        let timestamp = Self::current_timestamp_millis();
-        Self::add_to_history_u64(&mut stats.history_timestamps, timestamp, 60);
-
-        Self::add_to_history(&mut stats.upload_rate_history, upload_rate, 60);
-        Self::add_to_history(&mut stats.download_rate_history, download_rate, 60);
     }
 
     /// Update transfer stats (uploaded, downloaded, left). Returns true if just completed.
@@ -1528,7 +3001,6 @@ impl RatioFaker {
         let current_ratio =
             if torrent_size > 0 { stats.uploaded as f64 / torrent_size as f64 } else { 0.0 };
         stats.ratio = current_ratio;
-        Self::add_to_history(&mut stats.ratio_history, current_ratio, 60);
 
         // Session ratio = session_uploaded / torrent_size
         stats.session_ratio = if torrent_size > 0 {
@@ -1555,27 +3027,66 @@ impl RatioFaker {
         Self::update_progress_and_eta_with_size(stats, config, torrent_size);
     }
 
-    /// Add a value to a history vec, keeping only the last `max_len` items
-    fn add_to_history(history: &mut Vec<f64>, value: f64, max_len: usize) {
-        history.push(value);
-        if history.len() > max_len {
-            history.remove(0);
+    /// Append this tick's graph point (`timestamp` in Unix millis). The newest
+    /// `history_length` points are kept as they are; older ones are thinned to one per
+    /// `history_archive_resolution_secs` and dropped after `history_archive_secs`.
+    fn record_history(stats: &mut FakerStats, config: &FakerConfig, timestamp: u64) {
+        stats.history_timestamps.push(timestamp);
+        stats.upload_rate_history.push(stats.current_upload_rate);
+        stats.download_rate_history.push(stats.current_download_rate);
+        stats.ratio_history.push(stats.ratio);
+
+        let recent = config.history_length.max(1);
+        let Some(demoted) = stats.history_timestamps.len().checked_sub(recent + 1) else {
+            return;
+        };
+
+        // The point that just left the full-resolution window joins the archive only if
+        // it is far enough from the previous archived point
+        let archive_ms = config.history_archive_secs.saturating_mul(1000);
+        let resolution_ms = config.history_archive_resolution_secs.max(1).saturating_mul(1000);
+        let spaced_out = demoted.checked_sub(1).is_none_or(|previous| {
+            stats.history_timestamps[demoted].saturating_sub(stats.history_timestamps[previous])
+                >= resolution_ms
+        });
+        if archive_ms == 0 || !spaced_out {
+            Self::remove_history_point(stats, demoted);
+        }
+
+        let cutoff = timestamp.saturating_sub(archive_ms);
+        while stats.history_timestamps.len() > recent
+            && (archive_ms == 0 || stats.history_timestamps[0] < cutoff)
+        {
+            Self::remove_history_point(stats, 0);
         }
     }
 
-    /// Add a u64 value to a history vec, keeping only the last `max_len` items
-    fn add_to_history_u64(history: &mut Vec<u64>, value: u64, max_len: usize) {
-        history.push(value);
-        if history.len() > max_len {
-            history.remove(0);
+    fn remove_history_point(stats: &mut FakerStats, index: usize) {
+        if index < stats.history_timestamps.len() {
+            stats.history_timestamps.remove(index);
+        }
+        let series = [
+            &mut stats.upload_rate_history,
+            &mut stats.download_rate_history,
+            &mut stats.ratio_history,
+        ];
+        for series in series {
+            if index < series.len() {
+                series.remove(index);
+            }
         }
     }
 
-    /// Get current timestamp in milliseconds (cross-platform)
-    fn check_stop_conditions(&self, stats: &FakerStats) -> bool {
+    /// Returns the name of the first stop condition that has been reached
+    fn check_stop_conditions(&self, stats: &FakerStats) -> Option<&'static str> {
         // Don't re-trigger if already met
         if stats.stop_condition_met {
-            return false;
+            return None;
+        }
+
+        // Hit-and-run protection outranks every stop condition
+        if Self::hnr_pending(&self.config, stats) {
+            return None;
         }
 
         // Check ratio target (cumulative across all sessions)
@@ -1586,7 +3097,7 @@ impl RatioFaker {
                     stats.ratio,
                     target_ratio
                 );
-                return true;
+                return Some("ratio");
             }
         }
 
@@ -1598,7 +3109,7 @@ impl RatioFaker {
                     stats.uploaded,
                     target_uploaded
                 );
-                return true;
+                return Some("uploaded");
             }
         }
 
@@ -1610,7 +3121,7 @@ impl RatioFaker {
                     stats.downloaded,
                     target_downloaded
                 );
-                return true;
+                return Some("downloaded");
             }
         }
 
@@ -1622,27 +3133,35 @@ impl RatioFaker {
                     stats.elapsed_time.as_secs(),
                     target_seed_time
                 );
-                return true;
+                return Some("seed_time");
+            }
+        }
+
+        // Check wall-clock stop time
+        if let Some(deadline) = self.config.stop_at_timestamp {
+            let now = Self::current_timestamp_millis() / 1000;
+            if now >= deadline {
+                log_info!("Stop time reached: {} >= {} (unix seconds)", now, deadline);
+                return Some("stop_time");
             }
         }
 
-        false
+        None
     }
 
-    /// Calculate progressive rate (linear interpolation)
-    #[allow(clippy::unused_self)]
+    /// Calculate progressive rate, easing from start to target along the configured curve
     fn calculate_progressive_rate(
-        &self,
         start_rate: f64,
         target_rate: f64,
         elapsed_secs: u64,
         duration_secs: u64,
+        curve: ProgressiveCurve,
     ) -> f64 {
         if elapsed_secs >= duration_secs {
             return target_rate;
         }
 
-        let progress = elapsed_secs as f64 / duration_secs as f64;
+        let progress = curve.apply(elapsed_secs as f64 / duration_secs as f64);
         (target_rate - start_rate).mul_add(progress, start_rate)
     }
 
@@ -1711,6 +3230,40 @@ impl RatioFaker {
         } else {
             stats.eta_download_completion = None;
         }
+
+        stats.hnr_protected = Self::hnr_pending(config, stats);
+    }
+
+    /// Whether hit-and-run requirements are configured and neither is met yet.
+    fn hnr_pending(config: &FakerConfig, stats: &FakerStats) -> bool {
+        if config.hnr_min_seed_time.is_none() && config.hnr_min_ratio.is_none() {
+            return false;
+        }
+        if !matches!(stats.state, FakerState::Starting | FakerState::Running | FakerState::Paused) {
+            return false;
+        }
+
+        let seed_time_met =
+            config.hnr_min_seed_time.is_some_and(|min| stats.elapsed_time.as_secs() >= min);
+        let ratio_met = config.hnr_min_ratio.is_some_and(|min| stats.ratio >= min - 0.001);
+        !seed_time_met && !ratio_met
+    }
+
+    /// Why stopping or pausing now would risk a hit-and-run, if it would.
+    pub fn hnr_block_reason(&self) -> Option<String> {
+        if !Self::hnr_pending(&self.config, &self.stats) {
+            return None;
+        }
+
+        let mut needs = Vec::new();
+        if let Some(min) = self.config.hnr_min_seed_time {
+            let remaining = min.saturating_sub(self.stats.elapsed_time.as_secs());
+            needs.push(format!("seed for {remaining}s more"));
+        }
+        if let Some(min) = self.config.hnr_min_ratio {
+            needs.push(format!("reach ratio {min:.2} (currently {:.2})", self.stats.ratio));
+        }
+        Some(format!("Hit-and-run protection active: {} before stopping", needs.join(" or ")))
     }
 }
 
@@ -1765,9 +3318,13 @@ impl RatioFakerHandle {
     }
 
     pub async fn stop(&self) -> Result<()> {
+        self.stop_with_reason(StopReason::UserRequested).await
+    }
+
+    pub async fn stop_with_reason(&self, reason: StopReason) -> Result<()> {
         let plan = {
             let mut guard = self.inner.lock().await;
-            guard.begin_stop()
+            guard.begin_stop(reason)
         };
 
         if let Some(plan) = plan {
@@ -1780,17 +3337,56 @@ impl RatioFakerHandle {
     }
 
     pub async fn pause(&self) -> Result<()> {
+        let plan = {
+            let mut guard = self.inner.lock().await;
+            let plan = guard.begin_pause();
+            let _ = self.stats_tx.send(guard.stats_snapshot());
+            plan
+        };
+
+        if let Some(plan) = plan {
+            let result = plan.execute().await;
+            let mut guard = self.inner.lock().await;
+            guard.apply_pause_result(&result);
+            let _ = self.stats_tx.send(guard.stats_snapshot());
+        }
+        Ok(())
+    }
+
+    pub async fn pause_silently(&self) {
         let mut guard = self.inner.lock().await;
-        let result = guard.pause();
+        guard.pause_silently();
         let _ = self.stats_tx.send(guard.stats_snapshot());
-        result
+    }
+
+    pub async fn hnr_block_reason(&self) -> Option<String> {
+        self.inner.lock().await.hnr_block_reason()
+    }
+
+    pub async fn last_announce(&self) -> Option<LastAnnounce> {
+        self.inner.lock().await.last_announce()
+    }
+
+    pub async fn validate_with_tracker(&self) -> Result<()> {
+        let (started, stopped) = self.inner.lock().await.build_validation_plans();
+        RatioFaker::run_validation(started, stopped).await
     }
 
     pub async fn resume(&self) -> Result<()> {
-        let mut guard = self.inner.lock().await;
-        let result = guard.resume();
-        let _ = self.stats_tx.send(guard.stats_snapshot());
-        result
+        let plan = {
+            let mut guard = self.inner.lock().await;
+            let plan = guard.begin_resume();
+            let _ = self.stats_tx.send(guard.stats_snapshot());
+            plan
+        };
+
+        if let Some(plan) = plan {
+            let result = plan.execute().await;
+            let mut guard = self.inner.lock().await;
+            guard.apply_resume_result(result);
+            let _ = self.stats_tx.send(guard.stats_snapshot());
+        }
+        Ok(())
     }
 
     pub async fn restore_running(&self) -> Result<()> {
@@ -1812,7 +3408,7 @@ impl RatioFakerHandle {
         let _ = self.stats_tx.send(guard.stats_snapshot());
     }
 
-    async fn apply_post_stop_action(&self) -> Result<()> {
+    async fn apply_post_stop_action(&self, which: &str) -> Result<()> {
         let post_stop_action = {
             let guard = self.inner.lock().await;
             guard.config.post_stop_action
@@ -1832,7 +3428,7 @@ impl RatioFakerHandle {
                 let plan = {
                     let mut guard = self.inner.lock().await;
                     guard.stats.stop_condition_met = true;
-                    guard.begin_stop()
+                    guard.begin_stop(StopReason::StopConditionMet { which: which.to_string() })
                 };
                 if let Some(plan) = plan {
                     let result = plan.execute().await;
@@ -1845,29 +3441,19 @@ impl RatioFakerHandle {
     }
 
     pub async fn update(&self) -> Result<()> {
-        let now = Instant::now();
-        let outcome = {
+        let (now, outcome) = {
             let mut guard = self.inner.lock().await;
-            guard.tick(now)
+            let now = guard.clock.now();
+            (now, guard.tick(now))
         };
 
-        if outcome.completed {
-            let plan = {
-                let guard = self.inner.lock().await;
-                AnnouncePlan {
-                    tracker_client: Arc::clone(&guard.tracker_client),
-                    tracker_url: guard.torrent.get_tracker_url().to_string(),
-                    request: guard.build_announce_request(TrackerEvent::Completed),
-                }
-            };
-            if let Ok(response) = plan.execute().await {
-                let mut guard = self.inner.lock().await;
-                guard.stats.seeders = response.complete;
-                guard.stats.leechers = response.incomplete;
-                guard.stats.announce_count += 1;
-            }
+        let plan = self.inner.lock().await.completion_announce_plan(outcome.completed);
+        if let Some(plan) = plan {
+            let result = plan.execute().await;
+            self.inner.lock().await.apply_completion_result(result);
         }
 
+        let mut announce_due = outcome.announce_due;
         if outcome.scrape_due {
             let plan = {
                 let guard = self.inner.lock().await;
@@ -1875,10 +3461,10 @@ impl RatioFakerHandle {
             };
             let result = plan.execute().await;
             let mut guard = self.inner.lock().await;
-            guard.apply_scrape_result(&result, now);
+            announce_due |= guard.apply_scrape_result(&result, now);
         }
 
-        if outcome.announce_due {
+        if announce_due {
             let plan = {
                 let guard = self.inner.lock().await;
                 guard.build_periodic_announce_plan()
@@ -1888,8 +3474,8 @@ impl RatioFakerHandle {
             guard.apply_periodic_announce_result(result);
         }
 
-        if outcome.stop {
-            self.apply_post_stop_action().await?;
+        if let Some(which) = outcome.stop {
+            self.apply_post_stop_action(which).await?;
         }
 
         let guard = self.inner.lock().await;
@@ -1898,27 +3484,16 @@ impl RatioFakerHandle {
     }
 
     pub async fn update_stats_only(&self) -> Result<()> {
-        let now = Instant::now();
-        let outcome = {
+        let (now, outcome) = {
             let mut guard = self.inner.lock().await;
-            guard.tick(now)
+            let now = guard.clock.now();
+            (now, guard.tick(now))
         };
 
-        if outcome.completed {
-            let plan = {
-                let guard = self.inner.lock().await;
-                AnnouncePlan {
-                    tracker_client: Arc::clone(&guard.tracker_client),
-                    tracker_url: guard.torrent.get_tracker_url().to_string(),
-                    request: guard.build_announce_request(TrackerEvent::Completed),
-                }
-            };
-            if let Ok(response) = plan.execute().await {
-                let mut guard = self.inner.lock().await;
-                guard.stats.seeders = response.complete;
-                guard.stats.leechers = response.incomplete;
-                guard.stats.announce_count += 1;
-            }
+        let plan = self.inner.lock().await.completion_announce_plan(outcome.completed);
+        if let Some(plan) = plan {
+            let result = plan.execute().await;
+            self.inner.lock().await.apply_completion_result(result);
         }
 
         if outcome.scrape_due {
@@ -1927,12 +3502,20 @@ impl RatioFakerHandle {
                 guard.build_scrape_plan()
             };
             let result = plan.execute().await;
-            let mut guard = self.inner.lock().await;
-            guard.apply_scrape_result(&result, now);
+            let leecher_appeared = self.inner.lock().await.apply_scrape_result(&result, now);
+            if leecher_appeared {
+                let plan = {
+                    let guard = self.inner.lock().await;
+                    guard.build_periodic_announce_plan()
+                };
+                let result = plan.execute().await;
+                let mut guard = self.inner.lock().await;
+                guard.apply_periodic_announce_result(result);
+            }
         }
 
-        if outcome.stop {
-            self.apply_post_stop_action().await?;
+        if let Some(which) = outcome.stop {
+            self.apply_post_stop_action(which).await?;
         }
 
         let guard = self.inner.lock().await;
@@ -1946,13 +3529,38 @@ impl RatioFakerHandle {
             guard.build_scrape_plan()
         };
         let result = plan.execute().await;
-        let now = Instant::now();
         let mut guard = self.inner.lock().await;
+        let now = guard.clock.now();
         guard.apply_scrape_result(&result, now);
         let _ = self.stats_tx.send(guard.stats_snapshot());
         result
     }
 
+    /// The scrape this instance's next update would send, if one is due now
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn due_scrape(&self) -> Option<ScrapeTarget> {
+        let guard = self.inner.lock().await;
+        let now = guard.clock.now();
+        guard.due_scrape(now)
+    }
+
+    /// Apply a scrape sent on this instance's behalf (e.g. as part of a batch), as if its
+    /// own update had sent it, including the announce a first leecher triggers.
+    pub async fn apply_scrape(&self, result: Result<crate::protocol::ScrapeResponse>) {
+        let leecher_appeared = {
+            let mut guard = self.inner.lock().await;
+            let now = guard.clock.now();
+            guard.apply_scrape_result(&result, now)
+        };
+        if leecher_appeared {
+            let plan = self.inner.lock().await.build_periodic_announce_plan();
+            let result = plan.execute().await;
+            self.inner.lock().await.apply_periodic_announce_result(result);
+        }
+        let guard = self.inner.lock().await;
+        let _ = self.stats_tx.send(guard.stats_snapshot());
+    }
+
     pub async fn update_config(
         &self,
         config: FakerConfig,
@@ -1996,21 +3604,76 @@ impl RatioFakerHandle {
         guard.port()
     }
 
+    /// The IPv4 address announced as `ip=`, including a runtime override.
+    pub async fn effective_announce_ip(&self) -> Option<IpAddr> {
+        let guard = self.inner.lock().await;
+        guard.config.announce_ip
+    }
+
     pub async fn set_runtime_port(&self, port: u16) {
         let mut guard = self.inner.lock().await;
         guard.config.port = port;
         let _ = self.stats_tx.send(guard.stats_snapshot());
     }
 
+    /// Announce `ip` from now on: IPv4 as `ip=`, IPv6 as `ipv6=`.
+    pub async fn set_runtime_announce_ip(&self, ip: IpAddr) {
+        let mut guard = self.inner.lock().await;
+        match ip {
+            IpAddr::V4(_) => guard.config.announce_ip = Some(ip),
+            IpAddr::V6(v6) => guard.config.announce_ipv6 = Some(v6),
+        }
+        let _ = self.stats_tx.send(guard.stats_snapshot());
+    }
+
+    pub async fn set_rate_scale(&self, scale: f64) {
+        let mut guard = self.inner.lock().await;
+        guard.set_rate_scale(scale);
+    }
+
+    pub async fn set_throttle(&self, upload: f64, download: f64) {
+        let mut guard = self.inner.lock().await;
+        guard.set_throttle(upload, download);
+    }
+
+    pub async fn defer_next_announce(&self, at: Instant) {
+        let mut guard = self.inner.lock().await;
+        guard.defer_next_announce(at);
+        let _ = self.stats_tx.send(guard.stats_snapshot());
+    }
+
+    /// How many pieces the torrent has and how many of them the completion covers.
+    pub async fn pieces_held(&self) -> (usize, usize) {
+        let guard = self.inner.lock().await;
+        let total = guard.torrent.num_pieces;
+        let held = (total as f64 * guard.stats.torrent_completion / 100.0).floor() as usize;
+        (total, held.min(total))
+    }
+
     pub async fn is_peer_connectable(&self) -> bool {
         let guard = self.inner.lock().await;
-        handle_is_connectable(guard.stats.state)
+        guard.config.accept_connections && handle_is_connectable(guard.stats.state)
+    }
+}
+
+/// Offset in `[-jitter_secs, jitter_secs]` derived from an FNV-1a hash of the peer ID,
+/// so each instance keeps its own offset while instances spread apart.
+fn announce_jitter_offset_secs(peer_id: &str, jitter_secs: u64) -> i64 {
+    if jitter_secs == 0 {
+        return 0;
     }
+    let hash = peer_id.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    let jitter = i64::try_from(jitter_secs).unwrap_or(i64::MAX / 2);
+    let span = jitter.unsigned_abs() * 2 + 1;
+    i64::try_from(hash % span).unwrap_or_default() - jitter
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clock::MockClock;
 
     #[test]
     fn test_faker_config_default() {
@@ -2018,6 +3681,7 @@ mod tests {
         assert_eq!(config.upload_rate, 50.0);
         assert_eq!(config.download_rate, 100.0);
         assert!(!config.vpn_port_sync);
+        assert_eq!(config.completion_percent, DEFAULT_COMPLETION_PERCENT);
     }
 
     #[test]
@@ -2030,7 +3694,7 @@ mod tests {
         assert_eq!(config.port, 6881);
         assert!(!config.vpn_port_sync);
         assert_eq!(config.client_type, ClientType::QBittorrent);
-        assert_eq!(config.completion_percent, 100.0);
+        assert_eq!(config.completion_percent, DEFAULT_COMPLETION_PERCENT);
         assert!(config.randomize_rates);
         assert_eq!(config.random_range_percent, 20.0);
         assert!(!config.randomize_ratio);
@@ -2040,6 +3704,42 @@ mod tests {
         assert!(!config.progressive_rates);
     }
 
+    #[test]
+    fn validate_config_rejects_what_the_faker_would() {
+        assert!(RatioFaker::validate_config(&FakerConfig::default()).is_ok());
+        let inverted = FakerConfig {
+            min_announce_interval: Some(600),
+            max_announce_interval: Some(60),
+            ..FakerConfig::default()
+        };
+        assert!(RatioFaker::validate_config(&inverted).is_err());
+        let wild_jitter = FakerConfig { announce_jitter_secs: u64::MAX, ..FakerConfig::default() };
+        assert!(RatioFaker::validate_config(&wild_jitter).is_err());
+        let long_history =
+            FakerConfig { history_length: MAX_HISTORY_LENGTH + 1, ..FakerConfig::default() };
+        assert!(RatioFaker::validate_config(&long_history).is_err());
+    }
+
+    #[test]
+    fn preset_settings_apply_over_an_existing_config() {
+        let mut config = FakerConfig {
+            download_rate: 7.0,
+            stop_at_ratio: Some(3.0),
+            stop_at_seed_time: Some(3600),
+            ..FakerConfig::default()
+        };
+        let preset = PresetSettings {
+            upload_rate: Some(100.0),
+            stop_at_ratio_enabled: Some(false),
+            ..PresetSettings::default()
+        };
+        preset.apply_to(&mut config);
+        assert!((config.upload_rate - 100.0).abs() < f64::EPSILON);
+        assert!((config.download_rate - 7.0).abs() < f64::EPSILON);
+        assert_eq!(config.stop_at_ratio, None);
+        assert_eq!(config.stop_at_seed_time, Some(3600));
+    }
+
     #[test]
     fn test_preset_settings_to_faker_config_with_values() {
         let preset = PresetSettings {
@@ -2081,6 +3781,159 @@ mod tests {
         assert_eq!(config.progressive_duration, 2 * 3600);
     }
 
+    #[test]
+    fn seed_only_forces_the_values_it_derives() {
+        let preset = PresetSettings {
+            seed_only: Some(true),
+            download_rate: Some(200.0),
+            completion_percent: Some(40.0),
+            idle_when_no_seeders: Some(true),
+            target_download_rate: Some(800.0),
+            ..Default::default()
+        };
+        let config = FakerConfig::from(preset);
+        assert_eq!(config.completion_percent, 100.0);
+        assert_eq!(config.download_rate, 0.0);
+        assert_eq!(config.target_download_rate, None);
+        assert!(!config.idle_when_no_seeders);
+
+        // Configs that skip the preset conversion are settled by the faker itself
+        let raw = FakerConfig {
+            seed_only: true,
+            download_rate: 200.0,
+            completion_percent: 40.0,
+            ..FakerConfig::default()
+        };
+        let (mut faker, _clock) = clocked_faker(raw.clone());
+        assert_eq!(faker.stats.left, 0);
+        assert_eq!(faker.config.download_rate, 0.0);
+
+        faker.stats.state = FakerState::Stopped;
+        let update = FakerConfig { idle_when_no_seeders: true, ..raw };
+        faker.update_config(update, None).unwrap_or_else(|e| panic!("{e}"));
+        assert_eq!(faker.stats.left, 0);
+        assert!(!faker.config.idle_when_no_seeders);
+    }
+
+    #[test]
+    fn every_progressive_curve_reaches_the_target_on_time() {
+        let curves = [
+            ProgressiveCurve::Linear,
+            ProgressiveCurve::EaseIn,
+            ProgressiveCurve::EaseOut,
+            ProgressiveCurve::Logarithmic,
+        ];
+        for curve in curves {
+            let rate_at =
+                |elapsed| RatioFaker::calculate_progressive_rate(50.0, 250.0, elapsed, 3600, curve);
+            assert!((rate_at(0) - 50.0).abs() < 1e-9, "{curve:?} must start at the base rate");
+            assert!((rate_at(3600) - 250.0).abs() < 1e-9, "{curve:?} must hit the target");
+            assert!((rate_at(7200) - 250.0).abs() < 1e-9, "{curve:?} must hold the target");
+            let samples: Vec<f64> = (0..=3600).step_by(360).map(rate_at).collect();
+            assert!(samples.windows(2).all(|w| w[0] <= w[1]), "{curve:?} must not dip");
+        }
+
+        let halfway = |curve| RatioFaker::calculate_progressive_rate(0.0, 100.0, 1800, 3600, curve);
+        assert!((halfway(ProgressiveCurve::Linear) - 50.0).abs() < 1e-9);
+        assert!(halfway(ProgressiveCurve::EaseIn) < 50.0);
+        assert!(halfway(ProgressiveCurve::EaseOut) > 50.0);
+        assert!(halfway(ProgressiveCurve::Logarithmic) > 50.0);
+        let early = |curve| RatioFaker::calculate_progressive_rate(0.0, 100.0, 360, 3600, curve);
+        assert!(early(ProgressiveCurve::Logarithmic) > early(ProgressiveCurve::EaseOut));
+
+        let preset: PresetSettings = serde_json::from_str(r#"{"progressiveCurve": "ease_out"}"#)
+            .unwrap_or_else(|e| panic!("preset should parse: {e}"));
+        assert_eq!(FakerConfig::from(preset).progressive_curve, ProgressiveCurve::EaseOut);
+        assert_eq!(FakerConfig::default().progressive_curve, ProgressiveCurve::Linear);
+        assert!(serde_json::from_str::<PresetSettings>(r#"{"progressiveCurve": "steep"}"#).is_err());
+    }
+
+    #[test]
+    fn smoothed_rates_drift_within_the_band() {
+        let config = FakerConfig {
+            randomize_rates: true,
+            random_range_percent: 20.0,
+            randomize_mode: RandomizeMode::Smoothed,
+            ..FakerConfig::default()
+        };
+        let (mut faker, _clock) = clocked_faker(config.clone());
+        let rates: Vec<(f64, f64)> =
+            (0..1000).map(|_| faker.apply_randomized_rates(100.0, 100.0, 1)).collect();
+
+        // Each update moves at most (step + pull) of the 20 KB/s half-range
+        let max_step = (SMOOTHED_NOISE_STEP + SMOOTHED_NOISE_PULL).mul_add(20.0, 1e-9);
+        for pair in rates.windows(2) {
+            assert!((pair[1].0 - pair[0].0).abs() <= max_step);
+            assert!((pair[1].1 - pair[0].1).abs() <= max_step);
+        }
+        for (upload, download) in &rates {
+            assert!((80.0..=120.0).contains(upload), "upload {upload} left the band");
+            assert!((80.0..=120.0).contains(download), "download {download} left the band");
+        }
+        let spread = rates.iter().map(|r| r.0).fold(f64::MIN, f64::max)
+            - rates.iter().map(|r| r.0).fold(f64::MAX, f64::min);
+        assert!(spread > max_step, "smoothed rate should still wander, spread {spread}");
+        // ...and over time reach well across the 40 KB/s band, not just its middle
+        assert!(spread > 20.0, "smoothed rate should cover the band, spread {spread}");
+
+        // Uniform jitter jumps far more than that between updates
+        let (mut faker, _clock) =
+            clocked_faker(FakerConfig { randomize_mode: RandomizeMode::Uniform, ..config });
+        let uniform: Vec<f64> =
+            (0..1000).map(|_| faker.apply_randomized_rates(100.0, 100.0, 1).0).collect();
+        assert!(uniform.windows(2).any(|pair| (pair[1] - pair[0]).abs() > max_step));
+
+        let preset: PresetSettings = serde_json::from_str(r#"{"randomizeMode": "smoothed"}"#)
+            .unwrap_or_else(|e| panic!("preset should parse: {e}"));
+        assert_eq!(FakerConfig::from(preset).randomize_mode, RandomizeMode::Smoothed);
+        assert!(serde_json::from_str::<PresetSettings>(r#"{"randomizeMode": "wavy"}"#).is_err());
+    }
+
+    #[test]
+    fn custom_user_agent_overrides_client_default() {
+        let config = FakerConfig {
+            client_type: ClientType::Transmission,
+            custom_user_agent: Some(" Transmission/4.0.6 (custom) ".to_string()),
+            ..FakerConfig::default()
+        };
+        let client_config = RatioFaker::client_config_for(&config);
+        assert!(client_config.is_ok());
+        let client_config =
+            client_config.unwrap_or_else(|_| panic!("failed to build client config"));
+
+        assert_eq!(client_config.user_agent, "Transmission/4.0.6 (custom)");
+        assert!(client_config.peer_id_prefix.starts_with("-TR"));
+
+        let invalid = FakerConfig { custom_user_agent: Some(String::new()), ..config };
+        assert!(RatioFaker::client_config_for(&invalid).is_err());
+    }
+
+    #[test]
+    fn user_agent_override_is_sent_verbatim_on_announces() {
+        let mut json = serde_json::to_value(FakerConfig {
+            client_type: ClientType::QBittorrent,
+            ..FakerConfig::default()
+        })
+        .unwrap_or_else(|e| panic!("{e}"));
+        json["user_agent_override"] = "qBittorrent/5.2.1.1 (patched)".into();
+        if let Some(fields) = json.as_object_mut() {
+            fields.remove("custom_user_agent");
+        }
+        let config: FakerConfig = serde_json::from_value(json).unwrap_or_else(|e| panic!("{e}"));
+        let client_config =
+            RatioFaker::client_config_for(&config).unwrap_or_else(|e| panic!("{e}"));
+
+        assert!(client_config
+            .tracker_headers("tracker.test")
+            .contains(&("User-Agent", "qBittorrent/5.2.1.1 (patched)".to_string())));
+        assert!(client_config.generate_peer_id().starts_with("-qB5210-"));
+
+        let preset: PresetSettings =
+            serde_json::from_value(serde_json::json!({ "userAgentOverride": "uTorrent/3.6.0" }))
+                .unwrap_or_else(|e| panic!("{e}"));
+        assert_eq!(FakerConfig::from(preset).custom_user_agent.as_deref(), Some("uTorrent/3.6.0"));
+    }
+
     #[test]
     fn test_preset_settings_disabled_stop_conditions() {
         let preset = PresetSettings {
@@ -2131,12 +3984,15 @@ mod tests {
         assert!(parsed.is_ok());
         let parsed = parsed.unwrap_or_default();
         assert!(!parsed.vpn_port_sync);
+        assert!(parsed.scrape_enabled);
+        assert!(parsed.accept_connections);
     }
 
     #[test]
     fn update_config_uses_precomputed_effective_stop_ratio() {
         let torrent = Arc::new(TorrentInfo {
             info_hash: [7u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -2147,8 +4003,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         });
 
         let faker = RatioFaker::new(
@@ -2183,6 +4041,7 @@ mod tests {
     fn start_resets_session_stats_but_keeps_cumulative_ratio_progress() {
         let torrent = Arc::new(TorrentInfo {
             info_hash: [9u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -2193,8 +4052,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         });
 
         let faker = RatioFaker::new(
@@ -2224,13 +4085,14 @@ mod tests {
         assert_eq!(faker.stats.ratio_progress, 0.0);
         assert_eq!(faker.stats.announce_count, 0);
         assert!(faker.stats.ratio > 0.0);
-        assert!(!faker.check_stop_conditions(&faker.stats));
+        assert!(faker.check_stop_conditions(&faker.stats).is_none());
     }
 
     #[test]
     fn tick_stops_without_extra_transfer_when_condition_already_met() {
         let torrent = Arc::new(TorrentInfo {
             info_hash: [11u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -2241,8 +4103,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         });
 
         let faker = RatioFaker::new(
@@ -2268,7 +4132,7 @@ mod tests {
 
         let outcome = faker.tick(Instant::now());
 
-        assert!(outcome.stop);
+        assert_eq!(outcome.stop, Some("ratio"));
         assert_eq!(faker.stats.uploaded, uploaded_before);
         assert_eq!(faker.stats.downloaded, downloaded_before);
         assert_eq!(faker.stats.current_upload_rate, 0.0);
@@ -2284,10 +4148,18 @@ mod tests {
         assert_eq!(message, "Torrent not found on tracker");
     }
 
+    #[test]
+    fn fatal_tracker_failures_cover_unregistered_and_passkey() {
+        assert!(RatioFaker::is_fatal_tracker_failure("Unregistered torrent"));
+        assert!(RatioFaker::is_fatal_tracker_failure("Invalid passkey"));
+        assert!(!RatioFaker::is_fatal_tracker_failure("Tracker is overloaded, try later"));
+    }
+
     #[test]
     fn tracker_invalid_stops_faker_and_clears_runtime_rates() {
         let torrent = Arc::new(TorrentInfo {
             info_hash: [12u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -2298,8 +4170,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         });
 
         let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
@@ -2332,6 +4206,7 @@ mod tests {
     fn tracker_unreachable_stops_faker_and_sets_warning_message() {
         let torrent = Arc::new(TorrentInfo {
             info_hash: [13u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -2342,8 +4217,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         });
 
         let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
@@ -2372,6 +4249,7 @@ mod tests {
     fn tracker_missing_does_not_arm_retry() {
         let torrent = Arc::new(TorrentInfo {
             info_hash: [17u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -2382,8 +4260,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         });
 
         let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
@@ -2400,10 +4280,34 @@ mod tests {
         assert!(!faker.can_retry_tracker());
     }
 
+    #[test]
+    fn ipv6_only_tracker_is_retried_unless_disabled() {
+        let ipv6_error = || {
+            FakerError::TrackerError(TrackerError::Ipv6Unreachable(format!(
+                "{IPV6_ONLY_TRACKER_ERROR}: connection refused"
+            )))
+        };
+
+        let (mut faker, _clock) = clocked_faker(FakerConfig::default());
+        faker.apply_tracker_error(&ipv6_error());
+        assert_eq!(faker.stats.tracker_error.as_deref(), Some(IPV6_ONLY_TRACKER_ERROR));
+        assert_eq!(faker.stats.tracker_retry_attempt, 1);
+        assert!(faker.can_retry_tracker());
+
+        let (mut faker, _clock) = clocked_faker(FakerConfig {
+            retry_ipv6_only_trackers: false,
+            ..FakerConfig::default()
+        });
+        faker.apply_tracker_error(&ipv6_error());
+        assert_eq!(faker.stats.tracker_retry_attempt, 0);
+        assert!(!faker.can_retry_tracker());
+    }
+
     #[test]
     fn restore_runtime_seeds_retry_for_tracker_unavailable() {
         let torrent = Arc::new(TorrentInfo {
             info_hash: [18u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -2414,8 +4318,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         });
 
         let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
@@ -2440,6 +4346,7 @@ mod tests {
     fn successful_start_clears_tracker_retry_state() {
         let torrent = Arc::new(TorrentInfo {
             info_hash: [19u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -2450,8 +4357,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         });
 
         let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
@@ -2480,9 +4389,10 @@ mod tests {
     }
 
     #[test]
-    fn tracker_retry_backoff_caps_at_max_interval() {
+    fn announces_stay_started_until_the_tracker_accepts_one() {
         let torrent = Arc::new(TorrentInfo {
-            info_hash: [20u8; 20],
+            info_hash: [42u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -2493,32 +4403,101 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         });
-
         let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
-        assert!(faker.is_ok());
         let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+        faker.stats.state = FakerState::Stopped;
 
-        for _ in 0..6 {
-            faker.apply_tracker_error(&FakerError::TrackerError(TrackerError::HttpError(
-                "connection refused".to_string(),
-            )));
-        }
+        let start = faker.begin_start().unwrap_or_else(|| panic!("start not planned"));
+        assert!(matches!(start.request.event, TrackerEvent::Started));
+        faker.apply_start_result(Err(FakerError::InvalidState("no route".to_string())));
+        assert!(matches!(faker.stats.state, FakerState::Running));
+
+        // The retry goes out on the periodic path but must still say `started`
+        let retry = faker.build_periodic_announce_plan();
+        assert!(matches!(retry.request.event, TrackerEvent::Started));
+        faker.apply_periodic_announce_result(Err(FakerError::TrackerError(
+            TrackerError::HttpError("timed out".to_string()),
+        )));
+        assert!(matches!(faker.stats.state, FakerState::Stopped));
+
+        // Tracker recovery re-announces through the periodic plan as well
+        faker.recover_tracker().unwrap_or_else(|_| panic!("failed to arm recovery"));
+        let recovery = faker.build_periodic_announce_plan();
+        assert!(matches!(recovery.request.event, TrackerEvent::Started));
+        faker.apply_start_result(Ok(AnnounceResponse {
+            interval: 1800,
+            min_interval: None,
+            tracker_id: None,
+            complete: 3,
+            incomplete: 1,
+            warning: None,
+        }));
+        assert!(matches!(faker.build_periodic_announce_plan().request.event, TrackerEvent::None));
+
+        // A new session has to be acknowledged again
+        faker.stats.state = FakerState::Stopped;
+        assert!(faker.begin_start().is_some());
+        let next = faker.build_periodic_announce_plan();
+        assert!(matches!(next.request.event, TrackerEvent::Started));
+    }
+
+    #[test]
+    fn tracker_retry_backoff_caps_at_max_interval() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [20u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+
+        let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
+        assert!(faker.is_ok());
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+
+        for _ in 0..6 {
+            faker.apply_tracker_error(&FakerError::TrackerError(TrackerError::HttpError(
+                "connection refused".to_string(),
+            )));
+        }
 
         assert_eq!(faker.stats.tracker_retry_attempt, 6);
         let retry_at = faker.stats.tracker_retry_at_ms.unwrap_or_default();
         let now = RatioFaker::current_timestamp_millis();
         let delay_ms = retry_at.saturating_sub(now);
-        assert!(delay_ms <= 300_000);
-        assert!(delay_ms > 0);
+        assert!(delay_ms <= 960_000);
+        assert!(delay_ms > 900_000);
+
+        let interval = Duration::from_mins(30);
+        let delays: Vec<u64> =
+            (1..=8).map(|n| RatioFaker::announce_backoff_secs(n, interval)).collect();
+        assert_eq!(delays, vec![30, 60, 120, 240, 480, 960, 1800, 1800]);
+        assert_eq!(RatioFaker::announce_backoff_secs(8, Duration::from_mins(15)), 900);
+        assert_eq!(RatioFaker::announce_backoff_secs(3, Duration::from_secs(10)), 30);
+        assert_eq!(RatioFaker::announce_backoff_secs(u32::MAX, interval), 1800);
     }
 
     #[test]
     fn tracker_recovery_failures_increase_backoff_attempts() {
         let torrent = Arc::new(TorrentInfo {
             info_hash: [21u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -2529,8 +4508,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         });
 
         let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
@@ -2559,6 +4540,7 @@ mod tests {
     fn start_failure_keeps_faker_stopped_for_tracker_issues() {
         let torrent = Arc::new(TorrentInfo {
             info_hash: [14u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -2569,8 +4551,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         });
 
         let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
@@ -2591,6 +4575,7 @@ mod tests {
     fn pause_clears_current_rates() {
         let torrent = Arc::new(TorrentInfo {
             info_hash: [15u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -2601,8 +4586,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         });
 
         let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
@@ -2613,8 +4600,8 @@ mod tests {
         faker.stats.current_upload_rate = 42.0;
         faker.stats.current_download_rate = 24.0;
 
-        let paused = faker.pause();
-        assert!(paused.is_ok());
+        // Nothing to announce unless `announce_on_pause` is set
+        assert!(faker.begin_pause().is_none());
 
         assert!(matches!(faker.stats.state, FakerState::Paused));
         assert_eq!(faker.stats.current_upload_rate, 0.0);
@@ -2625,6 +4612,7 @@ mod tests {
     fn stop_result_clears_current_rates() {
         let torrent = Arc::new(TorrentInfo {
             info_hash: [16u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -2635,8 +4623,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         });
 
         let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
@@ -2655,4 +4645,1227 @@ mod tests {
         assert_eq!(faker.stats.current_upload_rate, 0.0);
         assert_eq!(faker.stats.current_download_rate, 0.0);
     }
+
+    #[test]
+    fn partial_completion_round_trips_to_exact_left() {
+        for total_size in [1_000_003u64, 7_340_033, 4_294_967_311, 123_456_789_012_347] {
+            for left in [0, 1, total_size / 3, total_size * 63 / 100, total_size - 1, total_size] {
+                let completion = RatioFaker::completion_for_left(total_size, left);
+                assert_eq!(RatioFaker::left_for_completion(total_size, completion), left);
+            }
+        }
+    }
+
+    #[test]
+    fn bonus_formula_is_validated_and_estimated_from_stats() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [25u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 2 * 1024 * 1024 * 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+
+        let invalid = FakerConfig {
+            bonus_formula: Some("size * bogus".to_string()),
+            ..FakerConfig::default()
+        };
+        assert!(RatioFaker::new(Arc::clone(&torrent), invalid, None).is_err());
+
+        let config =
+            FakerConfig { bonus_formula: Some("linear".to_string()), ..FakerConfig::default() };
+        let faker = RatioFaker::new(Arc::clone(&torrent), config, None);
+        assert!(faker.is_ok());
+        let faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+
+        let mut stats = faker.stats_snapshot();
+        stats.elapsed_time = Duration::from_hours(3);
+        let formula = faker.bonus_formula.as_ref();
+        assert_eq!(RatioFaker::estimate_bonus(formula, &stats, torrent.total_size), Some(6.0));
+        assert_eq!(RatioFaker::estimate_bonus(None, &stats, torrent.total_size), None);
+    }
+
+    #[test]
+    fn stop_paths_record_stop_reason() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [24u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+
+        let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
+        assert!(faker.is_ok());
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+
+        faker.stats.state = FakerState::Running;
+        let plan = faker.begin_stop(StopReason::StopConditionMet { which: "ratio".to_string() });
+        assert!(plan.is_some());
+        faker.apply_stop_result(Ok(AnnounceResponse {
+            interval: 1800,
+            min_interval: None,
+            tracker_id: None,
+            complete: 0,
+            incomplete: 0,
+            warning: None,
+        }));
+        assert_eq!(
+            faker.stats.stop_reason,
+            Some(StopReason::StopConditionMet { which: "ratio".to_string() })
+        );
+
+        let plan = faker.begin_start();
+        assert!(plan.is_some());
+        assert!(faker.stats.stop_reason.is_none());
+
+        faker.apply_tracker_error(&FakerError::TrackerError(TrackerError::HttpError(
+            "connection refused".to_string(),
+        )));
+        assert_eq!(
+            faker.stats.stop_reason,
+            Some(StopReason::Error { msg: "Tracker unavailable".to_string() })
+        );
+    }
+
+    #[test]
+    fn idle_announce_interval_is_floored_at_min_interval() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [23u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+
+        let config = FakerConfig { idle_announce_interval: Some(60), ..FakerConfig::default() };
+        let faker = RatioFaker::new(torrent, config, None);
+        assert!(faker.is_ok());
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+
+        faker.set_announce_intervals(&AnnounceResponse {
+            interval: 1800,
+            min_interval: Some(900),
+            tracker_id: None,
+            complete: 0,
+            incomplete: 0,
+            warning: None,
+        });
+
+        assert_eq!(faker.next_announce_delay(), Duration::from_mins(30));
+
+        faker.stats.is_idling = true;
+        assert_eq!(faker.next_announce_delay(), Duration::from_mins(15));
+
+        faker.min_announce_interval = None;
+        assert_eq!(faker.next_announce_delay(), Duration::from_mins(1));
+
+        let last = Instant::now();
+        faker.stats.last_announce = Some(last);
+        faker.stats.next_announce = Some(last + Duration::from_mins(30));
+        faker.pull_idle_announce_forward();
+        assert_eq!(faker.stats.next_announce, Some(last + Duration::from_mins(1)));
+    }
+
+    #[test]
+    fn first_leecher_while_idling_requests_announce() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [26u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+
+        let config = FakerConfig { announce_on_first_leecher: true, ..FakerConfig::default() };
+        let faker = RatioFaker::new(torrent, config, None);
+        assert!(faker.is_ok());
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+
+        let scrape = |leechers| {
+            Ok(crate::protocol::ScrapeResponse {
+                complete: 3,
+                incomplete: leechers,
+                downloaded: 0,
+                name: None,
+            })
+        };
+        let now = Instant::now();
+        faker.stats.next_announce = Some(now + Duration::from_mins(30));
+        faker.stats.is_idling = true;
+
+        assert!(!faker.apply_scrape_result(&scrape(0), now));
+        assert!(faker.apply_scrape_result(&scrape(2), now));
+        assert!(!faker.apply_scrape_result(&scrape(3), now));
+
+        faker.config.announce_on_first_leecher = false;
+        faker.stats.leechers = 0;
+        assert!(!faker.apply_scrape_result(&scrape(1), now));
+    }
+
+    #[test]
+    fn failed_scrapes_back_off_then_cool_down() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [27u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+        let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
+        assert!(faker.is_ok());
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+
+        let now = Instant::now();
+        let failure = Err(FakerError::TrackerError(TrackerError::HttpError("timeout".into())));
+        let mut delays = Vec::new();
+        for _ in 0..4 {
+            faker.apply_scrape_result(&failure, now);
+            delays.push(faker.scrape_retry_at.map(|at| at.duration_since(now).as_secs()));
+        }
+        assert_eq!(delays, vec![Some(30), Some(120), Some(600), Some(SCRAPE_COOLDOWN_SECS)]);
+        assert!(faker.stats.tracker_error.is_none());
+
+        let scrape = Ok(crate::protocol::ScrapeResponse {
+            complete: 4,
+            incomplete: 2,
+            downloaded: 0,
+            name: None,
+        });
+        faker.apply_scrape_result(&scrape, now);
+        assert_eq!(faker.scrape_consecutive_failures, 0);
+        assert!(faker.scrape_retry_at.is_none());
+    }
+
+    #[test]
+    fn hnr_protection_blocks_stop_conditions_until_requirement_met() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [31u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+        let config = FakerConfig {
+            stop_at_ratio: Some(0.5),
+            hnr_min_seed_time: Some(3600),
+            hnr_min_ratio: Some(2.0),
+            ..FakerConfig::default()
+        };
+
+        let faker = RatioFaker::new(torrent, config, None);
+        assert!(faker.is_ok());
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+        assert!(faker.hnr_block_reason().is_none());
+
+        faker.stats.state = FakerState::Running;
+        faker.stats.ratio = 1.0;
+        faker.stats.elapsed_time = Duration::from_mins(10);
+
+        let reason = faker.hnr_block_reason();
+        assert!(reason.as_deref().is_some_and(|reason| reason.contains("3000s")));
+        assert!(faker.check_stop_conditions(&faker.stats).is_none());
+
+        faker.stats.elapsed_time = Duration::from_hours(1);
+        assert!(faker.hnr_block_reason().is_none());
+        assert_eq!(faker.check_stop_conditions(&faker.stats), Some("ratio"));
+    }
+
+    #[test]
+    fn round_floats_trims_noise_from_rates_and_ratios() {
+        let mut stats = RatioFaker::stats_from_config(&FakerConfig::default());
+        stats.ratio = 2.000_000_000_4;
+        stats.current_upload_rate = 51.234_567;
+        stats.effective_stop_at_ratio = Some(1.999_6);
+        stats.ratio_history = vec![0.123_456];
+
+        stats.round_floats(3);
+
+        assert_eq!(stats.ratio, 2.0);
+        assert_eq!(stats.current_upload_rate, 51.235);
+        assert_eq!(stats.effective_stop_at_ratio, Some(2.0));
+        assert_eq!(stats.ratio_history, vec![0.123]);
+        assert!(round_to_decimals(f64::NAN, 3).is_nan());
+    }
+
+    #[test]
+    fn negative_swarm_counts_are_clamped_to_zero() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [33u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+        let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
+        assert!(faker.is_ok());
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+
+        let scrape = Ok(crate::protocol::ScrapeResponse {
+            complete: -3,
+            incomplete: -1,
+            downloaded: 0,
+            name: None,
+        });
+        faker.apply_scrape_result(&scrape, Instant::now());
+        assert_eq!((faker.stats.seeders, faker.stats.leechers), (0, 0));
+
+        faker.set_swarm_counts(i64::MAX, 7);
+        assert_eq!((faker.stats.seeders, faker.stats.leechers), (MAX_PLAUSIBLE_PEER_COUNT, 7));
+    }
+
+    #[test]
+    fn announce_interval_is_clamped_to_configured_bounds() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [34u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+        let config = FakerConfig { max_announce_interval: Some(600), ..FakerConfig::default() };
+        let faker = RatioFaker::new(Arc::clone(&torrent), config, None);
+        assert!(faker.is_ok());
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+
+        let response = |interval, min_interval| AnnounceResponse {
+            interval,
+            min_interval,
+            tracker_id: None,
+            complete: 0,
+            incomplete: 0,
+            warning: None,
+        };
+        faker.set_announce_intervals(&response(3600, None));
+        assert_eq!(faker.next_announce_delay(), Duration::from_mins(10));
+
+        faker.set_announce_intervals(&response(3600, Some(900)));
+        assert_eq!(faker.next_announce_delay(), Duration::from_mins(15));
+
+        faker.config.max_announce_interval = None;
+        faker.config.min_announce_interval = Some(1800);
+        faker.set_announce_intervals(&response(120, None));
+        assert_eq!(faker.next_announce_delay(), Duration::from_mins(30));
+
+        let invalid = FakerConfig {
+            min_announce_interval: Some(900),
+            max_announce_interval: Some(300),
+            ..FakerConfig::default()
+        };
+        assert!(RatioFaker::new(torrent, invalid, None).is_err());
+    }
+
+    #[test]
+    fn start_regenerates_identity_unless_pinned() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [37u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+        let restart = |faker: &mut RatioFaker| {
+            faker.stats.state = FakerState::Stopped;
+            faker.stats.uploaded = 4096;
+            assert!(faker.begin_start().is_some());
+            assert_eq!(faker.stats.uploaded, 4096);
+            (faker.peer_id.clone(), faker.key.clone())
+        };
+
+        let faker = RatioFaker::new(Arc::clone(&torrent), FakerConfig::default(), None);
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+        let first = restart(&mut faker);
+        let second = restart(&mut faker);
+        assert_ne!(first.0, second.0);
+        assert_ne!(first.1, second.1);
+        assert!(second.0.starts_with("-qB"));
+
+        let pinned = FakerConfig { regenerate_identity_on_start: false, ..FakerConfig::default() };
+        let faker = RatioFaker::new(torrent, pinned, None);
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+        let identity = (faker.peer_id.clone(), faker.key.clone());
+        assert_eq!(restart(&mut faker), identity);
+        assert_eq!(restart(&mut faker), identity);
+    }
+
+    #[test]
+    fn peer_id_override_is_announced_and_survives_restarts() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [41u8; 20],
+            info_hash_v2: None,
+            announce: "http://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "override".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+        let pinned = "-qB4670-Xy9Zw8Vu7Ts6";
+        let config = FakerConfig {
+            peer_id_override: Some(pinned.to_string()),
+            custom_user_agent: Some("qBittorrent/4.6.7".to_string()),
+            ..FakerConfig::default()
+        };
+
+        let faker = RatioFaker::new(Arc::clone(&torrent), config.clone(), None);
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+        assert_eq!(faker.peer_id, pinned);
+        faker.stats.state = FakerState::Stopped;
+        assert!(faker.begin_start().is_some());
+        assert_eq!(faker.peer_id, pinned);
+
+        let cleared = FakerConfig { peer_id_override: None, ..config.clone() };
+        assert!(faker.update_config(cleared, None).is_ok());
+        assert_ne!(faker.peer_id, pinned);
+        assert!(faker.peer_id.starts_with("-qB"));
+
+        let short = FakerConfig { peer_id_override: Some("-qB4670-short".to_string()), ..config };
+        assert!(RatioFaker::validate_config(&short).is_err());
+        assert!(RatioFaker::new(torrent, short, None).is_err());
+    }
+
+    #[test]
+    fn selected_files_limit_left_and_completion() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [39u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: false,
+            is_private: false,
+            file_count: 3,
+            files: Vec::new(),
+            file_lengths: vec![600, 300, 124],
+        });
+        let config = FakerConfig {
+            completion_percent: 50.0,
+            selected_files: Some(vec![1, 2, 2]),
+            ..FakerConfig::default()
+        };
+        let faker = RatioFaker::new(Arc::clone(&torrent), config.clone(), None);
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+        assert_eq!(faker.stats.left, 212);
+        assert_eq!(faker.stats.torrent_completion, 50.0);
+
+        let whole = FakerConfig { selected_files: None, ..config.clone() };
+        assert!(faker.update_config(whole, None).is_ok());
+        assert_eq!(faker.stats.left, 512);
+
+        for selected in [vec![], vec![3]] {
+            let invalid = FakerConfig { selected_files: Some(selected), ..config.clone() };
+            assert!(RatioFaker::new(Arc::clone(&torrent), invalid, None).is_err());
+        }
+    }
+
+    #[test]
+    fn announce_request_follows_emulated_client() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [38u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+        let request = |config: FakerConfig| {
+            let faker = RatioFaker::new(Arc::clone(&torrent), config, None);
+            let faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+            faker.build_announce_request(TrackerEvent::None)
+        };
+
+        let utorrent = FakerConfig { client_type: ClientType::UTorrent, ..FakerConfig::default() };
+        let announce = request(utorrent);
+        assert_eq!(announce.numwant, Some(200));
+        assert!(announce.compact && announce.no_peer_id);
+
+        let transmission =
+            FakerConfig { client_type: ClientType::Transmission, ..FakerConfig::default() };
+        let announce = request(transmission);
+        assert_eq!(announce.numwant, Some(80));
+        assert!(!announce.no_peer_id);
+
+        let custom = FakerConfig { num_want: Some(30), compact: false, ..FakerConfig::default() };
+        let announce = request(custom);
+        assert_eq!(announce.numwant, Some(30));
+        assert!(!announce.compact);
+    }
+
+    /// Answer `count` announces with a fixed interval and hand back the request heads
+    async fn serve_announces(listener: tokio::net::TcpListener, count: usize) -> Vec<String> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut requests = Vec::new();
+        for _ in 0..count {
+            let (mut socket, _) = listener.accept().await.unwrap_or_else(|e| panic!("{e}"));
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap_or_else(|e| panic!("{e}"));
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            let response = b"HTTP/1.1 200 OK\r\nContent-Length: 18\r\n\r\nd8:intervali1800ee";
+            socket.write_all(response).await.unwrap_or_else(|e| panic!("{e}"));
+            requests.push(String::from_utf8_lossy(&request).into_owned());
+        }
+        requests
+    }
+
+    /// A hybrid torrent whose v1 and truncated v2 hashes encode as `%61…` and `%62…`
+    fn hybrid_torrent(announce: String, is_private: bool) -> Arc<TorrentInfo> {
+        Arc::new(TorrentInfo {
+            info_hash: [b'a'; 20],
+            info_hash_v2: Some([b'b'; 32]),
+            announce,
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        })
+    }
+
+    #[tokio::test]
+    async fn hybrid_torrents_announce_the_v2_hash_only_when_enabled() {
+        let listener =
+            tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap_or_else(|e| panic!("{e}"));
+        let addr = listener.local_addr().unwrap_or_else(|e| panic!("{e}"));
+        let mut tracker = tokio::spawn(serve_announces(listener, 3));
+        let torrent = hybrid_torrent(format!("http://{addr}/announce"), false);
+        let faker = RatioFaker::new(Arc::clone(&torrent), FakerConfig::default(), None)
+            .unwrap_or_else(|_| panic!("failed to create faker"));
+
+        // Off by default: `stopped` goes out once, under the v1 hash
+        let response = faker.announce_plan(TrackerEvent::Stopped).execute().await;
+        assert!(response.is_ok());
+        let second = tokio::time::timeout(Duration::from_millis(200), &mut tracker).await;
+        assert!(second.is_err(), "the v2 hash was announced without announce_v2_hash");
+
+        let config = FakerConfig {
+            announce_v2_hash: true,
+            record_last_announce: true,
+            ..FakerConfig::default()
+        };
+        let faker = RatioFaker::new(torrent, config, None)
+            .unwrap_or_else(|_| panic!("failed to create faker"));
+        let response = faker.announce_plan(TrackerEvent::Stopped).execute().await;
+        let requests = tracker.await.unwrap_or_else(|e| panic!("{e}"));
+
+        assert_eq!(response.map(|r| r.interval).ok(), Some(1800));
+        assert!(requests[1].contains(&format!("info_hash={}&", "%61".repeat(20))));
+        assert!(requests[2].contains(&format!("info_hash={}&", "%62".repeat(20))));
+        assert!(requests.iter().all(|request| request.contains("event=stopped")));
+        let last = faker.last_announce().unwrap_or_else(|| panic!("announce not recorded"));
+        assert!(last.exchange.url.contains(&"%62".repeat(20)));
+
+        // Private torrents never announce the second hash
+        let private = hybrid_torrent(format!("http://{addr}/announce"), true);
+        let config = FakerConfig { announce_v2_hash: true, ..FakerConfig::default() };
+        let faker = RatioFaker::new(private, config, None)
+            .unwrap_or_else(|_| panic!("failed to create faker"));
+        assert!(faker.announce_plan(TrackerEvent::Stopped).info_hash_v2.is_none());
+    }
+
+    #[test]
+    fn announce_jitter_is_stable_per_peer_id_and_bounded() {
+        let offsets: Vec<i64> =
+            (0..50).map(|n| announce_jitter_offset_secs(&format!("-qB5000-{n:012}"), 30)).collect();
+        assert!(offsets.iter().all(|offset| (-30..=30).contains(offset)));
+        assert!(offsets.iter().any(|offset| *offset != offsets[0]));
+        assert_eq!(announce_jitter_offset_secs("-qB5000-000000000007", 30), offsets[7]);
+        assert_eq!(announce_jitter_offset_secs("-qB5000-000000000007", 0), 0);
+
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [36u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+        let config = FakerConfig { announce_jitter_secs: 600, ..FakerConfig::default() };
+        let faker = RatioFaker::new(torrent, config, None);
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+        faker.set_announce_intervals(&AnnounceResponse {
+            interval: 1800,
+            min_interval: Some(1800),
+            tracker_id: None,
+            complete: 0,
+            incomplete: 0,
+            warning: None,
+        });
+        let delay = faker.jittered_announce_delay();
+        assert!(delay >= Duration::from_mins(30) && delay <= Duration::from_mins(40));
+
+        // Jitter never pushes an announce past the configured maximum
+        faker.config.max_announce_interval = Some(1800);
+        faker.config.announce_jitter_secs = MAX_ANNOUNCE_JITTER_SECS;
+        faker.set_announce_intervals(&AnnounceResponse {
+            interval: 1800,
+            min_interval: None,
+            tracker_id: None,
+            complete: 0,
+            incomplete: 0,
+            warning: None,
+        });
+        assert!(faker.jittered_announce_delay() <= Duration::from_mins(30));
+    }
+
+    #[test]
+    fn tracker_failure_reason_is_kept_and_backs_off() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [35u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+        let faker = RatioFaker::new(torrent, FakerConfig::default(), None);
+        assert!(faker.is_ok());
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+
+        faker.stats.state = FakerState::Running;
+        let before_ms = RatioFaker::current_timestamp_millis();
+        faker.apply_tracker_error(&FakerError::TrackerError(TrackerError::TrackerFailure(
+            "Tracker is in maintenance".to_string(),
+        )));
+
+        assert_eq!(faker.stats.tracker_error.as_deref(), Some("Tracker unavailable"));
+        assert_eq!(
+            faker.stats.tracker_failure_reason.as_deref(),
+            Some("Tracker is in maintenance")
+        );
+        assert!(faker
+            .stats
+            .tracker_retry_at_ms
+            .is_some_and(|at| at >= before_ms + TRACKER_FAILURE_RETRY_SCHEDULE_SECS[0] * 1000));
+
+        faker.apply_start_result(Ok(AnnounceResponse {
+            interval: 1800,
+            min_interval: None,
+            tracker_id: None,
+            complete: 1,
+            incomplete: 0,
+            warning: Some("Slow down".to_string()),
+        }));
+        assert!(faker.stats.tracker_failure_reason.is_none());
+        assert_eq!(faker.stats.tracker_warning.as_deref(), Some("Slow down"));
+    }
+
+    #[test]
+    fn stop_at_timestamp_uses_wall_clock() {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [36u8; 20],
+            info_hash_v2: None,
+            announce: "https://tracker.test/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+        let now_secs = RatioFaker::current_timestamp_millis() / 1000;
+        let config =
+            FakerConfig { stop_at_timestamp: Some(now_secs + 3600), ..FakerConfig::default() };
+        let faker = RatioFaker::new(torrent, config, None);
+        assert!(faker.is_ok());
+        let mut faker = faker.unwrap_or_else(|_| panic!("failed to create faker"));
+
+        assert!(faker.check_stop_conditions(&faker.stats).is_none());
+
+        faker.config.stop_at_timestamp = Some(now_secs.saturating_sub(60));
+        assert_eq!(faker.check_stop_conditions(&faker.stats), Some("stop_time"));
+
+        let preset = PresetSettings {
+            stop_at_datetime_enabled: Some(true),
+            stop_at_datetime: Some("2025-01-01T06:00Z".to_string()),
+            ..Default::default()
+        };
+        assert!(preset.validate().is_ok());
+        assert_eq!(FakerConfig::from(preset).stop_at_timestamp, Some(1_735_711_200));
+
+        let typo = PresetSettings {
+            stop_at_datetime_enabled: Some(true),
+            stop_at_datetime: Some("01/01/2025 06:00".to_string()),
+            ..Default::default()
+        };
+        assert!(typo.validate().is_err());
+        let disabled = PresetSettings { stop_at_datetime_enabled: Some(false), ..typo };
+        assert!(disabled.validate().is_ok());
+    }
+
+    #[test]
+    fn upload_before_complete_is_capped_by_download() {
+        let mut stats = RatioFaker::new(
+            Arc::new(TorrentInfo {
+                info_hash: [37u8; 20],
+                info_hash_v2: None,
+                announce: "https://tracker.test/announce".to_string(),
+                announce_list: None,
+                name: "sample".to_string(),
+                total_size: 10 * 1024 * 1024,
+                piece_length: 256,
+                num_pieces: 4,
+                creation_date: None,
+                comment: None,
+                created_by: None,
+                is_single_file: true,
+                is_private: false,
+                file_count: 1,
+                files: Vec::new(),
+                file_lengths: Vec::new(),
+            }),
+            FakerConfig::default(),
+            None,
+        )
+        .unwrap_or_else(|_| panic!("failed to create faker"))
+        .stats;
+        stats.session_downloaded = 100 * 1024;
+        stats.session_uploaded = 40 * 1024;
+        let tick = Duration::from_secs(1);
+
+        let capped =
+            FakerConfig { max_upload_before_complete_ratio: Some(0.5), ..FakerConfig::default() };
+        // Budget: (100 KiB + 20 KiB this tick) * 0.5 - 40 KiB = 20 KiB
+        let rate = RatioFaker::cap_upload_before_complete(&stats, &capped, 500.0, 20.0, tick);
+        assert!((rate - 20.0).abs() < 1e-9);
+
+        let seed_only = FakerConfig { seed_only_after_complete: true, ..FakerConfig::default() };
+        assert_eq!(
+            RatioFaker::cap_upload_before_complete(&stats, &seed_only, 500.0, 20.0, tick),
+            0.0
+        );
+
+        stats.left = 0;
+        assert_eq!(
+            RatioFaker::cap_upload_before_complete(&stats, &seed_only, 500.0, 0.0, tick),
+            500.0
+        );
+    }
+
+    /// A running faker on a mock clock. Nothing listens on its tracker, so tracker
+    /// requests fail fast.
+    fn clocked_faker(config: FakerConfig) -> (RatioFaker, MockClock) {
+        let torrent = Arc::new(TorrentInfo {
+            info_hash: [31u8; 20],
+            info_hash_v2: None,
+            announce: "http://127.0.0.1:1/announce".to_string(),
+            announce_list: None,
+            name: "sample".to_string(),
+            total_size: 1024,
+            piece_length: 256,
+            num_pieces: 4,
+            creation_date: None,
+            comment: None,
+            created_by: None,
+            is_single_file: true,
+            is_private: false,
+            file_count: 1,
+            files: Vec::new(),
+            file_lengths: Vec::new(),
+        });
+        let clock = MockClock::new();
+        let mut faker = RatioFaker::with_clock(torrent, config, None, Arc::new(clock.clone()))
+            .unwrap_or_else(|_| panic!("failed to create faker"));
+        faker.stats.state = FakerState::Running;
+        (faker, clock)
+    }
+
+    async fn advance_and_update(faker: &mut RatioFaker, clock: &MockClock, secs: u64) {
+        clock.advance(Duration::from_secs(secs));
+        faker.update_stats_only().await.unwrap_or_else(|_| panic!("update failed"));
+    }
+
+    #[tokio::test]
+    async fn completion_is_announced_once_per_torrent() {
+        let (mut faker, clock) = clocked_faker(FakerConfig {
+            download_rate: 100.0,
+            randomize_rates: false,
+            completion_percent: 50.0,
+            scrape_enabled: false,
+            dry_run: true,
+            ..FakerConfig::default()
+        });
+        faker.stats.left = 512;
+
+        advance_and_update(&mut faker, &clock, 60).await;
+        assert_eq!(faker.stats.left, 0);
+        assert!(faker.stats.completed_already_sent);
+        assert_eq!(faker.stats.announce_count, 1);
+
+        // A later session that finishes the download again (e.g. restored from a
+        // restart below 100%) does not re-send it
+        faker.stats.left = 512;
+        advance_and_update(&mut faker, &clock, 60).await;
+        assert_eq!(faker.stats.left, 0);
+        assert_eq!(faker.stats.announce_count, 1);
+        assert!(faker.completion_announce_plan(true).is_none());
+
+        faker.stats.completed_already_sent = false;
+        faker.apply_completion_result(Err(FakerError::InvalidState("offline".to_string())));
+        assert!(!faker.stats.completed_already_sent);
+    }
+
+    #[tokio::test]
+    async fn update_stats_only_follows_simulated_time() {
+        let config = FakerConfig {
+            upload_rate: 10.0,
+            target_upload_rate: Some(110.0),
+            progressive_rates: true,
+            progressive_duration: 100,
+            randomize_rates: false,
+            completion_percent: 100.0,
+            scrape_interval: 60,
+            ..FakerConfig::default()
+        };
+
+        // Coarse polling: the rate interpolates on time since start
+        let (mut faker, clock) = clocked_faker(config.clone());
+        let mut rates = Vec::new();
+        for secs in [25, 25, 9] {
+            advance_and_update(&mut faker, &clock, secs).await;
+            rates.push(faker.stats.current_upload_rate);
+        }
+        assert_eq!(rates, vec![35.0, 60.0, 69.0]);
+        assert_eq!(faker.scrape_consecutive_failures, 0);
+
+        // The scrape fires once `scrape_interval` has passed
+        advance_and_update(&mut faker, &clock, 1).await;
+        assert_eq!(faker.scrape_consecutive_failures, 1);
+
+        advance_and_update(&mut faker, &clock, 90).await;
+        assert_eq!(faker.stats.current_upload_rate, 110.0);
+
+        // Polling every second reaches the same rate at the same time
+        let (mut faker, clock) = clocked_faker(config);
+        for _ in 0..50 {
+            advance_and_update(&mut faker, &clock, 1).await;
+        }
+        assert_eq!(faker.stats.current_upload_rate, 60.0);
+        assert_eq!(faker.scrape_consecutive_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn seed_time_counts_down_then_stops_the_faker() {
+        let (mut faker, clock) = clocked_faker(FakerConfig {
+            stop_at_seed_time: Some(120),
+            post_stop_action: PostStopAction::StopSeeding,
+            completion_percent: 100.0,
+            scrape_enabled: false,
+            ..FakerConfig::default()
+        });
+
+        advance_and_update(&mut faker, &clock, 30).await;
+        assert_eq!(faker.stats.eta_seed_time, Some(Duration::from_secs(90)));
+        assert_eq!(faker.stats.seed_time_progress, 25.0);
+
+        advance_and_update(&mut faker, &clock, 60).await;
+        assert_eq!(faker.stats.eta_seed_time, Some(Duration::from_secs(30)));
+        assert!(matches!(faker.stats.state, FakerState::Running));
+
+        advance_and_update(&mut faker, &clock, 30).await;
+        assert_eq!(faker.stats.eta_seed_time, Some(Duration::ZERO));
+        assert!(matches!(faker.stats.state, FakerState::Stopped));
+        assert!(matches!(
+            faker.stats.stop_reason,
+            Some(StopReason::StopConditionMet { ref which }) if which == "seed_time"
+        ));
+    }
+
+    #[tokio::test]
+    async fn dry_run_simulates_the_tracker_and_still_stops() {
+        let (mut faker, clock) = clocked_faker(FakerConfig {
+            dry_run: true,
+            upload_rate: 100.0,
+            randomize_rates: false,
+            completion_percent: 100.0,
+            stop_at_uploaded: Some(1_000 * 1024),
+            post_stop_action: PostStopAction::StopSeeding,
+            ..FakerConfig::default()
+        });
+        faker.stats.state = FakerState::Stopped;
+
+        assert!(faker.start().await.is_ok());
+        assert!(faker.stats.tracker_error.is_none());
+        assert_eq!(faker.announce_count(), 1);
+        let (seeders, leechers) = simulated_swarm(&[31u8; 20]);
+        assert_eq!((faker.stats.seeders, faker.stats.leechers), (seeders, leechers));
+
+        advance_and_update(&mut faker, &clock, 5).await;
+        assert!(matches!(faker.stats.state, FakerState::Running));
+        advance_and_update(&mut faker, &clock, 5).await;
+        assert!(matches!(faker.stats.state, FakerState::Stopped));
+        assert!(faker.stats.tracker_error.is_none());
+    }
+
+    #[tokio::test]
+    async fn implausible_upload_rates_are_clamped_and_warned_about() {
+        let config = FakerConfig {
+            upload_rate: 1_000_000.0,
+            randomize_rates: false,
+            completion_percent: 100.0,
+            scrape_enabled: false,
+            ..FakerConfig::default()
+        };
+        let warning = RatioFaker::implausible_upload_warning(&config, 1024 * 1024 * 1024);
+        assert!(warning.is_some_and(|w| w.contains("3433 copies")));
+        assert!(RatioFaker::implausible_upload_warning(&FakerConfig::default(), 1024).is_none());
+
+        let (mut faker, clock) = clocked_faker(config.clone());
+        advance_and_update(&mut faker, &clock, 1).await;
+        assert_eq!(faker.stats.current_upload_rate, DEFAULT_MAX_PLAUSIBLE_UPLOAD_KBPS);
+
+        let (mut faker, clock) =
+            clocked_faker(FakerConfig { max_plausible_upload_kbps: 0.0, ..config.clone() });
+        advance_and_update(&mut faker, &clock, 1).await;
+        assert_eq!(faker.stats.current_upload_rate, 1_000_000.0);
+
+        let invalid = FakerConfig { max_plausible_upload_kbps: -1.0, ..config };
+        assert!(RatioFaker::validate_max_plausible_upload(&invalid).is_err());
+
+        // What the session actually uploaded in the time it ran is judged too
+        let mut stats = faker.stats.clone();
+        stats.elapsed_time = Duration::from_mins(10);
+        stats.session_uploaded = 20 * 1024 * 1024 * 1024;
+        let warning = RatioFaker::implausible_session_warning(&stats, 1024 * 1024 * 1024);
+        assert!(
+            warning.is_some_and(|w| w.contains("20.0 GiB in 10 min") && w.contains("20 copies"))
+        );
+        stats.session_uploaded = 1024 * 1024 * 1024;
+        assert!(RatioFaker::implausible_session_warning(&stats, 1024 * 1024 * 1024).is_none());
+
+        let preset =
+            PresetSettings { max_plausible_upload_kbps: Some(0.0), ..PresetSettings::default() };
+        assert_eq!(FakerConfig::from(preset).max_plausible_upload_kbps, 0.0);
+    }
+
+    #[tokio::test]
+    async fn pausing_announces_stopped_when_configured() {
+        let (mut faker, _clock) =
+            clocked_faker(FakerConfig { announce_on_pause: true, ..FakerConfig::default() });
+        faker.stats.announce_count = 1;
+        faker.stats.uploaded = 4096;
+        faker.stats.current_upload_rate = 42.0;
+
+        let plan = faker.begin_pause().unwrap_or_else(|| panic!("expected a pause announce"));
+        assert!(matches!(plan.request.event, TrackerEvent::Stopped));
+        assert_eq!(plan.request.uploaded, 4096);
+        assert!(matches!(faker.stats.state, FakerState::Paused));
+        assert_eq!(faker.stats.current_upload_rate, 0.0);
+
+        // Already paused: the tracker was told, so nothing is sent again
+        assert!(faker.begin_pause().is_none());
+
+        // A failed pause announce still leaves the faker paused
+        faker.stats.state = FakerState::Running;
+        faker.pause().await.unwrap_or_else(|_| panic!("pause failed"));
+        assert!(matches!(faker.stats.state, FakerState::Paused));
+        assert_eq!(faker.stats.announce_count, 1);
+    }
+
+    #[test]
+    fn silent_pause_never_announces() {
+        let (mut faker, _clock) =
+            clocked_faker(FakerConfig { announce_on_pause: true, ..FakerConfig::default() });
+        faker.stats.announce_count = 1;
+        faker.stats.current_upload_rate = 42.0;
+
+        faker.pause_silently();
+        assert!(matches!(faker.stats.state, FakerState::Paused));
+        assert_eq!(faker.stats.current_upload_rate, 0.0);
+        assert_eq!(faker.stats.announce_count, 1);
+    }
+
+    #[tokio::test]
+    async fn resuming_announces_started_unless_disabled() {
+        let (mut faker, clock) = clocked_faker(FakerConfig::default());
+        faker.stats.uploaded = 4096;
+        assert!(faker.begin_pause().is_none());
+
+        let plan = faker.begin_resume().unwrap_or_else(|| panic!("expected a resume announce"));
+        assert!(matches!(plan.request.event, TrackerEvent::Started));
+        assert_eq!(plan.request.uploaded, 4096);
+        assert!(matches!(faker.stats.state, FakerState::Running));
+
+        // Resuming an instance that was not paused re-registers nothing
+        assert!(faker.begin_resume().is_none());
+
+        // A failed resume announce keeps the faker running and retries as `started`
+        assert!(faker.begin_pause().is_none());
+        faker.resume().await.unwrap_or_else(|_| panic!("resume failed"));
+        assert!(matches!(faker.stats.state, FakerState::Running));
+        assert!(faker.stats.next_announce.is_some_and(|next| next > clock.now()));
+        let retry = faker.build_periodic_announce_plan();
+        assert!(matches!(retry.request.event, TrackerEvent::Started));
+
+        let (mut faker, clock) =
+            clocked_faker(FakerConfig { announce_on_resume: false, ..FakerConfig::default() });
+        assert!(faker.begin_pause().is_none());
+        assert!(faker.begin_resume().is_none());
+        assert_eq!(faker.stats.next_announce, Some(clock.now()));
+    }
+
+    #[test]
+    fn announced_pause_reregisters_with_started_even_without_resume_announce() {
+        let (mut faker, _clock) = clocked_faker(FakerConfig {
+            announce_on_pause: true,
+            announce_on_resume: false,
+            ..FakerConfig::default()
+        });
+        faker.stats.announce_count = 1;
+        faker.started_acknowledged = true;
+
+        assert!(faker.begin_pause().is_some());
+        faker.apply_pause_result(&Ok(AnnounceResponse {
+            interval: 1800,
+            min_interval: None,
+            tracker_id: None,
+            complete: 0,
+            incomplete: 0,
+            warning: None,
+        }));
+        assert!(faker.begin_resume().is_none());
+
+        let next = faker.build_periodic_announce_plan();
+        assert!(matches!(next.request.event, TrackerEvent::Started));
+    }
+
+    #[tokio::test]
+    async fn last_announce_is_kept_only_when_recording() {
+        let (mut faker, _clock) =
+            clocked_faker(FakerConfig { record_last_announce: true, ..FakerConfig::default() });
+
+        assert!(faker.announce_plan(TrackerEvent::Started).execute().await.is_err());
+        let last = faker.last_announce().unwrap_or_else(|| panic!("announce not recorded"));
+        assert!(last.exchange.url.starts_with("http://127.0.0.1:1/announce?info_hash="));
+        assert!(last.exchange.url.contains("event=started"));
+        assert_eq!(last.exchange.status, None);
+        assert!(last.exchange.result.is_err());
+
+        faker.config.record_last_announce = false;
+        assert!(faker.announce_plan(TrackerEvent::None).recorder.is_none());
+        assert!(faker.last_announce().is_none());
+    }
+
+    #[tokio::test]
+    async fn paused_time_does_not_count_towards_averages() {
+        let (mut faker, clock) = clocked_faker(FakerConfig {
+            upload_rate: 100.0,
+            randomize_rates: false,
+            completion_percent: 100.0,
+            scrape_enabled: false,
+            ..FakerConfig::default()
+        });
+
+        advance_and_update(&mut faker, &clock, 60).await;
+        assert!(faker.begin_pause().is_none());
+
+        // A frontend still polling during the pause changes nothing
+        advance_and_update(&mut faker, &clock, 3600).await;
+        assert_eq!(faker.stats.elapsed_time, Duration::from_mins(1));
+
+        faker.resume().await.unwrap_or_else(|_| panic!("resume failed"));
+        advance_and_update(&mut faker, &clock, 60).await;
+
+        assert_eq!(faker.stats.elapsed_time, Duration::from_mins(2));
+        assert_eq!(faker.stats.session_uploaded, 100 * 1024 * 120);
+        assert_eq!(faker.stats.average_upload_rate, 100.0);
+    }
+
+    #[test]
+    fn older_history_is_thinned_then_dropped() {
+        let record_every_10s = |config: &FakerConfig| {
+            let mut stats = RatioFaker::stats_from_config(config);
+            for n in 0..20u64 {
+                stats.current_upload_rate = n as f64;
+                RatioFaker::record_history(&mut stats, config, n * 10_000);
+            }
+            stats
+        };
+        let secs = |stats: &FakerStats| -> Vec<u64> {
+            stats.history_timestamps.iter().map(|ms| ms / 1000).collect()
+        };
+
+        let recent_only = FakerConfig { history_length: 3, ..FakerConfig::default() };
+        let stats = record_every_10s(&recent_only);
+        assert_eq!(secs(&stats), vec![170, 180, 190]);
+        assert_eq!(stats.upload_rate_history, vec![17.0, 18.0, 19.0]);
+
+        let archived = FakerConfig {
+            history_length: 3,
+            history_archive_secs: 600,
+            history_archive_resolution_secs: 60,
+            ..FakerConfig::default()
+        };
+        let stats = record_every_10s(&archived);
+        assert_eq!(secs(&stats), vec![0, 60, 120, 170, 180, 190]);
+        assert_eq!(stats.upload_rate_history, vec![0.0, 6.0, 12.0, 17.0, 18.0, 19.0]);
+        assert_eq!(stats.ratio_history.len(), 6);
+
+        let short_archive = FakerConfig { history_archive_secs: 100, ..archived };
+        assert_eq!(secs(&record_every_10s(&short_archive)), vec![120, 170, 180, 190]);
+    }
 }
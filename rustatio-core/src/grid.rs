@@ -1,4 +1,4 @@
-use crate::faker::PresetSettings;
+use crate::faker::{round_to_decimals, PresetSettings, StopReason};
 use crate::torrent::ClientType;
 use serde::{Deserialize, Serialize};
 use url::Url;
@@ -95,7 +95,10 @@ impl GridImportSettings {
 #[serde(rename_all = "camelCase")]
 pub struct InstanceSummary {
     pub id: String,
+    /// Display name: the instance label when set, otherwise the torrent name
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
     pub info_hash: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub primary_tracker_host: Option<String>,
@@ -108,6 +111,8 @@ pub struct InstanceSummary {
     pub tracker_retry_attempt: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tracker_retry_at_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<StopReason>,
     pub tags: Vec<String>,
     pub total_size: u64,
     pub uploaded: u64,
@@ -123,6 +128,16 @@ pub struct InstanceSummary {
     pub created_at: u64,
 }
 
+impl InstanceSummary {
+    /// Round ratio, rate and completion fields to `decimals` places for API output.
+    pub fn round_floats(&mut self, decimals: u32) {
+        self.ratio = round_to_decimals(self.ratio, decimals);
+        self.current_upload_rate = round_to_decimals(self.current_upload_rate, decimals);
+        self.current_download_rate = round_to_decimals(self.current_download_rate, decimals);
+        self.torrent_completion = round_to_decimals(self.torrent_completion, decimals);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,26 +1,39 @@
+pub mod bonus;
+pub mod clock;
 pub mod config;
 pub mod faker;
+pub mod format;
 pub mod grid;
 pub mod logger;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod peer_listener;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod port_mapping;
 pub mod protocol;
+pub mod schedule;
 pub mod torrent;
 pub mod validation;
 
 // Re-export main types explicitly to avoid ambiguous Result types
+pub use bonus::{BonusFormula, BonusInputs, BUILTIN_BONUS_FORMULAS};
+pub use clock::{Clock, MockClock, SystemClock};
 pub use config::{AppConfig, ClientSettings, ConfigError, FakerSettings, UiSettings};
-#[cfg(not(target_arch = "wasm32"))]
-pub use faker::RatioFakerHandle;
 pub use faker::{
-    FakerConfig, FakerError, FakerState, FakerStats, PostStopAction, PresetSettings, RatioFaker,
+    round_to_decimals, FakerConfig, FakerError, FakerState, FakerStats, LastAnnounce,
+    PostStopAction, PresetSettings, ProgressiveCurve, RandomizeMode, RatioFaker, StopReason,
+    DEFAULT_COMPLETION_PERCENT,
 };
+#[cfg(not(target_arch = "wasm32"))]
+pub use faker::{RatioFakerHandle, ScrapeTarget};
 pub use grid::{primary_tracker_host, GridImportSettings, GridMode, InstanceSummary};
 #[cfg(not(target_arch = "wasm32"))]
 pub use peer_listener::{PeerCatalog, PeerListenerService, PeerListenerStatus, PeerLookup};
+#[cfg(not(target_arch = "wasm32"))]
+pub use port_mapping::{map_port, unmap_port, MappingMethod, PortMapping};
+pub use schedule::RateSchedule;
 pub use torrent::{
     ClientConfig, ClientInfo, ClientType, HttpVersion, TorrentError, TorrentFile, TorrentInfo,
-    TorrentSummary,
+    TorrentSummary, VersionEncoding,
 };
 pub use validation::*;
 
@@ -8,6 +8,21 @@ thread_local! {
     static INSTANCE_CONTEXT: RefCell<Option<String>> = const { RefCell::new(None) };
 }
 
+/// Instance a task is working on, see `with_instance_context`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstanceLogContext {
+    pub id: String,
+    /// Name prefixed to log messages
+    pub label: String,
+    /// Hex info hash of the instance's torrent
+    pub info_hash: Option<String>,
+}
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "native"))]
+tokio::task_local! {
+    static TASK_CONTEXT: InstanceLogContext;
+}
+
 /// Set the instance context for the current thread (string version for server/wasm)
 pub fn set_instance_context_str(instance_id: Option<&str>) {
     INSTANCE_CONTEXT.with(|ctx| {
@@ -22,10 +37,40 @@ pub fn set_instance_context(instance_id: Option<u32>) {
     });
 }
 
+/// Run `future` as work on an instance.
+///
+/// Log lines written while it runs are prefixed with the context's label and see it in
+/// [`current_instance_context`], whichever worker thread the future is polled on and
+/// whatever other tasks run in between. This takes precedence over the thread-local
+/// context. Tasks spawned from `future` do not inherit it.
+#[cfg(all(not(target_arch = "wasm32"), feature = "native"))]
+pub fn with_instance_context<F: std::future::Future>(
+    context: InstanceLogContext,
+    future: F,
+) -> impl std::future::Future<Output = F::Output> {
+    TASK_CONTEXT.scope(context, future)
+}
+
+#[cfg(all(not(target_arch = "wasm32"), feature = "native"))]
+fn task_context<T>(read: impl FnOnce(&InstanceLogContext) -> T) -> Option<T> {
+    TASK_CONTEXT.try_with(read).ok()
+}
+
+#[cfg(not(all(not(target_arch = "wasm32"), feature = "native")))]
+fn task_context<T>(_read: impl FnOnce(&InstanceLogContext) -> T) -> Option<T> {
+    None
+}
+
+/// The instance the current task is working on, set by `with_instance_context`
+pub fn current_instance_context() -> Option<InstanceLogContext> {
+    task_context(InstanceLogContext::clone)
+}
+
 /// Get the current instance context
 fn get_instance_prefix() -> String {
-    INSTANCE_CONTEXT
-        .with(|ctx| ctx.borrow().as_ref().map_or_else(String::new, |label| format!("[{label}] ")))
+    let label = task_context(|ctx| ctx.label.clone())
+        .or_else(|| INSTANCE_CONTEXT.with(|ctx| ctx.borrow().clone()));
+    label.map_or_else(String::new, |label| format!("[{label}] "))
 }
 
 #[cfg(all(not(target_arch = "wasm32"), feature = "desktop"))]
@@ -321,4 +366,39 @@ mod tests {
         set_instance_context(None);
         assert_eq!(get_instance_prefix(), "");
     }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "native"))]
+    #[tokio::test]
+    async fn test_task_context_follows_interleaved_tasks() {
+        fn context(id: &str, label: &str) -> InstanceLogContext {
+            InstanceLogContext { id: id.to_string(), label: label.to_string(), info_hash: None }
+        }
+
+        async fn record() -> Vec<(Option<String>, String)> {
+            let mut seen = Vec::new();
+            for _ in 0..5 {
+                // Let the other task run on this thread in between
+                tokio::task::yield_now().await;
+                let id = current_instance_context().map(|ctx| ctx.id);
+                seen.push((id, get_instance_prefix()));
+            }
+            seen
+        }
+
+        set_instance_context_str(Some("thread"));
+        let (alpha, beta) = tokio::join!(
+            with_instance_context(context("a", "Alpha"), record()),
+            with_instance_context(context("b", "Beta"), record()),
+        );
+        set_instance_context_str(None);
+
+        let tagged = |seen: &[(Option<String>, String)], id: &str, prefix: &str| {
+            seen.iter().all(|(seen_id, seen_prefix)| {
+                seen_id.as_deref() == Some(id) && seen_prefix == prefix
+            })
+        };
+        assert!(tagged(&alpha, "a", "[Alpha] "));
+        assert!(tagged(&beta, "b", "[Beta] "));
+        assert_eq!(current_instance_context(), None);
+    }
 }
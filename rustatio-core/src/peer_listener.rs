@@ -1,4 +1,4 @@
-use crate::protocol::PeerHandshake;
+use crate::protocol::{bitfield_message, PeerHandshake};
 use crate::{log_debug, log_info, log_warn, FakerState, RatioFakerHandle};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
@@ -220,6 +220,12 @@ async fn handle_peer(mut stream: TcpStream, catalog: PeerCatalog) -> Result<(),
     let peer_id = faker.peer_id_bytes().await.map_err(|e| e.to_string())?;
     let reply = PeerHandshake::new([0u8; 8], hs.info_hash, peer_id).to_bytes();
     stream.write_all(&reply).await.map_err(|e| e.to_string())?;
+
+    // Peers with nothing may skip the bitfield, so only send one when pieces are held.
+    let (num_pieces, held) = faker.pieces_held().await;
+    if held > 0 {
+        stream.write_all(&bitfield_message(num_pieces, held)).await.map_err(|e| e.to_string())?;
+    }
     stream.flush().await.map_err(|e| e.to_string())?;
     tokio::time::sleep(KEEP_OPEN_DELAY).await;
     let _ = stream.shutdown().await;
@@ -250,6 +256,7 @@ mod tests {
     fn torrent() -> Arc<TorrentInfo> {
         Arc::new(TorrentInfo {
             info_hash: [7u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -260,8 +267,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: vec![TorrentFile { path: vec!["sample.bin".to_string()], length: 1024 }],
+            file_lengths: Vec::new(),
         })
     }
 
@@ -384,6 +393,17 @@ mod tests {
         svc.shutdown().await;
     }
 
+    #[tokio::test]
+    async fn pieces_held_follow_completion() {
+        assert_eq!(faker_handle().pieces_held().await, (1, 0));
+
+        let config = FakerConfig { completion_percent: 100.0, ..FakerConfig::default() };
+        let faker = RatioFaker::new(torrent(), config, None);
+        assert!(faker.is_ok());
+        let handle = RatioFakerHandle::new(faker.unwrap_or_else(|_| unreachable!()));
+        assert_eq!(handle.pieces_held().await, (1, 1));
+    }
+
     #[tokio::test]
     async fn peer_id_bytes_uses_string_accessor() {
         let arr = peer_id_to_array("-UT0001-123456789012");
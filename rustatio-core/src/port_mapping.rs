@@ -0,0 +1,394 @@
+//! Router port mappings over NAT-PMP (RFC 6886) and `UPnP` IGD, so the announced
+//! peer port can be reachable from outside.
+
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+const NAT_PMP_PORT: u16 = 5351;
+const NAT_PMP_OP_MAP_TCP: u8 = 2;
+const NAT_PMP_RETRIES: u32 = 3;
+const NAT_PMP_INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+
+const SSDP_ADDR: &str = "239.255.255.250:1900";
+const SSDP_TIMEOUT: Duration = Duration::from_secs(2);
+const UPNP_HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+const UPNP_WAN_SERVICES: &[&str] = &["WANIPConnection", "WANPPPConnection"];
+const MAPPING_DESCRIPTION: &str = "rustatio";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MappingMethod {
+    NatPmp,
+    Upnp,
+}
+
+impl fmt::Display for MappingMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NatPmp => f.write_str("NAT-PMP"),
+            Self::Upnp => f.write_str("UPnP"),
+        }
+    }
+}
+
+/// A TCP mapping granted by the gateway.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortMapping {
+    pub method: MappingMethod,
+    pub gateway: IpAddr,
+    pub internal_port: u16,
+    /// Port opened on the gateway; NAT-PMP gateways may pick another one than requested
+    pub external_port: u16,
+    pub lifetime: Duration,
+}
+
+/// Ask the gateway to forward TCP `port` to this host for `lifetime`, trying NAT-PMP
+/// first and `UPnP` second. The error lists why each method failed.
+pub async fn map_port(port: u16, lifetime: Duration) -> Result<PortMapping, String> {
+    let nat_pmp_error = match default_gateway() {
+        Some(gateway) => match map_nat_pmp(gateway, port, lifetime).await {
+            Ok(mapping) => return Ok(mapping),
+            Err(e) => e,
+        },
+        None => "no default gateway found".to_string(),
+    };
+
+    map_upnp(port, lifetime)
+        .await
+        .map_err(|upnp_error| format!("NAT-PMP: {nat_pmp_error}; UPnP: {upnp_error}"))
+}
+
+/// Remove a mapping granted by `map_port`, so the router stops forwarding the port
+/// once nothing listens on it.
+pub async fn unmap_port(mapping: &PortMapping) -> Result<(), String> {
+    match (mapping.method, mapping.gateway) {
+        (MappingMethod::NatPmp, IpAddr::V4(gateway)) => {
+            map_nat_pmp(gateway, mapping.internal_port, Duration::ZERO).await.map(drop)
+        }
+        (MappingMethod::NatPmp, IpAddr::V6(gateway)) => {
+            Err(format!("NAT-PMP gateway {gateway} is not an IPv4 address"))
+        }
+        (MappingMethod::Upnp, _) => unmap_upnp(mapping.external_port).await,
+    }
+}
+
+fn default_gateway() -> Option<Ipv4Addr> {
+    std::fs::read_to_string("/proc/net/route").ok().as_deref().and_then(parse_default_gateway)
+}
+
+/// The gateway of the default route in a `/proc/net/route` table.
+fn parse_default_gateway(table: &str) -> Option<Ipv4Addr> {
+    table.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace().skip(1);
+        let destination = fields.next()?;
+        let gateway = fields.next()?;
+        if destination != "00000000" {
+            return None;
+        }
+        // The kernel prints the address as a native-endian hex word.
+        let raw = u32::from_str_radix(gateway, 16).ok()?;
+        Some(Ipv4Addr::from(raw.to_ne_bytes())).filter(|ip| !ip.is_unspecified())
+    })
+}
+
+/// A mapping request; a zero `lifetime` deletes the mapping of `port` (RFC 6886 3.4).
+fn nat_pmp_request(port: u16, lifetime: Duration) -> [u8; 12] {
+    let external_port = if lifetime.is_zero() { 0 } else { port };
+    let lifetime = u32::try_from(lifetime.as_secs()).unwrap_or(u32::MAX);
+    let mut request = [0u8; 12];
+    request[1] = NAT_PMP_OP_MAP_TCP;
+    request[4..6].copy_from_slice(&port.to_be_bytes());
+    request[6..8].copy_from_slice(&external_port.to_be_bytes());
+    request[8..12].copy_from_slice(&lifetime.to_be_bytes());
+    request
+}
+
+/// The mapped external port and granted lifetime of a NAT-PMP mapping response.
+fn parse_nat_pmp_response(response: &[u8], port: u16) -> Result<(u16, Duration), String> {
+    if response.len() < 16 || response[0] != 0 || response[1] != 128 + NAT_PMP_OP_MAP_TCP {
+        return Err("unexpected response from gateway".to_string());
+    }
+    let result = u16::from_be_bytes([response[2], response[3]]);
+    if result != 0 {
+        let reason = match result {
+            1 => "unsupported version",
+            2 => "not authorized or refused",
+            3 => "network failure",
+            4 => "out of resources",
+            5 => "unsupported opcode",
+            _ => "unknown error",
+        };
+        return Err(format!("gateway refused the mapping: {reason} (code {result})"));
+    }
+    if u16::from_be_bytes([response[8], response[9]]) != port {
+        return Err("gateway answered for another port".to_string());
+    }
+    let external_port = u16::from_be_bytes([response[10], response[11]]);
+    let lifetime = u32::from_be_bytes([response[12], response[13], response[14], response[15]]);
+    Ok((external_port, Duration::from_secs(u64::from(lifetime))))
+}
+
+async fn map_nat_pmp(
+    gateway: Ipv4Addr,
+    port: u16,
+    lifetime: Duration,
+) -> Result<PortMapping, String> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await.map_err(|e| e.to_string())?;
+    socket.connect((gateway, NAT_PMP_PORT)).await.map_err(|e| e.to_string())?;
+
+    let request = nat_pmp_request(port, lifetime);
+    let mut buf = [0u8; 16];
+    let mut timeout = NAT_PMP_INITIAL_TIMEOUT;
+    for _ in 0..NAT_PMP_RETRIES {
+        socket.send(&request).await.map_err(|e| e.to_string())?;
+        match tokio::time::timeout(timeout, socket.recv(&mut buf)).await {
+            Ok(Ok(len)) => {
+                let (external_port, lifetime) = parse_nat_pmp_response(&buf[..len], port)?;
+                return Ok(PortMapping {
+                    method: MappingMethod::NatPmp,
+                    gateway: IpAddr::V4(gateway),
+                    internal_port: port,
+                    external_port,
+                    lifetime,
+                });
+            }
+            Ok(Err(e)) => return Err(e.to_string()),
+            Err(_) => timeout *= 2,
+        }
+    }
+    Err(format!("no answer from gateway {gateway}"))
+}
+
+/// The `LOCATION` header of an SSDP search response.
+fn ssdp_location(response: &str) -> Option<&str> {
+    response.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        name.trim().eq_ignore_ascii_case("location").then(|| value.trim())
+    })
+}
+
+fn tag_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{tag}>"))? + tag.len() + 2;
+    let end = xml[start..].find(&format!("</{tag}>"))? + start;
+    Some(xml[start..end].trim())
+}
+
+/// The service type and control URL of the WAN connection service in a device description.
+fn find_wan_service(description: &str) -> Option<(&str, &str)> {
+    description.split("<service>").skip(1).find_map(|service| {
+        let service_type = tag_text(service, "serviceType")?;
+        if !UPNP_WAN_SERVICES.iter().any(|name| service_type.contains(name)) {
+            return None;
+        }
+        Some((service_type, tag_text(service, "controlURL")?))
+    })
+}
+
+fn soap_envelope(service_type: &str, action: &str, arguments: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?>\
+         <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+         s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+         <s:Body><u:{action} xmlns:u=\"{service_type}\">{arguments}</u:{action}>\
+         </s:Body></s:Envelope>"
+    )
+}
+
+fn add_port_mapping_body(
+    service_type: &str,
+    port: u16,
+    client: IpAddr,
+    lifetime: Duration,
+) -> String {
+    let arguments = format!(
+        "<NewRemoteHost></NewRemoteHost>\
+         <NewExternalPort>{port}</NewExternalPort>\
+         <NewProtocol>TCP</NewProtocol>\
+         <NewInternalPort>{port}</NewInternalPort>\
+         <NewInternalClient>{client}</NewInternalClient>\
+         <NewEnabled>1</NewEnabled>\
+         <NewPortMappingDescription>{MAPPING_DESCRIPTION}</NewPortMappingDescription>\
+         <NewLeaseDuration>{}</NewLeaseDuration>",
+        lifetime.as_secs()
+    );
+    soap_envelope(service_type, "AddPortMapping", &arguments)
+}
+
+fn delete_port_mapping_body(service_type: &str, port: u16) -> String {
+    let arguments = format!(
+        "<NewRemoteHost></NewRemoteHost>\
+         <NewExternalPort>{port}</NewExternalPort>\
+         <NewProtocol>TCP</NewProtocol>"
+    );
+    soap_envelope(service_type, "DeletePortMapping", &arguments)
+}
+
+async fn discover_igd() -> Result<String, String> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await.map_err(|e| e.to_string())?;
+    let search = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {SSDP_ADDR}\r\nMAN: \"ssdp:discover\"\r\nMX: 2\r\n\
+         ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\r\n"
+    );
+    socket.send_to(search.as_bytes(), SSDP_ADDR).await.map_err(|e| e.to_string())?;
+
+    let mut buf = [0u8; 2048];
+    let len = tokio::time::timeout(SSDP_TIMEOUT, socket.recv(&mut buf))
+        .await
+        .map_err(|_| "no internet gateway device answered".to_string())?
+        .map_err(|e| e.to_string())?;
+    ssdp_location(&String::from_utf8_lossy(&buf[..len]))
+        .map(str::to_string)
+        .ok_or_else(|| "gateway answer has no location".to_string())
+}
+
+/// The address this host uses to reach `gateway`.
+async fn local_address_towards(gateway: SocketAddr) -> Result<IpAddr, String> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await.map_err(|e| e.to_string())?;
+    socket.connect(gateway).await.map_err(|e| e.to_string())?;
+    socket.local_addr().map(|addr| addr.ip()).map_err(|e| e.to_string())
+}
+
+/// The WAN connection service of the gateway found over SSDP.
+struct UpnpService {
+    client: reqwest::Client,
+    gateway: SocketAddr,
+    service_type: String,
+    control_url: url::Url,
+}
+
+impl UpnpService {
+    async fn discover() -> Result<Self, String> {
+        let location = discover_igd().await?;
+        let location_url = url::Url::parse(&location).map_err(|e| e.to_string())?;
+        let gateway = location_url
+            .socket_addrs(|| Some(80))
+            .ok()
+            .and_then(|addrs| addrs.into_iter().next())
+            .ok_or_else(|| format!("invalid gateway location {location}"))?;
+
+        let client = reqwest::Client::builder()
+            .timeout(UPNP_HTTP_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        let description = client
+            .get(location_url.clone())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .text()
+            .await
+            .map_err(|e| e.to_string())?;
+        let (service_type, control_url) = find_wan_service(&description)
+            .ok_or_else(|| "gateway has no WAN connection service".to_string())?;
+        let control_url = location_url.join(control_url).map_err(|e| e.to_string())?;
+
+        Ok(Self { client, gateway, service_type: service_type.to_string(), control_url })
+    }
+
+    async fn call(&self, action: &str, body: String) -> Result<(), String> {
+        let response = self
+            .client
+            .post(self.control_url.clone())
+            .header("Content-Type", "text/xml; charset=\"utf-8\"")
+            .header("SOAPAction", format!("\"{}#{action}\"", self.service_type))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            let reason = tag_text(&body, "errorDescription").unwrap_or("no description");
+            return Err(format!("gateway refused {action}: {reason} (HTTP {status})"));
+        }
+        Ok(())
+    }
+}
+
+async fn map_upnp(port: u16, lifetime: Duration) -> Result<PortMapping, String> {
+    let service = UpnpService::discover().await?;
+    let local_ip = local_address_towards(service.gateway).await?;
+    let body = add_port_mapping_body(&service.service_type, port, local_ip, lifetime);
+    service.call("AddPortMapping", body).await?;
+
+    Ok(PortMapping {
+        method: MappingMethod::Upnp,
+        gateway: service.gateway.ip(),
+        internal_port: port,
+        external_port: port,
+        lifetime,
+    })
+}
+
+async fn unmap_upnp(port: u16) -> Result<(), String> {
+    let service = UpnpService::discover().await?;
+    let body = delete_port_mapping_body(&service.service_type, port);
+    service.call("DeletePortMapping", body).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_gateway_is_read_from_the_route_table() {
+        let table = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\n\
+                     eth0\t0000A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\n\
+                     eth0\t00000000\t0100A8C0\t0003\t0\t0\t0\t00000000\n";
+        let expected = Ipv4Addr::from(0x0100_A8C0_u32.to_ne_bytes());
+        assert_eq!(parse_default_gateway(table), Some(expected));
+        assert_eq!(parse_default_gateway("Iface\tDestination\tGateway\n"), None);
+    }
+
+    #[test]
+    fn nat_pmp_response_reports_the_mapped_port_or_the_refusal() {
+        let request = nat_pmp_request(6881, Duration::from_hours(1));
+        assert_eq!(request[..2], [0, NAT_PMP_OP_MAP_TCP]);
+        assert_eq!(u16::from_be_bytes([request[4], request[5]]), 6881);
+
+        let mut response = [0u8; 16];
+        response[1] = 128 + NAT_PMP_OP_MAP_TCP;
+        response[8..10].copy_from_slice(&6881u16.to_be_bytes());
+        response[10..12].copy_from_slice(&40000u16.to_be_bytes());
+        response[12..16].copy_from_slice(&1800u32.to_be_bytes());
+        assert_eq!(parse_nat_pmp_response(&response, 6881), Ok((40000, Duration::from_mins(30))));
+
+        response[3] = 2;
+        let err = parse_nat_pmp_response(&response, 6881).err().unwrap_or_default();
+        assert!(err.contains("not authorized"), "{err}");
+        assert!(parse_nat_pmp_response(&response[..8], 6881).is_err());
+
+        // Deleting asks for lifetime 0 with no suggested external port
+        let delete = nat_pmp_request(6881, Duration::ZERO);
+        assert_eq!(u16::from_be_bytes([delete[4], delete[5]]), 6881);
+        assert_eq!(delete[6..12], [0; 6]);
+    }
+
+    #[test]
+    fn upnp_description_yields_the_wan_control_url() {
+        let ssdp = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=120\r\n\
+                    Location: http://192.168.1.1:5000/rootDesc.xml\r\n\r\n";
+        assert_eq!(ssdp_location(ssdp), Some("http://192.168.1.1:5000/rootDesc.xml"));
+
+        let description = "<root><device><serviceList>\
+            <service><serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>\
+            <controlURL>/ctl/L3F</controlURL></service>\
+            <service><serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>\
+            <controlURL>/ctl/IPConn</controlURL></service>\
+            </serviceList></device></root>";
+        assert_eq!(
+            find_wan_service(description),
+            Some(("urn:schemas-upnp-org:service:WANIPConnection:1", "/ctl/IPConn"))
+        );
+        assert_eq!(find_wan_service("<root></root>"), None);
+
+        let delete =
+            delete_port_mapping_body("urn:schemas-upnp-org:service:WANIPConnection:1", 6881);
+        assert!(delete.contains(
+            "<u:DeletePortMapping xmlns:u=\"urn:schemas-upnp-org:service:WANIPConnection:1\">"
+        ));
+        assert!(delete.contains("<NewExternalPort>6881</NewExternalPort>"));
+        assert!(delete.contains("</u:DeletePortMapping></s:Body>"));
+    }
+}
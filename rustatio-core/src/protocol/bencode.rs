@@ -1,5 +1,6 @@
 use serde::Serialize;
 use std::collections::HashMap;
+use std::fmt::Write;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -14,16 +15,113 @@ pub enum BencodeError {
 
 pub type Result<T> = std::result::Result<T, BencodeError>;
 
+/// A decoded bencode dictionary, keyed by raw bytes
+pub type BencodeDict = HashMap<Vec<u8>, serde_bencode::value::Value>;
+
 /// Parse bencode data from bytes
 pub fn parse(data: &[u8]) -> Result<serde_bencode::value::Value> {
     serde_bencode::from_bytes(data).map_err(|e| BencodeError::ParseError(e.to_string()))
 }
 
+/// Raw bytes of the value under `key` in the top-level dictionary of `data`.
+///
+/// The bytes are exactly as they appear in the input. Hashes must be computed over
+/// them: a re-encoding sorts dictionary keys and can differ from what was hashed.
+pub fn raw_dict_value<'a>(data: &'a [u8], key: &[u8]) -> Result<Option<&'a [u8]>> {
+    if data.first() != Some(&b'd') {
+        return Err(BencodeError::InvalidStructure("Root is not a dictionary".into()));
+    }
+    let mut pos = 1;
+    while data.get(pos) != Some(&b'e') {
+        let (entry_key, value_start) = read_byte_string(data, pos)?;
+        let value_end = skip_value(data, value_start)?;
+        if entry_key == key {
+            return Ok(Some(&data[value_start..value_end]));
+        }
+        pos = value_end;
+    }
+    Ok(None)
+}
+
+/// The byte string starting at `pos` and the position just past it
+fn read_byte_string(data: &[u8], pos: usize) -> Result<(&[u8], usize)> {
+    let invalid = || BencodeError::ParseError(format!("Invalid byte string at offset {pos}"));
+    let colon = data
+        .get(pos..)
+        .and_then(|rest| rest.iter().position(|&b| b == b':'))
+        .map(|offset| pos + offset)
+        .ok_or_else(invalid)?;
+    let len: usize = std::str::from_utf8(&data[pos..colon])
+        .ok()
+        .and_then(|len| len.parse().ok())
+        .ok_or_else(invalid)?;
+    let start = colon + 1;
+    let end = start.checked_add(len).filter(|end| *end <= data.len()).ok_or_else(invalid)?;
+    Ok((&data[start..end], end))
+}
+
+/// The position just past the value starting at `pos`
+fn skip_value(data: &[u8], mut pos: usize) -> Result<usize> {
+    // Nesting is tracked with a counter so deeply nested input can't overflow the stack
+    let mut depth = 0usize;
+    loop {
+        match data.get(pos) {
+            Some(b'i') => {
+                pos = data[pos..]
+                    .iter()
+                    .position(|&b| b == b'e')
+                    .map(|offset| pos + offset + 1)
+                    .ok_or_else(|| {
+                        BencodeError::ParseError(format!("Unterminated integer at offset {pos}"))
+                    })?;
+            }
+            Some(b'l' | b'd') => {
+                depth += 1;
+                pos += 1;
+                continue;
+            }
+            Some(b'e') if depth > 0 => {
+                depth -= 1;
+                pos += 1;
+            }
+            Some(b'0'..=b'9') => pos = read_byte_string(data, pos)?.1,
+            _ => return Err(BencodeError::ParseError(format!("Unexpected data at offset {pos}"))),
+        }
+        if depth == 0 {
+            return Ok(pos);
+        }
+    }
+}
+
 /// Encode data to bencode format
 pub fn encode<T: Serialize>(value: &T) -> Result<Vec<u8>> {
     serde_bencode::to_bytes(value).map_err(|e| BencodeError::ParseError(e.to_string()))
 }
 
+/// Convert a decoded value to JSON for display. Byte strings become text when they are
+/// UTF-8 and lowercase hex otherwise, such as compact peer lists; keys are read lossily.
+pub fn to_json(value: &serde_bencode::value::Value) -> serde_json::Value {
+    use serde_bencode::value::Value;
+    match value {
+        Value::Int(i) => serde_json::Value::from(*i),
+        Value::Bytes(bytes) => std::str::from_utf8(bytes).map_or_else(
+            |_| {
+                let hex = bytes.iter().fold(String::new(), |mut acc, b| {
+                    let _ = write!(acc, "{b:02x}");
+                    acc
+                });
+                serde_json::Value::String(hex)
+            },
+            |text| serde_json::Value::String(text.to_string()),
+        ),
+        Value::List(items) => items.iter().map(to_json).collect(),
+        Value::Dict(dict) => dict
+            .iter()
+            .map(|(key, value)| (String::from_utf8_lossy(key).into_owned(), to_json(value)))
+            .collect(),
+    }
+}
+
 /// Extract a string value from a bencode dictionary
 #[allow(clippy::implicit_hasher)]
 pub fn get_string(
@@ -90,6 +188,16 @@ mod tests {
         dict
     }
 
+    #[test]
+    fn test_to_json_shows_binary_strings_as_hex() -> Result<()> {
+        let value = parse(b"d8:intervali900e5:peers6:\x7f\0\0\x01\x1a\xe1e")?;
+        assert_eq!(
+            to_json(&value),
+            serde_json::json!({ "interval": 900, "peers": "7f0000011ae1" })
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_parse_simple_string() -> Result<()> {
         let data = b"4:spam";
@@ -106,6 +214,19 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_raw_dict_value_keeps_original_bytes() -> Result<()> {
+        let data = b"d7:comment6:4:info4:infod4:name1:x6:lengthi5e4:listli1ed1:a0:eee3:zzzi0ee";
+
+        let info = raw_dict_value(data, b"info")?;
+        assert_eq!(info, Some(b"d4:name1:x6:lengthi5e4:listli1ed1:a0:eee".as_ref()));
+        assert_eq!(raw_dict_value(data, b"zzz")?, Some(b"i0e".as_ref()));
+        assert_eq!(raw_dict_value(data, b"missing")?, None);
+        assert!(raw_dict_value(b"li1ee", b"info").is_err());
+        assert!(raw_dict_value(b"d4:infod4:name9:xe", b"info").is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_parse_invalid() {
         let result = parse(b"notbencode");
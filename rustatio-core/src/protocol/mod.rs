@@ -1,12 +1,17 @@
 pub mod bencode;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod peer;
+#[cfg(not(target_arch = "wasm32"))]
+mod raw_http;
+pub mod tiers;
 pub mod tracker;
 
 // Re-export common types
 pub use bencode::BencodeError;
 #[cfg(not(target_arch = "wasm32"))]
-pub use peer::{peer_id_to_array, PeerHandshake, PeerProtocolError};
+pub use peer::{bitfield_message, peer_id_to_array, PeerHandshake, PeerProtocolError};
+pub use tiers::TrackerTiers;
 pub use tracker::{
-    AnnounceRequest, AnnounceResponse, ScrapeResponse, TrackerClient, TrackerError, TrackerEvent,
+    AnnounceExchange, AnnounceRequest, AnnounceResponse, ScrapeResponse, TrackerClient,
+    TrackerError, TrackerEvent, IPV6_ONLY_TRACKER_ERROR,
 };
@@ -6,6 +6,7 @@ pub const RESERVED_LEN: usize = 8;
 pub const INFO_HASH_LEN: usize = 20;
 pub const PEER_ID_LEN: usize = 20;
 pub const HANDSHAKE_LEN: usize = 68;
+pub const MSG_BITFIELD: u8 = 5;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PeerHandshake {
@@ -72,6 +73,20 @@ impl PeerHandshake {
     }
 }
 
+/// A length-prefixed `bitfield` message over `num_pieces` pieces claiming the first `have`.
+pub fn bitfield_message(num_pieces: usize, have: usize) -> Vec<u8> {
+    let mut bits = vec![0u8; num_pieces.div_ceil(8)];
+    for piece in 0..have.min(num_pieces) {
+        bits[piece / 8] |= 0x80 >> (piece % 8);
+    }
+    let len = u32::try_from(bits.len() + 1).unwrap_or(u32::MAX);
+    let mut out = Vec::with_capacity(bits.len() + 5);
+    out.extend_from_slice(&len.to_be_bytes());
+    out.push(MSG_BITFIELD);
+    out.extend_from_slice(&bits);
+    out
+}
+
 pub const fn peer_id_to_array(peer_id: &str) -> Result<[u8; PEER_ID_LEN], PeerProtocolError> {
     if peer_id.len() != PEER_ID_LEN {
         return Err(PeerProtocolError::InvalidPeerIdLength(peer_id.len()));
@@ -120,6 +135,13 @@ mod tests {
         assert_eq!(err, Err(PeerProtocolError::InvalidProtocol));
     }
 
+    #[test]
+    fn bitfield_sets_the_leading_pieces_and_leaves_spare_bits_clear() {
+        assert_eq!(bitfield_message(10, 10), vec![0, 0, 0, 3, MSG_BITFIELD, 0xFF, 0xC0]);
+        assert_eq!(bitfield_message(10, 3), vec![0, 0, 0, 3, MSG_BITFIELD, 0xE0, 0x00]);
+        assert_eq!(bitfield_message(8, 20), vec![0, 0, 0, 2, MSG_BITFIELD, 0xFF]);
+    }
+
     #[test]
     fn peer_id_to_array_accepts_twenty_bytes() {
         let arr = peer_id_to_array("-UT0001-123456789012");
@@ -0,0 +1,420 @@
+//! Minimal HTTP client for tracker requests.
+//!
+//! reqwest adds an `Accept` header of its own and chooses header order and casing, which
+//! trackers can tell apart from the client being emulated. Requests sent from here carry
+//! exactly the headers they are given, in that order.
+//!
+//! Only direct connections get this: requests through a proxy, whether an instance's
+//! `proxy_url` or one of the `HTTP_PROXY`, `HTTPS_PROXY` and `ALL_PROXY` environment
+//! variables, go through reqwest and lose that header fidelity.
+
+use super::tracker::{host_header, resolves_only_to_ipv6, IPV6_ONLY_TRACKER_ERROR};
+use crate::torrent::HttpVersion;
+use reqwest::StatusCode;
+use std::io::Read;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls;
+use tokio_rustls::TlsConnector;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tracker responses are small; anything past this is not a tracker talking
+const MAX_BODY_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Redirects followed before giving up, about what the emulated clients allow
+const MAX_REDIRECTS: usize = 5;
+
+/// Proxy variables reqwest picks up from the environment
+const PROXY_ENV_VARS: [&str; 6] =
+    ["HTTP_PROXY", "HTTPS_PROXY", "ALL_PROXY", "http_proxy", "https_proxy", "all_proxy"];
+
+struct RawResponse {
+    status: StatusCode,
+    location: Option<String>,
+    body: Vec<u8>,
+}
+
+/// Whether a proxy is set in the environment. Requests must then go through reqwest,
+/// which honours it, instead of connecting directly and bypassing the proxy.
+pub fn env_proxy_set() -> bool {
+    PROXY_ENV_VARS.iter().any(|name| std::env::var(name).is_ok_and(|v| !v.trim().is_empty()))
+}
+
+/// Send a GET request with exactly `headers` and return the status and decoded body
+pub async fn get(
+    url: &str,
+    version: HttpVersion,
+    headers: &[(&str, String)],
+) -> Result<(StatusCode, Vec<u8>), String> {
+    tokio::time::timeout(REQUEST_TIMEOUT, follow(url, version, headers))
+        .await
+        .map_err(|_| format!("Request timed out after {}s", REQUEST_TIMEOUT.as_secs()))?
+}
+
+/// `fetch`, following up to `MAX_REDIRECTS` redirects with the same headers and the
+/// `Host` header pointed at each new location.
+async fn follow(
+    url: &str,
+    version: HttpVersion,
+    headers: &[(&str, String)],
+) -> Result<(StatusCode, Vec<u8>), String> {
+    let mut url = url::Url::parse(url).map_err(|e| format!("Invalid tracker URL: {e}"))?;
+    let mut headers = headers.to_vec();
+    for _ in 0..=MAX_REDIRECTS {
+        let response = fetch(&url, version, &headers).await?;
+        let redirect = matches!(response.status.as_u16(), 301 | 302 | 303 | 307 | 308);
+        let Some(location) = response.location.filter(|_| redirect) else {
+            return Ok((response.status, response.body));
+        };
+        // Redirect targets on private trackers carry the passkey, keep them out of errors
+        url = url.join(&location).map_err(|e| format!("Invalid redirect location: {e}"))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("Refusing to follow redirect to a {} URL", url.scheme()));
+        }
+        for (name, value) in &mut headers {
+            if name.eq_ignore_ascii_case("host") {
+                *value = host_header(url.as_str());
+            }
+        }
+    }
+    Err(format!("Too many redirects (more than {MAX_REDIRECTS})"))
+}
+
+async fn fetch(
+    parsed: &url::Url,
+    version: HttpVersion,
+    headers: &[(&str, String)],
+) -> Result<RawResponse, String> {
+    let host = match parsed.host() {
+        Some(url::Host::Domain(domain)) => domain.to_string(),
+        Some(url::Host::Ipv4(ip)) => ip.to_string(),
+        Some(url::Host::Ipv6(ip)) => ip.to_string(),
+        None => return Err("Tracker URL has no host".to_string()),
+    };
+    let port = parsed.port_or_known_default().ok_or("Tracker URL has no port")?;
+    let request = request_head(parsed, version, headers);
+
+    let tcp = match TcpStream::connect((host.as_str(), port)).await {
+        Ok(tcp) => tcp,
+        Err(err) if resolves_only_to_ipv6(parsed.as_str()).await => {
+            return Err(format!("{IPV6_ONLY_TRACKER_ERROR}: {err}"));
+        }
+        Err(err) => return Err(format!("Failed to connect to {host}:{port}: {err}")),
+    };
+
+    match parsed.scheme() {
+        "http" => exchange(tcp, &request).await,
+        "https" => {
+            let server_name = rustls::pki_types::ServerName::try_from(host)
+                .map_err(|e| format!("Invalid TLS server name: {e}"))?;
+            let tls = tls_connector()?
+                .connect(server_name, tcp)
+                .await
+                .map_err(|e| format!("TLS handshake failed: {e}"))?;
+            exchange(tls, &request).await
+        }
+        scheme => Err(format!("Unsupported tracker scheme: {scheme}")),
+    }
+}
+
+/// Request line and headers, written exactly as they go on the wire. Nothing is added for
+/// HTTP/1.0: no `Connection: keep-alive`, so the tracker closes once it has answered.
+fn request_head(url: &url::Url, version: HttpVersion, headers: &[(&str, String)]) -> String {
+    let mut target = url.path().to_string();
+    if let Some(query) = url.query() {
+        target.push('?');
+        target.push_str(query);
+    }
+
+    let mut head = format!("GET {target} {}\r\n", version.as_str());
+    for (name, value) in headers {
+        head.push_str(name);
+        head.push_str(": ");
+        head.push_str(value);
+        head.push_str("\r\n");
+    }
+    head.push_str("\r\n");
+    head
+}
+
+fn tls_connector() -> Result<TlsConnector, String> {
+    static CONFIG: OnceLock<Result<Arc<rustls::ClientConfig>, String>> = OnceLock::new();
+    CONFIG
+        .get_or_init(|| {
+            let roots: rustls::RootCertStore =
+                webpki_roots::TLS_SERVER_ROOTS.iter().cloned().collect();
+            let provider = Arc::new(rustls::crypto::ring::default_provider());
+            let config = rustls::ClientConfig::builder_with_provider(provider)
+                .with_safe_default_protocol_versions()
+                .map_err(|e| format!("TLS setup failed: {e}"))?
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+            Ok(Arc::new(config))
+        })
+        .clone()
+        .map(TlsConnector::from)
+}
+
+async fn exchange<S>(mut stream: S, request: &str) -> Result<RawResponse, String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to send request: {e}"))?;
+    stream.flush().await.map_err(|e| format!("Failed to send request: {e}"))?;
+
+    let mut reader = BufReader::new(stream);
+    let status_line = read_line(&mut reader).await?;
+    let status = parse_status(&status_line)?;
+
+    let mut content_length = None;
+    let mut chunked = false;
+    let mut encoding = String::new();
+    let mut location = None;
+    loop {
+        let line = read_line(&mut reader).await?;
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => content_length = value.parse::<u64>().ok(),
+            "transfer-encoding" => chunked = value.to_ascii_lowercase().contains("chunked"),
+            "content-encoding" => encoding = value.to_ascii_lowercase(),
+            "location" => location = Some(value.to_string()),
+            _ => {}
+        }
+    }
+
+    let body = if chunked {
+        read_chunked(&mut reader).await?
+    } else if let Some(length) = content_length {
+        if length > MAX_BODY_BYTES {
+            return Err(format!("Response body too large ({length} bytes)"));
+        }
+        let mut body = vec![0; usize::try_from(length).map_err(|e| e.to_string())?];
+        reader.read_exact(&mut body).await.map_err(|e| format!("Failed to read body: {e}"))?;
+        body
+    } else {
+        let mut body = Vec::new();
+        (&mut reader)
+            .take(MAX_BODY_BYTES)
+            .read_to_end(&mut body)
+            .await
+            .map_err(|e| format!("Failed to read body: {e}"))?;
+        body
+    };
+
+    Ok(RawResponse { status, location, body: decode_body(&encoding, body)? })
+}
+
+/// Read one line without its line ending
+async fn read_line<R: AsyncBufReadExt + Unpin>(reader: &mut R) -> Result<String, String> {
+    let mut line = Vec::new();
+    let read = reader
+        .read_until(b'\n', &mut line)
+        .await
+        .map_err(|e| format!("Failed to read response: {e}"))?;
+    if read == 0 {
+        return Err("Connection closed before the response was complete".to_string());
+    }
+    while line.last().is_some_and(|b| *b == b'\n' || *b == b'\r') {
+        line.pop();
+    }
+    Ok(String::from_utf8_lossy(&line).into_owned())
+}
+
+fn parse_status(line: &str) -> Result<StatusCode, String> {
+    let mut parts = line.split_whitespace();
+    let version = parts.next().unwrap_or_default();
+    if !version.starts_with("HTTP/") {
+        return Err(format!("Not an HTTP response: {line}"));
+    }
+    parts
+        .next()
+        .and_then(|code| code.parse::<u16>().ok())
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .ok_or_else(|| format!("Invalid HTTP status line: {line}"))
+}
+
+async fn read_chunked<R: AsyncBufReadExt + Unpin>(reader: &mut R) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    loop {
+        let line = read_line(reader).await?;
+        let size = line.split(';').next().unwrap_or_default().trim();
+        let size =
+            u64::from_str_radix(size, 16).map_err(|_| format!("Invalid chunk size: {line}"))?;
+        if size == 0 {
+            break;
+        }
+        if body.len() as u64 + size > MAX_BODY_BYTES {
+            return Err("Response body too large".to_string());
+        }
+        let start = body.len();
+        body.resize(start + usize::try_from(size).map_err(|e| e.to_string())?, 0);
+        reader
+            .read_exact(&mut body[start..])
+            .await
+            .map_err(|e| format!("Failed to read body: {e}"))?;
+        read_line(reader).await?;
+    }
+    Ok(body)
+}
+
+/// Undo the `Content-Encoding` the clients we emulate ask for. The decoded body is held
+/// to `MAX_BODY_BYTES` too, a small compressed body can expand to gigabytes.
+fn decode_body(encoding: &str, body: Vec<u8>) -> Result<Vec<u8>, String> {
+    match encoding {
+        "gzip" | "x-gzip" => read_limited(flate2::read::GzDecoder::new(body.as_slice()))
+            .map_err(|e| format!("Failed to decode gzip body: {e}")),
+        // Servers disagree on whether deflate means zlib-wrapped or raw
+        "deflate" => read_limited(flate2::read::ZlibDecoder::new(body.as_slice()))
+            .or_else(|e| match e.kind() {
+                std::io::ErrorKind::FileTooLarge => Err(e),
+                _ => read_limited(flate2::read::DeflateDecoder::new(body.as_slice())),
+            })
+            .map_err(|e| format!("Failed to decode deflate body: {e}")),
+        _ => Ok(body),
+    }
+}
+
+/// Read `decoder` to the end, failing once it yields more than `MAX_BODY_BYTES`
+fn read_limited(decoder: impl Read) -> std::io::Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    decoder.take(MAX_BODY_BYTES + 1).read_to_end(&mut decoded)?;
+    if decoded.len() as u64 > MAX_BODY_BYTES {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::FileTooLarge,
+            "Response body too large",
+        ));
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tokio::net::TcpListener;
+
+    /// Accept one connection, answer it with `response` and hand back the raw request
+    async fn mock_tracker(response: Vec<u8>) -> (String, tokio::task::JoinHandle<Vec<u8>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap_or_else(|e| panic!("{e}"));
+        let addr = listener.local_addr().unwrap_or_else(|e| panic!("{e}"));
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap_or_else(|e| panic!("{e}"));
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap_or_else(|e| panic!("{e}"));
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            socket.write_all(&response).await.unwrap_or_else(|e| panic!("{e}"));
+            request
+        });
+        (format!("http://{addr}/announce?info_hash=%01%02&port=6881"), handle)
+    }
+
+    #[tokio::test]
+    async fn sends_exactly_the_given_headers_in_order() {
+        let (url, tracker) =
+            mock_tracker(b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\ni42e".to_vec()).await;
+        let headers = [
+            ("Host", "tracker.test".to_string()),
+            ("User-Agent", "qBittorrent/5.2.1".to_string()),
+            ("Accept-Encoding", "gzip".to_string()),
+            ("Connection", "close".to_string()),
+        ];
+
+        let (status, body) =
+            get(&url, HttpVersion::Http11, &headers).await.unwrap_or_else(|e| panic!("{e}"));
+        let request = tracker.await.unwrap_or_else(|e| panic!("{e}"));
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"i42e");
+        assert_eq!(
+            String::from_utf8_lossy(&request),
+            "GET /announce?info_hash=%01%02&port=6881 HTTP/1.1\r\nHost: tracker.test\r\n\
+             User-Agent: qBittorrent/5.2.1\r\nAccept-Encoding: gzip\r\nConnection: close\r\n\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn follows_redirects_with_the_host_header_updated() {
+        let (target, tracker) =
+            mock_tracker(b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\ni42e".to_vec()).await;
+        let moved =
+            format!("HTTP/1.1 302 Found\r\nLocation: {target}\r\nContent-Length: 0\r\n\r\n");
+        let (url, _old) = mock_tracker(moved.into_bytes()).await;
+        let headers = [("Host", "old.test".to_string()), ("User-Agent", "ua".to_string())];
+
+        let (status, body) =
+            get(&url, HttpVersion::Http11, &headers).await.unwrap_or_else(|e| panic!("{e}"));
+        let request = tracker.await.unwrap_or_else(|e| panic!("{e}"));
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"i42e");
+        assert!(String::from_utf8_lossy(&request)
+            .contains(&format!("\r\nHost: {}\r\n", host_header(&target))));
+    }
+
+    #[tokio::test]
+    async fn redirect_errors_leave_out_the_target() {
+        let moved =
+            "HTTP/1.1 302 Found\r\nLocation: ftp://tracker.test/0123456789abcdef0123/announce\r\n\
+                     Content-Length: 0\r\n\r\n";
+        let (url, _tracker) = mock_tracker(moved.as_bytes().to_vec()).await;
+
+        let err = get(&url, HttpVersion::Http11, &[]).await.err().unwrap_or_default();
+
+        assert_eq!(err, "Refusing to follow redirect to a ftp URL");
+    }
+
+    #[tokio::test]
+    async fn decodes_chunked_gzip_bodies() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(b"d8:intervali1800ee").unwrap_or_else(|e| panic!("{e}"));
+        let gzipped = encoder.finish().unwrap_or_else(|e| panic!("{e}"));
+
+        let mut response = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\
+                             Content-Encoding: gzip\r\n\r\n"
+            .to_vec();
+        for chunk in gzipped.chunks(gzipped.len() / 2 + 1) {
+            response.extend_from_slice(format!("{:x}\r\n", chunk.len()).as_bytes());
+            response.extend_from_slice(chunk);
+            response.extend_from_slice(b"\r\n");
+        }
+        response.extend_from_slice(b"0\r\n\r\n");
+        let (url, _tracker) = mock_tracker(response).await;
+
+        let (_, body) = get(&url, HttpVersion::Http11, &[]).await.unwrap_or_else(|e| panic!("{e}"));
+
+        assert_eq!(body, b"d8:intervali1800ee");
+    }
+
+    #[test]
+    fn rejects_gzip_bodies_that_expand_past_the_limit() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        let zeros = vec![0u8; 1024 * 1024];
+        for _ in 0..=MAX_BODY_BYTES / zeros.len() as u64 {
+            encoder.write_all(&zeros).unwrap_or_else(|e| panic!("{e}"));
+        }
+        let gzipped = encoder.finish().unwrap_or_else(|e| panic!("{e}"));
+        assert!((gzipped.len() as u64) < MAX_BODY_BYTES);
+
+        let err = decode_body("gzip", gzipped).err().unwrap_or_default();
+
+        assert_eq!(err, "Failed to decode gzip body: Response body too large");
+    }
+}
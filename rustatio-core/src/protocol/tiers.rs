@@ -0,0 +1,106 @@
+//! Multi-tracker announce-list handling (BEP 12).
+//!
+//! Trackers are tried tier by tier, in order within each tier. A tracker that
+//! answers is moved to the front of its tier and remembered as the active
+//! tracker, so later announces go to it first.
+
+use std::sync::{Mutex, PoisonError};
+
+#[derive(Debug)]
+struct TierState {
+    tiers: Vec<Vec<String>>,
+    active: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct TrackerTiers {
+    state: Mutex<TierState>,
+}
+
+impl TrackerTiers {
+    pub fn new(tiers: Vec<Vec<String>>) -> Self {
+        let tiers = tiers.into_iter().filter(|tier| !tier.is_empty()).collect();
+        Self { state: Mutex::new(TierState { tiers, active: None }) }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TierState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Trackers in the order they should be tried: the last working tracker first,
+    /// then every tier in order.
+    pub fn announce_order(&self) -> Vec<String> {
+        let state = self.lock();
+        let mut order: Vec<String> = state.active.iter().cloned().collect();
+        for url in state.tiers.iter().flatten() {
+            if !order.contains(url) {
+                order.push(url.clone());
+            }
+        }
+        order
+    }
+
+    /// Tracker announces and scrapes go to: the last working one, else the first listed.
+    pub fn active_url(&self) -> String {
+        let state = self.lock();
+        state
+            .active
+            .clone()
+            .or_else(|| state.tiers.iter().flatten().next().cloned())
+            .unwrap_or_default()
+    }
+
+    /// Record a successful announce: move the tracker to the front of its tier and
+    /// keep using it.
+    pub fn mark_working(&self, url: &str) {
+        let mut state = self.lock();
+        for tier in &mut state.tiers {
+            if let Some(idx) = tier.iter().position(|candidate| candidate == url) {
+                let tracker = tier.remove(idx);
+                tier.insert(0, tracker);
+                break;
+            }
+        }
+        state.active = Some(url.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tiers() -> TrackerTiers {
+        TrackerTiers::new(vec![
+            vec!["http://a/announce".to_string(), "http://b/announce".to_string()],
+            Vec::new(),
+            vec!["http://c/announce".to_string()],
+        ])
+    }
+
+    #[test]
+    fn order_follows_tiers_until_a_tracker_works() {
+        let tiers = tiers();
+        assert_eq!(tiers.active_url(), "http://a/announce");
+        assert_eq!(
+            tiers.announce_order(),
+            vec!["http://a/announce", "http://b/announce", "http://c/announce"]
+        );
+    }
+
+    #[test]
+    fn working_tracker_is_promoted_and_tried_first() {
+        let tiers = tiers();
+        tiers.mark_working("http://b/announce");
+        assert_eq!(tiers.active_url(), "http://b/announce");
+        assert_eq!(
+            tiers.announce_order(),
+            vec!["http://b/announce", "http://a/announce", "http://c/announce"]
+        );
+
+        tiers.mark_working("http://c/announce");
+        assert_eq!(
+            tiers.announce_order(),
+            vec!["http://c/announce", "http://b/announce", "http://a/announce"]
+        );
+    }
+}
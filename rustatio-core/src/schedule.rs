@@ -0,0 +1,224 @@
+//! Time-of-day rate windows.
+//!
+//! A window covers `[start_minute, end_minute)` in minutes since midnight and
+//! wraps past midnight when `end_minute` is before `start_minute`. Equal start
+//! and end cover the whole day. When windows overlap, the first one listed wins.
+//!
+//! Also holds the small datetime parser used for wall-clock stop times.
+
+use serde::{Deserialize, Serialize};
+
+pub const MINUTES_PER_DAY: u16 = 24 * 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateSchedule {
+    /// Window start in minutes since midnight (0-1439)
+    pub start_minute: u16,
+    /// Window end in minutes since midnight (0-1439), exclusive
+    pub end_minute: u16,
+    /// Upload rate in KB/s while the window is active (optional)
+    #[serde(default)]
+    pub upload_rate: Option<f64>,
+    /// Download rate in KB/s while the window is active (optional)
+    #[serde(default)]
+    pub download_rate: Option<f64>,
+}
+
+impl RateSchedule {
+    pub const fn contains(&self, minute: u16) -> bool {
+        if self.start_minute == self.end_minute {
+            true
+        } else if self.start_minute < self.end_minute {
+            minute >= self.start_minute && minute < self.end_minute
+        } else {
+            minute >= self.start_minute || minute < self.end_minute
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.start_minute >= MINUTES_PER_DAY || self.end_minute >= MINUTES_PER_DAY {
+            return Err(format!(
+                "rate schedule minutes must be below {MINUTES_PER_DAY} (got {}-{})",
+                self.start_minute, self.end_minute
+            ));
+        }
+        for rate in [self.upload_rate, self.download_rate].into_iter().flatten() {
+            if !rate.is_finite() || rate < 0.0 {
+                return Err(format!(
+                    "rate schedule rates must be finite and not negative (got {rate})"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The first window containing `minute`, if any.
+pub fn active_rate_schedule(schedules: &[RateSchedule], minute: u16) -> Option<&RateSchedule> {
+    schedules.iter().find(|schedule| schedule.contains(minute))
+}
+
+/// Minute of the day for a unix timestamp shifted by `utc_offset_minutes`.
+pub fn minute_of_day(unix_millis: u64, utc_offset_minutes: i32) -> u16 {
+    let day = u64::from(MINUTES_PER_DAY);
+    let offset = i64::from(utc_offset_minutes).rem_euclid(i64::from(MINUTES_PER_DAY)) as u64;
+    ((unix_millis / 60_000 + offset) % day) as u16
+}
+
+/// Parse `YYYY-MM-DD[THH:MM[:SS]][Z|+HH:MM|-HH:MM]` into unix seconds. A space may
+/// replace the `T`; a missing offset means UTC and a missing time means midnight.
+pub fn parse_datetime(input: &str) -> Option<u64> {
+    let input = input.trim();
+    let (date, time) = match input.split_once(['T', 't', ' ']) {
+        Some((date, time)) => (date, Some(time.trim())),
+        None => (input, None),
+    };
+
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: u32 = date_parts.next()?.parse().ok()?;
+    let day: u32 = date_parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let (clock, offset_secs) = match time {
+        None => ("00:00", 0),
+        Some(time) => split_utc_offset(time)?,
+    };
+    let mut clock_parts = clock.splitn(3, ':');
+    let hour: u32 = clock_parts.next()?.parse().ok()?;
+    let minute: u32 = clock_parts.next()?.parse().ok()?;
+    let second = match clock_parts.next() {
+        Some(second) => second.parse::<f64>().ok().filter(|s| (0.0..61.0).contains(s))? as i64,
+        None => 0,
+    };
+    if hour > 23 || minute > 59 {
+        return None;
+    }
+
+    let time_of_day = i64::from(hour) * 3600 + i64::from(minute) * 60 + second;
+    let secs = days_from_civil(year, month, day) * 86_400 + time_of_day - offset_secs;
+    u64::try_from(secs).ok()
+}
+
+/// Split a trailing `Z` or `±HH:MM` offset off a time, returning it in seconds.
+fn split_utc_offset(time: &str) -> Option<(&str, i64)> {
+    if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        return Some((clock, 0));
+    }
+    let Some(idx) = time.rfind(['+', '-']) else {
+        return Some((time, 0));
+    };
+    let (clock, offset) = time.split_at(idx);
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let offset = offset.get(1..)?;
+    let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "0"));
+    let hours: i64 = hours.parse().ok()?;
+    let minutes: i64 = minutes.parse().ok()?;
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+    Some((clock, sign * (hours * 3600 + minutes * 60)))
+}
+
+const fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+const fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * shifted_month + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Year, month and day of month for a count of days since 1970-01-01, the inverse
+/// of `days_from_civil`.
+pub const fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = era * 400 + year_of_era + if month <= 2 { 1 } else { 0 };
+    (year, month as u32, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(start_minute: u16, end_minute: u16, upload_rate: f64) -> RateSchedule {
+        RateSchedule {
+            start_minute,
+            end_minute,
+            upload_rate: Some(upload_rate),
+            download_rate: None,
+        }
+    }
+
+    #[test]
+    fn windows_wrap_past_midnight_and_first_match_wins() {
+        let schedules = vec![window(22 * 60, 6 * 60, 500.0), window(0, 12 * 60, 10.0)];
+
+        let at = |minute| active_rate_schedule(&schedules, minute).and_then(|s| s.upload_rate);
+        assert_eq!(at(23 * 60), Some(500.0));
+        assert_eq!(at(3 * 60), Some(500.0));
+        assert_eq!(at(8 * 60), Some(10.0));
+        assert_eq!(at(18 * 60), None);
+    }
+
+    #[test]
+    fn minute_of_day_applies_offset() {
+        let ten_past_midnight = 10 * 60_000;
+        assert_eq!(minute_of_day(ten_past_midnight, 0), 10);
+        assert_eq!(minute_of_day(ten_past_midnight, -60), 23 * 60 + 10);
+        assert_eq!(minute_of_day(ten_past_midnight, 120), 130);
+    }
+
+    #[test]
+    fn validate_rejects_out_of_range_minutes() {
+        assert!(window(0, MINUTES_PER_DAY, 1.0).validate().is_err());
+        assert!(window(0, 60, -1.0).validate().is_err());
+        assert!(window(60, 0, 1.0).validate().is_ok());
+    }
+
+    #[test]
+    fn parse_datetime_handles_dates_times_and_offsets() {
+        assert_eq!(parse_datetime("1970-01-01"), Some(0));
+        assert_eq!(parse_datetime("2025-01-01T00:00Z"), Some(1_735_689_600));
+        assert_eq!(parse_datetime("2025-01-01 06:30:15"), Some(1_735_689_600 + 23_415));
+        assert_eq!(parse_datetime("2025-01-01T02:00+02:00"), Some(1_735_689_600));
+        assert_eq!(parse_datetime("2024-12-31T19:00:00-05:00"), Some(1_735_689_600));
+        assert_eq!(parse_datetime("2024-02-29"), Some(1_709_164_800));
+        assert_eq!(parse_datetime("2023-02-29"), None);
+        assert_eq!(parse_datetime("2025-01-01T24:00Z"), None);
+        assert_eq!(parse_datetime("tomorrow"), None);
+    }
+
+    #[test]
+    fn civil_from_days_inverts_days_from_civil() {
+        for (year, month, day) in [(1970, 1, 1), (2024, 2, 29), (2025, 3, 1), (1969, 12, 31)] {
+            assert_eq!(civil_from_days(days_from_civil(year, month, day)), (year, month, day));
+        }
+    }
+}
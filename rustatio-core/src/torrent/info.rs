@@ -1,8 +1,9 @@
 use crate::protocol::bencode;
 use crate::protocol::BencodeError;
-use crate::{log_debug, log_error, log_trace};
+use crate::{log_debug, log_error, log_trace, log_warn};
 use serde::{Deserialize, Serialize};
 use sha1::{Digest, Sha1};
+use sha2::Sha256;
 use std::fmt::Write;
 use std::path::Path;
 use thiserror::Error;
@@ -13,6 +14,22 @@ pub enum TorrentError {
     BencodeError(#[from] BencodeError),
     #[error("Invalid torrent structure: {0}")]
     InvalidStructure(String),
+    #[error("Missing info dictionary")]
+    MissingInfoDict,
+    #[error("Missing torrent name")]
+    MissingName,
+    #[error("Invalid piece length: {0}")]
+    InvalidPieceLength(i64),
+    #[error("Info dictionary has neither 'length' nor 'files'")]
+    MissingFiles,
+    #[error("v2 info dictionary has no 'file tree'")]
+    MissingFileTree,
+    #[error("Invalid file entry: {0}")]
+    InvalidFileEntry(String),
+    #[error("Invalid file length: {0}")]
+    InvalidFileLength(i64),
+    #[error("Total size does not fit in 64 bits")]
+    TotalSizeOverflow,
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
@@ -21,11 +38,22 @@ pub type Result<T> = std::result::Result<T, TorrentError>;
 
 type BencodeDict = std::collections::HashMap<Vec<u8>, serde_bencode::value::Value>;
 
+/// The v1 info hash and, when the torrent has one, the v2 info hash
+type InfoHashes = ([u8; 20], Option<[u8; 32]>);
+
+/// The primary announce URL and the announce-list tiers
+type Trackers = (String, Option<Vec<Vec<String>>>);
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TorrentInfo {
-    /// SHA1 hash of the info dictionary (20 bytes)
+    /// SHA1 hash of the info dictionary (20 bytes). For v2-only torrents this is the
+    /// v2 hash truncated to 20 bytes, which is what trackers know them by (BEP 52).
     pub info_hash: [u8; 20],
 
+    /// SHA-256 hash of the info dictionary, for v2 and hybrid torrents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info_hash_v2: Option<[u8; 32]>,
+
     /// Announce URL (tracker)
     pub announce: String,
 
@@ -47,7 +75,7 @@ pub struct TorrentInfo {
 
     /// Creation date (Unix timestamp)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub creation_date: Option<i64>,
+    pub creation_date: Option<u64>,
 
     /// Comment
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -60,6 +88,10 @@ pub struct TorrentInfo {
     /// Is this a single-file or multi-file torrent
     pub is_single_file: bool,
 
+    /// Private torrent (BEP 27): peers come only from the listed trackers
+    #[serde(default)]
+    pub is_private: bool,
+
     /// Number of files in the torrent
     #[serde(default, skip_serializing_if = "is_zero_usize")]
     pub file_count: usize,
@@ -67,12 +99,20 @@ pub struct TorrentInfo {
     /// File list (for multi-file torrents)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub files: Vec<TorrentFile>,
+
+    /// Length of every file in torrent order; kept when the file list is dropped
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub file_lengths: Vec<u64>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TorrentSummary {
-    /// SHA1 hash of the info dictionary (20 bytes)
+    /// SHA1 hash of the info dictionary (20 bytes), or the truncated v2 hash for v2-only
+    /// torrents
     pub info_hash: [u8; 20],
+    /// SHA-256 hash of the info dictionary, for v2 and hybrid torrents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info_hash_v2: Option<[u8; 32]>,
     /// Announce URL (tracker)
     pub announce: String,
     /// Optional announce list for multiple trackers
@@ -88,7 +128,7 @@ pub struct TorrentSummary {
     pub num_pieces: usize,
     /// Creation date (Unix timestamp)
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub creation_date: Option<i64>,
+    pub creation_date: Option<u64>,
     /// Comment
     #[serde(skip_serializing_if = "Option::is_none")]
     pub comment: Option<String>,
@@ -97,9 +137,15 @@ pub struct TorrentSummary {
     pub created_by: Option<String>,
     /// Is this a single-file or multi-file torrent
     pub is_single_file: bool,
+    /// Private torrent (BEP 27)
+    #[serde(default)]
+    pub is_private: bool,
     /// Number of files (multi-file torrents)
     #[serde(default)]
     pub file_count: usize,
+    /// Length of every file in torrent order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub file_lengths: Vec<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -139,125 +185,50 @@ impl TorrentInfo {
             return Err(TorrentError::InvalidStructure("Root is not a dictionary".into()));
         };
 
-        // Extract announce URL
-        let announce = bencode::get_string(dict, "announce")?;
-
-        // Extract announce-list (optional)
-        let announce_list = dict
-            .get(b"announce-list".as_ref())
-            .and_then(|v| match v {
-                serde_bencode::value::Value::List(list) => Some(list),
-                _ => None,
-            })
-            .map(|list| {
-                list.iter()
-                    .filter_map(|tier| match tier {
-                        serde_bencode::value::Value::List(t) => Some(t),
-                        _ => None,
-                    })
-                    .map(|tier| {
-                        tier.iter()
-                            .filter_map(|url| match url {
-                                serde_bencode::value::Value::Bytes(b) => {
-                                    Some(String::from_utf8_lossy(b).to_string())
-                                }
-                                _ => None,
-                            })
-                            .collect()
-                    })
-                    .collect()
-            });
+        // Extract the announce URL and announce-list (optional), keeping usable trackers
+        let (announce, announce_list) = validated_trackers(
+            bencode::get_string(dict, "announce")?,
+            TorrentSummary::announce_list(dict),
+        )?;
 
         // Extract info dictionary
-        let info_dict = dict
-            .get(b"info".as_ref())
-            .and_then(|v| match v {
-                serde_bencode::value::Value::Dict(d) => Some(d),
-                _ => None,
-            })
-            .ok_or_else(|| TorrentError::InvalidStructure("Missing info dictionary".into()))?;
-
-        // Calculate info_hash (SHA1 of bencoded info dict)
-        let info_hash = calculate_info_hash(data)?;
-
-        // Extract name
-        let name = bencode::get_string(info_dict, "name")?;
+        let info_dict = TorrentSummary::info_dict(dict)?;
 
-        // Extract piece length
-        let piece_length = bencode::get_int(info_dict, "piece length")? as u64;
+        // Calculate info_hash (SHA1 of bencoded info dict) and the v2 hash if any
+        let (info_hash, info_hash_v2) = calculate_info_hashes(data, info_dict)?;
 
-        // Extract pieces length only (avoid cloning piece hash data)
-        let pieces_len = bencode::get_bytes_len(info_dict, "pieces")?;
-        let num_pieces = pieces_len / 20;
+        // Extract name, piece length and piece count
+        let (name, piece_length, num_pieces) = TorrentSummary::basic_info(info_dict)?;
 
         // Determine if single-file or multi-file
-        let (is_single_file, total_size, files, file_count) = if let Ok(length) =
-            bencode::get_int(info_dict, "length")
-        {
-            // Single file torrent
-            (
-                true,
-                length as u64,
-                vec![TorrentFile { path: vec![name.clone()], length: length as u64 }],
-                1,
-            )
-        } else if let Some(files_list) = info_dict.get(b"files".as_ref()).and_then(|v| match v {
-            serde_bencode::value::Value::List(l) => Some(l),
-            _ => None,
-        }) {
-            // Multi-file torrent
-            let mut files = Vec::new();
-            let mut total = 0u64;
-            let mut count = 0usize;
-
-            for file_val in files_list {
-                let serde_bencode::value::Value::Dict(file_dict) = file_val else {
-                    return Err(TorrentError::InvalidStructure("Invalid file entry".into()));
-                };
-
-                let length = bencode::get_int(file_dict, "length")? as u64;
-
-                let path = file_dict
-                    .get(b"path".as_ref())
-                    .and_then(|v| match v {
-                        serde_bencode::value::Value::List(l) => Some(l),
-                        _ => None,
-                    })
-                    .ok_or_else(|| TorrentError::InvalidStructure("Invalid file path".into()))?
-                    .iter()
-                    .filter_map(|p| match p {
-                        serde_bencode::value::Value::Bytes(b) => {
-                            Some(String::from_utf8_lossy(b).to_string())
-                        }
-                        _ => None,
-                    })
-                    .collect();
-
-                files.push(TorrentFile { path, length });
-                total += length;
-                count += 1;
-            }
-
-            (false, total, files, count)
+        let (is_single_file, files) = if info_dict.contains_key(b"length".as_ref()) {
+            let length = file_length(info_dict)?;
+            (true, vec![TorrentFile { path: vec![name.clone()], length }])
+        } else if let Some(files_list) = TorrentSummary::files_list(info_dict) {
+            let files = files_list
+                .iter()
+                .map(|file_val| {
+                    let serde_bencode::value::Value::Dict(file_dict) = file_val else {
+                        return Err(TorrentError::InvalidFileEntry("not a dictionary".into()));
+                    };
+                    Ok(TorrentFile { path: file_path(file_dict)?, length: file_length(file_dict)? })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            (false, files)
+        } else if TorrentSummary::is_v2(info_dict) {
+            // v2-only torrent: files live in the file tree
+            let files = TorrentSummary::v2_files(info_dict)?;
+            (is_single_file_tree(&files), files)
         } else {
-            return Err(TorrentError::InvalidStructure(
-                "Neither 'length' nor 'files' found in info dictionary".into(),
-            ));
+            return Err(TorrentError::MissingFiles);
         };
+        let total_size = total_size(files.iter().map(|file| file.length))?;
+        let file_count = files.len();
 
         // Extract optional fields
-        let creation_date = dict.get(b"creation date".as_ref()).and_then(|v| match v {
-            serde_bencode::value::Value::Int(i) => Some(*i),
-            _ => None,
-        });
-        let comment = dict.get(b"comment".as_ref()).and_then(|v| match v {
-            serde_bencode::value::Value::Bytes(b) => Some(String::from_utf8_lossy(b).to_string()),
-            _ => None,
-        });
-        let created_by = dict.get(b"created by".as_ref()).and_then(|v| match v {
-            serde_bencode::value::Value::Bytes(b) => Some(String::from_utf8_lossy(b).to_string()),
-            _ => None,
-        });
+        let (creation_date, comment, created_by) = TorrentSummary::optional_fields(dict);
+        let is_private = TorrentSummary::is_private(info_dict);
+        let file_lengths = files.iter().map(|file| file.length).collect();
 
         log_debug!(
             "Parsed torrent: name='{}', size={} bytes, pieces={}, tracker={}",
@@ -276,6 +247,7 @@ impl TorrentInfo {
 
         Ok(Self {
             info_hash,
+            info_hash_v2,
             announce,
             announce_list,
             name,
@@ -286,8 +258,10 @@ impl TorrentInfo {
             comment,
             created_by,
             is_single_file,
+            is_private,
             file_count,
             files,
+            file_lengths,
         })
     }
 
@@ -302,6 +276,25 @@ impl TorrentInfo {
         &self.announce
     }
 
+    /// Tracker tiers to announce to (BEP 12): the announce-list when it has any
+    /// trackers, otherwise the single announce URL. Tiers keep the order of the torrent
+    /// file and are never shuffled, which private trackers in particular rely on.
+    pub fn tracker_tiers(&self) -> Vec<Vec<String>> {
+        let tiers: Vec<Vec<String>> = self
+            .announce_list
+            .iter()
+            .flatten()
+            .map(|tier| tier.iter().filter(|url| !url.is_empty()).cloned().collect())
+            .filter(|tier: &Vec<String>| !tier.is_empty())
+            .collect();
+
+        if tiers.is_empty() {
+            vec![vec![self.announce.clone()]]
+        } else {
+            tiers
+        }
+    }
+
     /// Get all tracker URLs (from announce and announce-list)
     pub fn get_all_tracker_urls(&self) -> Vec<String> {
         let mut urls = vec![self.announce.clone()];
@@ -323,11 +316,37 @@ impl TorrentInfo {
         })
     }
 
+    /// Total size of the files at `indices` (duplicates counted once). `None` when an
+    /// index is out of range or the file lengths are unknown.
+    pub fn selected_size(&self, indices: &[usize]) -> Option<u64> {
+        let selected: std::collections::BTreeSet<usize> = indices.iter().copied().collect();
+        selected.iter().map(|&index| self.file_lengths.get(index).copied()).sum()
+    }
+
+    /// Format `info_hash_v2` as hex string, for v2 and hybrid torrents
+    pub fn info_hash_v2_hex(&self) -> Option<String> {
+        self.info_hash_v2.map(|hash| {
+            hash.iter().fold(String::new(), |mut acc, b| {
+                let _ = write!(acc, "{b:02x}");
+                acc
+            })
+        })
+    }
+
+    /// The truncated v2 hash hybrid torrents also announce under to join the v2 swarm
+    /// (BEP 52). `None` for v1 torrents and for v2-only ones, whose `info_hash` is it.
+    pub fn hybrid_info_hash_v2(&self) -> Option<[u8; 20]> {
+        let mut truncated = [0u8; 20];
+        truncated.copy_from_slice(&self.info_hash_v2?[..20]);
+        (truncated != self.info_hash).then_some(truncated)
+    }
+
     /// Build a lightweight summary (excludes file list)
     pub fn summary(&self) -> TorrentSummary {
         let file_count = if self.file_count > 0 { self.file_count } else { self.files.len() };
         TorrentSummary {
             info_hash: self.info_hash,
+            info_hash_v2: self.info_hash_v2,
             announce: self.announce.clone(),
             announce_list: self.announce_list.clone(),
             name: self.name.clone(),
@@ -338,7 +357,9 @@ impl TorrentInfo {
             comment: self.comment.clone(),
             created_by: self.created_by.clone(),
             is_single_file: self.is_single_file,
+            is_private: self.is_private,
             file_count,
+            file_lengths: self.file_lengths.clone(),
         }
     }
 
@@ -359,16 +380,19 @@ impl TorrentSummary {
         let value = bencode::parse(data)?;
 
         let dict = Self::root_dict(&value)?;
-        let announce = bencode::get_string(dict, "announce")?;
-        let announce_list = Self::announce_list(dict);
+        let (announce, announce_list) =
+            validated_trackers(bencode::get_string(dict, "announce")?, Self::announce_list(dict))?;
         let info_dict = Self::info_dict(dict)?;
-        let info_hash = calculate_info_hash(data)?;
+        let (info_hash, info_hash_v2) = calculate_info_hashes(data, info_dict)?;
         let (name, piece_length, num_pieces) = Self::basic_info(info_dict)?;
-        let (is_single_file, total_size, file_count) = Self::files_summary(info_dict)?;
+        let (is_single_file, total_size, file_lengths) = Self::files_summary(info_dict)?;
+        let file_count = file_lengths.len();
         let (creation_date, comment, created_by) = Self::optional_fields(dict);
+        let is_private = Self::is_private(info_dict);
 
         Ok(Self {
             info_hash,
+            info_hash_v2,
             announce,
             announce_list,
             name,
@@ -379,7 +403,9 @@ impl TorrentSummary {
             comment,
             created_by,
             is_single_file,
+            is_private,
             file_count,
+            file_lengths,
         })
     }
 
@@ -397,7 +423,14 @@ impl TorrentSummary {
                 serde_bencode::value::Value::Dict(d) => Some(d),
                 _ => None,
             })
-            .ok_or_else(|| TorrentError::InvalidStructure("Missing info dictionary".into()))
+            .ok_or(TorrentError::MissingInfoDict)
+    }
+
+    fn files_list(info_dict: &BencodeDict) -> Option<&Vec<serde_bencode::value::Value>> {
+        info_dict.get(b"files".as_ref()).and_then(|v| match v {
+            serde_bencode::value::Value::List(l) => Some(l),
+            _ => None,
+        })
     }
 
     fn announce_list(dict: &BencodeDict) -> Option<Vec<Vec<String>>> {
@@ -427,56 +460,75 @@ impl TorrentSummary {
     }
 
     fn basic_info(info_dict: &BencodeDict) -> Result<(String, u64, usize)> {
-        let name = bencode::get_string(info_dict, "name")?;
-        let piece_length = bencode::get_int(info_dict, "piece length")? as u64;
-        let pieces_len = bencode::get_bytes_len(info_dict, "pieces")?;
-        let num_pieces = pieces_len / 20;
+        let name = text_field(info_dict, "name").ok_or(TorrentError::MissingName)?;
+        let piece_length = match bencode::get_int(info_dict, "piece length") {
+            Ok(length) if length > 0 => length.cast_unsigned(),
+            Ok(length) => return Err(TorrentError::InvalidPieceLength(length)),
+            Err(e) => return Err(e.into()),
+        };
+        let num_pieces = match bencode::get_bytes_len(info_dict, "pieces") {
+            Ok(pieces_len) => pieces_len / 20,
+            // v2-only torrents keep piece hashes outside the info dict, one layer per file
+            Err(_) if Self::is_v2(info_dict) && piece_length > 0 => Self::v2_files(info_dict)?
+                .iter()
+                .map(|file| file.length.div_ceil(piece_length) as usize)
+                .sum(),
+            Err(e) => return Err(e.into()),
+        };
         Ok((name, piece_length, num_pieces))
     }
 
-    fn files_summary(info_dict: &BencodeDict) -> Result<(bool, u64, usize)> {
-        if let Ok(length) = bencode::get_int(info_dict, "length") {
-            return Ok((true, length as u64, 1));
-        }
-
-        let Some(files_list) = info_dict.get(b"files".as_ref()).and_then(|v| match v {
-            serde_bencode::value::Value::List(l) => Some(l),
-            _ => None,
-        }) else {
-            return Err(TorrentError::InvalidStructure(
-                "Neither 'length' nor 'files' found in info dictionary".into(),
-            ));
-        };
+    /// Whether the info dictionary sets `private` to 1 (BEP 27)
+    fn is_private(info_dict: &BencodeDict) -> bool {
+        bencode::get_int(info_dict, "private").is_ok_and(|private| private == 1)
+    }
 
-        let mut total = 0u64;
-        let mut count = 0usize;
+    /// Whether the info dictionary declares BEP 52 (`meta version` 2), as v2-only and
+    /// hybrid torrents do
+    fn is_v2(info_dict: &BencodeDict) -> bool {
+        bencode::get_int(info_dict, "meta version").is_ok_and(|version| version == 2)
+    }
 
-        for file_val in files_list {
-            let serde_bencode::value::Value::Dict(file_dict) = file_val else {
-                return Err(TorrentError::InvalidStructure("Invalid file entry".into()));
-            };
+    /// Files listed in a v2 `file tree`, in path order
+    fn v2_files(info_dict: &BencodeDict) -> Result<Vec<TorrentFile>> {
+        let Some(serde_bencode::value::Value::Dict(tree)) = info_dict.get(b"file tree".as_ref())
+        else {
+            return Err(TorrentError::MissingFileTree);
+        };
+        let mut files = Vec::new();
+        collect_file_tree(tree, &mut Vec::new(), &mut files)?;
+        Ok(files)
+    }
 
-            let length = bencode::get_int(file_dict, "length")? as u64;
-            total += length;
-            count += 1;
-        }
+    fn files_summary(info_dict: &BencodeDict) -> Result<(bool, u64, Vec<u64>)> {
+        let (is_single_file, lengths) = if info_dict.contains_key(b"length".as_ref()) {
+            (true, vec![file_length(info_dict)?])
+        } else if let Some(files_list) = Self::files_list(info_dict) {
+            let lengths = files_list
+                .iter()
+                .map(|file_val| match file_val {
+                    serde_bencode::value::Value::Dict(file_dict) => file_length(file_dict),
+                    _ => Err(TorrentError::InvalidFileEntry("not a dictionary".into())),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            (false, lengths)
+        } else if Self::is_v2(info_dict) {
+            let files = Self::v2_files(info_dict)?;
+            (is_single_file_tree(&files), files.iter().map(|file| file.length).collect())
+        } else {
+            return Err(TorrentError::MissingFiles);
+        };
 
-        Ok((false, total, count))
+        Ok((is_single_file, total_size(lengths.iter().copied())?, lengths))
     }
 
-    fn optional_fields(dict: &BencodeDict) -> (Option<i64>, Option<String>, Option<String>) {
+    fn optional_fields(dict: &BencodeDict) -> (Option<u64>, Option<String>, Option<String>) {
         let creation_date = dict.get(b"creation date".as_ref()).and_then(|v| match v {
-            serde_bencode::value::Value::Int(i) => Some(*i),
-            _ => None,
-        });
-        let comment = dict.get(b"comment".as_ref()).and_then(|v| match v {
-            serde_bencode::value::Value::Bytes(b) => Some(String::from_utf8_lossy(b).to_string()),
-            _ => None,
-        });
-        let created_by = dict.get(b"created by".as_ref()).and_then(|v| match v {
-            serde_bencode::value::Value::Bytes(b) => Some(String::from_utf8_lossy(b).to_string()),
+            serde_bencode::value::Value::Int(i) => u64::try_from(*i).ok(),
             _ => None,
         });
+        let comment = text_field(dict, "comment");
+        let created_by = text_field(dict, "created by");
 
         (creation_date, comment, created_by)
     }
@@ -485,6 +537,7 @@ impl TorrentSummary {
     pub fn to_info(&self) -> TorrentInfo {
         TorrentInfo {
             info_hash: self.info_hash,
+            info_hash_v2: self.info_hash_v2,
             announce: self.announce.clone(),
             announce_list: self.announce_list.clone(),
             name: self.name.clone(),
@@ -495,47 +548,167 @@ impl TorrentSummary {
             comment: self.comment.clone(),
             created_by: self.created_by.clone(),
             is_single_file: self.is_single_file,
+            is_private: self.is_private,
             file_count: self.file_count,
             files: Vec::new(),
+            file_lengths: self.file_lengths.clone(),
         }
     }
 }
 
-/// Calculate the SHA1 `info_hash` from torrent bytes
-fn calculate_info_hash(torrent_data: &[u8]) -> Result<[u8; 20]> {
-    // Parse the torrent to find the info dictionary
-    let value = bencode::parse(torrent_data)?;
-    let serde_bencode::value::Value::Dict(_dict) = &value else {
-        return Err(TorrentError::InvalidStructure("Root is not a dictionary".into()));
-    };
+/// Walk a v2 file tree: every key is a path component, and an empty key holds the file.
+fn collect_file_tree(
+    node: &BencodeDict,
+    path: &mut Vec<String>,
+    files: &mut Vec<TorrentFile>,
+) -> Result<()> {
+    let mut entries: Vec<_> = node.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    for (key, value) in entries {
+        let serde_bencode::value::Value::Dict(child) = value else {
+            return Err(TorrentError::InvalidStructure("Invalid file tree entry".into()));
+        };
+        if key.is_empty() {
+            files.push(TorrentFile { path: path.clone(), length: file_length(child)? });
+        } else {
+            path.push(decode_text(key, "file tree path"));
+            collect_file_tree(child, path, files)?;
+            path.pop();
+        }
+    }
+    Ok(())
+}
 
-    // We need to find the raw bytes of the info dictionary in the original data
-    // This is a bit tricky because we need the exact bencoded representation
+/// A text field, preferring the `<key>.utf-8` variant some clients write next to a
+/// field in the creator's local encoding
+fn text_field(dict: &BencodeDict, key: &str) -> Option<String> {
+    [format!("{key}.utf-8"), key.to_string()].iter().find_map(|key| {
+        match dict.get(key.as_bytes()) {
+            Some(serde_bencode::value::Value::Bytes(b)) => Some(decode_text(b, key)),
+            _ => None,
+        }
+    })
+}
+
+/// Decode text that should be UTF-8, replacing invalid sequences with a warning instead
+/// of rejecting the torrent
+fn decode_text(bytes: &[u8], what: &str) -> String {
+    std::str::from_utf8(bytes).map_or_else(
+        |_| {
+            let text = String::from_utf8_lossy(bytes).to_string();
+            log_warn!("Torrent {} is not valid UTF-8, showing it as '{}'", what, text);
+            text
+        },
+        str::to_string,
+    )
+}
+
+/// A file's path components from `path.utf-8` or `path`
+fn file_path(file_dict: &BencodeDict) -> Result<Vec<String>> {
+    let components = ["path.utf-8", "path"]
+        .iter()
+        .find_map(|key| match file_dict.get(key.as_bytes()) {
+            Some(serde_bencode::value::Value::List(l)) => Some(l),
+            _ => None,
+        })
+        .ok_or_else(|| TorrentError::InvalidFileEntry("missing path".into()))?;
+    Ok(components
+        .iter()
+        .filter_map(|component| match component {
+            serde_bencode::value::Value::Bytes(b) => Some(decode_text(b, "file path")),
+            _ => None,
+        })
+        .collect())
+}
+
+/// The `length` of a file entry, or of the single file in the info dictionary
+fn file_length(dict: &BencodeDict) -> Result<u64> {
+    match dict.get(b"length".as_ref()) {
+        Some(serde_bencode::value::Value::Int(length)) => {
+            u64::try_from(*length).map_err(|_| TorrentError::InvalidFileLength(*length))
+        }
+        Some(_) => Err(TorrentError::InvalidFileEntry("length is not an integer".into())),
+        None => Err(TorrentError::InvalidFileEntry("missing length".into())),
+    }
+}
+
+/// Sum of the file lengths, rejected when it doesn't fit in 64 bits
+fn total_size(lengths: impl IntoIterator<Item = u64>) -> Result<u64> {
+    lengths.into_iter().try_fold(0u64, |total, length| {
+        total.checked_add(length).ok_or(TorrentError::TotalSizeOverflow)
+    })
+}
 
-    // Find "4:info" in the data to locate the info dict
-    let info_marker = b"4:info";
-    let info_start = torrent_data
-        .windows(info_marker.len())
-        .position(|window| window == info_marker)
-        .ok_or_else(|| TorrentError::InvalidStructure("Could not find info dictionary".into()))?
-        + info_marker.len();
+/// A v2 single-file torrent has one file at the root of its tree
+fn is_single_file_tree(files: &[TorrentFile]) -> bool {
+    matches!(files, [file] if file.path.len() == 1)
+}
+
+/// The announce URL and announce-list without the URLs no tracker could be reached
+/// through. An unusable primary URL is replaced by the first usable tier entry; a torrent
+/// left without any tracker is rejected with the validation module's message.
+fn validated_trackers(
+    announce: String,
+    announce_list: Option<Vec<Vec<String>>>,
+) -> Result<Trackers> {
+    let usable = |url: &String| crate::validation::validate_announce_url(url).is_ok();
+    let announce_list = announce_list
+        .map(|tiers| {
+            tiers
+                .into_iter()
+                .map(|tier| tier.into_iter().filter(usable).collect::<Vec<_>>())
+                .filter(|tier| !tier.is_empty())
+                .collect::<Vec<_>>()
+        })
+        .filter(|tiers| !tiers.is_empty());
 
-    // Parse just the info dictionary to get its bencoded representation
-    let info_value =
-        serde_bencode::from_bytes::<serde_bencode::value::Value>(&torrent_data[info_start..])
-            .map_err(|e| BencodeError::ParseError(e.to_string()))?;
+    if let Err(e) = crate::validation::validate_announce_url(&announce) {
+        let Some(fallback) = announce_list.iter().flatten().flatten().next().cloned() else {
+            return Err(TorrentError::InvalidStructure(e.to_string()));
+        };
+        log_warn!("Ignoring the torrent's unusable announce URL, using its announce-list");
+        return Ok((fallback, announce_list));
+    }
+    Ok((announce, announce_list))
+}
 
-    let info_bytes = serde_bencode::to_bytes(&info_value)
-        .map_err(|e| BencodeError::ParseError(e.to_string()))?;
+/// Calculate the v1 `info_hash` and, for v2 and hybrid torrents, the SHA-256 one.
+/// v2-only torrents have no v1 hash, so the truncated v2 hash stands in for it.
+fn calculate_info_hashes(torrent_data: &[u8], info_dict: &BencodeDict) -> Result<InfoHashes> {
+    let info_bytes = info_dict_bytes(torrent_data)?;
+
+    let info_hash_v2 = TorrentSummary::is_v2(info_dict).then(|| {
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&Sha256::digest(info_bytes));
+        hash
+    });
+
+    let info_hash = match info_hash_v2 {
+        Some(v2) if !info_dict.contains_key(b"pieces".as_ref()) => {
+            let mut hash = [0u8; 20];
+            hash.copy_from_slice(&v2[..20]);
+            hash
+        }
+        _ => sha1_digest(info_bytes),
+    };
+    Ok((info_hash, info_hash_v2))
+}
 
-    // Calculate SHA1
+fn sha1_digest(bytes: &[u8]) -> [u8; 20] {
     let mut hasher = Sha1::new();
-    hasher.update(&info_bytes);
+    hasher.update(bytes);
     let result = hasher.finalize();
 
     let mut hash = [0u8; 20];
     hash.copy_from_slice(&result);
-    Ok(hash)
+    hash
+}
+
+/// The bencoded info dictionary exactly as it appears in the torrent bytes. Hashing a
+/// re-encoding instead would give torrents whose keys aren't sorted the wrong hash.
+fn info_dict_bytes(torrent_data: &[u8]) -> Result<&[u8]> {
+    bencode::raw_dict_value(torrent_data, b"info")?.ok_or(TorrentError::MissingInfoDict)
 }
 
 #[cfg(test)]
@@ -546,7 +719,7 @@ mod tests {
     use std::path::PathBuf;
 
     fn info_hash(data: &[u8]) -> Result<[u8; 20]> {
-        calculate_info_hash(data)
+        info_dict_bytes(data).map(sha1_digest)
     }
 
     fn dict(entries: Vec<(Vec<u8>, Value)>) -> Value {
@@ -634,6 +807,7 @@ mod tests {
                 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0, 0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc,
                 0xde, 0xf0, 0x12, 0x34, 0x56, 0x78,
             ],
+            info_hash_v2: None,
             announce: "http://tracker.example.com/announce".to_string(),
             announce_list: None,
             name: "test".to_string(),
@@ -644,8 +818,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: vec![],
+            file_lengths: Vec::new(),
         };
 
         assert_eq!(info.info_hash_hex(), "123456789abcdef0123456789abcdef012345678");
@@ -680,12 +856,94 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_from_bytes_metadata_fields() -> Result<()> {
+        let Value::Dict(mut root) = sample_single_file() else {
+            unreachable!("sample torrent root is a dict");
+        };
+        root.insert(b"comment".to_vec(), bytes("from the test tracker"));
+        root.insert(b"created by".to_vec(), bytes("mktorrent 1.1"));
+        root.insert(b"creation date".to_vec(), int(1_700_000_000));
+        let data = encode(&Value::Dict(root.clone()))?;
+
+        let torrent = TorrentInfo::from_bytes(&data)?;
+        let summary = TorrentSummary::from_bytes(&data)?;
+
+        assert_eq!(torrent.comment.as_deref(), Some("from the test tracker"));
+        assert_eq!(torrent.created_by.as_deref(), Some("mktorrent 1.1"));
+        assert_eq!(torrent.creation_date, Some(1_700_000_000));
+        assert_eq!(summary.comment, torrent.comment);
+        assert_eq!(summary.created_by, torrent.created_by);
+        assert_eq!(summary.creation_date, torrent.creation_date);
+
+        root.insert(b"creation date".to_vec(), int(-1));
+        let data = encode(&Value::Dict(root))?;
+        assert_eq!(TorrentInfo::from_bytes(&data)?.creation_date, None);
+        Ok(())
+    }
+
     #[test]
     fn test_from_bytes_missing_info() -> Result<()> {
-        let data = encode(&dict(vec![(b"announce".to_vec(), bytes("x"))]))?;
+        let announce = bytes("http://tracker.test/announce");
+        let data = encode(&dict(vec![(b"announce".to_vec(), announce)]))?;
         let res = TorrentInfo::from_bytes(&data);
 
-        assert!(matches!(res, Err(TorrentError::InvalidStructure(_))));
+        assert!(matches!(res, Err(TorrentError::MissingInfoDict)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_unusable_announce_url() -> Result<()> {
+        let info = dict(vec![
+            (b"name".to_vec(), bytes("file.txt")),
+            (b"piece length".to_vec(), int(1)),
+            (b"pieces".to_vec(), pieces(1)),
+            (b"length".to_vec(), int(1)),
+        ]);
+        let data = encode(&dict(vec![
+            (b"announce".to_vec(), bytes("wss://tracker.test/announce")),
+            (b"info".to_vec(), info),
+        ]))?;
+
+        for res in [
+            TorrentInfo::from_bytes(&data).map(|_| ()),
+            TorrentSummary::from_bytes(&data).map(|_| ()),
+        ] {
+            let Err(err) = res else { panic!("unusable announce URL accepted") };
+            assert_eq!(
+                err.to_string(),
+                "Invalid torrent structure: Invalid announce URL: scheme must be http, https \
+                 or udp, got wss (wss://tracker.test/announce)"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bytes_drops_unusable_trackers_but_keeps_usable_ones() -> Result<()> {
+        let info = dict(vec![
+            (b"name".to_vec(), bytes("file.txt")),
+            (b"piece length".to_vec(), int(1)),
+            (b"pieces".to_vec(), pieces(1)),
+            (b"length".to_vec(), int(1)),
+        ]);
+        let tiers = Value::List(vec![
+            Value::List(vec![bytes("wss://tracker.test/announce")]),
+            Value::List(vec![bytes("not a url"), bytes("https://backup.test/announce")]),
+        ]);
+        let data = encode(&dict(vec![
+            (b"announce".to_vec(), bytes("wss://tracker.test/announce")),
+            (b"announce-list".to_vec(), tiers),
+            (b"info".to_vec(), info),
+        ]))?;
+
+        let expected = Some(vec![vec!["https://backup.test/announce".to_string()]]);
+        let torrent = TorrentInfo::from_bytes(&data)?;
+        assert_eq!(torrent.announce, "https://backup.test/announce");
+        assert_eq!(torrent.announce_list, expected);
+        let summary = TorrentSummary::from_bytes(&data)?;
+        assert_eq!(summary.announce, "https://backup.test/announce");
+        assert_eq!(summary.announce_list, expected);
         Ok(())
     }
 
@@ -709,7 +967,7 @@ mod tests {
     #[test]
     fn test_from_bytes_missing_length_and_files() -> Result<()> {
         let data = encode(&dict(vec![
-            (b"announce".to_vec(), bytes("x")),
+            (b"announce".to_vec(), bytes("http://tracker.test/announce")),
             (
                 b"info".to_vec(),
                 dict(vec![
@@ -721,14 +979,14 @@ mod tests {
         ]))?;
         let res = TorrentInfo::from_bytes(&data);
 
-        assert!(matches!(res, Err(TorrentError::InvalidStructure(_))));
+        assert!(matches!(res, Err(TorrentError::MissingFiles)));
         Ok(())
     }
 
     #[test]
     fn test_from_bytes_invalid_file_entry() -> Result<()> {
         let data = encode(&dict(vec![
-            (b"announce".to_vec(), bytes("x")),
+            (b"announce".to_vec(), bytes("http://tracker.test/announce")),
             (
                 b"info".to_vec(),
                 dict(vec![
@@ -741,7 +999,7 @@ mod tests {
         ]))?;
         let res = TorrentInfo::from_bytes(&data);
 
-        assert!(matches!(res, Err(TorrentError::InvalidStructure(_))));
+        assert!(matches!(res, Err(TorrentError::InvalidFileEntry(_))));
         Ok(())
     }
 
@@ -749,7 +1007,7 @@ mod tests {
     fn test_from_bytes_invalid_file_path() -> Result<()> {
         let bad_file = dict(vec![(b"length".to_vec(), int(1))]);
         let data = encode(&dict(vec![
-            (b"announce".to_vec(), bytes("x")),
+            (b"announce".to_vec(), bytes("http://tracker.test/announce")),
             (
                 b"info".to_vec(),
                 dict(vec![
@@ -762,7 +1020,112 @@ mod tests {
         ]))?;
         let res = TorrentInfo::from_bytes(&data);
 
-        assert!(matches!(res, Err(TorrentError::InvalidStructure(_))));
+        assert!(matches!(res, Err(TorrentError::InvalidFileEntry(_))));
+        Ok(())
+    }
+
+    /// Torrent whose info dict holds the given raw entries plus a piece length and one piece
+    fn raw_torrent(info_entries: &str) -> Vec<u8> {
+        format!(
+            "d8:announce28:http://tracker.test/announce4:infod{info_entries}\
+             12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaaee"
+        )
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_from_bytes_prefers_utf8_name_and_path_keys() -> Result<()> {
+        // Made with an old client that wrote the legacy-encoded name next to the UTF-8 one
+        let mut data = b"d8:announce28:http://tracker.test/announce4:infod5:filesl\
+                         d6:lengthi3e4:pathl6:a\xe9.txte10:path.utf-8l7:a\xc3\xa9.txteee\
+                         4:name2:f\xe910:name.utf-83:f\xc3\xa9"
+            .to_vec();
+        data.extend_from_slice(b"12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaaee");
+
+        let torrent = TorrentInfo::from_bytes(&data)?;
+        assert_eq!(torrent.name, "f\u{e9}");
+        assert_eq!(torrent.files[0].path, vec!["a\u{e9}.txt"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bytes_decodes_non_utf8_names_lossily() -> Result<()> {
+        let mut data = b"d8:announce28:http://tracker.test/announce4:infod6:lengthi5e".to_vec();
+        data.extend_from_slice(b"4:name5:caf\xe9s");
+        data.extend_from_slice(b"12:piece lengthi16384e6:pieces20:aaaaaaaaaaaaaaaaaaaaee");
+
+        let torrent = TorrentInfo::from_bytes(&data)?;
+        assert_eq!(torrent.name, "caf\u{fffd}s");
+        assert_eq!(TorrentSummary::from_bytes(&data)?.name, torrent.name);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bytes_malformed_fixtures_fail_with_specific_errors() {
+        type Expected = fn(&TorrentError) -> bool;
+        let cases: Vec<(&str, Vec<u8>, Expected)> = vec![
+            ("no info dict", b"d8:announce28:http://tracker.test/announcee".to_vec(), |e| {
+                matches!(e, TorrentError::MissingInfoDict)
+            }),
+            (
+                "info is a list",
+                b"d8:announce28:http://tracker.test/announce4:infoleee".to_vec(),
+                |e| matches!(e, TorrentError::MissingInfoDict),
+            ),
+            ("no name", raw_torrent("6:lengthi5e"), |e| matches!(e, TorrentError::MissingName)),
+            (
+                "zero piece length",
+                b"d8:announce28:http://tracker.test/announce4:infod6:lengthi5e4:name1:x\
+                  12:piece lengthi0e6:pieces0:ee"
+                    .to_vec(),
+                |e| matches!(e, TorrentError::InvalidPieceLength(0)),
+            ),
+            ("negative length", raw_torrent("6:lengthi-5e4:name1:x"), |e| {
+                matches!(e, TorrentError::InvalidFileLength(-5))
+            }),
+            ("no length or files", raw_torrent("4:name1:x"), |e| {
+                matches!(e, TorrentError::MissingFiles)
+            }),
+            ("file without length", raw_torrent("5:filesld4:pathl1:aeee4:name1:x"), |e| {
+                matches!(e, TorrentError::InvalidFileEntry(_))
+            }),
+            ("v2 without file tree", raw_torrent("12:meta versioni2e4:name1:x"), |e| {
+                matches!(e, TorrentError::MissingFileTree)
+            }),
+            (
+                "total size past 64 bits",
+                raw_torrent(&format!(
+                    "5:filesl{}{}{}e4:name1:x",
+                    "d6:lengthi9223372036854775807e4:pathl1:aee",
+                    "d6:lengthi9223372036854775807e4:pathl1:bee",
+                    "d6:lengthi9223372036854775807e4:pathl1:cee",
+                )),
+                |e| matches!(e, TorrentError::TotalSizeOverflow),
+            ),
+        ];
+
+        for (case, data, expected) in cases {
+            for res in [
+                TorrentInfo::from_bytes(&data).map(|_| ()),
+                TorrentSummary::from_bytes(&data).map(|_| ()),
+            ] {
+                match res {
+                    Err(e) => assert!(expected(&e), "{case}: unexpected error {e}"),
+                    Ok(()) => panic!("{case}: parsed"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_total_size_sums_every_file() -> Result<()> {
+        let data =
+            raw_torrent("5:filesld6:lengthi3e4:pathl1:aeed6:lengthi4e4:pathl1:beee4:name1:x");
+
+        assert_eq!(TorrentInfo::from_bytes(&data)?.total_size, 7);
+        assert_eq!(TorrentSummary::from_bytes(&data)?.total_size, 7);
+        let single = raw_torrent("6:lengthi5e4:name1:x");
+        assert_eq!(TorrentInfo::from_bytes(&single)?.total_size, 5);
         Ok(())
     }
 
@@ -818,6 +1181,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_tracker_tiers() -> Result<()> {
+        let single = TorrentInfo::from_bytes(&encode(&sample_single_file())?)?;
+        assert_eq!(single.tracker_tiers(), vec![vec!["http://tracker.test/announce".to_string()]]);
+
+        let mut multi = TorrentInfo::from_bytes(&encode(&sample_multi_file())?)?;
+        multi.announce_list = Some(vec![
+            vec!["http://a.test/announce".to_string(), "http://b.test/announce".to_string()],
+            Vec::new(),
+            vec!["http://c.test/announce".to_string()],
+        ]);
+        assert_eq!(
+            multi.tracker_tiers(),
+            vec![
+                vec!["http://a.test/announce".to_string(), "http://b.test/announce".to_string()],
+                vec!["http://c.test/announce".to_string()],
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_summary_uses_files_len() -> Result<()> {
         let data = encode(&sample_multi_file())?;
@@ -897,12 +1281,139 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_file_lengths_and_selected_size() -> Result<()> {
+        let data = encode(&sample_multi_file())?;
+        let info = TorrentInfo::from_bytes(&data)?.without_files();
+        assert_eq!(info.file_lengths, vec![100, 50]);
+        assert_eq!(TorrentSummary::from_bytes(&data)?.file_lengths, vec![100, 50]);
+
+        assert_eq!(info.selected_size(&[1]), Some(50));
+        assert_eq!(info.selected_size(&[0, 1, 0]), Some(150));
+        assert_eq!(info.selected_size(&[2]), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bytes_private_flag() -> Result<()> {
+        let private = dict(vec![
+            (b"announce".to_vec(), bytes("http://tracker.test/announce")),
+            (
+                b"info".to_vec(),
+                dict(vec![
+                    (b"name".to_vec(), bytes("file.txt")),
+                    (b"piece length".to_vec(), int(16384)),
+                    (b"pieces".to_vec(), pieces(2)),
+                    (b"length".to_vec(), int(123)),
+                    (b"private".to_vec(), int(1)),
+                ]),
+            ),
+        ]);
+        let data = encode(&private)?;
+        assert!(TorrentInfo::from_bytes(&data)?.is_private);
+        assert!(TorrentSummary::from_bytes(&data)?.to_info().is_private);
+
+        let public = encode(&sample_single_file())?;
+        assert!(!TorrentInfo::from_bytes(&public)?.is_private);
+        Ok(())
+    }
+
+    fn v2_file(length: i64) -> Value {
+        dict(vec![(
+            Vec::new(),
+            dict(vec![
+                (b"length".to_vec(), int(length)),
+                (b"pieces root".to_vec(), Value::Bytes(vec![0u8; 32])),
+            ]),
+        )])
+    }
+
+    fn v2_info() -> Value {
+        let folder =
+            dict(vec![(b"a.bin".to_vec(), v2_file(40_000)), (b"b.bin".to_vec(), v2_file(100))]);
+        dict(vec![
+            (b"name".to_vec(), bytes("folder")),
+            (b"piece length".to_vec(), int(16384)),
+            (b"meta version".to_vec(), int(2)),
+            (b"file tree".to_vec(), dict(vec![(b"folder".to_vec(), folder)])),
+        ])
+    }
+
+    #[test]
+    fn test_from_bytes_v2_only() -> Result<()> {
+        let data = encode(&dict(vec![
+            (b"announce".to_vec(), bytes("http://tracker.test/announce")),
+            (b"info".to_vec(), v2_info()),
+        ]))?;
+        let info = TorrentInfo::from_bytes(&data)?;
+
+        let expected: [u8; 32] = Sha256::digest(encode(&v2_info())?).into();
+        assert_eq!(info.info_hash_v2, Some(expected));
+        assert_eq!(info.info_hash, expected[..20]);
+        assert_eq!(info.hybrid_info_hash_v2(), None);
+        assert_eq!(info.total_size, 40_100);
+        assert_eq!(info.num_pieces, 4);
+        assert!(!info.is_single_file);
+        assert_eq!(info.files[0].path, vec!["folder".to_string(), "a.bin".to_string()]);
+
+        let summary = TorrentSummary::from_bytes(&data)?;
+        assert_eq!(summary.info_hash, info.info_hash);
+        assert_eq!((summary.file_count, summary.num_pieces), (2, 4));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bytes_hybrid_keeps_v1_hash() -> Result<()> {
+        let hybrid_info = dict(vec![
+            (b"name".to_vec(), bytes("file.txt")),
+            (b"piece length".to_vec(), int(16384)),
+            (b"pieces".to_vec(), pieces(2)),
+            (b"length".to_vec(), int(123)),
+            (b"meta version".to_vec(), int(2)),
+            (b"file tree".to_vec(), dict(vec![(b"file.txt".to_vec(), v2_file(123))])),
+        ]);
+        let data = encode(&dict(vec![
+            (b"announce".to_vec(), bytes("http://tracker.test/announce")),
+            (b"info".to_vec(), hybrid_info.clone()),
+        ]))?;
+        let info = TorrentInfo::from_bytes(&data)?;
+
+        let info_bytes = encode(&hybrid_info)?;
+        assert_eq!(info.info_hash, sha1_digest(&info_bytes));
+        let expected_v2: [u8; 32] = Sha256::digest(&info_bytes).into();
+        assert_eq!(info.info_hash_v2, Some(expected_v2));
+        assert_eq!(info.num_pieces, 2);
+        assert!(info.is_single_file);
+        assert_eq!(info.info_hash_v2_hex().map(|hex| hex.len()), Some(64));
+        assert_eq!(
+            info.hybrid_info_hash_v2().map(|hash| hash.to_vec()),
+            Some(expected_v2[..20].to_vec())
+        );
+
+        let v1 = TorrentInfo::from_bytes(&encode(&sample_single_file())?)?;
+        assert_eq!(v1.info_hash_v2, None);
+        Ok(())
+    }
+
     #[test]
     fn test_info_hash_missing_info_marker() -> Result<()> {
         let data = bencode::encode(&dict(vec![(b"foo".to_vec(), bytes("bar"))]))?;
         let res = info_hash(&data);
 
-        assert!(matches!(res, Err(TorrentError::InvalidStructure(_))));
+        assert!(matches!(res, Err(TorrentError::MissingInfoDict)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_info_hash_uses_the_original_key_order() -> Result<()> {
+        // `name` before `length`: re-encoding would sort the keys and change the hash
+        let data = b"d8:announce28:http://tracker.test/announce7:comment6:4:info\
+                     4:infod4:name1:x6:lengthi5e12:piece lengthi16384e\
+                     6:pieces20:aaaaaaaaaaaaaaaaaaaaee";
+
+        let expected = "112e90d6a6c05c630813b18cb9c4fef90cfb9e34";
+        assert_eq!(TorrentInfo::from_bytes(data)?.info_hash_hex(), expected);
+        assert_eq!(TorrentSummary::from_bytes(data)?.to_info().info_hash_hex(), expected);
         Ok(())
     }
 }
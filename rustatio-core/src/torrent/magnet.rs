@@ -0,0 +1,159 @@
+//! Magnet link parsing (BEP 9).
+//!
+//! A magnet carries no info dictionary, so the torrent built from it has no
+//! pieces or file list. Its size comes from the caller or the `xl` parameter and
+//! defaults to 0, which makes the instance seed-only (`left=0`).
+
+use super::info::{Result, TorrentError, TorrentInfo};
+
+const BTIH_PREFIX: &str = "urn:btih:";
+
+impl TorrentInfo {
+    /// Build a torrent from a `magnet:?xt=urn:btih:...` URI. The info hash may be
+    /// hex (40 chars) or base32 (32 chars); `dn` sets the name and every `tr` is a
+    /// tracker tier. `declared_size` overrides the magnet's `xl` length.
+    pub fn from_magnet(uri: &str, declared_size: Option<u64>) -> Result<Self> {
+        let url = url::Url::parse(uri.trim())
+            .map_err(|e| TorrentError::InvalidStructure(format!("Invalid magnet link: {e}")))?;
+        if url.scheme() != "magnet" {
+            return Err(TorrentError::InvalidStructure("Not a magnet link".into()));
+        }
+
+        let mut info_hash = None;
+        let mut name = None;
+        let mut trackers: Vec<String> = Vec::new();
+        let mut exact_length = None;
+
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "xt" => {
+                    if let Some(hash) = value.strip_prefix(BTIH_PREFIX) {
+                        info_hash = Some(parse_btih(hash)?);
+                    }
+                }
+                "dn" if !value.trim().is_empty() => name = Some(value.trim().to_string()),
+                "tr" if !value.trim().is_empty() => {
+                    let tracker = value.trim().to_string();
+                    if !trackers.contains(&tracker) {
+                        trackers.push(tracker);
+                    }
+                }
+                "xl" => exact_length = value.parse::<u64>().ok(),
+                _ => {}
+            }
+        }
+
+        let info_hash = info_hash.ok_or_else(|| {
+            TorrentError::InvalidStructure("Magnet link has no urn:btih info hash".into())
+        })?;
+        let Some(announce) = trackers.first().cloned() else {
+            return Err(TorrentError::InvalidStructure("Magnet link has no trackers (tr)".into()));
+        };
+
+        let mut torrent = Self {
+            info_hash,
+            announce,
+            announce_list: Some(trackers.into_iter().map(|tracker| vec![tracker]).collect()),
+            total_size: declared_size.or(exact_length).unwrap_or(0),
+            is_single_file: true,
+            file_count: 1,
+            ..Self::default()
+        };
+        torrent.name = name.unwrap_or_else(|| torrent.info_hash_hex());
+        Ok(torrent)
+    }
+}
+
+fn parse_btih(hash: &str) -> Result<[u8; 20]> {
+    let bytes = match hash.len() {
+        40 => decode_hex(hash),
+        32 => decode_base32(hash),
+        _ => None,
+    };
+    let bytes = bytes.ok_or_else(|| {
+        TorrentError::InvalidStructure(format!(
+            "Invalid magnet info hash '{hash}': expected 40 hex or 32 base32 characters"
+        ))
+    })?;
+    crate::validation::validate_info_hash(&bytes)
+        .map_err(|e| TorrentError::InvalidStructure(e.to_string()))
+}
+
+fn decode_hex(hash: &str) -> Option<Vec<u8>> {
+    (0..hash.len())
+        .step_by(2)
+        .map(|i| hash.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect()
+}
+
+/// RFC 4648 base32 without padding, case-insensitive.
+fn decode_base32(hash: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(hash.len() * 5 / 8);
+    let mut buffer = 0u64;
+    let mut bits = 0u32;
+
+    for c in hash.bytes() {
+        let value = match c.to_ascii_uppercase() {
+            c @ b'A'..=b'Z' => c - b'A',
+            c @ b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | u64::from(value);
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEX_HASH: &str = "c12fe1c06bba254a9dc9f519b335aa7c1367a88a";
+    const BASE32_HASH: &str = "YEX6DQDLXISUVHOJ6UM3GNNKPQJWPKEK";
+
+    #[test]
+    fn parses_hex_magnet_with_name_and_trackers() {
+        let uri = format!(
+            "magnet:?xt=urn:btih:{HEX_HASH}&dn=Some+Linux.iso\
+             &tr=https%3A%2F%2Ftracker.test%2Fannounce&tr=udp%3A%2F%2Fbackup.test%3A80&xl=4096"
+        );
+        let torrent = TorrentInfo::from_magnet(&uri, None)
+            .unwrap_or_else(|e| panic!("magnet should parse: {e}"));
+
+        assert_eq!(torrent.info_hash_hex(), HEX_HASH);
+        assert_eq!(torrent.name, "Some Linux.iso");
+        assert_eq!(torrent.announce, "https://tracker.test/announce");
+        assert_eq!(
+            torrent.tracker_tiers(),
+            vec![vec!["https://tracker.test/announce"], vec!["udp://backup.test:80"]]
+        );
+        assert_eq!(torrent.total_size, 4096);
+    }
+
+    #[test]
+    fn base32_hash_matches_hex_and_declared_size_wins() {
+        let uri =
+            format!("magnet:?xt=urn:btih:{BASE32_HASH}&tr=https://tracker.test/announce&xl=4096");
+        let torrent = TorrentInfo::from_magnet(&uri, Some(1_000))
+            .unwrap_or_else(|e| panic!("magnet should parse: {e}"));
+
+        assert_eq!(torrent.info_hash_hex(), HEX_HASH);
+        assert_eq!(torrent.name, HEX_HASH);
+        assert_eq!(torrent.total_size, 1_000);
+    }
+
+    #[test]
+    fn rejects_magnets_without_hash_or_trackers() {
+        assert!(TorrentInfo::from_magnet("magnet:?dn=x&tr=https://t.test/a", None).is_err());
+        let no_tracker = format!("magnet:?xt=urn:btih:{HEX_HASH}");
+        assert!(TorrentInfo::from_magnet(&no_tracker, None).is_err());
+        let short_hash = "magnet:?xt=urn:btih:abc&tr=https://t.test/a";
+        assert!(TorrentInfo::from_magnet(short_hash, None).is_err());
+        assert!(TorrentInfo::from_magnet("https://example.com", None).is_err());
+    }
+}
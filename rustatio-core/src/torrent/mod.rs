@@ -1,5 +1,6 @@
 pub mod client;
 pub mod info;
+pub mod magnet;
 
 // Re-export all types
 pub use client::*;
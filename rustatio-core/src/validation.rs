@@ -9,6 +9,10 @@ pub enum ValidationError {
     InvalidRange { field: String, min: f64, max: f64, value: f64 },
     InvalidPort(u16),
     MissingField(String),
+    InvalidUserAgent(String),
+    InvalidPeerId(String),
+    InvalidInfoHash(String),
+    InvalidAnnounceUrl(String),
 }
 
 impl Display for ValidationError {
@@ -25,6 +29,10 @@ impl Display for ValidationError {
                 write!(f, "Invalid port number: {port}. Must be between 1024 and 65535")
             }
             Self::MissingField(field) => write!(f, "Missing required field: {field}"),
+            Self::InvalidUserAgent(msg) => write!(f, "Invalid User-Agent: {msg}"),
+            Self::InvalidPeerId(msg) => write!(f, "Invalid peer ID: {msg}"),
+            Self::InvalidInfoHash(msg) => write!(f, "Invalid info hash: {msg}"),
+            Self::InvalidAnnounceUrl(msg) => write!(f, "Invalid announce URL: {msg}"),
         }
     }
 }
@@ -113,6 +121,71 @@ pub fn validate_percentage(value: f64, field_name: &str) -> Result<f64, Validati
     Ok(value)
 }
 
+/// Validate a custom User-Agent header value, returning it trimmed
+pub fn validate_user_agent(agent: &str) -> Result<&str, ValidationError> {
+    const MAX_LEN: usize = 256;
+
+    let agent = agent.trim();
+    if agent.is_empty() {
+        return Err(ValidationError::InvalidUserAgent("must not be empty".to_string()));
+    }
+    if agent.len() > MAX_LEN {
+        return Err(ValidationError::InvalidUserAgent(format!(
+            "must be at most {MAX_LEN} characters"
+        )));
+    }
+    if agent.chars().any(|c| c.is_control() || !c.is_ascii()) {
+        return Err(ValidationError::InvalidUserAgent(
+            "must contain printable ASCII characters only".to_string(),
+        ));
+    }
+
+    Ok(agent)
+}
+
+/// Validate a peer ID: exactly 20 bytes of printable ASCII
+pub fn validate_peer_id(peer_id: &str) -> Result<&str, ValidationError> {
+    const LEN: usize = 20;
+
+    if peer_id.len() != LEN {
+        return Err(ValidationError::InvalidPeerId(format!(
+            "must be exactly {LEN} bytes, got {}",
+            peer_id.len()
+        )));
+    }
+    if !peer_id.bytes().all(|b| b.is_ascii_graphic()) {
+        return Err(ValidationError::InvalidPeerId(
+            "must contain printable ASCII characters only".to_string(),
+        ));
+    }
+
+    Ok(peer_id)
+}
+
+/// Validate a v1 info hash: exactly 20 bytes (SHA-1)
+pub fn validate_info_hash(hash: &[u8]) -> Result<[u8; 20], ValidationError> {
+    hash.try_into().map_err(|_| {
+        ValidationError::InvalidInfoHash(format!("must be 20 bytes, got {}", hash.len()))
+    })
+}
+
+/// Validate a tracker announce URL: parseable, with a host and an http, https or udp scheme
+pub fn validate_announce_url(url: &str) -> Result<&str, ValidationError> {
+    let parsed = url::Url::parse(url)
+        .map_err(|e| ValidationError::InvalidAnnounceUrl(format!("{e} ({url})")))?;
+    if !matches!(parsed.scheme(), "http" | "https" | "udp") {
+        return Err(ValidationError::InvalidAnnounceUrl(format!(
+            "scheme must be http, https or udp, got {} ({url})",
+            parsed.scheme()
+        )));
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(ValidationError::InvalidAnnounceUrl(format!("missing host ({url})")));
+    }
+
+    Ok(url)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -174,6 +247,52 @@ mod tests {
         assert!(validate_percentage(99.9999, "completion").is_ok());
     }
 
+    #[test]
+    fn test_validate_user_agent() {
+        assert_eq!(validate_user_agent(" qBittorrent/4.6.2 ").ok(), Some("qBittorrent/4.6.2"));
+        assert!(validate_user_agent("Transmission/4.0.5 (libcurl)").is_ok());
+
+        assert!(validate_user_agent("").is_err());
+        assert!(validate_user_agent("   ").is_err());
+        assert!(validate_user_agent("agent\r\nX-Injected: 1").is_err());
+        assert!(validate_user_agent(&"a".repeat(257)).is_err());
+    }
+
+    #[test]
+    fn test_validate_peer_id() {
+        assert_eq!(validate_peer_id("-qB5210-a1B2c3D4e5F6").ok(), Some("-qB5210-a1B2c3D4e5F6"));
+        assert!(validate_peer_id("-UT355S-~_.!*()abcde").is_ok());
+
+        assert!(validate_peer_id("").is_err());
+        assert!(validate_peer_id("-qB5210-a1B2c3D4e5F").is_err());
+        assert!(validate_peer_id("-qB5210-a1B2c3D4e5F67").is_err());
+        assert!(validate_peer_id("-qB5210-a1B2c3 4e5F6").is_err());
+        assert!(validate_peer_id("-qB5210-a1B2c3\n4e5F6").is_err());
+        assert!(validate_peer_id("-qB5210-a1B2c3é4e5F").is_err());
+    }
+
+    #[test]
+    fn test_validate_info_hash() {
+        assert_eq!(validate_info_hash(&[7u8; 20]).ok(), Some([7u8; 20]));
+
+        assert!(validate_info_hash(&[]).is_err());
+        assert!(validate_info_hash(&[7u8; 19]).is_err());
+        assert!(validate_info_hash(&[7u8; 32]).is_err());
+    }
+
+    #[test]
+    fn test_validate_announce_url() {
+        assert!(validate_announce_url("http://tracker.test/announce").is_ok());
+        assert!(validate_announce_url("https://tracker.test:443/abc/announce?pk=1").is_ok());
+        assert!(validate_announce_url("udp://tracker.test:6969/announce").is_ok());
+        assert!(validate_announce_url("http://[2001:db8::1]:6969/announce").is_ok());
+
+        assert!(validate_announce_url("").is_err());
+        assert!(validate_announce_url("tracker.test/announce").is_err());
+        assert!(validate_announce_url("wss://tracker.test/announce").is_err());
+        assert!(validate_announce_url("udp:///announce").is_err());
+    }
+
     #[test]
     fn test_validate_update_interval() {
         // Valid intervals
@@ -282,5 +401,8 @@ mod tests {
 
         let err = ValidationError::MissingField("torrent".to_string());
         assert_eq!(format!("{err}"), "Missing required field: torrent");
+
+        let err = ValidationError::InvalidInfoHash("must be 20 bytes, got 3".to_string());
+        assert_eq!(format!("{err}"), "Invalid info hash: must be 20 bytes, got 3");
     }
 }
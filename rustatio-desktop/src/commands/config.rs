@@ -2,8 +2,12 @@ use rustatio_core::validation;
 use rustatio_core::{AppConfig, ClientInfo, ClientType};
 use tauri::State;
 
+use crate::persistence;
 use crate::state::AppState;
 
+/// Bundle format version the server's `POST /api/import` reads
+const BUNDLE_VERSION: u32 = 1;
+
 #[tauri::command]
 pub async fn get_config(state: State<'_, AppState>) -> Result<AppConfig, String> {
     let config = state.config.read().await;
@@ -47,6 +51,42 @@ pub fn set_log_level(level: String) {
     log::info!("IPC log level set to: {level}");
 }
 
+/// Every instance and the default config as a bundle the server's `POST /api/import`
+/// accepts, for moving a desktop setup to a server. The desktop keeps no `.torrent`
+/// files, so instances carry their torrent metadata only.
+#[tauri::command]
+pub async fn export_bundle(state: State<'_, AppState>) -> Result<String, String> {
+    let persisted = state.build_persisted_state().await;
+    let mut instances: Vec<_> = persisted.instances.into_values().collect();
+    instances.sort_by_key(|instance| instance.id);
+    let instances: Vec<_> = instances
+        .into_iter()
+        .map(|instance| {
+            serde_json::json!({
+                "id": instance.id.to_string(),
+                "torrent": instance.torrent,
+                "config": instance.config,
+                "cumulative_uploaded": instance.cumulative_uploaded,
+                "cumulative_downloaded": instance.cumulative_downloaded,
+                "state": instance.state,
+                "created_at": instance.created_at,
+                "updated_at": instance.updated_at,
+                "source": if instance.from_watch_folder { "watch_folder" } else { "manual" },
+                "tags": instance.tags,
+            })
+        })
+        .collect();
+    log::info!("Exported {} instance(s)", instances.len());
+    let bundle = serde_json::json!({
+        "version": BUNDLE_VERSION,
+        "app_version": env!("CARGO_PKG_VERSION"),
+        "exported_at": persistence::now_timestamp(),
+        "instances": instances,
+        "default_config": persisted.default_config,
+    });
+    serde_json::to_string_pretty(&bundle).map_err(|e| format!("Failed to export: {e}"))
+}
+
 #[tauri::command]
 pub async fn write_file(path: String, contents: String) -> Result<(), String> {
     std::fs::write(&path, contents).map_err(|e| format!("Failed to write file: {e}"))?;
@@ -210,6 +210,7 @@ pub async fn grid_import_folder(
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<GridImportResponse, String> {
+    config.base_config.validate().map_err(|e| e.to_string())?;
     log_and_emit!(&app, info, "Grid importing from folder: {}", path);
 
     let dir = std::path::Path::new(&path);
@@ -257,6 +258,7 @@ pub async fn grid_import_files(
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<GridImportResponse, String> {
+    config.base_config.validate().map_err(|e| e.to_string())?;
     log_and_emit!(&app, info, "Grid importing {} files", paths.len());
 
     let torrent_paths: Vec<std::path::PathBuf> =
@@ -522,6 +524,7 @@ pub async fn grid_update_config(
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<GridActionResponse, String> {
+    config.validate().map_err(|e| e.to_string())?;
     let faker_config: FakerConfig = config.into();
     let mut succeeded = Vec::new();
     let mut failed = Vec::new();
@@ -702,6 +705,7 @@ pub async fn list_summaries(state: State<'_, AppState>) -> Result<Vec<InstanceSu
         summaries.push(InstanceSummary {
             id: id.to_string(),
             name,
+            label: None,
             info_hash,
             primary_tracker_host: primary_tracker_host(&announce),
             state: state_str.to_string(),
@@ -709,6 +713,7 @@ pub async fn list_summaries(state: State<'_, AppState>) -> Result<Vec<InstanceSu
             tracker_error: stats.tracker_error.clone(),
             tracker_retry_attempt: stats.tracker_retry_attempt,
             tracker_retry_at_ms: stats.tracker_retry_at_ms,
+            stop_reason: stats.stop_reason.clone(),
             tags,
             total_size,
             uploaded: stats.uploaded,
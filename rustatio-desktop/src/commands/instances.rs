@@ -72,6 +72,55 @@ pub async fn delete_instance(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn clone_instance(
+    instance_id: u32,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<u32, String> {
+    let clone = {
+        let fakers = state.fakers.read().await;
+        let source =
+            fakers.get(&instance_id).ok_or_else(|| format!("Instance {instance_id} not found"))?;
+
+        // Fresh totals and a new peer_id/key; a clone is never tied to a watched file
+        let mut config = source.config.clone();
+        config.initial_uploaded = 0;
+        config.initial_downloaded = 0;
+        config.peer_id_override = None;
+        let faker = RatioFaker::new(
+            Arc::clone(&source.torrent),
+            config.clone(),
+            Some(state.http_client.clone()),
+        )
+        .map_err(|e| format!("Failed to create faker: {e}"))?;
+
+        FakerInstance {
+            faker: Arc::new(RatioFakerHandle::new(faker)),
+            torrent: Arc::clone(&source.torrent),
+            summary: Arc::clone(&source.summary),
+            config,
+            cumulative_uploaded: 0,
+            cumulative_downloaded: 0,
+            tags: source.tags.clone(),
+            created_at: crate::state::now_secs(),
+            source: InstanceSource::Manual,
+        }
+    };
+
+    let new_id = {
+        let mut next_id = state.next_instance_id.write().await;
+        let id = *next_id;
+        *next_id += 1;
+        id
+    };
+    state.fakers.write().await.insert(new_id, clone);
+
+    log_and_emit!(&app, info, "Cloned instance {} into {}", instance_id, new_id);
+    state.save_state().await?;
+    Ok(new_id)
+}
+
 #[tauri::command]
 pub async fn list_instances(state: State<'_, AppState>) -> Result<Vec<InstanceInfo>, String> {
     let fakers = state.fakers.read().await;
@@ -126,6 +175,31 @@ pub async fn load_torrent(path: String, app: AppHandle) -> Result<TorrentInfo, S
     }
 }
 
+#[tauri::command]
+pub async fn load_magnet(
+    uri: String,
+    declared_size: Option<u64>,
+    app: AppHandle,
+) -> Result<TorrentInfo, String> {
+    match TorrentInfo::from_magnet(&uri, declared_size) {
+        Ok(torrent) => {
+            log_and_emit!(
+                &app,
+                info,
+                "Magnet loaded: {} ({} bytes)",
+                torrent.name,
+                torrent.total_size
+            );
+            Ok(torrent)
+        }
+        Err(e) => {
+            let error_msg = format!("Failed to load magnet link: {e}");
+            log_and_emit!(&app, error, "{}", error_msg);
+            Err(error_msg)
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn load_instance_torrent(
     instance_id: u32,
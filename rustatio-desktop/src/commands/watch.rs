@@ -20,6 +20,7 @@ pub async fn get_watch_status(state: State<'_, AppState>) -> Result<WatchStatus,
         enabled: false,
         watch_dir: watch_dir.to_string_lossy().to_string(),
         auto_start: settings.auto_start,
+        uses_default_config: state.default_config.read().await.is_some(),
         file_count: 0,
         loaded_count: 0,
     })
@@ -169,6 +170,7 @@ pub async fn set_default_config(
     config: PresetSettings,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
+    config.validate().map_err(|e| e.to_string())?;
     let updated: FakerConfig = config.into();
     {
         let mut defaults = state.default_config.write().await;
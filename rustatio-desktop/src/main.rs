@@ -269,8 +269,10 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             commands::create_instance,
             commands::delete_instance,
+            commands::clone_instance,
             commands::list_instances,
             commands::load_torrent,
+            commands::load_magnet,
             commands::load_instance_torrent,
             commands::get_instance_torrent,
             commands::get_instance_summary,
@@ -290,6 +292,7 @@ fn main() {
             commands::get_client_infos,
             commands::get_network_status,
             commands::write_file,
+            commands::export_bundle,
             commands::set_log_level,
             commands::detect_linux_package_type,
             commands::grid_import_folder,
@@ -70,7 +70,7 @@ impl AppState {
         let fakers = self.fakers.read().await;
         let mut ports = BTreeSet::new();
 
-        for instance in fakers.values() {
+        for instance in fakers.values().filter(|instance| instance.config.accept_connections) {
             let state = instance.faker.stats_snapshot().state;
             if matches!(state, FakerState::Starting | FakerState::Running | FakerState::Paused) {
                 ports.insert(instance.config.port);
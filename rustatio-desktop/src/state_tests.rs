@@ -19,6 +19,7 @@ mod tests {
     fn torrent() -> TorrentInfo {
         TorrentInfo {
             info_hash: [3u8; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "saved-torrent".to_string(),
@@ -29,8 +30,10 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         }
     }
 
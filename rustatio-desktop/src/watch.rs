@@ -2,7 +2,8 @@ use crate::persistence::WatchSettings;
 use crate::state::{now_secs, AppState, FakerInstance};
 use rustatio_core::{FakerConfig, FakerState, RatioFaker, RatioFakerHandle};
 use rustatio_watch::{
-    EngineConfig, InstanceSource, InstanceState, NewInstance, WatchEngine, WatchService,
+    AfterImport, EngineConfig, InstanceSource, InstanceState, NewInstance, WatchEngine,
+    WatchService,
 };
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -187,6 +188,9 @@ pub fn build_watch_service(
         auto_start: watch_settings.auto_start,
         enabled: true,
         max_depth: watch_settings.max_depth,
+        include: Vec::new(),
+        exclude: Vec::new(),
+        after_import: AfterImport::Keep,
     };
 
     WatchService::new(config, Arc::new(DesktopWatchEngine::new(state, defaults)))
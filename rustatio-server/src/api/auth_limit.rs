@@ -0,0 +1,184 @@
+//! Per-IP limit on failed authentication attempts.
+//!
+//! Each client IP may fail `AUTH_MAX_FAILURES` times (default 10, `0` disables the
+//! limit) within `AUTH_FAILURE_WINDOW_SECONDS` (default 300). Past that, every request
+//! from it is answered with 429 until its oldest failure leaves the window, so tokens
+//! cannot be guessed by brute force.
+
+use std::collections::{HashMap, VecDeque};
+use std::net::IpAddr;
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+const DEFAULT_MAX_FAILURES: usize = 10;
+const DEFAULT_WINDOW_SECS: u64 = 300;
+
+/// Above this many tracked IPs, IPs without a recent failure are dropped.
+const PRUNE_THRESHOLD: usize = 1024;
+
+static AUTH_LIMITER: LazyLock<Mutex<AuthLimiter>> =
+    LazyLock::new(|| Mutex::new(AuthLimiter::new(AuthLimitConfig::from_env())));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthLimitConfig {
+    /// Failed attempts allowed per IP within `window`; 0 disables the limit
+    pub max_failures: usize,
+    pub window: Duration,
+    /// Never limit loopback clients
+    pub exempt_localhost: bool,
+    /// Key clients by the last `X-Forwarded-For` entry, as set by a reverse proxy
+    pub trust_forwarded_for: bool,
+}
+
+impl AuthLimitConfig {
+    pub fn from_env() -> Self {
+        let flag = |name: &str| {
+            std::env::var(name).is_ok_and(|v| {
+                matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")
+            })
+        };
+        let max_failures = std::env::var("AUTH_MAX_FAILURES")
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .unwrap_or(DEFAULT_MAX_FAILURES);
+        let window_secs = std::env::var("AUTH_FAILURE_WINDOW_SECONDS")
+            .ok()
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_WINDOW_SECS);
+
+        Self {
+            max_failures,
+            window: Duration::from_secs(window_secs),
+            exempt_localhost: flag("AUTH_RATE_LIMIT_EXEMPT_LOCALHOST"),
+            trust_forwarded_for: flag("TRUST_FORWARDED_FOR"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AuthLimiter {
+    config: AuthLimitConfig,
+    failures: HashMap<IpAddr, VecDeque<Instant>>,
+}
+
+impl AuthLimiter {
+    pub fn new(config: AuthLimitConfig) -> Self {
+        Self { config, failures: HashMap::new() }
+    }
+
+    const fn applies_to(&self, ip: IpAddr) -> bool {
+        self.config.max_failures > 0 && !(self.config.exempt_localhost && ip.is_loopback())
+    }
+
+    /// `Err(retry_after_secs)` when `ip` has used up its failed attempts.
+    pub fn check(&mut self, ip: IpAddr, now: Instant) -> Result<(), u64> {
+        if !self.applies_to(ip) {
+            return Ok(());
+        }
+        let window = self.config.window;
+        let Some(attempts) = self.failures.get_mut(&ip) else {
+            return Ok(());
+        };
+        while attempts.front().is_some_and(|at| now.duration_since(*at) >= window) {
+            attempts.pop_front();
+        }
+        if attempts.len() < self.config.max_failures {
+            return Ok(());
+        }
+        let oldest = attempts.front().copied().unwrap_or(now);
+        Err(window.saturating_sub(now.duration_since(oldest)).as_secs().max(1))
+    }
+
+    pub fn record_failure(&mut self, ip: IpAddr, now: Instant) {
+        if !self.applies_to(ip) {
+            return;
+        }
+        if self.failures.len() > PRUNE_THRESHOLD {
+            let window = self.config.window;
+            self.failures.retain(|_, attempts| {
+                attempts.back().is_some_and(|at| now.duration_since(*at) < window)
+            });
+        }
+        let attempts = self.failures.entry(ip).or_default();
+        attempts.push_back(now);
+        if attempts.len() == self.config.max_failures {
+            tracing::warn!(
+                "{} failed authentication attempts from {} within {}s, blocking it",
+                attempts.len(),
+                ip,
+                self.config.window.as_secs()
+            );
+        }
+    }
+
+    pub fn record_success(&mut self, ip: IpAddr) {
+        self.failures.remove(&ip);
+    }
+}
+
+pub fn trust_forwarded_for() -> bool {
+    AUTH_LIMITER.lock().unwrap_or_else(PoisonError::into_inner).config.trust_forwarded_for
+}
+
+pub fn check(ip: IpAddr) -> Result<(), u64> {
+    AUTH_LIMITER.lock().unwrap_or_else(PoisonError::into_inner).check(ip, Instant::now())
+}
+
+pub fn record_failure(ip: IpAddr) {
+    AUTH_LIMITER.lock().unwrap_or_else(PoisonError::into_inner).record_failure(ip, Instant::now());
+}
+
+pub fn record_success(ip: IpAddr) {
+    AUTH_LIMITER.lock().unwrap_or_else(PoisonError::into_inner).record_success(ip);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(exempt_localhost: bool) -> AuthLimiter {
+        AuthLimiter::new(AuthLimitConfig {
+            max_failures: 3,
+            window: Duration::from_mins(1),
+            exempt_localhost,
+            trust_forwarded_for: false,
+        })
+    }
+
+    #[test]
+    fn failures_past_the_limit_block_until_the_window_passes() {
+        let mut limiter = limiter(false);
+        let ip: IpAddr = "192.0.2.7".parse().unwrap_or_else(|_| panic!("valid ip"));
+        let start = Instant::now();
+
+        for secs in 0..3 {
+            assert!(limiter.check(ip, start + Duration::from_secs(secs)).is_ok());
+            limiter.record_failure(ip, start + Duration::from_secs(secs));
+        }
+        assert_eq!(limiter.check(ip, start + Duration::from_secs(10)), Err(50));
+        assert!(limiter.check(ip, start + Duration::from_mins(1)).is_ok());
+
+        let other: IpAddr = "192.0.2.8".parse().unwrap_or_else(|_| panic!("valid ip"));
+        assert!(limiter.check(other, start + Duration::from_secs(10)).is_ok());
+    }
+
+    #[test]
+    fn success_clears_failures_and_localhost_can_be_exempt() {
+        let ip: IpAddr = "192.0.2.7".parse().unwrap_or_else(|_| panic!("valid ip"));
+        let now = Instant::now();
+        let mut limiter = limiter(true);
+        for _ in 0..2 {
+            limiter.record_failure(ip, now);
+        }
+        limiter.record_success(ip);
+        limiter.record_failure(ip, now);
+        assert!(limiter.check(ip, now).is_ok());
+
+        let localhost = IpAddr::from([127, 0, 0, 1]);
+        for _ in 0..5 {
+            limiter.record_failure(localhost, now);
+        }
+        assert!(limiter.check(localhost, now).is_ok());
+    }
+}
@@ -1,23 +1,107 @@
 //! Authentication middleware for API token validation.
+//!
+//! Tokens come from `AUTH_TOKEN` (labelled `default`), `AUTH_TOKENS`
+//! (`label:token` pairs separated by commas) and `AUTH_TOKENS_FILE` (one
+//! `label:token` pair per line). Any of them grants access; the label of the
+//! token used is logged so one person's token can be revoked on its own.
 
 use axum::{
-    extract::Request,
-    http::{header::AUTHORIZATION, StatusCode},
+    extract::{ConnectInfo, Request},
+    http::{
+        header::{AUTHORIZATION, RETRY_AFTER},
+        StatusCode,
+    },
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
 use serde::Serialize;
-use std::sync::OnceLock;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{PoisonError, RwLock};
 
-static AUTH_TOKEN: OnceLock<Option<String>> = OnceLock::new();
+use super::auth_limit;
+use crate::services::runtime_config::env_var;
 
-pub fn get_auth_token() -> Option<&'static str> {
-    AUTH_TOKEN.get_or_init(|| std::env::var("AUTH_TOKEN").ok().filter(|s| !s.is_empty())).as_deref()
+/// Filled by `reload_auth_token`, which `main` calls before serving any request
+static AUTH_TOKENS: RwLock<Vec<AuthToken>> = RwLock::new(Vec::new());
+
+/// An accepted API token and the label it is logged under
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthToken {
+    pub label: String,
+    pub token: String,
+}
+
+/// Fails when `AUTH_TOKENS_FILE` is set but cannot be read, so a missing file never
+/// silently turns authentication off.
+fn auth_tokens_from_env() -> Result<Vec<AuthToken>, String> {
+    let mut tokens = Vec::new();
+    if let Some(token) = env_var("AUTH_TOKEN").filter(|s| !s.is_empty()) {
+        tokens.push(AuthToken { label: "default".into(), token });
+    }
+    if let Some(list) = env_var("AUTH_TOKENS") {
+        tokens.extend(parse_token_entries(list.split(',')));
+    }
+    if let Some(path) = env_var("AUTH_TOKENS_FILE").filter(|s| !s.is_empty()) {
+        tokens.extend(read_tokens_file(&path)?);
+    }
+    Ok(tokens)
+}
+
+fn read_tokens_file(path: &str) -> Result<Vec<AuthToken>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read AUTH_TOKENS_FILE {path}: {e}"))?;
+    Ok(parse_token_entries(contents.lines()))
+}
+
+/// Parse `label:token` entries, skipping blanks and `#` comments. An entry without
+/// a label is labelled by its position.
+fn parse_token_entries<'a>(entries: impl Iterator<Item = &'a str>) -> Vec<AuthToken> {
+    entries
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty() && !entry.starts_with('#'))
+        .enumerate()
+        .filter_map(|(idx, entry)| {
+            let (label, token) = match entry.split_once(':') {
+                Some((label, token)) => (label.trim().to_string(), token.trim()),
+                None => (format!("token{}", idx + 1), entry),
+            };
+            if token.is_empty() {
+                tracing::warn!("Ignoring auth token entry '{}' without a token", label);
+                return None;
+            }
+            Some(AuthToken { label, token: token.to_string() })
+        })
+        .collect()
+}
+
+pub fn get_auth_tokens() -> Vec<AuthToken> {
+    AUTH_TOKENS.read().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+/// Re-read `AUTH_TOKEN`, `AUTH_TOKENS` and `AUTH_TOKENS_FILE`. Returns true when the
+/// set of accepted tokens changed. On error the previous tokens stay in place.
+pub fn reload_auth_token() -> Result<bool, String> {
+    let tokens = auth_tokens_from_env()?;
+    let mut current = AUTH_TOKENS.write().unwrap_or_else(PoisonError::into_inner);
+    if *current == tokens {
+        return Ok(false);
+    }
+    *current = tokens;
+    Ok(true)
 }
 
 pub fn is_auth_enabled() -> bool {
-    get_auth_token().is_some()
+    !AUTH_TOKENS.read().unwrap_or_else(PoisonError::into_inner).is_empty()
+}
+
+/// Label of the token matching `provided`. Every token is compared so the time taken
+/// does not reveal which one matched.
+fn matching_label<'a>(tokens: &'a [AuthToken], provided: &str) -> Option<&'a str> {
+    tokens.iter().fold(None, |found, candidate| {
+        let matches = constant_time_eq(candidate.token.as_bytes(), provided.as_bytes());
+        found.or_else(|| matches.then_some(candidate.label.as_str()))
+    })
 }
 
 #[derive(Serialize)]
@@ -41,6 +125,21 @@ impl AuthError {
             .into_response()
     }
 
+    fn too_many_attempts(retry_after_secs: u64) -> Response {
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(RETRY_AFTER, retry_after_secs.to_string())],
+            Json(Self {
+                success: false,
+                error: format!(
+                    "Too many failed authentication attempts. Try again in {retry_after_secs}s."
+                ),
+                auth_required: true,
+            }),
+        )
+            .into_response()
+    }
+
     fn forbidden() -> Response {
         (
             StatusCode::FORBIDDEN,
@@ -54,38 +153,72 @@ impl AuthError {
     }
 }
 
-/// Validates Authorization header or query token against `AUTH_TOKEN`.
-/// If `AUTH_TOKEN` is not set, all requests are allowed.
+/// Validates Authorization header or query token against the configured tokens.
+/// If no token is configured, all requests are allowed.
 pub async fn auth_middleware(request: Request, next: Next) -> Response {
-    let Some(expected_token) = get_auth_token() else {
+    let tokens = get_auth_tokens();
+    if tokens.is_empty() {
         return next.run(request).await;
-    };
+    }
 
-    let auth_header = request.headers().get(AUTHORIZATION).and_then(|value| value.to_str().ok());
+    let client_ip = client_ip(&request);
+    if let Some(ip) = client_ip {
+        if let Err(retry_after_secs) = auth_limit::check(ip) {
+            return AuthError::too_many_attempts(retry_after_secs);
+        }
+    }
 
-    if let Some(header) = auth_header {
-        if let Some(provided_token) = header.strip_prefix("Bearer ") {
-            if constant_time_eq(provided_token.as_bytes(), expected_token.as_bytes()) {
-                return next.run(request).await;
-            }
-            return AuthError::forbidden();
+    let Some(provided) = provided_token(&request) else {
+        return AuthError::unauthorized();
+    };
+    let Some(label) = matching_label(&tokens, &provided) else {
+        if let Some(ip) = client_ip {
+            tracing::warn!("Invalid authentication token from {}", ip);
+            auth_limit::record_failure(ip);
         }
+        return AuthError::forbidden();
+    };
+    if let Some(ip) = client_ip {
+        auth_limit::record_success(ip);
     }
 
-    if let Some(query) = request.uri().query() {
-        for param in query.split('&') {
-            if let Some(token_value) = param.strip_prefix("token=") {
-                // URL decode the token
-                let decoded_token = urlencoding::decode(token_value).unwrap_or_default();
-                if constant_time_eq(decoded_token.as_bytes(), expected_token.as_bytes()) {
-                    return next.run(request).await;
-                }
-                return AuthError::forbidden();
-            }
+    tracing::debug!(
+        token = label,
+        method = %request.method(),
+        path = request.uri().path(),
+        "Authenticated API request"
+    );
+    next.run(request).await
+}
+
+/// Address failed attempts are counted against: the connecting peer, or the last
+/// `X-Forwarded-For` entry when `TRUST_FORWARDED_FOR` is on.
+fn client_ip(request: &Request) -> Option<IpAddr> {
+    if auth_limit::trust_forwarded_for() {
+        let forwarded = request
+            .headers()
+            .get("x-forwarded-for")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.rsplit(',').next())
+            .and_then(|last| last.trim().parse::<IpAddr>().ok());
+        if forwarded.is_some() {
+            return forwarded;
         }
     }
+    request.extensions().get::<ConnectInfo<SocketAddr>>().map(|info| info.0.ip())
+}
+
+/// Token from the `Authorization: Bearer` header, or else from the `token` query parameter.
+fn provided_token(request: &Request) -> Option<String> {
+    let auth_header = request.headers().get(AUTHORIZATION).and_then(|value| value.to_str().ok());
+    if let Some(token) = auth_header.and_then(|header| header.strip_prefix("Bearer ")) {
+        return Some(token.to_string());
+    }
 
-    AuthError::unauthorized()
+    let query = request.uri().query()?;
+    let token_value = query.split('&').find_map(|param| param.strip_prefix("token="))?;
+    // URL decode the token
+    Some(urlencoding::decode(token_value).unwrap_or_default().into_owned())
 }
 
 fn constant_time_eq(expected: &[u8], actual: &[u8]) -> bool {
@@ -112,4 +245,33 @@ mod tests {
         assert!(!constant_time_eq(b"", b"a"));
         assert!(constant_time_eq(b"", b""));
     }
+
+    #[test]
+    fn token_entries_are_labelled() {
+        let tokens =
+            parse_token_entries("alice:tok1, bob : tok2 ,,# comment,bare,carol:".split(','));
+        let pairs: Vec<(&str, &str)> =
+            tokens.iter().map(|t| (t.label.as_str(), t.token.as_str())).collect();
+        assert_eq!(pairs, vec![("alice", "tok1"), ("bob", "tok2"), ("token3", "bare")]);
+    }
+
+    #[test]
+    fn any_configured_token_matches() {
+        let tokens = parse_token_entries(["alice:tok1", "bob:tok2"].into_iter());
+        assert_eq!(matching_label(&tokens, "tok2"), Some("bob"));
+        assert_eq!(matching_label(&tokens, "tok1"), Some("alice"));
+        assert_eq!(matching_label(&tokens, "tok3"), None);
+    }
+
+    #[test]
+    fn unreadable_tokens_file_is_an_error() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        assert!(read_tokens_file(&temp.path().join("missing").to_string_lossy()).is_err());
+
+        let path = temp.path().join("tokens");
+        assert!(std::fs::write(&path, "alice:tok1\n").is_ok());
+        assert_eq!(read_tokens_file(&path.to_string_lossy()).map(|t| t.len()), Ok(1));
+    }
 }
@@ -1,5 +1,6 @@
 //! HTTP API module - router assembly, `OpenAPI` documentation, and shared types.
 
+pub mod auth_limit;
 pub mod common;
 pub mod middleware;
 pub mod routes;
@@ -13,11 +14,13 @@ use utoipa::{Modify, OpenApi};
 
 use crate::services::state::AppState;
 use crate::services::watch::WatchService;
+use crate::services::webhooks::WebhookService;
 
 #[derive(Clone)]
 pub struct ServerState {
     pub app: AppState,
     pub watch: Arc<RwLock<WatchService>>,
+    pub webhooks: Arc<tokio::sync::Mutex<WebhookService>>,
 }
 
 struct SecurityAddon;
@@ -58,8 +61,13 @@ impl Modify for SecurityAddon {
         (name = "clients", description = "BitTorrent client emulation"),
         (name = "network", description = "Network and VPN status"),
         (name = "watch", description = "Watch folder management"),
-        (name = "config", description = "Default configuration and presets"),
-        (name = "events", description = "Server-Sent Events streams")
+        (name = "config", description = "Default configuration, presets and export/import"),
+        (name = "events", description = "Server-Sent Events streams"),
+        (name = "capabilities", description = "Server feature discovery"),
+        (name = "info", description = "Server runtime info and global upload cap"),
+        (name = "admin", description = "Server administration"),
+        (name = "stats", description = "Aggregate stats and the server-wide rate cap"),
+        (name = "notify", description = "Webhook notifications")
     ),
     paths(
         // Auth
@@ -73,8 +81,17 @@ impl Modify for SecurityAddon {
         routes::instances::get_instance_torrent,
         routes::instances::get_instance_torrent_summary,
         routes::instances::update_instance_config,
+        routes::instances::set_instance_enabled,
+        routes::instances::set_instance_label,
+        routes::instances::set_instance_schedule,
+        routes::instances::clone_instance,
+        routes::instances::get_instance_logs,
+        routes::instances::get_last_announce,
+        routes::instances::list_tags,
         // Torrents
         routes::torrents::load_torrent,
+        routes::torrents::load_magnet,
+        routes::torrents::validate_torrent,
         // Faker
         routes::faker::start_faker,
         routes::faker::stop_faker,
@@ -101,15 +118,31 @@ impl Modify for SecurityAddon {
         routes::config::get_default_config,
         routes::config::set_default_config,
         routes::config::clear_default_config,
+        routes::config::preview_config,
         routes::config::get_default_preset,
         routes::config::set_default_preset,
         routes::config::clear_default_preset,
         routes::presets::list_custom_presets,
         routes::presets::upsert_custom_preset,
         routes::presets::delete_custom_preset,
+        routes::bundle::export_bundle,
+        routes::bundle::import_bundle,
         // Events
         routes::events::logs_sse,
         routes::events::instances_sse,
+        routes::events::stats_sse,
+        routes::events::instance_stats_sse,
+        // Capabilities
+        routes::capabilities::get_capabilities,
+        // Info
+        routes::info::get_info,
+        routes::info::reset_upload_cap,
+        // Admin
+        routes::admin::reload_config,
+        // Stats
+        routes::stats::get_aggregate_stats,
+        // Notify
+        routes::notify::test_notification,
     ),
     components(
         schemas(
@@ -119,23 +152,57 @@ impl Modify for SecurityAddon {
             routes::auth::AuthStatusResponse,
             routes::instances::CreateInstanceResponse,
             routes::instances::DeleteInstanceQuery,
+            routes::instances::InstanceLogsQuery,
+            routes::instances::LastAnnounceResponse,
+            routes::instances::ListInstancesQuery,
+            routes::instances::SetInstanceEnabledRequest,
+            routes::instances::SetInstanceLabelRequest,
+            routes::instances::InstanceLabelResponse,
+            routes::instances::InstanceScheduleResponse,
             routes::torrents::LoadTorrentResponse,
+            routes::torrents::LoadMagnetRequest,
+            routes::torrents::LoadMagnetResponse,
+            routes::torrents::ValidateTorrentResponse,
+            crate::services::tracker_check::TrackerCheck,
             routes::faker::StartFakerRequest,
             routes::network::NetworkStatus,
             routes::watch::ReloadAllResponse,
             routes::clients::ClientInfoResponse,
             crate::services::InstanceInfo,
+            crate::services::InstanceSchedule,
+            crate::services::instance::InstanceSortKey,
+            crate::services::instance::SortOrder,
+            crate::services::instance::TagMatch,
+            crate::services::instance::TagCount,
             crate::services::LogEvent,
             crate::services::InstanceEvent,
             crate::services::persistence::InstanceSource,
             crate::services::persistence::CustomPreset,
             crate::services::persistence::DefaultPreset,
+            crate::services::bundle::ConfigBundle,
+            crate::services::bundle::ImportMode,
+            crate::services::bundle::ImportReport,
+            routes::bundle::ImportBundleQuery,
+            routes::config::ConfigPreviewResponse,
             crate::services::watch::WatchStatus,
             crate::services::watch::WatchedFile,
             crate::services::watch::WatchedFileStatus,
             crate::services::persistence::WatchSettings,
             routes::watch::WatchConfigResponse,
             routes::watch::WatchConfigRequest,
+            routes::capabilities::CapabilitiesResponse,
+            routes::capabilities::FeatureSupport,
+            routes::info::InfoResponse,
+            routes::admin::ReloadConfigResponse,
+            routes::stats::AggregateStatsResponse,
+            routes::stats::AggregateStatsQuery,
+            routes::stats::AggregateGroupBy,
+            crate::services::instance::InstanceTotals,
+            crate::services::scrape_cache::ScrapeCacheStats,
+            crate::services::instance::TagTotals,
+            routes::notify::NotifyTestResponse,
+            routes::events::StatsStreamQuery,
+            routes::events::LogStreamQuery,
         )
     ),
     modifiers(&SecurityAddon),
@@ -159,6 +226,12 @@ pub fn router() -> Router<ServerState> {
         .merge(routes::events::router())
         .merge(routes::grid::router())
         .merge(routes::browse::router())
+        .merge(routes::bundle::router())
+        .merge(routes::capabilities::router())
+        .merge(routes::info::router())
+        .merge(routes::admin::router())
+        .merge(routes::stats::router())
+        .merge(routes::notify::router())
 }
 
 pub fn public_router() -> Router<ServerState> {
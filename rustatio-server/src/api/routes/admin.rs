@@ -0,0 +1,106 @@
+//! Server administration endpoints.
+
+use axum::{extract::State, http::StatusCode, response::Response, routing::post, Router};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::api::{
+    common::{ApiError, ApiSuccess},
+    middleware, ServerState,
+};
+use crate::services::runtime_config;
+use crate::services::{WatchConfig, WatchService};
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReloadConfigResponse {
+    /// Env file loaded before re-reading settings, when `RELOAD_ENV_FILE` is set
+    pub env_file: Option<String>,
+    /// Env vars whose new values were applied
+    pub applied: Vec<String>,
+    /// Env vars that changed since startup but only take effect after a restart
+    pub restart_required: Vec<String>,
+    /// Settings that changed but could not be applied
+    pub errors: Vec<String>,
+}
+
+/// Rebuild the watch service when its env-driven config changed.
+async fn reload_watch_service(state: &ServerState) -> Result<bool, String> {
+    let (config, _) = WatchConfig::resolve(&state.app).await;
+    let mut watch = state.watch.write().await;
+    let current = watch.config();
+    if current.enabled == config.enabled
+        && current.watch_dir == config.watch_dir
+        && current.auto_start == config.auto_start
+        && current.max_depth == config.max_depth
+        && current.include == config.include
+        && current.exclude == config.exclude
+        && current.after_import == config.after_import
+    {
+        return Ok(false);
+    }
+
+    watch.stop().await;
+    *watch = WatchService::new(config, state.app.clone());
+    watch.start().await?;
+    Ok(true)
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/reload-config",
+    tag = "admin",
+    summary = "Reload configuration from environment",
+    description = "Loads RELOAD_ENV_FILE over the environment when set, then re-reads env-driven settings and applies them live: AUTH_TOKEN, AUTH_TOKENS, AUTH_TOKENS_FILE (whose contents are re-read on every reload), the WATCH_* settings, announce de-correlation, GLOBAL_MAX_TOTAL_UPLOADED_BYTES, MAX_TOTAL_UPLOAD_KBPS, MAX_TOTAL_DOWNLOAD_KBPS, STATS_PRECISION and VALIDATE_ON_CREATE. Changed settings that are only read at startup are reported as requiring a restart.",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Configuration reloaded", body = ApiSuccess<ReloadConfigResponse>),
+        (status = 401, description = "Unauthorized", body = ApiError),
+        (status = 500, description = "Failed to read the env file", body = ApiError)
+    )
+)]
+pub async fn reload_config(State(state): State<ServerState>) -> Response {
+    let env_file = match runtime_config::load_env_file() {
+        Ok(path) => path,
+        Err(e) => return ApiError::response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+
+    let outcome = state.app.reload_runtime_settings().await;
+    let mut errors = Vec::new();
+
+    // Always re-read: AUTH_TOKENS_FILE can change without any env var changing
+    match middleware::reload_auth_token() {
+        Ok(true) => tracing::info!(
+            "Auth tokens reloaded, authentication {} ({} token(s))",
+            if middleware::is_auth_enabled() { "enabled" } else { "disabled" },
+            middleware::get_auth_tokens().len()
+        ),
+        Ok(false) => {}
+        Err(e) => {
+            tracing::error!("{}, keeping the previous auth tokens", e);
+            errors.push(format!("Auth tokens: {e}"));
+        }
+    }
+
+    if outcome.applied.iter().any(|name| name.starts_with("WATCH_")) {
+        match reload_watch_service(&state).await {
+            Ok(true) => tracing::info!("Watch folder service reloaded"),
+            Ok(false) => {}
+            Err(e) => errors.push(format!("Watch folder service: {e}")),
+        }
+    }
+
+    for name in &outcome.restart_required {
+        tracing::warn!("{} changed but only takes effect after a restart", name);
+    }
+
+    ApiSuccess::response(ReloadConfigResponse {
+        env_file,
+        applied: outcome.applied.iter().map(ToString::to_string).collect(),
+        restart_required: outcome.restart_required.iter().map(ToString::to_string).collect(),
+        errors,
+    })
+}
+
+pub fn router() -> Router<ServerState> {
+    Router::new().route("/admin/reload-config", post(reload_config))
+}
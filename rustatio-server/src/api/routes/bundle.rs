@@ -0,0 +1,83 @@
+//! Configuration export and import endpoints.
+
+use axum::{
+    extract::{DefaultBodyLimit, Query, State},
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::api::{
+    common::{ApiError, ApiSuccess},
+    ServerState,
+};
+use crate::services::bundle::{ConfigBundle, ImportMode, ImportReport};
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct ImportBundleQuery {
+    pub mode: ImportMode,
+}
+
+#[utoipa::path(
+    get,
+    path = "/export",
+    tag = "config",
+    summary = "Export all instances and settings",
+    description = "Downloads a JSON bundle with every instance (torrent metadata and, for instances added from a file, the hex-encoded .torrent, config, tags, cumulative stats), the default config and custom presets. POST it to /import on another server to move the setup there.",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Configuration bundle", body = ConfigBundle),
+        (status = 401, description = "Unauthorized", body = ApiError)
+    )
+)]
+pub async fn export_bundle(State(state): State<ServerState>) -> Response {
+    let bundle = state.app.export_bundle().await;
+    let file_name = format!("attachment; filename=\"rustatio-export-{}.json\"", bundle.exported_at);
+    let mut response = (StatusCode::OK, Json(bundle)).into_response();
+    if let Ok(value) = HeaderValue::from_str(&file_name) {
+        response.headers_mut().insert(header::CONTENT_DISPOSITION, value);
+    }
+    response
+}
+
+#[utoipa::path(
+    post,
+    path = "/import",
+    tag = "config",
+    summary = "Import a configuration bundle",
+    description = "Restores a bundle written by /export. Instances are matched to existing ones by info hash: mode=merge (default) skips them and keeps the current default config and presets, mode=replace overwrites them. Instances that were running when exported are started again. Bundles from a newer Rustatio version are refused.",
+    security(("bearer_auth" = [])),
+    params(
+        ("mode" = Option<ImportMode>, Query, description = "merge or replace (default: merge)")
+    ),
+    request_body = ConfigBundle,
+    responses(
+        (status = 200, description = "Import results", body = ApiSuccess<ImportReport>),
+        (status = 400, description = "Unsupported bundle version", body = ApiError),
+        (status = 401, description = "Unauthorized", body = ApiError)
+    )
+)]
+pub async fn import_bundle(
+    State(state): State<ServerState>,
+    Query(query): Query<ImportBundleQuery>,
+    Json(bundle): Json<ConfigBundle>,
+) -> Response {
+    if let Err(e) = bundle.check_version() {
+        return ApiError::response(StatusCode::BAD_REQUEST, e);
+    }
+    match state.app.import_bundle(bundle, query.mode).await {
+        Ok(report) => ApiSuccess::response(report),
+        Err(e) => ApiError::response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+pub fn router() -> Router<ServerState> {
+    Router::new()
+        .route("/import", post(import_bundle))
+        .layer(DefaultBodyLimit::max(100 * 1024 * 1024))
+        .route("/export", get(export_bundle))
+}
@@ -0,0 +1,116 @@
+//! Server capability discovery endpoint.
+
+use axum::{extract::State, response::Response, routing::get, Router};
+use rustatio_core::ClientType;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::api::{common::ApiSuccess, middleware, ServerState};
+
+/// Optional protocol features and whether this build supports them.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FeatureSupport {
+    pub udp_trackers: bool,
+    pub magnet_links: bool,
+    pub proxy: bool,
+    pub encryption: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CapabilitiesResponse {
+    pub version: String,
+    pub auth_enabled: bool,
+    pub watch_enabled: bool,
+    pub vpn_port_sync_enabled: bool,
+    pub peer_listener_enabled: bool,
+    /// `DRY_RUN` is set: no instance contacts its tracker
+    pub dry_run: bool,
+    pub storage_backend: String,
+    pub tracker_schemes: Vec<String>,
+    pub client_types: Vec<String>,
+    pub features: FeatureSupport,
+}
+
+/// Options the server was started with
+#[derive(Debug, Clone, Copy, Default)]
+struct RuntimeFlags {
+    auth_enabled: bool,
+    watch_enabled: bool,
+    vpn_port_sync_enabled: bool,
+    peer_listener_enabled: bool,
+    dry_run: bool,
+}
+
+impl CapabilitiesResponse {
+    fn new(runtime: RuntimeFlags) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            auth_enabled: runtime.auth_enabled,
+            watch_enabled: runtime.watch_enabled,
+            vpn_port_sync_enabled: runtime.vpn_port_sync_enabled,
+            peer_listener_enabled: runtime.peer_listener_enabled,
+            dry_run: runtime.dry_run,
+            storage_backend: "json_file".to_string(),
+            tracker_schemes: vec!["http".to_string(), "https".to_string()],
+            client_types: ClientType::all_ids(),
+            features: FeatureSupport {
+                udp_trackers: false,
+                magnet_links: true,
+                proxy: true,
+                encryption: false,
+            },
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/capabilities",
+    tag = "capabilities",
+    summary = "Get server capabilities",
+    description = "Returns the features compiled into this server and the options it was configured with, so clients can enable or hide controls without probing endpoints.",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Server capabilities", body = ApiSuccess<CapabilitiesResponse>),
+        (status = 401, description = "Unauthorized", body = crate::api::common::ApiError)
+    )
+)]
+pub async fn get_capabilities(State(state): State<ServerState>) -> Response {
+    let runtime = RuntimeFlags {
+        auth_enabled: middleware::is_auth_enabled(),
+        watch_enabled: state.watch.read().await.config().enabled,
+        vpn_port_sync_enabled: state.app.vpn_port_sync_enabled(),
+        peer_listener_enabled: state.app.peer_listener_status().await.enabled,
+        dry_run: state.app.dry_run_enabled(),
+    };
+    ApiSuccess::response(CapabilitiesResponse::new(runtime))
+}
+
+pub fn router() -> Router<ServerState> {
+    Router::new().route("/capabilities", get(get_capabilities))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capabilities_report_runtime_flags_and_http_schemes() {
+        let caps = CapabilitiesResponse::new(RuntimeFlags {
+            auth_enabled: true,
+            vpn_port_sync_enabled: true,
+            ..RuntimeFlags::default()
+        });
+
+        assert!(caps.auth_enabled);
+        assert!(!caps.watch_enabled);
+        assert!(caps.vpn_port_sync_enabled);
+        assert!(!caps.peer_listener_enabled);
+        assert!(!caps.dry_run);
+        assert_eq!(caps.tracker_schemes, vec!["http".to_string(), "https".to_string()]);
+        assert!(caps.client_types.contains(&"qbittorrent".to_string()));
+        assert!(!caps.features.udp_trackers);
+        assert!(caps.features.magnet_links);
+        assert!(caps.features.proxy);
+    }
+}
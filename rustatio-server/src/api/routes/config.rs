@@ -4,16 +4,51 @@ use axum::{
     extract::State,
     http::StatusCode,
     response::Response,
-    routing::{delete, get, put},
+    routing::{delete, get, post, put},
     Json, Router,
 };
-use rustatio_core::{FakerConfig, PresetSettings};
+use rustatio_core::{FakerConfig, PresetSettings, RatioFaker};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 use crate::api::{
     common::{ApiError, ApiSuccess, EmptyData},
     ServerState,
 };
 use crate::services::persistence::DefaultPreset;
+use crate::services::rate_cap::RateCap;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DefaultConfigRequest {
+    #[serde(flatten)]
+    pub preset: PresetSettings,
+    /// Combined upload cap in KB/s across all instances, 0 to remove it
+    #[serde(default)]
+    pub max_total_upload_kbps: Option<f64>,
+    /// Combined download cap in KB/s across all instances, 0 to remove it
+    #[serde(default)]
+    pub max_total_download_kbps: Option<f64>,
+}
+
+impl DefaultConfigRequest {
+    /// Rate cap after applying the provided limits to `current`, or `None` when the
+    /// request leaves the cap untouched.
+    fn rate_cap(&self, current: RateCap) -> Option<RateCap> {
+        if self.max_total_upload_kbps.is_none() && self.max_total_download_kbps.is_none() {
+            return None;
+        }
+        let limit = |value: Option<f64>, current: Option<f64>| match value {
+            Some(kbps) if kbps.is_finite() && kbps > 0.0 => Some(kbps),
+            Some(_) => None,
+            None => current,
+        };
+        Some(RateCap {
+            upload_kbps: limit(self.max_total_upload_kbps, current.upload_kbps),
+            download_kbps: limit(self.max_total_download_kbps, current.download_kbps),
+        })
+    }
+}
 
 #[utoipa::path(
     get,
@@ -37,26 +72,66 @@ pub async fn get_default_config(State(state): State<ServerState>) -> Response {
     path = "/config/default",
     tag = "config",
     summary = "Set default configuration",
-    description = "Sets the default configuration to be used for new instances.",
+    description = "Sets the default configuration to be used for new instances. `maxTotalUploadKbps` and `maxTotalDownloadKbps` optionally set the server-wide combined rate cap (overriding MAX_TOTAL_UPLOAD_KBPS / MAX_TOTAL_DOWNLOAD_KBPS); 0 removes a limit.",
     security(("bearer_auth" = [])),
-    request_body(content = Object, description = "Preset settings in UI-friendly format"),
+    request_body(content = Object, description = "Preset settings in UI-friendly format, plus optional rate cap"),
     responses(
         (status = 200, description = "Configuration saved", body = ApiSuccess<EmptyData>),
+        (status = 400, description = "Invalid preset settings", body = ApiError),
         (status = 401, description = "Unauthorized", body = ApiError),
         (status = 500, description = "Failed to save configuration", body = ApiError)
     )
 )]
 pub async fn set_default_config(
     State(state): State<ServerState>,
-    Json(preset): Json<PresetSettings>,
+    Json(request): Json<DefaultConfigRequest>,
 ) -> Response {
-    let config: FakerConfig = preset.into();
+    if let Err(e) = request.preset.validate() {
+        return ApiError::response(StatusCode::BAD_REQUEST, e.to_string());
+    }
+    if let Some(cap) = request.rate_cap(state.app.rate_cap().await) {
+        if let Err(e) = state.app.set_rate_cap(Some(cap)).await {
+            return ApiError::response(StatusCode::INTERNAL_SERVER_ERROR, e);
+        }
+    }
+
+    let config: FakerConfig = request.preset.into();
     match state.app.set_default_config(Some(config)).await {
         Ok(()) => ApiSuccess::response(EmptyData {}),
         Err(e) => ApiError::response(StatusCode::INTERNAL_SERVER_ERROR, e),
     }
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConfigPreviewResponse {
+    /// The config an instance would run with, in API units (bytes, seconds, KB/s)
+    #[schema(value_type = Object)]
+    pub config: FakerConfig,
+    /// Why an instance would reject this config, `null` when it is valid
+    pub error: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/config/preview",
+    tag = "config",
+    summary = "Preview the config a preset resolves to",
+    description = "Converts preset settings the way creating an instance does (GB to bytes, hours to seconds, defaults for missing fields) and returns the resulting config without saving anything, along with the reason it would be rejected, if any.",
+    security(("bearer_auth" = [])),
+    request_body(content = Object, description = "Preset settings in UI-friendly format"),
+    responses(
+        (status = 200, description = "Resolved configuration", body = ApiSuccess<ConfigPreviewResponse>),
+        (status = 401, description = "Unauthorized", body = ApiError)
+    )
+)]
+pub async fn preview_config(Json(preset): Json<PresetSettings>) -> Response {
+    let preset_error = preset.validate().err();
+    let config: FakerConfig = preset.into();
+    let error =
+        preset_error.or_else(|| RatioFaker::validate_config(&config).err()).map(|e| e.to_string());
+    ApiSuccess::response(ConfigPreviewResponse { config, error })
+}
+
 #[utoipa::path(
     delete,
     path = "/config/default",
@@ -103,6 +178,7 @@ pub async fn get_default_preset(State(state): State<ServerState>) -> Response {
     request_body(content = Object, description = "Preset metadata and settings"),
     responses(
         (status = 200, description = "Default preset saved", body = ApiSuccess<EmptyData>),
+        (status = 400, description = "Invalid preset settings", body = ApiError),
         (status = 401, description = "Unauthorized", body = ApiError),
         (status = 500, description = "Failed to save default preset", body = ApiError)
     )
@@ -111,6 +187,9 @@ pub async fn set_default_preset(
     State(state): State<ServerState>,
     Json(preset): Json<DefaultPreset>,
 ) -> Response {
+    if let Err(e) = preset.settings.validate() {
+        return ApiError::response(StatusCode::BAD_REQUEST, e.to_string());
+    }
     match state.app.set_default_preset(Some(preset)).await {
         Ok(()) => ApiSuccess::response(EmptyData {}),
         Err(e) => ApiError::response(StatusCode::INTERNAL_SERVER_ERROR, e),
@@ -142,6 +221,7 @@ pub fn router() -> Router<ServerState> {
         .route("/config/default", get(get_default_config))
         .route("/config/default", put(set_default_config))
         .route("/config/default", delete(clear_default_config))
+        .route("/config/preview", post(preview_config))
         .route("/config/default-preset", get(get_default_preset))
         .route("/config/default-preset", put(set_default_preset))
         .route("/config/default-preset", delete(clear_default_preset))
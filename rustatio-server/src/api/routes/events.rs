@@ -1,26 +1,86 @@
 //! Server-Sent Events (SSE) streaming endpoints.
 
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
+    http::StatusCode,
     response::sse::{Event, KeepAlive, Sse},
+    response::Response,
     routing::get,
     Router,
 };
 use futures::stream::Stream;
+use serde::Deserialize;
 use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::StreamExt;
+use utoipa::ToSchema;
 
-use crate::api::ServerState;
-use crate::services::EventBroadcaster;
+use crate::api::{common::ApiError, ServerState};
+use crate::services::{EventBroadcaster, LogEvent, StatsEvent};
+
+const DEFAULT_STATS_INTERVAL_SECS: u64 = 5;
+const MAX_STATS_INTERVAL_SECS: u64 = 300;
+/// Stats are published every second; an event this much early still counts as due.
+const STATS_TICK_SLACK: Duration = Duration::from_millis(500);
+
+#[derive(Deserialize, ToSchema)]
+pub struct StatsStreamQuery {
+    /// Seconds between stats events (default 5, minimum 1)
+    pub interval: Option<u64>,
+    /// Keep the rate/ratio history vectors in each event
+    #[serde(default)]
+    pub include_history: bool,
+}
+
+impl StatsStreamQuery {
+    fn period(&self) -> Duration {
+        let secs = self.interval.unwrap_or(DEFAULT_STATS_INTERVAL_SECS);
+        Duration::from_secs(secs.clamp(1, MAX_STATS_INTERVAL_SECS))
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct LogStreamQuery {
+    /// Only stream lines written for this instance ID
+    pub instance: Option<String>,
+}
+
+impl LogStreamQuery {
+    fn matches(&self, event: &LogEvent) -> bool {
+        self.instance.as_ref().is_none_or(|id| event.instance_id.as_ref() == Some(id))
+    }
+}
+
+/// Stats events from the scheduler, at most one per `period`.
+fn throttled_stats(
+    rx: broadcast::Receiver<Arc<StatsEvent>>,
+    period: Duration,
+) -> impl Stream<Item = Arc<StatsEvent>> {
+    let mut last_sent: Option<Instant> = None;
+    BroadcastStream::new(rx).filter_map(move |result| {
+        let event = result.ok()?;
+        let now = Instant::now();
+        if last_sent.is_some_and(|sent| now.duration_since(sent) + STATS_TICK_SLACK < period) {
+            return None;
+        }
+        last_sent = Some(now);
+        Some(event)
+    })
+}
 
 #[utoipa::path(
     get,
     path = "/logs",
     tag = "events",
     summary = "Stream logs via SSE",
-    description = "Server-Sent Events stream for real-time log messages. Events are of type 'log' with LogEvent data.",
+    description = "Server-Sent Events stream for real-time log messages. Events are of type 'log' with LogEvent data; lines written for an instance carry its instance_id and info_hash. The instance query parameter limits the stream to one instance's lines.",
     security(("bearer_auth" = [])),
+    params(
+        ("instance" = Option<String>, Query, description = "Only stream lines of this instance ID")
+    ),
     responses(
         (status = 200, description = "SSE stream established", content_type = "text/event-stream"),
         (status = 401, description = "Unauthorized", body = crate::api::common::ApiError)
@@ -28,16 +88,19 @@ use crate::services::EventBroadcaster;
 )]
 pub async fn logs_sse(
     State(state): State<ServerState>,
+    Query(query): Query<LogStreamQuery>,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
     let rx = state.app.subscribe_logs();
 
-    let stream = BroadcastStream::new(rx).filter_map(|result| {
-        result.ok().map(|log_event| {
-            Ok(Event::default()
-                .event("log")
-                .json_data(&log_event)
-                .unwrap_or_else(|_| Event::default()))
-        })
+    let stream = BroadcastStream::new(rx).filter_map(move |result| {
+        let log_event = result.ok()?;
+        if !query.matches(&log_event) {
+            return None;
+        }
+        Some(Ok(Event::default()
+            .event("log")
+            .json_data(&log_event)
+            .unwrap_or_else(|_| Event::default())))
     });
 
     Sse::new(stream).keep_alive(KeepAlive::default())
@@ -48,7 +111,7 @@ pub async fn logs_sse(
     path = "/events",
     tag = "events",
     summary = "Stream instance events via SSE",
-    description = "Server-Sent Events stream for real-time instance updates. Events are of type 'instance' with InstanceEvent data (created/deleted).",
+    description = "Server-Sent Events stream for real-time instance updates. Events are of type 'instance' with InstanceEvent data (created/deleted/paused/resumed/stop_condition_met/tracker_failing).",
     security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "SSE stream established", content_type = "text/event-stream"),
@@ -72,6 +135,116 @@ pub async fn instances_sse(
     Sse::new(stream).keep_alive(KeepAlive::default())
 }
 
+#[utoipa::path(
+    get,
+    path = "/stats/stream",
+    tag = "events",
+    summary = "Stream stats of all instances via SSE",
+    description = "Server-Sent Events stream replacing stats polling. Events are of type 'stats' with the stats of every starting, running or paused instance. History vectors are omitted unless include_history=true. The interval query parameter sets the cadence in seconds (default 5, minimum 1).",
+    security(("bearer_auth" = [])),
+    params(
+        ("interval" = Option<u64>, Query, description = "Seconds between events (default: 5)"),
+        ("include_history" = Option<bool>, Query, description = "Include rate/ratio history vectors in stats (default: false)")
+    ),
+    responses(
+        (status = 200, description = "SSE stream established", content_type = "text/event-stream"),
+        (status = 401, description = "Unauthorized", body = crate::api::common::ApiError)
+    )
+)]
+pub async fn stats_sse(
+    State(state): State<ServerState>,
+    Query(query): Query<StatsStreamQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let rx = state.app.subscribe_stats();
+
+    let include_history = query.include_history;
+    let stream = throttled_stats(rx, query.period()).map(move |stats_event| {
+        let stats_event =
+            if include_history { stats_event } else { Arc::new(stats_event.without_history()) };
+        Ok(Event::default()
+            .event("stats")
+            .json_data(&*stats_event)
+            .unwrap_or_else(|_| Event::default()))
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[utoipa::path(
+    get,
+    path = "/faker/{id}/stats/stream",
+    tag = "events",
+    summary = "Stream stats of one instance via SSE",
+    description = "Server-Sent Events stream of one instance's stats. Events are of type 'stats' with FakerStats data, sent while the instance is starting, running or paused. History vectors are omitted unless include_history=true. The interval query parameter sets the cadence in seconds (default 5, minimum 1).",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Instance ID"),
+        ("interval" = Option<u64>, Query, description = "Seconds between events (default: 5)"),
+        ("include_history" = Option<bool>, Query, description = "Include rate/ratio history vectors in stats (default: false)")
+    ),
+    responses(
+        (status = 200, description = "SSE stream established", content_type = "text/event-stream"),
+        (status = 401, description = "Unauthorized", body = crate::api::common::ApiError),
+        (status = 404, description = "Instance not found", body = crate::api::common::ApiError)
+    )
+)]
+pub async fn instance_stats_sse(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+    Query(query): Query<StatsStreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, Response> {
+    if !state.app.instance_exists(&id).await {
+        return Err(ApiError::response(StatusCode::NOT_FOUND, "Instance not found"));
+    }
+    let rx = state.app.subscribe_stats();
+
+    let include_history = query.include_history;
+    let stream = throttled_stats(rx, query.period()).filter_map(move |stats_event| {
+        let instance = stats_event.instances.iter().find(|instance| instance.id == id)?;
+        let mut stats = instance.stats.clone();
+        if !include_history {
+            stats.clear_history();
+        }
+        Some(Ok(Event::default()
+            .event("stats")
+            .json_data(&stats)
+            .unwrap_or_else(|_| Event::default())))
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
 pub fn router() -> Router<ServerState> {
-    Router::new().route("/logs", get(logs_sse)).route("/events", get(instances_sse))
+    Router::new()
+        .route("/logs", get(logs_sse))
+        .route("/events", get(instances_sse))
+        .route("/stats/stream", get(stats_sse))
+        .route("/faker/{id}/stats/stream", get(instance_stats_sse))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustatio_core::logger::InstanceLogContext;
+
+    #[test]
+    fn log_stream_filters_by_instance() {
+        let context = InstanceLogContext {
+            id: "a".to_string(),
+            label: "sample".to_string(),
+            info_hash: Some("ab".repeat(20)),
+        };
+        let from_a = LogEvent::new("info", "announced".to_string()).for_instance(context);
+        let global = LogEvent::new("info", "server started".to_string());
+        assert_eq!(from_a.info_hash.as_deref(), Some("ab".repeat(20).as_str()));
+
+        let all = LogStreamQuery { instance: None };
+        assert!(all.matches(&from_a) && all.matches(&global));
+
+        let only_a = LogStreamQuery { instance: Some("a".to_string()) };
+        assert!(only_a.matches(&from_a));
+        assert!(!only_a.matches(&global));
+        let only_b = LogStreamQuery { instance: Some("b".to_string()) };
+        assert!(!only_b.matches(&from_a));
+    }
 }
@@ -78,8 +78,8 @@ pub async fn start_faker(
     )
 )]
 pub async fn stop_faker(State(state): State<ServerState>, Path(id): Path<String>) -> Response {
-    match state.app.stop_instance(&id).await {
-        Ok(stats) => ApiSuccess::response(stats),
+    match state.app.stop_instance(&id, false).await {
+        Ok(stats) => ApiSuccess::response(state.app.shape_stats(stats)),
         Err(e) => ApiError::response(StatusCode::NOT_FOUND, e),
     }
 }
@@ -101,7 +101,7 @@ pub async fn stop_faker(State(state): State<ServerState>, Path(id): Path<String>
     )
 )]
 pub async fn pause_faker(State(state): State<ServerState>, Path(id): Path<String>) -> Response {
-    match state.app.pause_instance(&id).await {
+    match state.app.pause_instance(&id, false).await {
         Ok(()) => ApiSuccess::response(EmptyData {}),
         Err(e) => ApiError::response(StatusCode::NOT_FOUND, e),
     }
@@ -151,7 +151,7 @@ pub async fn recover_tracker_faker(
     Path(id): Path<String>,
 ) -> Response {
     match state.app.recover_tracker_instance(&id).await {
-        Ok(stats) => ApiSuccess::response(stats),
+        Ok(stats) => ApiSuccess::response(state.app.shape_stats(stats)),
         Err(e) => ApiError::response(StatusCode::NOT_FOUND, e),
     }
 }
@@ -174,7 +174,7 @@ pub async fn recover_tracker_faker(
 )]
 pub async fn update_faker(State(state): State<ServerState>, Path(id): Path<String>) -> Response {
     match state.app.update_instance(&id).await {
-        Ok(stats) => ApiSuccess::response(stats),
+        Ok(stats) => ApiSuccess::response(state.app.shape_stats(stats)),
         Err(e) => ApiError::response(StatusCode::NOT_FOUND, e),
     }
 }
@@ -200,7 +200,7 @@ pub async fn update_stats_only(
     Path(id): Path<String>,
 ) -> Response {
     match state.app.update_stats_only(&id).await {
-        Ok(stats) => ApiSuccess::response(stats),
+        Ok(stats) => ApiSuccess::response(state.app.shape_stats(stats)),
         Err(e) => ApiError::response(StatusCode::NOT_FOUND, e),
     }
 }
@@ -223,7 +223,7 @@ pub async fn update_stats_only(
 )]
 pub async fn get_stats(State(state): State<ServerState>, Path(id): Path<String>) -> Response {
     match state.app.get_stats(&id).await {
-        Ok(stats) => ApiSuccess::response(stats),
+        Ok(stats) => ApiSuccess::response(state.app.shape_stats(stats)),
         Err(e) => ApiError::response(StatusCode::NOT_FOUND, e),
     }
 }
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{DefaultBodyLimit, Multipart, Path, State},
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
     http::StatusCode,
     response::Response,
     routing::{get, post, put},
@@ -9,6 +9,7 @@ use rustatio_core::{
     FakerConfig, GridImportSettings, InstanceSummary, PresetSettings, TorrentSummary,
 };
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tokio::task::JoinSet;
 
 use crate::api::{
@@ -16,9 +17,11 @@ use crate::api::{
     ServerState,
 };
 use crate::services::events::{EventBroadcaster, InstanceEvent};
+use crate::services::import_jobs::{ImportJob, ImportedInstance};
+use crate::services::instance::TagFilter;
 use crate::services::persistence::InstanceSource;
-use crate::services::InstanceBuildContext;
 use crate::services::InstanceLifecycle;
+use crate::services::{AppState, InstanceBuildContext};
 
 #[derive(Deserialize)]
 pub struct GridIdsRequest {
@@ -49,6 +52,86 @@ pub struct GridImportFolderRequest {
     pub config: GridImportSettings,
 }
 
+/// Server-side instance filter. All set criteria must match.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GridQueryFilter {
+    /// Instance must carry every one of these tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Summary state, e.g. `running`, `paused`, `stopped`, `disabled`.
+    #[serde(default)]
+    pub state: Option<String>,
+    /// Case-insensitive substring of the torrent name.
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl GridQueryFilter {
+    fn matches(&self, summary: &InstanceSummary) -> bool {
+        self.tags.iter().all(|tag| summary.tags.contains(tag))
+            && self.state.as_deref().is_none_or(|state| summary.state.eq_ignore_ascii_case(state))
+            && self
+                .name
+                .as_deref()
+                .is_none_or(|name| summary.name.to_lowercase().contains(&name.to_lowercase()))
+    }
+}
+
+/// Optional filter for the `*-all` grid actions, taken from the query string.
+#[derive(Debug, Default, Deserialize)]
+pub struct GridAllQuery {
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub state: Option<String>,
+    /// Seconds to wait between starts for `start-all`
+    #[serde(default)]
+    pub stagger_start_secs: Option<u64>,
+}
+
+impl GridAllQuery {
+    fn filter(&self) -> GridQueryFilter {
+        GridQueryFilter {
+            tags: self.tag.iter().cloned().collect(),
+            state: self.state.clone(),
+            name: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GridQueryAction {
+    Start,
+    Stop,
+    Pause,
+    Resume,
+    Delete,
+    Enable,
+    Disable,
+}
+
+impl GridQueryAction {
+    /// Whether an instance in summary state `state` is one this action changes, so
+    /// the `*-all` actions skip instances that are already there.
+    fn applies_to(self, state: &str) -> bool {
+        match self {
+            Self::Start => matches!(state, "idle" | "stopped"),
+            Self::Stop => matches!(state, "starting" | "running" | "paused"),
+            Self::Pause => state == "running",
+            Self::Resume => state == "paused",
+            Self::Delete | Self::Enable | Self::Disable => true,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GridQueryActionRequest {
+    #[serde(default)]
+    pub filter: GridQueryFilter,
+    pub action: GridQueryAction,
+}
+
 #[derive(Deserialize)]
 pub struct GridBulkUpdateConfigEntry {
     pub id: String,
@@ -57,16 +140,22 @@ pub struct GridBulkUpdateConfigEntry {
 
 #[derive(Serialize)]
 pub struct GridImportResponse {
-    pub imported: Vec<GridImportedInstance>,
+    pub imported: Vec<ImportedInstance>,
     pub duplicates: Vec<String>,
     pub errors: Vec<String>,
 }
 
+#[derive(Deserialize)]
+pub struct GridImportQuery {
+    /// Return a job id immediately and import in the background
+    #[serde(default)]
+    pub background: bool,
+}
+
 #[derive(Serialize)]
-pub struct GridImportedInstance {
-    pub id: String,
-    pub name: String,
-    pub info_hash: String,
+pub struct GridImportJobResponse {
+    pub job_id: String,
+    pub total: usize,
 }
 
 #[derive(Serialize)]
@@ -81,6 +170,13 @@ pub struct GridActionError {
     pub error: String,
 }
 
+#[derive(Serialize)]
+pub struct GridQueryActionResponse {
+    pub matched: Vec<String>,
+    pub succeeded: Vec<String>,
+    pub failed: Vec<GridActionError>,
+}
+
 fn is_torrent_upload_field(name: Option<&str>, file_name: Option<&str>) -> bool {
     if matches!(name, Some("config")) {
         return false;
@@ -90,15 +186,19 @@ fn is_torrent_upload_field(name: Option<&str>, file_name: Option<&str>) -> bool
 }
 
 const fn has_grid_import_items(
-    torrents: &[(String, TorrentSummary)],
+    torrents: &[(String, TorrentSummary, Vec<u8>)],
     duplicates: &[String],
     errors: &[String],
 ) -> bool {
     !torrents.is_empty() || !duplicates.is_empty() || !errors.is_empty()
 }
 
-pub async fn grid_import(State(state): State<ServerState>, mut multipart: Multipart) -> Response {
-    let mut torrents: Vec<(String, TorrentSummary)> = Vec::new();
+pub async fn grid_import(
+    State(state): State<ServerState>,
+    Query(query): Query<GridImportQuery>,
+    mut multipart: Multipart,
+) -> Response {
+    let mut torrents: Vec<(String, TorrentSummary, Vec<u8>)> = Vec::new();
     let mut config = GridImportSettings::default();
     let mut duplicates: Vec<String> = Vec::new();
     let mut errors: Vec<String> = Vec::new();
@@ -109,7 +209,12 @@ pub async fn grid_import(State(state): State<ServerState>, mut multipart: Multip
             Ok(Some(field)) => match field.name() {
                 Some("config") => match field.text().await {
                     Ok(text) => match serde_json::from_str::<GridImportSettings>(&text) {
-                        Ok(c) => config = c,
+                        Ok(c) => {
+                            if let Err(e) = c.base_config.validate() {
+                                return ApiError::response(StatusCode::BAD_REQUEST, e.to_string());
+                            }
+                            config = c;
+                        }
                         Err(e) => {
                             return ApiError::response(
                                 StatusCode::BAD_REQUEST,
@@ -144,7 +249,7 @@ pub async fn grid_import(State(state): State<ServerState>, mut multipart: Multip
                                     continue;
                                 }
                                 let id = state.app.next_instance_id();
-                                torrents.push((id, summary));
+                                torrents.push((id, summary, bytes.to_vec()));
                             }
                             Err(e) => {
                                 errors.push(format!("{filename}: {e}"));
@@ -169,62 +274,108 @@ pub async fn grid_import(State(state): State<ServerState>, mut multipart: Multip
         return ApiError::response(StatusCode::BAD_REQUEST, "No torrent files provided");
     }
 
-    let mut imported = Vec::new();
+    let total = torrents.len();
+    let job_id = state.app.import_jobs().create(total, duplicates, errors).await;
+
+    if query.background {
+        let app = state.app.clone();
+        let id = job_id.clone();
+        tokio::spawn(async move {
+            run_grid_import(&app, &id, torrents, &config).await;
+        });
+        return ApiSuccess::response(GridImportJobResponse { job_id, total });
+    }
+
+    import_job_response(run_grid_import(&state.app, &job_id, torrents, &config).await)
+}
 
-    for (id, summary) in &torrents {
-        let preset = config.resolve_for_instance();
-        let faker_config: FakerConfig = preset.into();
+pub async fn grid_import_status(
+    State(state): State<ServerState>,
+    Path(job_id): Path<String>,
+) -> Response {
+    state.app.import_jobs().get(&job_id).await.map_or_else(
+        || ApiError::response(StatusCode::NOT_FOUND, format!("Import job not found: {job_id}")),
+        ApiSuccess::response,
+    )
+}
 
+/// Create instances for parsed torrents, recording per-file results on the job as
+/// they complete, then auto-start them if requested.
+async fn run_grid_import(
+    app: &AppState,
+    job_id: &str,
+    torrents: Vec<(String, TorrentSummary, Vec<u8>)>,
+    config: &GridImportSettings,
+) -> Option<ImportJob> {
+    let jobs = app.import_jobs();
+    let mut created: Vec<(String, String)> = Vec::new();
+
+    for (id, summary, torrent_file) in torrents {
+        let faker_config: FakerConfig = config.resolve_for_instance().into();
         let context =
-            InstanceBuildContext::new(id, summary.to_info(), faker_config, InstanceSource::Manual);
-        match state.app.create_instance_with_tags(context, config.tags.clone()).await {
-            Ok(()) => {
-                imported.push(GridImportedInstance {
-                    id: id.clone(),
-                    name: summary.name.clone(),
-                    info_hash: hex::encode(summary.info_hash),
-                });
-
-                state.app.emit_instance_event(InstanceEvent::Created {
-                    id: id.clone(),
-                    torrent_name: summary.name.clone(),
-                    info_hash: hex::encode(summary.info_hash),
-                    auto_started: config.auto_start,
-                });
-            }
-            Err(e) => {
-                if e.starts_with("Duplicate torrent skipped:") {
-                    duplicates.push(format!("{}: {}", summary.name, e));
-                } else {
-                    errors.push(format!("{}: {}", summary.name, e));
+            InstanceBuildContext::new(&id, summary.to_info(), faker_config, InstanceSource::Manual)
+                .with_torrent_file(torrent_file);
+        let result = app.create_instance_with_tags(context, config.tags.clone()).await;
+        let info_hash = hex::encode(summary.info_hash);
+
+        if result.is_ok() {
+            app.emit_instance_event(InstanceEvent::Created {
+                id: id.clone(),
+                torrent_name: summary.name.clone(),
+                info_hash: info_hash.clone(),
+                auto_started: config.auto_start,
+            });
+            created.push((id.clone(), summary.name.clone()));
+        }
+
+        jobs.update(job_id, |job| {
+            job.processed += 1;
+            match result {
+                Ok(()) => job.imported.push(ImportedInstance { id, name: summary.name, info_hash }),
+                Err(e) if e.starts_with("Duplicate torrent skipped:") => {
+                    job.duplicates.push(format!("{}: {}", summary.name, e));
                 }
+                Err(e) => job.errors.push(format!("{}: {}", summary.name, e)),
             }
-        }
+        })
+        .await;
     }
 
     if config.auto_start {
-        for inst in &imported {
-            if let Some(stagger) = config.stagger_start_secs {
-                if stagger > 0 {
-                    let idx = imported.iter().position(|i| i.id == inst.id).unwrap_or(0);
-                    if idx > 0 {
-                        tokio::time::sleep(tokio::time::Duration::from_secs(stagger)).await;
-                    }
-                }
+        let stagger = config.stagger_start_secs.filter(|secs| *secs > 0);
+        for (idx, (id, name)) in created.iter().enumerate() {
+            if let Some(stagger) = stagger.filter(|_| idx > 0) {
+                tokio::time::sleep(tokio::time::Duration::from_secs(stagger)).await;
             }
-            if let Err(e) = state.app.start_instance(&inst.id).await {
-                errors.push(format!("{}: failed to start: {}", inst.name, e));
+            if let Err(e) = app.start_instance(id).await {
+                let error = format!("{name}: failed to start: {e}");
+                jobs.update(job_id, |job| job.errors.push(error)).await;
             }
         }
     }
 
-    ApiSuccess::response(GridImportResponse { imported, duplicates, errors })
+    jobs.finish(job_id).await;
+    jobs.get(job_id).await
+}
+
+fn import_job_response(job: Option<ImportJob>) -> Response {
+    match job {
+        Some(job) => ApiSuccess::response(GridImportResponse {
+            imported: job.imported,
+            duplicates: job.duplicates,
+            errors: job.errors,
+        }),
+        None => ApiError::response(StatusCode::INTERNAL_SERVER_ERROR, "Import job was lost"),
+    }
 }
 
 pub async fn grid_import_folder(
     State(state): State<ServerState>,
     Json(request): Json<GridImportFolderRequest>,
 ) -> Response {
+    if let Err(e) = request.config.base_config.validate() {
+        return ApiError::response(StatusCode::BAD_REQUEST, e.to_string());
+    }
     let path = std::path::Path::new(&request.path);
     if !path.exists() || !path.is_dir() {
         return ApiError::response(
@@ -233,7 +384,7 @@ pub async fn grid_import_folder(
         );
     }
 
-    let mut torrents: Vec<(String, TorrentSummary)> = Vec::new();
+    let mut torrents: Vec<(String, TorrentSummary, Vec<u8>)> = Vec::new();
     let mut duplicates: Vec<String> = Vec::new();
     let mut errors: Vec<String> = Vec::new();
     let mut seen_hashes = std::collections::HashSet::new();
@@ -269,7 +420,7 @@ pub async fn grid_import_folder(
                         continue;
                     }
                     let id = state.app.next_instance_id();
-                    torrents.push((id, summary));
+                    torrents.push((id, summary, bytes));
                 }
                 Err(e) => {
                     errors.push(format!("{filename}: {e}"));
@@ -281,54 +432,8 @@ pub async fn grid_import_folder(
         }
     }
 
-    let config = request.config;
-    let mut imported = Vec::new();
-
-    for (id, summary) in &torrents {
-        let preset = config.resolve_for_instance();
-        let faker_config: FakerConfig = preset.into();
-
-        let context =
-            InstanceBuildContext::new(id, summary.to_info(), faker_config, InstanceSource::Manual);
-        match state.app.create_instance_with_tags(context, config.tags.clone()).await {
-            Ok(()) => {
-                imported.push(GridImportedInstance {
-                    id: id.clone(),
-                    name: summary.name.clone(),
-                    info_hash: hex::encode(summary.info_hash),
-                });
-
-                state.app.emit_instance_event(InstanceEvent::Created {
-                    id: id.clone(),
-                    torrent_name: summary.name.clone(),
-                    info_hash: hex::encode(summary.info_hash),
-                    auto_started: config.auto_start,
-                });
-            }
-            Err(e) => {
-                if e.starts_with("Duplicate torrent skipped:") {
-                    duplicates.push(format!("{}: {}", summary.name, e));
-                } else {
-                    errors.push(format!("{}: {}", summary.name, e));
-                }
-            }
-        }
-    }
-
-    if config.auto_start {
-        for (idx, inst) in imported.iter().enumerate() {
-            if let Some(stagger) = config.stagger_start_secs {
-                if stagger > 0 && idx > 0 {
-                    tokio::time::sleep(tokio::time::Duration::from_secs(stagger)).await;
-                }
-            }
-            if let Err(e) = state.app.start_instance(&inst.id).await {
-                errors.push(format!("{}: failed to start: {}", inst.name, e));
-            }
-        }
-    }
-
-    ApiSuccess::response(GridImportResponse { imported, duplicates, errors })
+    let job_id = state.app.import_jobs().create(torrents.len(), duplicates, errors).await;
+    import_job_response(run_grid_import(&state.app, &job_id, torrents, &request.config).await)
 }
 
 pub async fn grid_start(
@@ -353,7 +458,7 @@ pub async fn grid_stop(
     let ids = request.ids;
     tokio::spawn(async move {
         for id in &ids {
-            if let Err(e) = state.app.stop_instance(id).await {
+            if let Err(e) = state.app.stop_instance(id, false).await {
                 tracing::warn!("Background grid_stop failed for {}: {}", id, e);
             }
         }
@@ -365,7 +470,8 @@ pub async fn grid_pause(
     State(state): State<ServerState>,
     Json(request): Json<GridIdsRequest>,
 ) -> Response {
-    let (succeeded, failed) = grid_action_concurrent(&state, request.ids, GridAction::Pause).await;
+    let (succeeded, failed) =
+        grid_action_concurrent(&state, request.ids, None, GridQueryAction::Pause).await;
     ApiSuccess::response(GridActionResponse { succeeded, failed })
 }
 
@@ -373,7 +479,8 @@ pub async fn grid_resume(
     State(state): State<ServerState>,
     Json(request): Json<GridIdsRequest>,
 ) -> Response {
-    let (succeeded, failed) = grid_action_concurrent(&state, request.ids, GridAction::Resume).await;
+    let (succeeded, failed) =
+        grid_action_concurrent(&state, request.ids, None, GridQueryAction::Resume).await;
     ApiSuccess::response(GridActionResponse { succeeded, failed })
 }
 
@@ -381,7 +488,8 @@ pub async fn grid_delete(
     State(state): State<ServerState>,
     Json(request): Json<GridIdsRequest>,
 ) -> Response {
-    let (succeeded, failed) = grid_action_concurrent(&state, request.ids, GridAction::Delete).await;
+    let (succeeded, failed) =
+        grid_action_concurrent(&state, request.ids, None, GridQueryAction::Delete).await;
     ApiSuccess::response(GridActionResponse { succeeded, failed })
 }
 
@@ -389,6 +497,9 @@ pub async fn grid_update_config(
     State(state): State<ServerState>,
     Json(request): Json<GridUpdateConfigRequest>,
 ) -> Response {
+    if let Err(e) = request.config.validate() {
+        return ApiError::response(StatusCode::BAD_REQUEST, e.to_string());
+    }
     let faker_config: FakerConfig = request.config.into();
 
     let mut succeeded = Vec::new();
@@ -414,8 +525,110 @@ pub async fn grid_tag(
     }
 }
 
-pub async fn list_summaries(State(state): State<ServerState>) -> Response {
-    let summaries: Vec<InstanceSummary> = state.app.list_instance_summaries().await;
+/// Evaluate a filter against the current instances and apply an action to the matched
+/// set. Each instance is checked against the filter again right before the action, so
+/// one that stopped matching in between is reported as failed instead of acted on.
+pub async fn grid_query_action(
+    State(state): State<ServerState>,
+    Json(request): Json<GridQueryActionRequest>,
+) -> Response {
+    let mut matched: Vec<String> = state
+        .app
+        .list_instance_summaries()
+        .await
+        .into_iter()
+        .filter(|summary| request.filter.matches(summary))
+        .map(|summary| summary.id)
+        .collect();
+    matched.sort();
+
+    let filter = Arc::new(request.filter);
+    let (succeeded, failed) =
+        grid_action_concurrent(&state, matched.clone(), Some(filter), request.action).await;
+    ApiSuccess::response(GridQueryActionResponse { matched, succeeded, failed })
+}
+
+/// Ids of every instance matching `query` that `action` would change, oldest first.
+async fn grid_all_ids(
+    state: &ServerState,
+    query: &GridAllQuery,
+    action: GridQueryAction,
+) -> Vec<String> {
+    let filter = query.filter();
+    state
+        .app
+        .list_instance_summaries()
+        .await
+        .into_iter()
+        .filter(|summary| filter.matches(summary) && action.applies_to(&summary.state))
+        .map(|summary| summary.id)
+        .collect()
+}
+
+/// Start every matching instance in the background, `stagger_start_secs` apart.
+pub async fn grid_start_all(
+    State(state): State<ServerState>,
+    Query(query): Query<GridAllQuery>,
+) -> Response {
+    let ids = grid_all_ids(&state, &query, GridQueryAction::Start).await;
+    let stagger = query.stagger_start_secs.filter(|secs| *secs > 0);
+    tokio::spawn(async move {
+        for (idx, id) in ids.iter().enumerate() {
+            if let Some(stagger) = stagger.filter(|_| idx > 0) {
+                tokio::time::sleep(tokio::time::Duration::from_secs(stagger)).await;
+            }
+            if let Err(e) = state.app.start_instance(id).await {
+                tracing::warn!("Background grid_start_all failed for {}: {}", id, e);
+            }
+        }
+    });
+    ApiSuccess::response(GridActionResponse { succeeded: Vec::new(), failed: Vec::new() })
+}
+
+pub async fn grid_stop_all(
+    State(state): State<ServerState>,
+    Query(query): Query<GridAllQuery>,
+) -> Response {
+    let ids = grid_all_ids(&state, &query, GridQueryAction::Stop).await;
+    tokio::spawn(async move {
+        for id in &ids {
+            if let Err(e) = state.app.stop_instance(id, false).await {
+                tracing::warn!("Background grid_stop_all failed for {}: {}", id, e);
+            }
+        }
+    });
+    ApiSuccess::response(GridActionResponse { succeeded: Vec::new(), failed: Vec::new() })
+}
+
+pub async fn grid_pause_all(
+    State(state): State<ServerState>,
+    Query(query): Query<GridAllQuery>,
+) -> Response {
+    let ids = grid_all_ids(&state, &query, GridQueryAction::Pause).await;
+    let (succeeded, failed) =
+        grid_action_concurrent(&state, ids, None, GridQueryAction::Pause).await;
+    ApiSuccess::response(GridActionResponse { succeeded, failed })
+}
+
+pub async fn grid_resume_all(
+    State(state): State<ServerState>,
+    Query(query): Query<GridAllQuery>,
+) -> Response {
+    let ids = grid_all_ids(&state, &query, GridQueryAction::Resume).await;
+    let (succeeded, failed) =
+        grid_action_concurrent(&state, ids, None, GridQueryAction::Resume).await;
+    ApiSuccess::response(GridActionResponse { succeeded, failed })
+}
+
+pub async fn list_summaries(
+    State(state): State<ServerState>,
+    Query(filter): Query<TagFilter>,
+) -> Response {
+    let mut summaries: Vec<InstanceSummary> =
+        state.app.list_instance_summaries_matching(&filter).await;
+    if let Some(decimals) = state.app.stats_precision() {
+        summaries.iter_mut().for_each(|summary| summary.round_floats(decimals));
+    }
     ApiSuccess::response(summaries)
 }
 
@@ -448,31 +661,34 @@ pub async fn grid_bulk_update_configs(
     ApiSuccess::response(GridActionResponse { succeeded, failed })
 }
 
-enum GridAction {
-    Pause,
-    Resume,
-    Delete,
-}
-
+/// Apply `action` to every id concurrently. With a `filter`, an instance that no longer
+/// matches it when its turn comes is skipped and reported as failed.
 async fn grid_action_concurrent(
     state: &ServerState,
     ids: Vec<String>,
-    action: GridAction,
+    filter: Option<Arc<GridQueryFilter>>,
+    action: GridQueryAction,
 ) -> (Vec<String>, Vec<GridActionError>) {
     let mut set = JoinSet::new();
 
     for id in ids {
         let state = state.clone();
-        let action_kind = match action {
-            GridAction::Pause => 0u8,
-            GridAction::Resume => 1,
-            GridAction::Delete => 2,
-        };
+        let filter = filter.clone();
         set.spawn(async move {
-            let result = match action_kind {
-                0 => state.app.pause_instance(&id).await,
-                1 => state.app.resume_instance(&id).await,
-                _ => state.app.delete_instance(&id, true).await,
+            if let Some(filter) = filter {
+                let summary = state.app.find_instance_summary(&id).await;
+                if !summary.is_some_and(|summary| filter.matches(&summary)) {
+                    return (id, Err("Instance no longer matches the filter".to_string()));
+                }
+            }
+            let result = match action {
+                GridQueryAction::Start => state.app.start_instance(&id).await,
+                GridQueryAction::Stop => state.app.stop_instance(&id, false).await.map(|_| ()),
+                GridQueryAction::Pause => state.app.pause_instance(&id, false).await,
+                GridQueryAction::Resume => state.app.resume_instance(&id).await,
+                GridQueryAction::Delete => state.app.delete_instance(&id, true).await,
+                GridQueryAction::Enable => state.app.set_instance_enabled(&id, true).await,
+                GridQueryAction::Disable => state.app.set_instance_enabled(&id, false).await,
             };
             (id, result)
         });
@@ -498,23 +714,61 @@ pub fn router() -> Router<ServerState> {
     Router::new()
         .route("/grid/import", post(grid_import))
         .layer(DefaultBodyLimit::max(512 * 1024 * 1024))
+        .route("/grid/import/{job_id}/status", get(grid_import_status))
         .route("/grid/import-folder", post(grid_import_folder))
         .route("/grid/start", post(grid_start))
         .route("/grid/stop", post(grid_stop))
         .route("/grid/pause", post(grid_pause))
         .route("/grid/resume", post(grid_resume))
+        .route("/grid/start-all", post(grid_start_all))
+        .route("/grid/stop-all", post(grid_stop_all))
+        .route("/grid/pause-all", post(grid_pause_all))
+        .route("/grid/resume-all", post(grid_resume_all))
         .route("/grid/delete", post(grid_delete))
         .route("/grid/update-config", post(grid_update_config))
         .route("/grid/bulk-update-configs", post(grid_bulk_update_configs))
         .route("/grid/tag", post(grid_tag))
+        .route("/grid/query-action", post(grid_query_action))
         .route("/instances/summary", get(list_summaries))
         .route("/instances/{id}/tags", put(set_instance_tags))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{has_grid_import_items, is_torrent_upload_field};
-    use rustatio_core::TorrentSummary;
+    use super::{
+        has_grid_import_items, is_torrent_upload_field, GridAllQuery, GridQueryAction,
+        GridQueryFilter,
+    };
+    use rustatio_core::{InstanceSummary, TorrentSummary};
+
+    fn summary(name: &str, state: &str, tags: &[&str]) -> InstanceSummary {
+        InstanceSummary {
+            id: name.to_string(),
+            name: name.to_string(),
+            label: None,
+            info_hash: String::new(),
+            primary_tracker_host: None,
+            state: state.to_string(),
+            is_tracker_invalid: false,
+            tracker_error: None,
+            tracker_retry_attempt: 0,
+            tracker_retry_at_ms: None,
+            stop_reason: None,
+            tags: tags.iter().map(ToString::to_string).collect(),
+            total_size: 0,
+            uploaded: 0,
+            downloaded: 0,
+            ratio: 0.0,
+            current_upload_rate: 0.0,
+            current_download_rate: 0.0,
+            seeders: 0,
+            leechers: 0,
+            left: 0,
+            torrent_completion: 100.0,
+            source: "manual".to_string(),
+            created_at: 0,
+        }
+    }
 
     #[test]
     fn torrent_upload_field_accepts_known_names() {
@@ -537,7 +791,7 @@ mod tests {
 
     #[test]
     fn grid_import_items_treat_duplicates_as_valid_result() {
-        let torrents: Vec<(String, TorrentSummary)> = Vec::new();
+        let torrents: Vec<(String, TorrentSummary, Vec<u8>)> = Vec::new();
         let duplicates = vec!["sample.torrent: already imported".to_string()];
         let errors: Vec<String> = Vec::new();
 
@@ -546,10 +800,45 @@ mod tests {
 
     #[test]
     fn grid_import_items_require_torrents_duplicates_or_errors() {
-        let torrents: Vec<(String, TorrentSummary)> = Vec::new();
+        let torrents: Vec<(String, TorrentSummary, Vec<u8>)> = Vec::new();
         let duplicates: Vec<String> = Vec::new();
         let errors: Vec<String> = Vec::new();
 
         assert!(!has_grid_import_items(&torrents, &duplicates, &errors));
     }
+
+    #[test]
+    fn query_filter_requires_all_criteria() {
+        let filter = GridQueryFilter {
+            tags: vec!["linux".to_string()],
+            state: Some("running".to_string()),
+            name: Some("UBUNTU".to_string()),
+        };
+
+        assert!(filter.matches(&summary("ubuntu-24.04.iso", "running", &["linux", "iso"])));
+        assert!(!filter.matches(&summary("ubuntu-24.04.iso", "paused", &["linux"])));
+        assert!(!filter.matches(&summary("ubuntu-24.04.iso", "running", &["iso"])));
+        assert!(!filter.matches(&summary("debian-12.iso", "running", &["linux"])));
+    }
+
+    #[test]
+    fn empty_query_filter_matches_everything() {
+        let filter = GridQueryFilter::default();
+
+        assert!(filter.matches(&summary("anything", "stopped", &[])));
+    }
+
+    #[test]
+    fn all_actions_skip_instances_already_in_the_target_state() {
+        let query = GridAllQuery { tag: Some("linux".to_string()), ..GridAllQuery::default() };
+        let filter = query.filter();
+        let running = summary("ubuntu", "running", &["linux"]);
+
+        assert!(filter.matches(&running));
+        assert!(GridQueryAction::Pause.applies_to(&running.state));
+        assert!(GridQueryAction::Stop.applies_to(&running.state));
+        assert!(!GridQueryAction::Start.applies_to(&running.state));
+        assert!(!GridQueryAction::Resume.applies_to(&running.state));
+        assert!(!filter.matches(&summary("debian", "running", &[])));
+    }
 }
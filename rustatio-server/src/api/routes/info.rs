@@ -0,0 +1,85 @@
+//! Server-wide runtime info, including the global upload cap.
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::Response,
+    routing::{get, post},
+    Router,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::api::{
+    common::{ApiError, ApiSuccess},
+    ServerState,
+};
+use crate::services::AppState;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InfoResponse {
+    pub version: String,
+    pub instance_count: usize,
+    /// Cumulative uploaded bytes across all instances
+    pub total_uploaded: u64,
+    /// Configured `GLOBAL_MAX_TOTAL_UPLOADED_BYTES`, if any
+    pub upload_cap: Option<u64>,
+    /// Uploaded bytes counted toward the cap since its last reset
+    pub upload_cap_used: u64,
+    pub upload_cap_reached: bool,
+}
+
+impl InfoResponse {
+    async fn collect(app: &AppState) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            instance_count: app.instances.read().await.len(),
+            total_uploaded: app.total_uploaded().await,
+            upload_cap: app.global_upload_cap(),
+            upload_cap_used: app.upload_cap_usage().await,
+            upload_cap_reached: app.upload_cap_reached(),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/info",
+    tag = "info",
+    summary = "Get server info",
+    description = "Returns the server version, the cumulative upload total across all instances and the state of the global upload cap.",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Server info", body = ApiSuccess<InfoResponse>),
+        (status = 401, description = "Unauthorized", body = ApiError)
+    )
+)]
+pub async fn get_info(State(state): State<ServerState>) -> Response {
+    ApiSuccess::response(InfoResponse::collect(&state.app).await)
+}
+
+#[utoipa::path(
+    post,
+    path = "/info/upload-cap/reset",
+    tag = "info",
+    summary = "Reset the global upload cap",
+    description = "Restarts global upload cap accounting from the current total and unblocks instance starts. Paused instances are not resumed automatically.",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Upload cap reset", body = ApiSuccess<InfoResponse>),
+        (status = 401, description = "Unauthorized", body = ApiError),
+        (status = 500, description = "Failed to save state", body = ApiError)
+    )
+)]
+pub async fn reset_upload_cap(State(state): State<ServerState>) -> Response {
+    match state.app.reset_global_upload_cap().await {
+        Ok(()) => ApiSuccess::response(InfoResponse::collect(&state.app).await),
+        Err(e) => ApiError::response(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+pub fn router() -> Router<ServerState> {
+    Router::new()
+        .route("/info", get(get_info))
+        .route("/info/upload-cap/reset", post(reset_upload_cap))
+}
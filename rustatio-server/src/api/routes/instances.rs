@@ -2,13 +2,14 @@
 
 use axum::{
     extract::{DefaultBodyLimit, Multipart, Path, Query, State},
-    http::StatusCode,
+    http::{HeaderValue, StatusCode},
     response::Response,
-    routing::{delete, get, patch},
+    routing::{delete, get, patch, post, put},
     Json, Router,
 };
-use rustatio_core::{FakerConfig, TorrentSummary};
-use serde::Deserialize;
+use rustatio_core::protocol::{bencode, AnnounceResponse};
+use rustatio_core::{LastAnnounce, TorrentSummary};
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::api::{
@@ -16,20 +17,106 @@ use crate::api::{
     routes::torrents::LoadTorrentResponse,
     ServerState,
 };
+use crate::services::instance::{InstanceQuery, InstanceSortKey, SortOrder, TagCount, TagMatch};
 use crate::services::persistence::InstanceSource;
-use crate::services::InstanceInfo;
+use crate::services::tracker_check::{redact_tracker_url, redact_urls_in_text};
+use crate::services::{InstanceInfo, InstanceSchedule, LogEvent};
 
 #[derive(serde::Serialize, ToSchema)]
 pub struct CreateInstanceResponse {
     pub id: String,
 }
 
+#[derive(Deserialize, ToSchema)]
+pub struct InstanceLogsQuery {
+    /// Return only the most recent lines
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LastAnnounceResponse {
+    /// Announce URL as sent, with passkeys and the announce key redacted
+    pub url: String,
+    /// When the announce was sent, in Unix milliseconds
+    pub sent_at_ms: u64,
+    /// HTTP status of the answer; `null` when the tracker could not be reached
+    pub status: Option<u16>,
+    /// Response body decoded from bencode, or as text when it is not bencode
+    #[schema(value_type = Object)]
+    pub raw_response: serde_json::Value,
+    /// Announce response as parsed; `null` when the announce failed
+    #[schema(value_type = Option<Object>)]
+    pub response: Option<AnnounceResponse>,
+    /// Why the announce failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<LastAnnounce> for LastAnnounceResponse {
+    fn from(last: LastAnnounce) -> Self {
+        let exchange = last.exchange;
+        let raw_response = if exchange.body.is_empty() {
+            serde_json::Value::Null
+        } else {
+            bencode::parse(&exchange.body).map_or_else(
+                |_| String::from_utf8_lossy(&exchange.body).into_owned().into(),
+                |value| bencode::to_json(&value),
+            )
+        };
+        let (response, error) = match exchange.result {
+            Ok(response) => (Some(response), None),
+            Err(e) => (None, Some(redact_urls_in_text(&e))),
+        };
+        Self {
+            url: redact_tracker_url(&exchange.url),
+            sent_at_ms: last.sent_at_ms,
+            status: exchange.status,
+            raw_response,
+            response,
+            error,
+        }
+    }
+}
+
 #[derive(Deserialize, ToSchema)]
 pub struct DeleteInstanceQuery {
     #[serde(default)]
     pub force: bool,
 }
 
+#[derive(Deserialize, ToSchema)]
+pub struct ListInstancesQuery {
+    #[serde(default)]
+    pub include_history: bool,
+    /// Omit history vectors even when `include_history` is set
+    #[serde(default)]
+    pub lite: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetInstanceEnabledRequest {
+    pub enabled: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetInstanceLabelRequest {
+    /// Custom name for the instance; `null` or blank clears it
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+pub struct InstanceLabelResponse {
+    /// The stored label, `null` when it was cleared
+    pub label: Option<String>,
+}
+
+#[derive(serde::Serialize, ToSchema)]
+pub struct InstanceScheduleResponse {
+    /// The stored schedule, `null` when it was cleared
+    pub schedule: Option<InstanceSchedule>,
+}
+
 #[utoipa::path(
     post,
     path = "/instances",
@@ -52,16 +139,41 @@ pub async fn create_instance(State(state): State<ServerState>) -> Response {
     path = "/instances",
     tag = "instances",
     summary = "List all instances",
-    description = "Returns a list of all faker instances with their current statistics and configuration.",
+    description = "Returns faker instances with their current statistics and configuration, optionally filtered by state or tag, sorted and paged. The X-Total-Count header holds the number of matching instances before paging. Rate and ratio history vectors are omitted unless include_history=true.",
     security(("bearer_auth" = [])),
+    params(
+        ("include_history" = Option<bool>, Query, description = "Include rate/ratio history vectors in stats (default: false)"),
+        ("lite" = Option<bool>, Query, description = "Omit history vectors even when include_history is set (default: false)"),
+        ("state" = Option<String>, Query, description = "Only instances in this state: running, paused, idle, starting, stopping, stopped or disabled"),
+        ("tag" = Option<String>, Query, description = "Only instances with this tag (case-insensitive)"),
+        ("tags" = Option<String>, Query, description = "Comma-separated tags (case-insensitive), combined according to match"),
+        ("match" = Option<TagMatch>, Query, description = "any: at least one of tags, all: every one of tags (default: any)"),
+        ("sort" = Option<InstanceSortKey>, Query, description = "Sort key (default: created_at)"),
+        ("order" = Option<SortOrder>, Query, description = "asc or desc (default: asc)"),
+        ("limit" = Option<usize>, Query, description = "Maximum number of instances to return"),
+        ("offset" = Option<usize>, Query, description = "Number of matching instances to skip (default: 0)")
+    ),
     responses(
         (status = 200, description = "List of instances", body = ApiSuccess<Vec<InstanceInfo>>),
         (status = 401, description = "Unauthorized", body = ApiError)
     )
 )]
-pub async fn list_instances(State(state): State<ServerState>) -> Response {
-    let instances: Vec<InstanceInfo> = state.app.list_instances().await;
-    ApiSuccess::response(instances)
+pub async fn list_instances(
+    State(state): State<ServerState>,
+    Query(query): Query<ListInstancesQuery>,
+    Query(filter): Query<InstanceQuery>,
+) -> Response {
+    let total = state.app.count_instances(&filter).await;
+    let mut instances: Vec<InstanceInfo> = state.app.list_instances(&filter).await;
+    if query.lite || !query.include_history {
+        instances.iter_mut().for_each(InstanceInfo::strip_history);
+    }
+    if let Some(decimals) = state.app.stats_precision() {
+        instances.iter_mut().for_each(|instance| instance.stats.round_floats(decimals));
+    }
+    let mut response = ApiSuccess::response(instances);
+    response.headers_mut().insert("x-total-count", HeaderValue::from(total));
+    response
 }
 
 #[utoipa::path(
@@ -108,7 +220,7 @@ pub async fn delete_instance(
     path = "/instances/{id}/torrent",
     tag = "instances",
     summary = "Load torrent for instance",
-    description = "Uploads a torrent file and associates it with the specified instance ID. Creates an idle instance that persists across page refreshes.",
+    description = "Uploads a torrent file and associates it with the specified instance ID. Creates an idle instance that persists across page refreshes. The response includes a quick reachability check of the primary tracker; an unreachable tracker does not prevent creation.",
     security(("bearer_auth" = [])),
     params(
         ("id" = String, Path, description = "Instance ID to associate with the torrent")
@@ -145,8 +257,14 @@ pub async fn load_instance_torrent(
                                 }
                                 let response_torrent = summary.clone();
                                 let compact_torrent = summary.to_info();
-                                if let Err(e) =
-                                    state.app.create_idle_instance(&id, compact_torrent).await
+                                if let Err(e) = state
+                                    .app
+                                    .create_idle_instance(
+                                        &id,
+                                        compact_torrent,
+                                        Some(bytes.to_vec()),
+                                    )
+                                    .await
                                 {
                                     return ApiError::response(
                                         StatusCode::INTERNAL_SERVER_ERROR,
@@ -154,8 +272,10 @@ pub async fn load_instance_torrent(
                                     );
                                 }
 
+                                let tracker = Some(state.app.check_tracker(&summary).await);
                                 return ApiSuccess::response(LoadTorrentResponse {
                                     torrent: response_torrent,
+                                    tracker,
                                 });
                             }
                             Err(e) => {
@@ -192,14 +312,15 @@ pub async fn load_instance_torrent(
     path = "/instances/{id}/config",
     tag = "instances",
     summary = "Update instance configuration",
-    description = "Updates the configuration for an existing instance without starting it. Used to persist form changes.",
+    description = "Updates the configuration for an existing instance without starting it. Used to persist form changes. Accepts a partial config: only the fields present are changed, the rest keep their current values. Unknown fields and invalid values are rejected with 400.",
     security(("bearer_auth" = [])),
     params(
         ("id" = String, Path, description = "Instance ID to update")
     ),
-    request_body(content = Object, description = "Faker configuration settings"),
+    request_body(content = Object, description = "Faker configuration fields to change"),
     responses(
         (status = 200, description = "Configuration updated", body = ApiSuccess<EmptyData>),
+        (status = 400, description = "Unknown field or invalid value", body = ApiError),
         (status = 401, description = "Unauthorized", body = ApiError),
         (status = 404, description = "Instance not found", body = ApiError)
     )
@@ -207,14 +328,137 @@ pub async fn load_instance_torrent(
 pub async fn update_instance_config(
     State(state): State<ServerState>,
     Path(id): Path<String>,
-    Json(config): Json<FakerConfig>,
+    Json(patch): Json<serde_json::Value>,
 ) -> Response {
-    match state.app.update_instance_config_only(&id, config).await {
+    if !state.app.instance_exists(&id).await {
+        return ApiError::response(StatusCode::NOT_FOUND, "Instance not found");
+    }
+    match state.app.patch_instance_config(&id, patch).await {
         Ok(()) => ApiSuccess::response(()),
+        Err(e) => ApiError::response(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+#[utoipa::path(
+    patch,
+    path = "/instances/{id}/enabled",
+    tag = "instances",
+    summary = "Enable or disable an instance",
+    description = "Disabled instances keep their configuration and statistics but are stopped, skipped by the scheduler and not auto-started on restore. Unlike pause, this is meant for parking a torrent long-term.",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Instance ID")
+    ),
+    request_body = SetInstanceEnabledRequest,
+    responses(
+        (status = 200, description = "Enabled flag updated", body = ApiSuccess<EmptyData>),
+        (status = 401, description = "Unauthorized", body = ApiError),
+        (status = 404, description = "Instance not found", body = ApiError)
+    )
+)]
+pub async fn set_instance_enabled(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+    Json(request): Json<SetInstanceEnabledRequest>,
+) -> Response {
+    match state.app.set_instance_enabled(&id, request.enabled).await {
+        Ok(()) => ApiSuccess::response(EmptyData {}),
         Err(e) => ApiError::response(StatusCode::NOT_FOUND, e),
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/instances/{id}/clone",
+    tag = "instances",
+    summary = "Clone an instance",
+    description = "Creates a new idle instance with the same torrent, config and tags as the source, but fresh cumulative stats and a new peer_id/key. The clone is always a manual instance, even when the source comes from the watch folder.",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Source instance ID")
+    ),
+    responses(
+        (status = 200, description = "Instance cloned", body = ApiSuccess<CreateInstanceResponse>),
+        (status = 400, description = "Clone could not be created", body = ApiError),
+        (status = 401, description = "Unauthorized", body = ApiError),
+        (status = 404, description = "Instance not found", body = ApiError)
+    )
+)]
+pub async fn clone_instance(State(state): State<ServerState>, Path(id): Path<String>) -> Response {
+    if !state.app.instance_exists(&id).await {
+        return ApiError::response(StatusCode::NOT_FOUND, "Instance not found");
+    }
+    let new_id = state.app.next_instance_id();
+    match state.app.clone_instance(&id, &new_id).await {
+        Ok(()) => ApiSuccess::response(CreateInstanceResponse { id: new_id }),
+        Err(e) => ApiError::response(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/instances/{id}/label",
+    tag = "instances",
+    summary = "Set an instance's label",
+    description = "Sets a custom name the instance is shown under instead of its torrent name, for example in the grid view. A null or blank label clears it.",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Instance ID")
+    ),
+    request_body = SetInstanceLabelRequest,
+    responses(
+        (status = 200, description = "Label updated", body = ApiSuccess<InstanceLabelResponse>),
+        (status = 400, description = "Label too long", body = ApiError),
+        (status = 401, description = "Unauthorized", body = ApiError),
+        (status = 404, description = "Instance not found", body = ApiError)
+    )
+)]
+pub async fn set_instance_label(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+    Json(request): Json<SetInstanceLabelRequest>,
+) -> Response {
+    if !state.app.instance_exists(&id).await {
+        return ApiError::response(StatusCode::NOT_FOUND, "Instance not found");
+    }
+    match state.app.set_instance_label(&id, request.label).await {
+        Ok(label) => ApiSuccess::response(InstanceLabelResponse { label }),
+        Err(e) => ApiError::response(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/instances/{id}/schedule",
+    tag = "instances",
+    summary = "Set an instance's start/stop schedule",
+    description = "Sets five-field cron expressions (minute hour day-of-month month day-of-week) at which the instance is started and stopped, evaluated every minute in UTC shifted by utc_offset_minutes. Either expression may be omitted; omitting both clears the schedule. When both match the same minute, stop wins.",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Instance ID")
+    ),
+    request_body = InstanceSchedule,
+    responses(
+        (status = 200, description = "Schedule updated", body = ApiSuccess<InstanceScheduleResponse>),
+        (status = 400, description = "Invalid cron expression", body = ApiError),
+        (status = 401, description = "Unauthorized", body = ApiError),
+        (status = 404, description = "Instance not found", body = ApiError)
+    )
+)]
+pub async fn set_instance_schedule(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+    Json(schedule): Json<InstanceSchedule>,
+) -> Response {
+    if !state.app.instance_exists(&id).await {
+        return ApiError::response(StatusCode::NOT_FOUND, "Instance not found");
+    }
+    match state.app.set_instance_schedule(&id, Some(schedule)).await {
+        Ok(schedule) => ApiSuccess::response(InstanceScheduleResponse { schedule }),
+        Err(e) => ApiError::response(StatusCode::BAD_REQUEST, e),
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/instances/{id}/torrent",
@@ -265,6 +509,80 @@ pub async fn get_instance_torrent_summary(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/instances/{id}/logs",
+    tag = "instances",
+    summary = "Get recent log lines of an instance",
+    description = "Returns the last log lines (up to 500) written while working on this instance, oldest first. The buffer lives in memory only and is cleared when the instance is deleted or the server restarts.",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Instance ID"),
+        ("limit" = Option<usize>, Query, description = "Return only the most recent lines")
+    ),
+    responses(
+        (status = 200, description = "Log lines", body = ApiSuccess<Vec<LogEvent>>),
+        (status = 401, description = "Unauthorized", body = ApiError),
+        (status = 404, description = "Instance not found", body = ApiError)
+    )
+)]
+pub async fn get_instance_logs(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+    Query(query): Query<InstanceLogsQuery>,
+) -> Response {
+    match state.app.get_instance_logs(&id, query.limit).await {
+        Ok(lines) => ApiSuccess::response(lines),
+        Err(e) => ApiError::response(StatusCode::NOT_FOUND, e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/instances/{id}/debug/last-announce",
+    tag = "instances",
+    summary = "Get the last announce of an instance",
+    description = "Returns the last announce URL (passkeys redacted), the raw tracker response decoded to JSON and the parsed response, to see exactly what the tracker returned. Only recorded while the instance config sets `record_last_announce`; kept in memory until the server restarts.",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Instance ID")
+    ),
+    responses(
+        (status = 200, description = "Last announce", body = ApiSuccess<LastAnnounceResponse>),
+        (status = 401, description = "Unauthorized", body = ApiError),
+        (status = 404, description = "Instance not found or no announce recorded", body = ApiError)
+    )
+)]
+pub async fn get_last_announce(
+    State(state): State<ServerState>,
+    Path(id): Path<String>,
+) -> Response {
+    match state.app.get_last_announce(&id).await {
+        Ok(Some(last)) => ApiSuccess::response(LastAnnounceResponse::from(last)),
+        Ok(None) => ApiError::response(
+            StatusCode::NOT_FOUND,
+            "No announce recorded. Set record_last_announce in the instance config",
+        ),
+        Err(e) => ApiError::response(StatusCode::NOT_FOUND, e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/tags",
+    tag = "instances",
+    summary = "List tags in use",
+    description = "Returns every distinct instance tag, sorted by name, with the number of instances carrying it. Tags differing only in case are counted together.",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Tags with instance counts", body = ApiSuccess<Vec<TagCount>>),
+        (status = 401, description = "Unauthorized", body = ApiError)
+    )
+)]
+pub async fn list_tags(State(state): State<ServerState>) -> Response {
+    ApiSuccess::response(state.app.tag_counts().await)
+}
+
 pub fn router() -> Router<ServerState> {
     Router::new()
         .route("/instances/{id}/torrent", get(get_instance_torrent).post(load_instance_torrent))
@@ -273,4 +591,11 @@ pub fn router() -> Router<ServerState> {
         .route("/instances", get(list_instances).post(create_instance))
         .route("/instances/{id}", delete(delete_instance))
         .route("/instances/{id}/config", patch(update_instance_config))
+        .route("/instances/{id}/enabled", patch(set_instance_enabled))
+        .route("/instances/{id}/label", put(set_instance_label))
+        .route("/instances/{id}/schedule", put(set_instance_schedule))
+        .route("/instances/{id}/clone", post(clone_instance))
+        .route("/instances/{id}/logs", get(get_instance_logs))
+        .route("/instances/{id}/debug/last-announce", get(get_last_announce))
+        .route("/tags", get(list_tags))
 }
@@ -1,14 +1,20 @@
 //! API route handlers organized by domain.
 
+pub mod admin;
 pub mod auth;
 pub mod browse;
+pub mod bundle;
+pub mod capabilities;
 pub mod clients;
 pub mod config;
 pub mod events;
 pub mod faker;
 pub mod grid;
+pub mod info;
 pub mod instances;
 pub mod network;
+pub mod notify;
 pub mod presets;
+pub mod stats;
 pub mod torrents;
 pub mod watch;
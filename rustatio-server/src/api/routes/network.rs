@@ -22,11 +22,10 @@ pub struct NetworkStatus {
     pub peer_listener_active: bool,
     pub peer_listener_error: Option<String>,
     pub vpn_port_sync_enabled: bool,
-}
-
-#[derive(Deserialize)]
-struct GluetunVpnStatus {
-    status: String,
+    /// `VPN_KILLSWITCH` is set
+    pub vpn_killswitch_enabled: bool,
+    /// The VPN is down and the kill-switch is holding instances paused
+    pub vpn_killswitch_engaged: bool,
 }
 
 #[derive(Deserialize)]
@@ -46,7 +45,7 @@ struct GluetunForwardedPort {
     path = "/network/status",
     tag = "network",
     summary = "Get network status",
-    description = "Returns the current public IP address and VPN connection status. When no VPN is configured, returns a normal response with configured=false.",
+    description = "Returns the current public IP address, VPN connection status and VPN kill-switch state. When no VPN is configured, returns a normal response with configured=false.",
     security(("bearer_auth" = [])),
     responses(
         (status = 200, description = "Network status retrieved", body = ApiSuccess<NetworkStatus>),
@@ -55,17 +54,19 @@ struct GluetunForwardedPort {
 )]
 pub async fn get_network_status(State(state): State<ServerState>) -> Response {
     let listener_status = state.app.peer_listener_status().await;
-    try_gluetun_detection(
+    let status = try_gluetun_detection(
         &GluetunAuth::from_env(),
         state.app.current_forwarded_port(),
         state.app.vpn_port_sync_enabled(),
         listener_status.clone(),
     )
     .await
-    .map_or_else(
-        || ApiSuccess::response(no_vpn_status(state.app.vpn_port_sync_enabled(), listener_status)),
-        ApiSuccess::response,
-    )
+    .unwrap_or_else(|| no_vpn_status(state.app.vpn_port_sync_enabled(), listener_status));
+    ApiSuccess::response(NetworkStatus {
+        vpn_killswitch_enabled: state.app.vpn_killswitch_enabled(),
+        vpn_killswitch_engaged: state.app.killswitch_engaged(),
+        ..status
+    })
 }
 
 fn no_vpn_status(
@@ -83,6 +84,8 @@ fn no_vpn_status(
         peer_listener_active: listener_status.bound_port.is_some(),
         peer_listener_error: listener_status.last_error,
         vpn_port_sync_enabled,
+        vpn_killswitch_enabled: false,
+        vpn_killswitch_engaged: false,
     }
 }
 
@@ -95,17 +98,7 @@ async fn try_gluetun_detection(
     let client =
         reqwest::Client::builder().timeout(std::time::Duration::from_secs(1)).build().ok()?;
 
-    // Get VPN status
-    let vpn_status = auth
-        .get(&client, "/v1/vpn/status")
-        .send()
-        .await
-        .ok()?
-        .json::<GluetunVpnStatus>()
-        .await
-        .ok()?;
-
-    let is_vpn = vpn_status.status == "running";
+    let is_vpn = auth.vpn_running(&client).await?;
 
     let public_ip = auth
         .get(&client, "/v1/publicip/ip")
@@ -138,6 +131,8 @@ async fn try_gluetun_detection(
         peer_listener_active: listener_status.bound_port.is_some(),
         peer_listener_error: listener_status.last_error,
         vpn_port_sync_enabled,
+        vpn_killswitch_enabled: false,
+        vpn_killswitch_engaged: false,
     })
 }
 
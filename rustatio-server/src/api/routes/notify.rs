@@ -0,0 +1,49 @@
+//! Notification endpoints.
+
+use axum::{extract::State, http::StatusCode, response::Response, routing::post, Router};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::api::{
+    common::{ApiError, ApiSuccess},
+    ServerState,
+};
+use crate::services::webhooks;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotifyTestResponse {
+    /// `NOTIFY_FORMAT` the sample was sent in
+    pub format: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/notify/test",
+    tag = "notify",
+    summary = "Send a test notification",
+    description = "Posts a sample notification to WEBHOOK_URL using the configured NOTIFY_FORMAT and WEBHOOK_TEMPLATE, regardless of WEBHOOK_EVENTS, so the setup can be verified.",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Test notification delivered", body = ApiSuccess<NotifyTestResponse>),
+        (status = 400, description = "WEBHOOK_URL is not set", body = ApiError),
+        (status = 401, description = "Unauthorized", body = ApiError),
+        (status = 502, description = "The webhook endpoint failed or rejected the notification", body = ApiError)
+    )
+)]
+pub async fn test_notification(State(state): State<ServerState>) -> Response {
+    let config = state.webhooks.lock().await.config().clone();
+    if config.url.is_none() {
+        return ApiError::response(StatusCode::BAD_REQUEST, "WEBHOOK_URL is not set");
+    }
+
+    match webhooks::send_test(&config).await {
+        Ok(()) => {
+            ApiSuccess::response(NotifyTestResponse { format: config.format.as_str().to_string() })
+        }
+        Err(e) => ApiError::response(StatusCode::BAD_GATEWAY, format!("Webhook failed: {e}")),
+    }
+}
+
+pub fn router() -> Router<ServerState> {
+    Router::new().route("/notify/test", post(test_notification))
+}
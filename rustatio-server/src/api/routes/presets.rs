@@ -37,6 +37,7 @@ pub async fn list_custom_presets(State(state): State<ServerState>) -> Response {
     request_body(content = Object, description = "Custom preset"),
     responses(
         (status = 200, description = "Preset saved", body = ApiSuccess<EmptyData>),
+        (status = 400, description = "Invalid preset settings", body = ApiError),
         (status = 401, description = "Unauthorized", body = ApiError),
         (status = 500, description = "Failed to save preset", body = ApiError)
     )
@@ -47,6 +48,9 @@ pub async fn upsert_custom_preset(
     Json(mut preset): Json<CustomPreset>,
 ) -> Response {
     preset.id = id;
+    if let Err(e) = preset.settings.validate() {
+        return ApiError::response(StatusCode::BAD_REQUEST, e.to_string());
+    }
 
     match state.app.upsert_custom_preset(preset).await {
         Ok(()) => ApiSuccess::response(EmptyData {}),
@@ -0,0 +1,106 @@
+//! Aggregate stats across all instances.
+
+use axum::{
+    extract::{Query, State},
+    response::Response,
+    routing::get,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::{
+    common::{ApiError, ApiSuccess},
+    ServerState,
+};
+use crate::services::instance::{InstanceTotals, TagTotals};
+use crate::services::scrape_cache::ScrapeCacheStats;
+use crate::services::AppState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregateGroupBy {
+    Tag,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct AggregateStatsQuery {
+    /// Add subtotals per group
+    pub group_by: Option<AggregateGroupBy>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AggregateStatsResponse {
+    pub running_instances: usize,
+    /// Combined current upload rate of running instances in KB/s
+    pub total_upload_rate: f64,
+    /// Combined current download rate of running instances in KB/s
+    pub total_download_rate: f64,
+    /// Combined upload cap in KB/s, if any
+    pub upload_cap_kbps: Option<f64>,
+    /// Combined download cap in KB/s, if any
+    pub download_cap_kbps: Option<f64>,
+    /// Factor applied to every instance's upload rate to respect the cap
+    pub upload_throttle: f64,
+    /// Factor applied to every instance's download rate to respect the cap
+    pub download_throttle: f64,
+    /// True while the rate cap is scaling instances down
+    pub throttled: bool,
+    /// Scrapes answered from the shared scrape cache versus sent to trackers
+    pub scrape_cache: ScrapeCacheStats,
+    /// Totals across all instances
+    pub totals: InstanceTotals,
+    /// Totals per tag, only with `group_by=tag`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_tag: Option<Vec<TagTotals>>,
+}
+
+impl AggregateStatsResponse {
+    async fn collect(app: &AppState, group_by: Option<AggregateGroupBy>) -> Self {
+        let by_tag = group_by == Some(AggregateGroupBy::Tag);
+        let (totals, tag_totals) = app.instance_totals(by_tag).await;
+        let cap = app.rate_cap().await;
+        let throttle = app.rate_throttle();
+
+        Self {
+            running_instances: totals.running,
+            total_upload_rate: totals.upload_rate,
+            total_download_rate: totals.download_rate,
+            upload_cap_kbps: cap.upload_kbps,
+            download_cap_kbps: cap.download_kbps,
+            upload_throttle: throttle.upload_factor,
+            download_throttle: throttle.download_factor,
+            throttled: throttle.is_active(),
+            scrape_cache: app.scrape_cache().stats(),
+            totals,
+            by_tag: by_tag.then_some(tag_totals),
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/stats/aggregate",
+    tag = "stats",
+    summary = "Get aggregate stats",
+    description = "Returns totals across all instances (uploaded and downloaded bytes, current rates, instances per state and the overall ratio), the server-wide rate cap and whether it is currently throttling instances. group_by=tag adds the same totals per tag.",
+    security(("bearer_auth" = [])),
+    params(
+        ("group_by" = Option<AggregateGroupBy>, Query, description = "Add subtotals per tag")
+    ),
+    responses(
+        (status = 200, description = "Aggregate stats", body = ApiSuccess<AggregateStatsResponse>),
+        (status = 401, description = "Unauthorized", body = ApiError)
+    )
+)]
+pub async fn get_aggregate_stats(
+    State(state): State<ServerState>,
+    Query(query): Query<AggregateStatsQuery>,
+) -> Response {
+    ApiSuccess::response(AggregateStatsResponse::collect(&state.app, query.group_by).await)
+}
+
+pub fn router() -> Router<ServerState> {
+    Router::new().route("/stats/aggregate", get(get_aggregate_stats))
+}
@@ -1,25 +1,72 @@
-//! Torrent file upload endpoint.
+//! Torrent file upload and magnet link endpoints.
 
 use axum::{
     extract::{DefaultBodyLimit, Multipart, State},
     http::StatusCode,
     response::Response,
     routing::post,
-    Router,
+    Json, Router,
 };
-use rustatio_core::TorrentSummary;
-use serde::Serialize;
+use rustatio_core::{TorrentInfo, TorrentSummary};
+use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
 use crate::api::{
     common::{ApiError, ApiSuccess},
     ServerState,
 };
+use crate::services::tracker_check::TrackerCheck;
 
 #[derive(Serialize, ToSchema)]
 pub struct LoadTorrentResponse {
     #[schema(value_type = Object)]
     pub torrent: TorrentSummary,
+    /// Reachability of the primary tracker, checked for at most a couple of seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tracker: Option<TrackerCheck>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ValidateTorrentResponse {
+    #[schema(value_type = Object)]
+    pub torrent: TorrentSummary,
+    pub tracker: TrackerCheck,
+}
+
+/// Read and parse the `file` field of a torrent upload.
+async fn read_torrent_upload(multipart: &mut Multipart) -> Result<TorrentSummary, String> {
+    loop {
+        match multipart.next_field().await {
+            Ok(Some(field)) if field.name() == Some("file") => {
+                let bytes = field.bytes().await.map_err(|e| format!("Failed to read file: {e}"))?;
+                return TorrentSummary::from_bytes(&bytes)
+                    .map_err(|e| format!("Failed to parse torrent: {e}"));
+            }
+            Ok(Some(_)) => {}
+            Ok(None) => return Err("No torrent file provided".to_string()),
+            Err(e) => return Err(format!("Failed to parse upload: {e}")),
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadMagnetRequest {
+    /// `magnet:?xt=urn:btih:...` URI with at least one `tr` tracker
+    pub uri: String,
+    /// Torrent size in bytes; defaults to the magnet's `xl`, else 0 (seed-only)
+    #[serde(default)]
+    pub declared_size: Option<u64>,
+    /// Instance ID to create; generated when omitted
+    #[serde(default)]
+    pub id: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct LoadMagnetResponse {
+    pub id: String,
+    #[schema(value_type = Object)]
+    pub torrent: TorrentSummary,
 }
 
 #[utoipa::path(
@@ -27,7 +74,7 @@ pub struct LoadTorrentResponse {
     path = "/torrent/load",
     tag = "torrents",
     summary = "Load a torrent file",
-    description = "Uploads and parses a .torrent file. Returns the parsed torrent summary.",
+    description = "Uploads and parses a .torrent file. Returns the parsed torrent summary and a quick reachability check of its primary tracker.",
     security(("bearer_auth" = [])),
     request_body(content_type = "multipart/form-data", description = "Torrent file upload (field name: file)"),
     responses(
@@ -36,47 +83,98 @@ pub struct LoadTorrentResponse {
         (status = 401, description = "Unauthorized", body = ApiError)
     )
 )]
-pub async fn load_torrent(State(_state): State<ServerState>, mut multipart: Multipart) -> Response {
-    loop {
-        match multipart.next_field().await {
-            Ok(Some(field)) => {
-                if field.name() == Some("file") {
-                    match field.bytes().await {
-                        Ok(bytes) => match TorrentSummary::from_bytes(&bytes) {
-                            Ok(torrent) => {
-                                return ApiSuccess::response(LoadTorrentResponse { torrent });
-                            }
-                            Err(e) => {
-                                return ApiError::response(
-                                    StatusCode::BAD_REQUEST,
-                                    format!("Failed to parse torrent: {e}"),
-                                );
-                            }
-                        },
-                        Err(e) => {
-                            return ApiError::response(
-                                StatusCode::BAD_REQUEST,
-                                format!("Failed to read file: {e}"),
-                            );
-                        }
-                    }
-                }
-            }
-            Ok(None) => break,
-            Err(e) => {
-                return ApiError::response(
-                    StatusCode::BAD_REQUEST,
-                    format!("Failed to parse upload: {e}"),
-                );
-            }
+pub async fn load_torrent(State(state): State<ServerState>, mut multipart: Multipart) -> Response {
+    match read_torrent_upload(&mut multipart).await {
+        Ok(torrent) => {
+            let tracker = Some(state.app.check_tracker(&torrent).await);
+            ApiSuccess::response(LoadTorrentResponse { torrent, tracker })
         }
+        Err(e) => ApiError::response(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/torrent/validate",
+    tag = "torrents",
+    summary = "Check a torrent's tracker",
+    description = "Parses a .torrent file and checks its primary tracker with a scrape (or a plain GET when the announce URL has no scrape counterpart), for at most 2 seconds. Reports whether the tracker answered, whether it supports scrape and whether its URL carries a passkey. Nothing is created.",
+    security(("bearer_auth" = [])),
+    request_body(content_type = "multipart/form-data", description = "Torrent file upload (field name: file)"),
+    responses(
+        (status = 200, description = "Tracker checked", body = ApiSuccess<ValidateTorrentResponse>),
+        (status = 400, description = "Invalid torrent file", body = ApiError),
+        (status = 401, description = "Unauthorized", body = ApiError)
+    )
+)]
+pub async fn validate_torrent(
+    State(state): State<ServerState>,
+    mut multipart: Multipart,
+) -> Response {
+    match read_torrent_upload(&mut multipart).await {
+        Ok(torrent) => {
+            let tracker = state.app.check_tracker(&torrent).await;
+            ApiSuccess::response(ValidateTorrentResponse { torrent, tracker })
+        }
+        Err(e) => ApiError::response(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/torrent/magnet",
+    tag = "torrents",
+    summary = "Create an instance from a magnet link",
+    description = "Parses a magnet link (hex or base32 info hash, dn name, tr trackers) and creates an idle instance from it. Magnets carry no size, so declaredSize (or the magnet's xl) sets it; without either the instance seeds only with left=0.",
+    security(("bearer_auth" = [])),
+    request_body = LoadMagnetRequest,
+    responses(
+        (status = 200, description = "Instance created", body = ApiSuccess<LoadMagnetResponse>),
+        (status = 400, description = "Invalid magnet link or duplicate torrent", body = ApiError),
+        (status = 401, description = "Unauthorized", body = ApiError),
+        (status = 500, description = "Failed to create instance", body = ApiError)
+    )
+)]
+pub async fn load_magnet(
+    State(state): State<ServerState>,
+    Json(request): Json<LoadMagnetRequest>,
+) -> Response {
+    let torrent = match TorrentInfo::from_magnet(&request.uri, request.declared_size) {
+        Ok(torrent) => torrent,
+        Err(e) => {
+            return ApiError::response(
+                StatusCode::BAD_REQUEST,
+                format!("Failed to parse magnet link: {e}"),
+            );
+        }
+    };
+
+    let id = request
+        .id
+        .filter(|id| !id.trim().is_empty())
+        .unwrap_or_else(|| state.app.next_instance_id());
+    if let Some(existing_id) = state.app.duplicate_instance_id(&id, &torrent.info_hash).await {
+        return ApiError::response(
+            StatusCode::BAD_REQUEST,
+            format!("Duplicate torrent skipped: already imported as instance {existing_id}"),
+        );
+    }
+
+    let summary = torrent.summary();
+    if let Err(e) = state.app.create_idle_instance(&id, torrent, None).await {
+        return ApiError::response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to create instance: {e}"),
+        );
     }
 
-    ApiError::response(StatusCode::BAD_REQUEST, "No torrent file provided")
+    ApiSuccess::response(LoadMagnetResponse { id, torrent: summary })
 }
 
 pub fn router() -> Router<ServerState> {
     Router::new()
         .route("/torrent/load", post(load_torrent))
+        .route("/torrent/validate", post(validate_torrent))
+        .route("/torrent/magnet", post(load_magnet))
         .layer(DefaultBodyLimit::max(50 * 1024 * 1024))
 }
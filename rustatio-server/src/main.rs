@@ -17,9 +17,13 @@ use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::api::{ApiDoc, ServerState};
+use crate::services::persistence::check_data_dir;
+use crate::services::state::tracker_proxy_from_env;
 use crate::services::{
-    AppState, Scheduler, ServerPeerLookup, VpnPortSync, VpnPortSyncConfig, WatchConfig,
-    WatchDisabledReason, WatchService,
+    AppState, NetworkRecovery, NetworkRecoveryConfig, PortMappingConfig, PortMappingService,
+    Scheduler, ServerPeerLookup, VpnKillswitch, VpnKillswitchConfig, VpnPortSync,
+    VpnPortSyncConfig, WatchConfig, WatchDisabledReason, WatchService, WebhookConfig,
+    WebhookService,
 };
 use crate::util::BroadcastLayer;
 use rustatio_core::PeerListenerService;
@@ -37,11 +41,29 @@ async fn main() {
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| default_filter.into()),
         )
-        .with(BroadcastLayer::new(state.log_sender.clone()))
+        .with(BroadcastLayer::new(state.log_sender.clone(), Arc::clone(&state.instance_logs)))
         .with(tracing_subscriber::fmt::layer());
 
     tracing::subscriber::set_global_default(subscriber).expect("Failed to set subscriber");
 
+    if let Err(e) = api::middleware::reload_auth_token() {
+        tracing::error!("{}", e);
+        std::process::exit(1);
+    }
+
+    match tracker_proxy_from_env() {
+        Ok(Some(_)) => tracing::info!("Routing tracker requests through TRACKER_PROXY"),
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!("{}", e);
+            std::process::exit(1);
+        }
+    }
+
+    if let Err(e) = check_data_dir(&data_dir) {
+        tracing::error!("{}", e);
+    }
+
     match state.load_saved_state().await {
         Ok(count) => {
             if count > 0 {
@@ -82,12 +104,23 @@ async fn main() {
     vpn_port_sync.start(state.clone(), vpn_port_sync_config);
     let vpn_port_sync = Arc::new(tokio::sync::Mutex::new(vpn_port_sync));
 
-    let (mut watch_config, disabled_reason) = WatchConfig::from_env();
+    let mut port_mapping = PortMappingService::new();
+    port_mapping.start(state.clone(), PortMappingConfig::from_env());
+    let port_mapping = Arc::new(tokio::sync::Mutex::new(port_mapping));
 
-    if let Some(settings) = state.get_watch_settings_optional().await {
-        watch_config.max_depth = settings.max_depth;
-        watch_config.auto_start = settings.auto_start;
-    }
+    let mut network_recovery = NetworkRecovery::new();
+    network_recovery.start(state.clone(), NetworkRecoveryConfig::from_env());
+    let network_recovery = Arc::new(tokio::sync::Mutex::new(network_recovery));
+
+    let mut vpn_killswitch = VpnKillswitch::new();
+    vpn_killswitch.start(state.clone(), VpnKillswitchConfig::from_env());
+    let vpn_killswitch = Arc::new(tokio::sync::Mutex::new(vpn_killswitch));
+
+    let mut webhooks = WebhookService::new();
+    webhooks.start(state.clone(), WebhookConfig::from_env());
+    let webhooks = Arc::new(tokio::sync::Mutex::new(webhooks));
+
+    let (watch_config, disabled_reason) = WatchConfig::resolve(&state).await;
 
     if let Some(reason) = &disabled_reason {
         match reason {
@@ -111,7 +144,11 @@ async fn main() {
     }
     let watch_service = Arc::new(RwLock::new(watch_service));
 
-    let server_state = ServerState { app: state.clone(), watch: Arc::clone(&watch_service) };
+    let server_state = ServerState {
+        app: state.clone(),
+        watch: Arc::clone(&watch_service),
+        webhooks: Arc::clone(&webhooks),
+    };
 
     let port: u16 = std::env::var("PORT").ok().and_then(|p| p.parse().ok()).unwrap_or(8080);
     let cors = CorsLayer::new().allow_origin(Any).allow_methods(Any).allow_headers(Any);
@@ -137,11 +174,18 @@ async fn main() {
     tracing::info!("Web UI available at http://localhost:{}", port);
     tracing::info!("API documentation at http://localhost:{}/docs", port);
     tracing::info!("Data directory: {}", data_dir);
+    if state.dry_run_enabled() {
+        tracing::warn!("DRY_RUN is set: instances simulate stats without contacting trackers");
+    }
 
     if api::middleware::is_auth_enabled() {
-        tracing::info!("Authentication enabled (AUTH_TOKEN is set)");
+        let labels: Vec<String> =
+            api::middleware::get_auth_tokens().into_iter().map(|token| token.label).collect();
+        tracing::info!("Authentication enabled with token(s): {}", labels.join(", "));
     } else {
-        tracing::warn!("Authentication disabled - API is open to all. Set AUTH_TOKEN to enable.");
+        tracing::warn!(
+            "Authentication disabled - API is open to all. Set AUTH_TOKEN or AUTH_TOKENS to enable."
+        );
     }
 
     let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
@@ -149,6 +193,10 @@ async fn main() {
     let watch_for_shutdown = Arc::clone(&watch_service);
     let scheduler_for_shutdown = Arc::clone(&scheduler);
     let vpn_port_sync_for_shutdown = Arc::clone(&vpn_port_sync);
+    let port_mapping_for_shutdown = Arc::clone(&port_mapping);
+    let network_recovery_for_shutdown = Arc::clone(&network_recovery);
+    let vpn_killswitch_for_shutdown = Arc::clone(&vpn_killswitch);
+    let webhooks_for_shutdown = Arc::clone(&webhooks);
     let peer_listener_for_shutdown = Arc::clone(&peer_listener);
 
     tokio::spawn(async move {
@@ -160,6 +208,18 @@ async fn main() {
         tracing::info!("Stopping VPN port sync...");
         vpn_port_sync_for_shutdown.lock().await.shutdown().await;
 
+        tracing::info!("Stopping port mapping...");
+        port_mapping_for_shutdown.lock().await.shutdown().await;
+
+        tracing::info!("Stopping network recovery watcher...");
+        network_recovery_for_shutdown.lock().await.shutdown().await;
+
+        tracing::info!("Stopping VPN kill-switch...");
+        vpn_killswitch_for_shutdown.lock().await.shutdown().await;
+
+        tracing::info!("Stopping webhook notifications...");
+        webhooks_for_shutdown.lock().await.shutdown().await;
+
         tracing::info!("Stopping peer listener...");
         peer_listener_for_shutdown.lock().await.shutdown().await;
 
@@ -170,17 +230,21 @@ async fn main() {
         state_for_shutdown.shutdown_all().await;
 
         tracing::info!("Saving state before shutdown...");
-        if let Err(e) = state_for_shutdown.save_state().await {
-            tracing::error!("Failed to save state on shutdown: {}", e);
-        } else {
-            tracing::info!("State saved successfully");
+        match state_for_shutdown.save_state_verified().await {
+            Ok(check) => tracing::info!(
+                "State saved and verified: {} ({} bytes, {} instance(s))",
+                check.path,
+                check.bytes,
+                check.instances
+            ),
+            Err(e) => tracing::error!("State was NOT saved on shutdown: {}", e),
         }
 
         let _ = shutdown_tx.send(());
     });
 
     let listener = tokio::net::TcpListener::bind(addr).await.expect("failed to bind TCP listener");
-    axum::serve(listener, app)
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .with_graceful_shutdown(async {
             let _ = shutdown_rx.await;
         })
@@ -0,0 +1,119 @@
+//! Configuration bundles for moving instances and settings between installs.
+//!
+//! A bundle holds every instance in the form the state file stores it (torrent
+//! metadata, config, tags, cumulative and runtime stats) plus the default config and
+//! custom presets. Instances created from a `.torrent` file also carry that file,
+//! hex-encoded, and are rebuilt from it on import. Magnet instances only have the
+//! metadata, which is all they need to announce again.
+
+use super::persistence::{CustomPreset, PersistedInstance};
+use rustatio_core::{FakerConfig, TorrentSummary};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Format version written by this server; newer bundles are refused.
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConfigBundle {
+    /// Bundle format version
+    pub version: u32,
+    /// Rustatio version that wrote the bundle
+    #[serde(default)]
+    pub app_version: String,
+    /// Unix timestamp of the export
+    #[serde(default)]
+    pub exported_at: u64,
+    #[serde(default)]
+    #[schema(value_type = Vec<Object>)]
+    pub instances: Vec<BundledInstance>,
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub default_config: Option<FakerConfig>,
+    #[serde(default)]
+    pub custom_presets: Vec<CustomPreset>,
+}
+
+/// An instance as the state file stores it, plus its original `.torrent`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundledInstance {
+    #[serde(flatten)]
+    pub instance: PersistedInstance,
+    /// Hex-encoded bencoded `.torrent`, when the instance was created from one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub torrent_file: Option<String>,
+}
+
+impl BundledInstance {
+    pub fn display_name(&self) -> &str {
+        self.instance.label.as_deref().unwrap_or(&self.instance.torrent.name)
+    }
+
+    /// The instance with its torrent metadata re-parsed from the embedded `.torrent`,
+    /// which must match the info hash it was exported under, and that file's bytes.
+    pub fn into_parts(self) -> Result<(PersistedInstance, Option<Vec<u8>>), String> {
+        let mut instance = self.instance;
+        let Some(encoded) = self.torrent_file else {
+            return Ok((instance, None));
+        };
+        let bytes = hex::decode(encoded).map_err(|e| format!("Invalid torrent file: {e}"))?;
+        let summary =
+            TorrentSummary::from_bytes(&bytes).map_err(|e| format!("Invalid torrent file: {e}"))?;
+        if summary.info_hash != instance.torrent.info_hash {
+            return Err("Torrent file does not match the instance's info hash".to_string());
+        }
+        instance.torrent = summary;
+        Ok((instance, Some(bytes)))
+    }
+}
+
+impl ConfigBundle {
+    pub fn check_version(&self) -> Result<(), String> {
+        if self.version > BUNDLE_VERSION {
+            return Err(format!(
+                "Bundle version {} is newer than this server supports ({}), upgrade Rustatio first",
+                self.version, BUNDLE_VERSION
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// What happens to bundle entries that already exist on the server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    /// Keep what the server has: instances with a known info hash are skipped, the
+    /// default config is only set when none exists and existing presets are kept
+    #[default]
+    Merge,
+    /// The bundle wins: matching instances, the default config and presets are overwritten
+    Replace,
+}
+
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct ImportReport {
+    /// IDs of instances created from the bundle
+    pub imported: Vec<String>,
+    /// IDs of existing instances overwritten by the bundle
+    pub replaced: Vec<String>,
+    /// Names of bundle instances skipped because their torrent already exists
+    pub skipped: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newer_bundles_are_refused() {
+        let bundle: ConfigBundle = serde_json::from_str(r#"{"version": 1}"#)
+            .unwrap_or_else(|e| panic!("minimal bundle should parse: {e}"));
+        assert!(bundle.check_version().is_ok());
+        assert!(bundle.instances.is_empty());
+
+        let newer = ConfigBundle { version: BUNDLE_VERSION + 1, ..bundle };
+        assert!(newer.check_version().is_err());
+    }
+}
@@ -0,0 +1,310 @@
+//! Cron schedules that start and stop instances.
+//!
+//! Expressions use the classic five fields `minute hour day-of-month month
+//! day-of-week` with `*`, lists, ranges and `/step`. Day-of-week takes 0-7, where
+//! both 0 and 7 are Sunday. As in cron, when both day fields are restricted a day
+//! matches if either one does. Expressions are evaluated in UTC shifted by the
+//! schedule's `utc_offset_minutes`; when start and stop match the same minute,
+//! stop wins.
+
+use super::lifecycle::InstanceLifecycle;
+use super::state::AppState;
+use rustatio_core::schedule::civil_from_days;
+use rustatio_core::FakerState;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct InstanceSchedule {
+    /// Cron expression at which the instance is started
+    #[serde(default)]
+    pub start_cron: Option<String>,
+    /// Cron expression at which the instance is stopped
+    #[serde(default)]
+    pub stop_cron: Option<String>,
+    /// Offset from UTC, in minutes, the expressions are evaluated in
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+}
+
+impl InstanceSchedule {
+    /// Trim the expressions and drop empty ones. Returns `None` when nothing is left.
+    pub fn normalized(mut self) -> Option<Self> {
+        let trim =
+            |cron: Option<String>| cron.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+        self.start_cron = trim(self.start_cron);
+        self.stop_cron = trim(self.stop_cron);
+        (self.start_cron.is_some() || self.stop_cron.is_some()).then_some(self)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for cron in [&self.start_cron, &self.stop_cron].into_iter().flatten() {
+            CronExpr::parse(cron)?;
+        }
+        if self.utc_offset_minutes.abs() >= 24 * 60 {
+            return Err(format!(
+                "utc_offset_minutes must be within a day (got {})",
+                self.utc_offset_minutes
+            ));
+        }
+        Ok(())
+    }
+
+    fn action_at(&self, unix_minute: i64) -> Option<ScheduledAction> {
+        let time = CronTime::from_unix_minute(unix_minute + i64::from(self.utc_offset_minutes));
+        let matches = |cron: Option<&str>| {
+            cron.and_then(|cron| CronExpr::parse(cron).ok()).is_some_and(|expr| expr.matches(time))
+        };
+        if matches(self.stop_cron.as_deref()) {
+            Some(ScheduledAction::Stop)
+        } else if matches(self.start_cron.as_deref()) {
+            Some(ScheduledAction::Start)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScheduledAction {
+    Start,
+    Stop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct CronTime {
+    minute: u32,
+    hour: u32,
+    day: u32,
+    month: u32,
+    weekday: u32,
+}
+
+impl CronTime {
+    const fn from_unix_minute(unix_minute: i64) -> Self {
+        let days = unix_minute.div_euclid(1440);
+        let minute_of_day = unix_minute.rem_euclid(1440) as u32;
+        let (_, month, day) = civil_from_days(days);
+        Self {
+            minute: minute_of_day % 60,
+            hour: minute_of_day / 60,
+            day,
+            month,
+            // 1970-01-01 was a Thursday
+            weekday: (days + 4).rem_euclid(7) as u32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CronExpr {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl CronExpr {
+    fn parse(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("Cron expression '{expr}' must have 5 fields"));
+        };
+        let field = |spec: &str, min: u32, max: u32, name: &str| {
+            parse_field(spec, min, max)
+                .map_err(|e| format!("Invalid {name} field '{spec}' in '{expr}': {e}"))
+        };
+
+        let mut weekdays = field(weekday, 0, 7, "day-of-week")?;
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: field(minute, 0, 59, "minute")?,
+            hours: field(hour, 0, 23, "hour")?,
+            days: field(day, 1, 31, "day-of-month")?,
+            months: field(month, 1, 12, "month")?,
+            weekdays,
+            days_restricted: !day.starts_with('*'),
+            weekdays_restricted: !weekday.starts_with('*'),
+        })
+    }
+
+    const fn matches(&self, time: CronTime) -> bool {
+        let day = self.days & (1 << time.day) != 0;
+        let weekday = self.weekdays & (1 << time.weekday) != 0;
+        let day_matches = if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        };
+        self.minutes & (1 << time.minute) != 0
+            && self.hours & (1 << time.hour) != 0
+            && self.months & (1 << time.month) != 0
+            && day_matches
+    }
+}
+
+/// Bitmask of the values a field allows, bit `n` standing for value `n`.
+fn parse_field(spec: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+    for part in spec.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("bad step '{step}'"))?;
+                if step == 0 {
+                    return Err("step must be at least 1".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let value = |v: &str| -> Result<u32, String> {
+            let v: u32 = v.parse().map_err(|_| format!("bad value '{v}'"))?;
+            if (min..=max).contains(&v) {
+                Ok(v)
+            } else {
+                Err(format!("{v} is outside {min}-{max}"))
+            }
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // `5/15` means every 15 starting at 5
+                None if step > 1 => (value(range)?, max),
+                None => {
+                    let v = value(range)?;
+                    (v, v)
+                }
+            },
+        };
+        if start > end {
+            return Err(format!("range {start}-{end} is reversed"));
+        }
+        for v in (start..=end).step_by(step as usize) {
+            mask |= 1 << v;
+        }
+    }
+    Ok(mask)
+}
+
+/// Start or stop every enabled instance whose schedule matches `unix_minute`.
+pub async fn run_schedules(state: &AppState, unix_minute: i64) {
+    let due: Vec<(String, ScheduledAction, FakerState)> = {
+        let instances = state.instances.read().await;
+        instances
+            .iter()
+            .filter(|(_, instance)| instance.enabled)
+            .filter_map(|(id, instance)| {
+                let action = instance.schedule.as_ref()?.action_at(unix_minute)?;
+                Some((id.clone(), action, instance.faker.stats_snapshot().state))
+            })
+            .collect()
+    };
+
+    for (id, action, faker_state) in due {
+        match action {
+            ScheduledAction::Start
+                if matches!(faker_state, FakerState::Idle | FakerState::Stopped) =>
+            {
+                match state.start_instance(&id).await {
+                    Ok(()) => tracing::info!("Schedule started instance {}", id),
+                    Err(e) => tracing::warn!("Schedule failed to start instance {}: {}", id, e),
+                }
+            }
+            ScheduledAction::Stop
+                if matches!(
+                    faker_state,
+                    FakerState::Starting | FakerState::Running | FakerState::Paused
+                ) =>
+            {
+                if let Some(reason) = state.hnr_hold(&id).await {
+                    tracing::info!("Schedule held instance {} for HnR: {}", id, reason);
+                    continue;
+                }
+                match state.stop_instance(&id, false).await {
+                    Ok(_) => tracing::info!("Schedule stopped instance {}", id),
+                    Err(e) => tracing::warn!("Schedule failed to stop instance {}: {}", id, e),
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2025-01-06T08:30Z, a Monday
+    const MONDAY_0830: i64 = 1_736_152_200 / 60;
+
+    fn parse(expr: &str) -> CronExpr {
+        CronExpr::parse(expr).unwrap_or_else(|e| panic!("{expr} should parse: {e}"))
+    }
+
+    #[test]
+    fn cron_time_splits_unix_minutes() {
+        let time = CronTime::from_unix_minute(MONDAY_0830);
+        assert_eq!(time, CronTime { minute: 30, hour: 8, day: 6, month: 1, weekday: 1 });
+
+        let leap_day = CronTime::from_unix_minute(1_709_164_800 / 60);
+        assert_eq!((leap_day.month, leap_day.day, leap_day.weekday), (2, 29, 4));
+    }
+
+    #[test]
+    fn expressions_support_lists_ranges_and_steps() {
+        let time = CronTime::from_unix_minute(MONDAY_0830);
+
+        assert!(parse("30 8 * * *").matches(time));
+        assert!(parse("*/15 6-10 * * 1-5").matches(time));
+        assert!(parse("0,30 8 * 1 7,1").matches(time));
+        assert!(!parse("30 8 * * 0,6").matches(time));
+        assert!(!parse("5/10 * * * *").matches(time));
+        // Either restricted day field is enough
+        assert!(parse("30 8 15 * 1").matches(time));
+        assert!(!parse("30 8 15 * *").matches(time));
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        let invalid =
+            ["* * * *", "60 * * * *", "* * 0 * *", "*/0 * * * *", "10-5 * * * *", "x * * * *"];
+        for expr in invalid {
+            assert!(CronExpr::parse(expr).is_err(), "{expr} should be rejected");
+        }
+    }
+
+    #[test]
+    fn schedule_applies_offset_and_stop_wins() {
+        let schedule = InstanceSchedule {
+            start_cron: Some("30 10 * * *".to_string()),
+            stop_cron: None,
+            utc_offset_minutes: 120,
+        };
+        assert_eq!(schedule.action_at(MONDAY_0830), Some(ScheduledAction::Start));
+
+        let both = InstanceSchedule {
+            start_cron: Some("* * * * *".to_string()),
+            stop_cron: Some("30 8 * * *".to_string()),
+            utc_offset_minutes: 0,
+        };
+        assert_eq!(both.action_at(MONDAY_0830), Some(ScheduledAction::Stop));
+        assert_eq!(both.action_at(MONDAY_0830 + 1), Some(ScheduledAction::Start));
+    }
+
+    #[test]
+    fn normalized_drops_blank_expressions() {
+        let blank = InstanceSchedule {
+            start_cron: Some("  ".to_string()),
+            stop_cron: None,
+            utc_offset_minutes: 0,
+        };
+        assert_eq!(blank.normalized(), None);
+    }
+}
@@ -0,0 +1,175 @@
+//! Announce de-correlation for fleets of instances sharing one egress IP.
+//!
+//! Every instance on a server announces from the same public IP, so a tracker can
+//! see dozens of peer IDs moving in lockstep. When enabled, the scheduler spreads
+//! pending announces across a window and each instance gets a stable rate scale.
+
+use super::runtime_config::env_var;
+use super::state::AppState;
+use rustatio_core::{FakerState, RatioFakerHandle};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const DEFAULT_STAGGER_WINDOW_SECS: u64 = 300;
+const DEFAULT_RATE_SPREAD_PERCENT: f64 = 15.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecorrelationConfig {
+    pub enabled: bool,
+    pub stagger_window: Duration,
+    pub rate_spread_percent: f64,
+    pub max_instances_per_ip: Option<usize>,
+}
+
+impl Default for DecorrelationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            stagger_window: Duration::from_secs(DEFAULT_STAGGER_WINDOW_SECS),
+            rate_spread_percent: DEFAULT_RATE_SPREAD_PERCENT,
+            max_instances_per_ip: None,
+        }
+    }
+}
+
+impl DecorrelationConfig {
+    pub fn from_env() -> Self {
+        let enabled = env_var("ANNOUNCE_DECORRELATION").is_some_and(|v| {
+            matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")
+        });
+
+        let stagger_window = env_var("ANNOUNCE_STAGGER_WINDOW_SECONDS")
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_STAGGER_WINDOW_SECS);
+
+        let rate_spread_percent = env_var("ANNOUNCE_RATE_SPREAD_PERCENT")
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|pct| pct.is_finite())
+            .map_or(DEFAULT_RATE_SPREAD_PERCENT, |pct| pct.clamp(0.0, 50.0));
+
+        let max_instances_per_ip = env_var("MAX_INSTANCES_PER_IP")
+            .and_then(|v| v.parse::<usize>().ok())
+            .filter(|max| *max > 0);
+
+        Self {
+            enabled,
+            stagger_window: Duration::from_secs(stagger_window),
+            rate_spread_percent,
+            max_instances_per_ip,
+        }
+    }
+
+    /// Stable per-instance rate multiplier in `[1 - spread, 1 + spread]`.
+    ///
+    /// Derived from the instance ID (FNV-1a) so it survives restarts.
+    pub fn rate_scale_for(&self, id: &str) -> f64 {
+        if !self.enabled || self.rate_spread_percent <= 0.0 {
+            return 1.0;
+        }
+
+        let hash = id.bytes().fold(0xcbf2_9ce4_8422_2325u64, |acc, byte| {
+            (acc ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+        let unit = (hash % 10_001) as f64 / 10_000.0;
+        let spread = self.rate_spread_percent / 100.0;
+        unit.mul_add(spread * 2.0, 1.0 - spread)
+    }
+}
+
+/// Given pending announce times, return the ones that must be pushed back so
+/// consecutive announces are at least `min_gap` apart. Announces only move later.
+fn plan_stagger(mut pending: Vec<(String, Instant)>, min_gap: Duration) -> Vec<(String, Instant)> {
+    pending.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut deferred = Vec::new();
+    let mut previous: Option<Instant> = None;
+    for (id, at) in pending {
+        let slot = previous.map_or(at, |prev| at.max(prev + min_gap));
+        if slot > at {
+            deferred.push((id, slot));
+        }
+        previous = Some(slot);
+    }
+    deferred
+}
+
+/// Spread pending announces of running instances across the stagger window.
+pub async fn stagger_announces(state: &AppState, config: &DecorrelationConfig) {
+    let items: Vec<(String, Arc<RatioFakerHandle>)> = {
+        let guard = state.instances.read().await;
+        guard
+            .iter()
+            .filter(|(_, inst)| inst.enabled)
+            .map(|(id, inst)| (id.clone(), Arc::clone(&inst.faker)))
+            .collect()
+    };
+
+    let pending: Vec<(String, Instant)> = items
+        .iter()
+        .filter_map(|(id, faker)| {
+            let stats = faker.stats_snapshot();
+            if !matches!(stats.state, FakerState::Running) {
+                return None;
+            }
+            stats.next_announce.map(|at| (id.clone(), at))
+        })
+        .collect();
+
+    if pending.len() < 2 {
+        return;
+    }
+
+    let min_gap = config.stagger_window / pending.len() as u32;
+    for (id, at) in plan_stagger(pending, min_gap) {
+        if let Some((_, faker)) = items.iter().find(|(item_id, _)| *item_id == id) {
+            faker.defer_next_announce(at).await;
+            tracing::debug!(
+                "De-correlation: deferred announce for instance {} by {}s",
+                id,
+                at.saturating_duration_since(Instant::now()).as_secs()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_stagger_spaces_clustered_announces() {
+        let now = Instant::now();
+        let pending = vec![
+            ("a".to_string(), now),
+            ("b".to_string(), now + Duration::from_secs(1)),
+            ("c".to_string(), now + Duration::from_secs(200)),
+        ];
+
+        let deferred = plan_stagger(pending, Duration::from_mins(1));
+
+        assert_eq!(deferred, vec![("b".to_string(), now + Duration::from_mins(1))]);
+    }
+
+    #[test]
+    fn plan_stagger_leaves_spread_announces_alone() {
+        let now = Instant::now();
+        let pending = vec![("a".to_string(), now), ("b".to_string(), now + Duration::from_mins(2))];
+
+        assert!(plan_stagger(pending, Duration::from_mins(1)).is_empty());
+    }
+
+    #[test]
+    fn rate_scale_is_stable_and_within_spread() {
+        let config = DecorrelationConfig { enabled: true, ..DecorrelationConfig::default() };
+
+        let first = config.rate_scale_for("instance-1");
+        assert_eq!(first, config.rate_scale_for("instance-1"));
+        for id in ["a", "b", "instance-2", "xyz123"] {
+            let scale = config.rate_scale_for(id);
+            assert!((0.849..=1.151).contains(&scale), "scale {scale} out of range");
+        }
+
+        assert_eq!(DecorrelationConfig::default().rate_scale_for("instance-1"), 1.0);
+    }
+}
@@ -1,4 +1,7 @@
+use rustatio_core::logger::InstanceLogContext;
+use rustatio_core::FakerStats;
 use serde::Serialize;
+use std::sync::Arc;
 use tokio::sync::broadcast;
 use utoipa::ToSchema;
 
@@ -7,6 +10,10 @@ pub struct LogEvent {
     pub timestamp: u64,
     pub level: String,
     pub message: String,
+    /// Instance the line was written for, if any
+    pub instance_id: Option<String>,
+    /// Hex info hash of that instance's torrent
+    pub info_hash: Option<String>,
 }
 
 impl LogEvent {
@@ -15,7 +22,14 @@ impl LogEvent {
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
             .as_millis() as u64;
-        Self { timestamp, level: level.to_string(), message }
+        Self { timestamp, level: level.to_string(), message, instance_id: None, info_hash: None }
+    }
+
+    #[must_use]
+    pub fn for_instance(mut self, context: InstanceLogContext) -> Self {
+        self.instance_id = Some(context.id);
+        self.info_hash = context.info_hash;
+        self
     }
 }
 
@@ -24,10 +38,37 @@ impl LogEvent {
 pub enum InstanceEvent {
     Created { id: String, torrent_name: String, info_hash: String, auto_started: bool },
     Deleted { id: String },
+    Paused { id: String, reason: String },
+    Resumed { id: String, reason: String },
+    StopConditionMet { id: String, condition: String },
+    TrackerFailing { id: String, attempts: u32, error: String },
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct InstanceStats {
+    pub id: String,
+    pub stats: FakerStats,
+}
+
+/// Stats of active instances, published by the scheduler while anyone listens.
+#[derive(Clone, Debug, Serialize)]
+pub struct StatsEvent {
+    pub timestamp: u64,
+    pub instances: Vec<InstanceStats>,
+}
+
+impl StatsEvent {
+    /// Copy of the event with the history vectors of every instance dropped.
+    pub fn without_history(&self) -> Self {
+        let mut event = self.clone();
+        event.instances.iter_mut().for_each(|instance| instance.stats.clear_history());
+        event
+    }
 }
 
 pub trait EventBroadcaster {
     fn subscribe_logs(&self) -> broadcast::Receiver<LogEvent>;
     fn subscribe_instance_events(&self) -> broadcast::Receiver<InstanceEvent>;
+    fn subscribe_stats(&self) -> broadcast::Receiver<Arc<StatsEvent>>;
     fn emit_instance_event(&self, event: InstanceEvent);
 }
@@ -1,9 +1,15 @@
 use reqwest::header::{HeaderMap, HeaderValue};
+use serde::Deserialize;
 
 const GLUETUN_BASE_URL: &str = "http://localhost:8000";
 const GLUETUN_API_KEY_ENV: &str = "GLUETUN_CONTROL_SERVER_API_KEY";
 const GLUETUN_API_KEY_HEADER: &str = "X-API-Key";
 
+#[derive(Deserialize)]
+struct GluetunVpnStatus {
+    status: String,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct GluetunAuth {
     api_key: Option<String>,
@@ -31,6 +37,14 @@ impl GluetunAuth {
 
         client.get(url).headers(headers)
     }
+
+    /// Whether Gluetun reports the VPN as running, or `None` when Gluetun can't be
+    /// reached or answers with something unexpected.
+    pub async fn vpn_running(&self, client: &reqwest::Client) -> Option<bool> {
+        let response = self.get(client, "/v1/vpn/status").send().await.ok()?;
+        let status = response.json::<GluetunVpnStatus>().await.ok()?;
+        Some(status.status == "running")
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,143 @@
+//! Progress tracking for grid torrent imports.
+//!
+//! Large multipart imports can be processed in the background; each import is
+//! tracked as a job so clients can poll per-file results while it runs.
+
+use super::persistence::now_timestamp;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Finished jobs kept around for status queries.
+const MAX_FINISHED_JOBS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportJobState {
+    Running,
+    Completed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedInstance {
+    pub id: String,
+    pub name: String,
+    pub info_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportJob {
+    pub id: String,
+    pub state: ImportJobState,
+    /// Torrents accepted for import (duplicates and unreadable files excluded)
+    pub total: usize,
+    /// Torrents created or rejected so far
+    pub processed: usize,
+    pub imported: Vec<ImportedInstance>,
+    pub duplicates: Vec<String>,
+    pub errors: Vec<String>,
+    pub created_at: u64,
+    pub finished_at: Option<u64>,
+}
+
+#[derive(Clone, Default)]
+pub struct ImportJobs {
+    jobs: Arc<RwLock<HashMap<String, ImportJob>>>,
+}
+
+impl ImportJobs {
+    /// Register a running job seeded with the results of parsing the upload.
+    pub async fn create(
+        &self,
+        total: usize,
+        duplicates: Vec<String>,
+        errors: Vec<String>,
+    ) -> String {
+        let id = nanoid::nanoid!(10);
+        let job = ImportJob {
+            id: id.clone(),
+            state: ImportJobState::Running,
+            total,
+            processed: 0,
+            imported: Vec::new(),
+            duplicates,
+            errors,
+            created_at: now_timestamp(),
+            finished_at: None,
+        };
+
+        let mut jobs = self.jobs.write().await;
+        prune_finished(&mut jobs);
+        jobs.insert(id.clone(), job);
+        id
+    }
+
+    pub async fn get(&self, id: &str) -> Option<ImportJob> {
+        self.jobs.read().await.get(id).cloned()
+    }
+
+    pub async fn update(&self, id: &str, apply: impl FnOnce(&mut ImportJob) + Send) {
+        if let Some(job) = self.jobs.write().await.get_mut(id) {
+            apply(job);
+        }
+    }
+
+    pub async fn finish(&self, id: &str) {
+        self.update(id, |job| {
+            job.state = ImportJobState::Completed;
+            job.finished_at = Some(now_timestamp());
+        })
+        .await;
+    }
+}
+
+fn prune_finished(jobs: &mut HashMap<String, ImportJob>) {
+    let mut finished: Vec<(u64, String)> =
+        jobs.values().filter_map(|job| job.finished_at.map(|at| (at, job.id.clone()))).collect();
+    if finished.len() < MAX_FINISHED_JOBS {
+        return;
+    }
+
+    finished.sort();
+    let excess = finished.len() + 1 - MAX_FINISHED_JOBS;
+    for (_, id) in finished.into_iter().take(excess) {
+        jobs.remove(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn job_tracks_progress_until_finished() {
+        let jobs = ImportJobs::default();
+        let id = jobs.create(2, vec!["a.torrent: already imported".to_string()], Vec::new()).await;
+
+        jobs.update(&id, |job| job.processed += 1).await;
+        let job = jobs.get(&id).await;
+        assert!(job.as_ref().is_some_and(|job| job.state == ImportJobState::Running));
+        assert_eq!(job.map(|job| (job.processed, job.duplicates.len())), Some((1, 1)));
+
+        jobs.finish(&id).await;
+        let job = jobs.get(&id).await;
+        assert!(job.as_ref().is_some_and(|job| job.state == ImportJobState::Completed));
+        assert!(job.is_some_and(|job| job.finished_at.is_some()));
+        assert!(jobs.get("missing").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn finished_jobs_are_pruned() {
+        let jobs = ImportJobs::default();
+        for _ in 0..MAX_FINISHED_JOBS + 5 {
+            let id = jobs.create(0, Vec::new(), Vec::new()).await;
+            jobs.finish(&id).await;
+        }
+        let running = jobs.create(1, Vec::new(), Vec::new()).await;
+
+        let guard = jobs.jobs.read().await;
+        assert!(guard.len() <= MAX_FINISHED_JOBS);
+        assert!(guard.contains_key(&running));
+    }
+}
@@ -1,9 +1,13 @@
+use super::cron::InstanceSchedule;
 use super::persistence::InstanceSource;
 use async_trait::async_trait;
 use rustatio_core::{
-    FakerConfig, FakerStats, PeerCatalog, PeerLookup, RatioFakerHandle, TorrentInfo, TorrentSummary,
+    primary_tracker_host, FakerConfig, FakerState, FakerStats, InstanceSummary, PeerCatalog,
+    PeerLookup, RatioFakerHandle, TorrentInfo, TorrentSummary,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use utoipa::ToSchema;
 
@@ -11,6 +15,8 @@ pub struct FakerInstance {
     pub faker: Arc<RatioFakerHandle>,
     pub torrent: Arc<TorrentInfo>,
     pub summary: Arc<TorrentSummary>,
+    /// Original `.torrent` file, when the instance was created from one
+    pub torrent_file: Option<Arc<[u8]>>,
     pub config: FakerConfig,
     pub torrent_info_hash: [u8; 20],
     pub cumulative_uploaded: u64,
@@ -18,6 +24,52 @@ pub struct FakerInstance {
     pub created_at: u64,
     pub source: InstanceSource,
     pub tags: Vec<String>,
+    pub enabled: bool,
+    pub schedule: Option<InstanceSchedule>,
+    pub label: Option<String>,
+}
+
+impl FakerInstance {
+    /// The label when one is set, otherwise the torrent name.
+    pub fn display_name(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.summary.name)
+    }
+
+    /// Row shown for this instance in the grid.
+    pub fn grid_summary(&self, id: &str) -> InstanceSummary {
+        let stats = self.faker.stats_snapshot();
+        let source = match self.source {
+            InstanceSource::Manual => "manual",
+            InstanceSource::WatchFolder => "watch_folder",
+        };
+
+        InstanceSummary {
+            id: id.to_string(),
+            name: self.display_name().to_string(),
+            label: self.label.clone(),
+            info_hash: hex::encode(self.torrent_info_hash),
+            primary_tracker_host: primary_tracker_host(&self.summary.announce),
+            state: instance_state_label(self.enabled, &stats).to_string(),
+            is_tracker_invalid: stats.tracker_error.is_some(),
+            tracker_error: stats.tracker_error.clone(),
+            tracker_retry_attempt: stats.tracker_retry_attempt,
+            tracker_retry_at_ms: stats.tracker_retry_at_ms,
+            stop_reason: stats.stop_reason.clone(),
+            tags: self.tags.clone(),
+            total_size: self.summary.total_size,
+            uploaded: stats.uploaded,
+            downloaded: stats.downloaded,
+            ratio: stats.ratio,
+            current_upload_rate: stats.current_upload_rate,
+            current_download_rate: stats.current_download_rate,
+            seeders: stats.seeders,
+            leechers: stats.leechers,
+            left: stats.left,
+            torrent_completion: stats.torrent_completion,
+            source: source.to_string(),
+            created_at: self.created_at,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -53,4 +105,231 @@ pub struct InstanceInfo {
     pub created_at: u64,
     pub source: InstanceSource,
     pub tags: Vec<String>,
+    pub enabled: bool,
+    pub schedule: Option<InstanceSchedule>,
+    /// Custom name shown instead of the torrent name
+    pub label: Option<String>,
+}
+
+impl InstanceInfo {
+    pub fn display_name(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.torrent.name)
+    }
+
+    /// Drop the rate/ratio history vectors to keep list payloads small.
+    pub fn strip_history(&mut self) {
+        self.stats.clear_history();
+    }
+}
+
+/// State name shown in listings; disabled and idling take precedence over the faker state.
+pub const fn instance_state_label(enabled: bool, stats: &FakerStats) -> &'static str {
+    match stats.state {
+        _ if !enabled => "disabled",
+        FakerState::Paused => "paused",
+        _ if stats.is_idling => "idle",
+        FakerState::Idle => "idle",
+        FakerState::Starting => "starting",
+        FakerState::Running => "running",
+        FakerState::Stopping => "stopping",
+        FakerState::Stopped => "stopped",
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceSortKey {
+    #[default]
+    CreatedAt,
+    Name,
+    Ratio,
+    Uploaded,
+    Downloaded,
+    UploadRate,
+    DownloadRate,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// How a comma-separated `tags` filter combines its tags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TagMatch {
+    /// The instance carries at least one of the tags
+    #[default]
+    Any,
+    /// The instance carries every one of the tags
+    All,
+}
+
+/// Whether `tags` satisfies the comma-separated `wanted` list (case-insensitive).
+/// An absent or empty list matches everything.
+fn tag_list_matches(wanted: Option<&str>, mode: TagMatch, tags: &[String]) -> bool {
+    let mut wanted =
+        wanted.unwrap_or_default().split(',').map(str::trim).filter(|tag| !tag.is_empty());
+    let has = |tag: &str| tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+    match mode {
+        TagMatch::All => wanted.all(has),
+        TagMatch::Any => {
+            let mut wanted = wanted.peekable();
+            wanted.peek().is_none() || wanted.any(has)
+        }
+    }
+}
+
+/// Tag filter for `AppState::list_instance_summaries_matching`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TagFilter {
+    /// Comma-separated tags
+    pub tags: Option<String>,
+    #[serde(rename = "match")]
+    pub tag_match: TagMatch,
+}
+
+impl TagFilter {
+    pub fn matches(&self, tags: &[String]) -> bool {
+        tag_list_matches(self.tags.as_deref(), self.tag_match, tags)
+    }
+}
+
+/// A tag in use and how many instances carry it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// An instance's distinct tags keyed by lowercase name, each with the spelling that
+/// sorts first among those differing only in case.
+pub fn distinct_tags(tags: &[String]) -> BTreeMap<String, &str> {
+    let mut distinct: BTreeMap<String, &str> = BTreeMap::new();
+    for tag in tags {
+        let spelling = distinct.entry(tag.to_lowercase()).or_insert(tag.as_str());
+        if tag.as_str() < *spelling {
+            *spelling = tag.as_str();
+        }
+    }
+    distinct
+}
+
+/// Combined stats of a set of instances.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, ToSchema)]
+pub struct InstanceTotals {
+    pub instances: usize,
+    /// Running or starting
+    pub running: usize,
+    pub paused: usize,
+    /// Stopped or stopping
+    pub stopped: usize,
+    pub idle: usize,
+    /// Total uploaded bytes across all sessions
+    pub uploaded: u64,
+    /// Total downloaded bytes across all sessions
+    pub downloaded: u64,
+    /// Combined current upload rate of running instances in KB/s
+    pub upload_rate: f64,
+    /// Combined current download rate of running instances in KB/s
+    pub download_rate: f64,
+    /// Combined uploaded bytes over the combined torrent size, like an instance's ratio
+    pub ratio: f64,
+    #[serde(skip)]
+    torrent_size: u64,
+}
+
+impl InstanceTotals {
+    pub fn add(&mut self, stats: &FakerStats, torrent_size: u64) {
+        self.instances += 1;
+        match stats.state {
+            FakerState::Running | FakerState::Starting => {
+                self.running += 1;
+                self.upload_rate += stats.current_upload_rate;
+                self.download_rate += stats.current_download_rate;
+            }
+            FakerState::Paused => self.paused += 1,
+            FakerState::Stopped | FakerState::Stopping => self.stopped += 1,
+            FakerState::Idle => self.idle += 1,
+        }
+        self.uploaded = self.uploaded.saturating_add(stats.uploaded);
+        self.downloaded = self.downloaded.saturating_add(stats.downloaded);
+        self.torrent_size = self.torrent_size.saturating_add(torrent_size);
+        self.ratio = if self.torrent_size > 0 {
+            self.uploaded as f64 / self.torrent_size as f64
+        } else {
+            0.0
+        };
+    }
+}
+
+/// Totals of the instances carrying a tag.
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct TagTotals {
+    pub tag: String,
+    #[serde(flatten)]
+    pub totals: InstanceTotals,
+}
+
+/// Filter, sort and page applied by `AppState::list_instances`.
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct InstanceQuery {
+    /// Only instances in this state (`running`, `paused`, `idle`, `stopped`, `disabled`, ...)
+    pub state: Option<String>,
+    /// Only instances carrying this tag (case-insensitive)
+    pub tag: Option<String>,
+    /// Comma-separated tags, combined according to `match`
+    pub tags: Option<String>,
+    #[serde(rename = "match")]
+    pub tag_match: TagMatch,
+    pub sort: InstanceSortKey,
+    pub order: SortOrder,
+    pub limit: Option<usize>,
+    pub offset: usize,
+}
+
+impl InstanceQuery {
+    pub fn matches(&self, enabled: bool, stats: &FakerStats, tags: &[String]) -> bool {
+        let state_matches = self
+            .state
+            .as_deref()
+            .is_none_or(|state| state.eq_ignore_ascii_case(instance_state_label(enabled, stats)));
+        let tag_matches =
+            self.tag.as_deref().is_none_or(|tag| tags.iter().any(|t| t.eq_ignore_ascii_case(tag)));
+        state_matches && tag_matches && tag_list_matches(self.tags.as_deref(), self.tag_match, tags)
+    }
+
+    /// Sort by the requested key, then by creation time and id so pages are stable.
+    pub fn sort(&self, instances: &mut [InstanceInfo]) {
+        instances.sort_by(|a, b| {
+            let ordering = match self.sort {
+                InstanceSortKey::CreatedAt => Ordering::Equal,
+                InstanceSortKey::Name => a.display_name().cmp(b.display_name()),
+                InstanceSortKey::Ratio => a.stats.ratio.total_cmp(&b.stats.ratio),
+                InstanceSortKey::Uploaded => a.stats.uploaded.cmp(&b.stats.uploaded),
+                InstanceSortKey::Downloaded => a.stats.downloaded.cmp(&b.stats.downloaded),
+                InstanceSortKey::UploadRate => {
+                    a.stats.current_upload_rate.total_cmp(&b.stats.current_upload_rate)
+                }
+                InstanceSortKey::DownloadRate => {
+                    a.stats.current_download_rate.total_cmp(&b.stats.current_download_rate)
+                }
+            }
+            .then_with(|| a.created_at.cmp(&b.created_at))
+            .then_with(|| a.id.cmp(&b.id));
+            match self.order {
+                SortOrder::Asc => ordering,
+                SortOrder::Desc => ordering.reverse(),
+            }
+        });
+    }
+
+    pub fn paginate(&self, instances: Vec<InstanceInfo>) -> Vec<InstanceInfo> {
+        instances.into_iter().skip(self.offset).take(self.limit.unwrap_or(usize::MAX)).collect()
+    }
 }
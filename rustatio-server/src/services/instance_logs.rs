@@ -0,0 +1,64 @@
+//! Recent log lines of each instance, kept in a bounded buffer per instance id.
+
+use super::events::LogEvent;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, PoisonError};
+
+/// Lines kept per instance; older ones are dropped first.
+pub const INSTANCE_LOG_CAPACITY: usize = 500;
+
+#[derive(Debug, Default)]
+pub struct InstanceLogs {
+    lines: Mutex<HashMap<String, VecDeque<LogEvent>>>,
+}
+
+impl InstanceLogs {
+    pub fn push(&self, id: &str, event: LogEvent) {
+        let mut lines = self.lines.lock().unwrap_or_else(PoisonError::into_inner);
+        let buffer = lines.entry(id.to_string()).or_default();
+        if buffer.len() == INSTANCE_LOG_CAPACITY {
+            buffer.pop_front();
+        }
+        buffer.push_back(event);
+    }
+
+    /// The last `limit` lines of an instance (all kept lines when `None`), oldest first.
+    pub fn recent(&self, id: &str, limit: Option<usize>) -> Vec<LogEvent> {
+        let lines = self.lines.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(buffer) = lines.get(id) else {
+            return Vec::new();
+        };
+        let skip = limit.map_or(0, |limit| buffer.len().saturating_sub(limit));
+        buffer.iter().skip(skip).cloned().collect()
+    }
+
+    pub fn remove(&self, id: &str) {
+        self.lines.lock().unwrap_or_else(PoisonError::into_inner).remove(id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffers_are_per_instance_and_bounded() {
+        let logs = InstanceLogs::default();
+        for i in 0..=INSTANCE_LOG_CAPACITY {
+            logs.push("a", LogEvent::new("info", format!("line {i}")));
+        }
+        logs.push("b", LogEvent::new("warn", "other".to_string()));
+
+        let all = logs.recent("a", None);
+        assert_eq!(all.len(), INSTANCE_LOG_CAPACITY);
+        assert_eq!(all[0].message, "line 1");
+
+        let last = logs.recent("a", Some(2));
+        assert_eq!(last.len(), 2);
+        assert_eq!(last[1].message, format!("line {INSTANCE_LOG_CAPACITY}"));
+        assert_eq!(logs.recent("b", None).len(), 1);
+
+        logs.remove("a");
+        assert!(logs.recent("a", None).is_empty());
+    }
+}
@@ -1,26 +1,53 @@
 use super::state::AppState;
 use async_trait::async_trait;
-use rustatio_core::logger::set_instance_context_str;
+use rustatio_core::logger::{with_instance_context, InstanceLogContext};
 use rustatio_core::FakerStats;
+use std::future::Future;
 use std::sync::Arc;
 
-fn resolve_instance_label(
+const GLOBAL_UPLOAD_CAP_ERROR: &str = "Global upload cap reached; reset it to start instances";
+const VPN_KILLSWITCH_ERROR: &str = "VPN is down; the kill-switch blocks instances until it is back";
+
+fn resolve_log_context(
     instances: &std::collections::HashMap<String, super::instance::FakerInstance>,
     id: &str,
-) -> String {
-    instances
-        .get(id)
-        .map(|instance| instance.summary.name.clone())
-        .filter(|name| !name.is_empty())
-        .unwrap_or_else(|| id.to_string())
+) -> InstanceLogContext {
+    let instance = instances.get(id);
+    InstanceLogContext {
+        id: id.to_string(),
+        label: instance
+            .map(|instance| instance.display_name().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| id.to_string()),
+        info_hash: instance.map(|instance| hex::encode(instance.torrent_info_hash)),
+    }
+}
+
+impl AppState {
+    /// Run `work` with instance `id` as the log context, so core log lines it writes are
+    /// prefixed with the instance name and captured for it even when other instances'
+    /// tasks run on the same worker thread in between.
+    pub async fn in_log_context<T>(
+        &self,
+        id: &str,
+        work: impl Future<Output = Result<T, String>>,
+    ) -> Result<T, String> {
+        let context = {
+            let instances = self.instances.read().await;
+            resolve_log_context(&instances, id)
+        };
+        with_instance_context(context, work).await
+    }
 }
 
 #[async_trait]
 pub trait InstanceLifecycle {
     async fn start_instance(&self, id: &str) -> Result<(), String>;
     async fn recover_tracker_instance(&self, id: &str) -> Result<FakerStats, String>;
-    async fn stop_instance(&self, id: &str) -> Result<FakerStats, String>;
-    async fn pause_instance(&self, id: &str) -> Result<(), String>;
+    /// Stop the instance. Hit-and-run protection refuses it unless `bypass_hnr` is set.
+    async fn stop_instance(&self, id: &str, bypass_hnr: bool) -> Result<FakerStats, String>;
+    /// Pause the instance, subject to hit-and-run protection like [`Self::stop_instance`].
+    async fn pause_instance(&self, id: &str, bypass_hnr: bool) -> Result<(), String>;
     async fn resume_instance(&self, id: &str) -> Result<(), String>;
     async fn update_instance(&self, id: &str) -> Result<FakerStats, String>;
     async fn update_stats_only(&self, id: &str) -> Result<FakerStats, String>;
@@ -29,203 +56,241 @@ pub trait InstanceLifecycle {
 #[async_trait]
 impl InstanceLifecycle for AppState {
     async fn start_instance(&self, id: &str) -> Result<(), String> {
-        let label = {
-            let instances = self.instances.read().await;
-            resolve_instance_label(&instances, id)
-        };
-        set_instance_context_str(Some(&label));
-
-        let (faker, restore) = {
-            let instances = self.instances.read().await;
-            let instance = instances.get(id).ok_or("Instance not found")?;
-            let stats = instance.faker.stats_snapshot();
-            let restore = matches!(
-                stats.state,
-                rustatio_core::FakerState::Running | rustatio_core::FakerState::Starting
-            ) && stats.elapsed_time.as_secs() > 0;
-            (Arc::clone(&instance.faker), restore)
-        };
-
-        if restore {
-            faker.restore_running().await.map_err(|e| e.to_string())?;
-        } else {
-            faker.start().await.map_err(|e| e.to_string())?;
-        }
-        if let Err(e) = self.save_state().await {
-            tracing::warn!("Failed to save state after starting instance: {}", e);
-        }
+        self.in_log_context(id, async move {
+            let (faker, restore) = {
+                let instances = self.instances.read().await;
+                let instance = instances.get(id).ok_or("Instance not found")?;
+                if !instance.enabled {
+                    return Err("Instance is disabled".to_string());
+                }
+                if self.upload_cap_reached() {
+                    return Err(GLOBAL_UPLOAD_CAP_ERROR.to_string());
+                }
+                if self.killswitch_engaged() {
+                    return Err(VPN_KILLSWITCH_ERROR.to_string());
+                }
+                if let Some(max) = self.decorrelation().max_instances_per_ip {
+                    let active = instances
+                        .iter()
+                        .filter(|(other_id, other)| {
+                            other_id.as_str() != id
+                                && matches!(
+                                    other.faker.stats_snapshot().state,
+                                    rustatio_core::FakerState::Starting
+                                        | rustatio_core::FakerState::Running
+                                        | rustatio_core::FakerState::Paused
+                                )
+                        })
+                        .count();
+                    if active >= max {
+                        return Err(format!(
+                            "Instance limit reached: {max} active instance(s) already share this IP"
+                        ));
+                    }
+                }
+                let stats = instance.faker.stats_snapshot();
+                let restore = matches!(
+                    stats.state,
+                    rustatio_core::FakerState::Running | rustatio_core::FakerState::Starting
+                ) && stats.elapsed_time.as_secs() > 0;
+                (Arc::clone(&instance.faker), restore)
+            };
+
+            faker.set_rate_scale(self.decorrelation().rate_scale_for(id)).await;
+            let throttle = self.rate_throttle();
+            faker.set_throttle(throttle.upload_factor, throttle.download_factor).await;
+
+            if restore {
+                faker.restore_running().await.map_err(|e| e.to_string())?;
+            } else {
+                faker.start().await.map_err(|e| e.to_string())?;
+            }
+            self.invalidate_scrape_cache(id).await;
+            if let Err(e) = self.save_state().await {
+                tracing::warn!("Failed to save state after starting instance: {}", e);
+            }
 
-        self.refresh_peer_listener_port().await;
+            self.refresh_peer_listener_port().await;
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
     async fn recover_tracker_instance(&self, id: &str) -> Result<FakerStats, String> {
-        let label = {
-            let instances = self.instances.read().await;
-            resolve_instance_label(&instances, id)
-        };
-        set_instance_context_str(Some(&label));
-
-        let faker = {
-            let instances = self.instances.read().await;
-            let instance = instances.get(id).ok_or("Instance not found")?;
-            Arc::clone(&instance.faker)
-        };
-
-        let stats = faker.recover_tracker().await.map_err(|e| e.to_string())?;
-
-        {
-            let mut instances = self.instances.write().await;
-            if let Some(instance) = instances.get_mut(id) {
-                instance.cumulative_uploaded = stats.uploaded;
-                instance.cumulative_downloaded = stats.downloaded;
-                instance.config.completion_percent = stats.torrent_completion;
+        self.in_log_context(id, async move {
+            let faker = {
+                let instances = self.instances.read().await;
+                let instance = instances.get(id).ok_or("Instance not found")?;
+                if !instance.enabled {
+                    return Err("Instance is disabled".to_string());
+                }
+                Arc::clone(&instance.faker)
+            };
+
+            let stats = faker.recover_tracker().await.map_err(|e| e.to_string())?;
+            self.invalidate_scrape_cache(id).await;
+
+            {
+                let mut instances = self.instances.write().await;
+                if let Some(instance) = instances.get_mut(id) {
+                    instance.cumulative_uploaded = stats.uploaded;
+                    instance.cumulative_downloaded = stats.downloaded;
+                    instance.config.completion_percent = stats.torrent_completion;
+                }
             }
-        }
 
-        if let Err(e) = self.save_state().await {
-            tracing::warn!("Failed to save state after tracker recovery attempt: {}", e);
-        }
+            if let Err(e) = self.save_state().await {
+                tracing::warn!("Failed to save state after tracker recovery attempt: {}", e);
+            }
 
-        self.refresh_peer_listener_port().await;
+            self.refresh_peer_listener_port().await;
 
-        Ok(stats)
+            Ok(stats)
+        })
+        .await
     }
 
-    async fn stop_instance(&self, id: &str) -> Result<FakerStats, String> {
-        let label = {
-            let instances = self.instances.read().await;
-            resolve_instance_label(&instances, id)
-        };
-        set_instance_context_str(Some(&label));
-
-        let faker = {
-            let instances = self.instances.read().await;
-            let instance = instances.get(id).ok_or("Instance not found")?;
-            Arc::clone(&instance.faker)
-        };
-
-        faker.stop().await.map_err(|e| e.to_string())?;
-        let stats = faker.stats_snapshot();
+    async fn stop_instance(&self, id: &str, bypass_hnr: bool) -> Result<FakerStats, String> {
+        self.in_log_context(id, async move {
+            let faker = {
+                let instances = self.instances.read().await;
+                let instance = instances.get(id).ok_or("Instance not found")?;
+                Arc::clone(&instance.faker)
+            };
+
+            if !bypass_hnr {
+                if let Some(reason) = faker.hnr_block_reason().await {
+                    return Err(reason);
+                }
+            }
 
-        {
-            let mut instances = self.instances.write().await;
-            if let Some(instance) = instances.get_mut(id) {
-                instance.cumulative_uploaded = stats.uploaded;
-                instance.cumulative_downloaded = stats.downloaded;
-                instance.config.completion_percent = stats.torrent_completion;
+            faker.stop().await.map_err(|e| e.to_string())?;
+            self.invalidate_scrape_cache(id).await;
+            let stats = faker.stats_snapshot();
+
+            {
+                let mut instances = self.instances.write().await;
+                if let Some(instance) = instances.get_mut(id) {
+                    instance.cumulative_uploaded = stats.uploaded;
+                    instance.cumulative_downloaded = stats.downloaded;
+                    instance.config.completion_percent = stats.torrent_completion;
+                }
             }
-        }
 
-        if let Err(e) = self.save_state().await {
-            tracing::warn!("Failed to save state after stopping instance: {}", e);
-        }
+            if let Err(e) = self.save_state().await {
+                tracing::warn!("Failed to save state after stopping instance: {}", e);
+            }
 
-        self.refresh_peer_listener_port().await;
+            self.refresh_peer_listener_port().await;
 
-        Ok(stats)
+            Ok(stats)
+        })
+        .await
     }
 
-    async fn pause_instance(&self, id: &str) -> Result<(), String> {
-        let label = {
-            let instances = self.instances.read().await;
-            resolve_instance_label(&instances, id)
-        };
-        set_instance_context_str(Some(&label));
-
-        let faker = {
-            let instances = self.instances.read().await;
-            let instance = instances.get(id).ok_or("Instance not found")?;
-            Arc::clone(&instance.faker)
-        };
+    async fn pause_instance(&self, id: &str, bypass_hnr: bool) -> Result<(), String> {
+        self.in_log_context(id, async move {
+            let faker = {
+                let instances = self.instances.read().await;
+                let instance = instances.get(id).ok_or("Instance not found")?;
+                Arc::clone(&instance.faker)
+            };
+
+            if !bypass_hnr {
+                if let Some(reason) = faker.hnr_block_reason().await {
+                    return Err(reason);
+                }
+            }
 
-        faker.pause().await.map_err(|e| e.to_string())?;
-        if let Err(e) = self.save_state().await {
-            tracing::warn!("Failed to save state after pausing instance: {}", e);
-        }
+            faker.pause().await.map_err(|e| e.to_string())?;
+            self.invalidate_scrape_cache(id).await;
+            if let Err(e) = self.save_state().await {
+                tracing::warn!("Failed to save state after pausing instance: {}", e);
+            }
 
-        self.refresh_peer_listener_port().await;
+            self.refresh_peer_listener_port().await;
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
     async fn resume_instance(&self, id: &str) -> Result<(), String> {
-        let label = {
-            let instances = self.instances.read().await;
-            resolve_instance_label(&instances, id)
-        };
-        set_instance_context_str(Some(&label));
-
-        let faker = {
-            let instances = self.instances.read().await;
-            let instance = instances.get(id).ok_or("Instance not found")?;
-            Arc::clone(&instance.faker)
-        };
-
-        faker.resume().await.map_err(|e| e.to_string())?;
-        if let Err(e) = self.save_state().await {
-            tracing::warn!("Failed to save state after resuming instance: {}", e);
+        if self.upload_cap_reached() {
+            return Err(GLOBAL_UPLOAD_CAP_ERROR.to_string());
+        }
+        if self.killswitch_engaged() {
+            return Err(VPN_KILLSWITCH_ERROR.to_string());
         }
 
-        self.refresh_peer_listener_port().await;
+        self.in_log_context(id, async move {
+            let faker = {
+                let instances = self.instances.read().await;
+                let instance = instances.get(id).ok_or("Instance not found")?;
+                Arc::clone(&instance.faker)
+            };
+
+            faker.resume().await.map_err(|e| e.to_string())?;
+            self.invalidate_scrape_cache(id).await;
+            if let Err(e) = self.save_state().await {
+                tracing::warn!("Failed to save state after resuming instance: {}", e);
+            }
 
-        Ok(())
+            self.refresh_peer_listener_port().await;
+
+            Ok(())
+        })
+        .await
     }
 
     async fn update_instance(&self, id: &str) -> Result<FakerStats, String> {
-        let label = {
-            let instances = self.instances.read().await;
-            resolve_instance_label(&instances, id)
-        };
-        set_instance_context_str(Some(&label));
-
-        let faker = {
-            let instances = self.instances.read().await;
-            let instance = instances.get(id).ok_or("Instance not found")?;
-            Arc::clone(&instance.faker)
-        };
-
-        faker.update().await.map_err(|e| e.to_string())?;
-        let stats = faker.stats_snapshot();
-
-        {
-            let mut instances = self.instances.write().await;
-            if let Some(instance) = instances.get_mut(id) {
-                instance.cumulative_uploaded = stats.uploaded;
-                instance.cumulative_downloaded = stats.downloaded;
-                instance.config.completion_percent = stats.torrent_completion;
+        self.in_log_context(id, async move {
+            let faker = {
+                let instances = self.instances.read().await;
+                let instance = instances.get(id).ok_or("Instance not found")?;
+                Arc::clone(&instance.faker)
+            };
+
+            faker.update().await.map_err(|e| e.to_string())?;
+            let stats = faker.stats_snapshot();
+
+            {
+                let mut instances = self.instances.write().await;
+                if let Some(instance) = instances.get_mut(id) {
+                    instance.cumulative_uploaded = stats.uploaded;
+                    instance.cumulative_downloaded = stats.downloaded;
+                    instance.config.completion_percent = stats.torrent_completion;
+                }
             }
-        }
 
-        Ok(stats)
+            Ok(stats)
+        })
+        .await
     }
 
     async fn update_stats_only(&self, id: &str) -> Result<FakerStats, String> {
-        let label = {
-            let instances = self.instances.read().await;
-            resolve_instance_label(&instances, id)
-        };
-        set_instance_context_str(Some(&label));
-
-        let faker = {
-            let instances = self.instances.read().await;
-            let instance = instances.get(id).ok_or("Instance not found")?;
-            Arc::clone(&instance.faker)
-        };
-
-        faker.update_stats_only().await.map_err(|e| e.to_string())?;
-        let stats = faker.stats_snapshot();
-
-        {
-            let mut instances = self.instances.write().await;
-            if let Some(instance) = instances.get_mut(id) {
-                instance.cumulative_uploaded = stats.uploaded;
-                instance.cumulative_downloaded = stats.downloaded;
-                instance.config.completion_percent = stats.torrent_completion;
+        self.in_log_context(id, async move {
+            let faker = {
+                let instances = self.instances.read().await;
+                let instance = instances.get(id).ok_or("Instance not found")?;
+                Arc::clone(&instance.faker)
+            };
+
+            faker.update_stats_only().await.map_err(|e| e.to_string())?;
+            let stats = faker.stats_snapshot();
+
+            {
+                let mut instances = self.instances.write().await;
+                if let Some(instance) = instances.get_mut(id) {
+                    instance.cumulative_uploaded = stats.uploaded;
+                    instance.cumulative_downloaded = stats.downloaded;
+                    instance.config.completion_percent = stats.torrent_completion;
+                }
             }
-        }
 
-        Ok(stats)
+            Ok(stats)
+        })
+        .await
     }
 }
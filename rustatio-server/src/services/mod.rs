@@ -1,18 +1,39 @@
+pub mod bundle;
+pub mod cron;
+pub mod decorrelation;
 pub mod events;
 pub mod gluetun;
+pub mod import_jobs;
 pub mod instance;
+pub mod instance_logs;
 pub mod lifecycle;
+pub mod network_recovery;
 pub mod persistence;
+pub mod port_mapping;
+pub mod rate_cap;
+pub mod runtime_config;
 pub mod scheduler;
+pub mod scrape_batch;
+pub mod scrape_cache;
 pub mod state;
+pub mod tracker_check;
+pub mod upload_cap;
+pub mod vpn_killswitch;
 pub mod vpn_port_sync;
 pub mod watch;
+pub mod webhooks;
 
-pub use events::{EventBroadcaster, InstanceEvent, LogEvent};
+pub use cron::InstanceSchedule;
+pub use events::{EventBroadcaster, InstanceEvent, LogEvent, StatsEvent};
 pub use gluetun::GluetunAuth;
 pub use instance::{InstanceInfo, ServerPeerLookup};
+pub use instance_logs::InstanceLogs;
 pub use lifecycle::InstanceLifecycle;
+pub use network_recovery::{NetworkRecovery, NetworkRecoveryConfig};
+pub use port_mapping::{PortMappingConfig, PortMappingService};
 pub use scheduler::Scheduler;
 pub use state::{AppState, InstanceBuildContext};
+pub use vpn_killswitch::{VpnKillswitch, VpnKillswitchConfig};
 pub use vpn_port_sync::{VpnPortSync, VpnPortSyncConfig};
 pub use watch::{WatchConfig, WatchDisabledReason, WatchService};
+pub use webhooks::{WebhookConfig, WebhookService};
@@ -0,0 +1,234 @@
+use super::lifecycle::InstanceLifecycle;
+use super::state::AppState;
+use crate::services::GluetunAuth;
+use rustatio_core::RatioFakerHandle;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+const DEFAULT_INTERVAL_SECS: u64 = 15;
+
+/// Watches network connectivity and retries backed-off trackers as soon as
+/// connectivity comes back after an outage, instead of waiting for the backoff.
+pub struct NetworkRecovery {
+    shutdown_tx: Option<mpsc::Sender<()>>,
+    task_handle: Option<tokio::task::JoinHandle<()>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkRecoveryConfig {
+    pub enabled: bool,
+    pub interval: Duration,
+    pub probe_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Connectivity {
+    Online,
+    Offline,
+    Unknown,
+}
+
+#[derive(Debug, Default)]
+struct OutageTracker {
+    offline: bool,
+}
+
+impl OutageTracker {
+    /// Returns true when connectivity is back after at least one offline probe.
+    fn observe(&mut self, connectivity: Connectivity) -> bool {
+        match connectivity {
+            Connectivity::Offline => {
+                self.offline = true;
+                false
+            }
+            Connectivity::Online => std::mem::take(&mut self.offline),
+            Connectivity::Unknown => false,
+        }
+    }
+}
+
+impl NetworkRecoveryConfig {
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("NETWORK_RECOVERY").map_or(true, |v| {
+            matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")
+        });
+
+        let interval = std::env::var("NETWORK_RECOVERY_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_INTERVAL_SECS);
+
+        let probe_url = std::env::var("NETWORK_RECOVERY_PROBE_URL")
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+
+        Self { enabled, interval: Duration::from_secs(interval), probe_url }
+    }
+}
+
+impl NetworkRecovery {
+    pub const fn new() -> Self {
+        Self { shutdown_tx: None, task_handle: None }
+    }
+
+    pub fn start(&mut self, state: AppState, config: NetworkRecoveryConfig) {
+        if !config.enabled || self.task_handle.is_some() {
+            return;
+        }
+
+        let interval = config.interval;
+        let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+        let handle = tokio::spawn(recovery_loop(state, config, shutdown_rx));
+        self.shutdown_tx = Some(shutdown_tx);
+        self.task_handle = Some(handle);
+
+        tracing::info!("Network recovery watcher started (interval={}s)", interval.as_secs());
+    }
+
+    pub async fn shutdown(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(()).await;
+        }
+        if let Some(handle) = self.task_handle.take() {
+            let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
+        }
+        tracing::info!("Network recovery watcher stopped");
+    }
+}
+
+async fn recovery_loop(
+    state: AppState,
+    config: NetworkRecoveryConfig,
+    mut shutdown_rx: mpsc::Receiver<()>,
+) {
+    let auth = GluetunAuth::from_env();
+    let client = match reqwest::Client::builder().timeout(Duration::from_secs(2)).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!("Network recovery failed to build HTTP client: {}", err);
+            return;
+        }
+    };
+
+    let mut tracker = OutageTracker::default();
+    let mut ticker = tokio::time::interval(config.interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = shutdown_rx.recv() => break,
+            _ = ticker.tick() => {
+                let connectivity = probe(&client, &auth, config.probe_url.as_deref()).await;
+                if connectivity == Connectivity::Offline && !tracker.offline {
+                    tracing::warn!("Network connectivity lost, waiting for recovery");
+                }
+                if tracker.observe(connectivity) {
+                    let retried = retry_backed_off_instances(&state).await;
+                    tracing::info!(
+                        "Network connectivity restored, retried {} backed-off instance(s)",
+                        retried
+                    );
+                }
+            }
+        }
+    }
+}
+
+async fn probe(
+    client: &reqwest::Client,
+    auth: &GluetunAuth,
+    probe_url: Option<&str>,
+) -> Connectivity {
+    if let Some(url) = probe_url {
+        return match client.get(url).send().await {
+            Ok(_) => Connectivity::Online,
+            Err(_) => Connectivity::Offline,
+        };
+    }
+
+    // Without an explicit probe URL, rely on Gluetun's VPN status when it is reachable.
+    match auth.vpn_running(client).await {
+        Some(true) => Connectivity::Online,
+        Some(false) => Connectivity::Offline,
+        None => Connectivity::Unknown,
+    }
+}
+
+async fn retry_backed_off_instances(state: &AppState) -> usize {
+    let items: Vec<(String, Arc<RatioFakerHandle>)> = {
+        let guard = state.instances.read().await;
+        guard
+            .iter()
+            .filter(|(_, inst)| inst.enabled)
+            .map(|(id, inst)| (id.clone(), Arc::clone(&inst.faker)))
+            .collect()
+    };
+
+    let mut retried = 0usize;
+    for (id, faker) in items {
+        // A tracker that answered with a failure reason was reachable; keep its backoff.
+        if !faker.can_retry_tracker().await
+            || faker.stats_snapshot().tracker_failure_reason.is_some()
+        {
+            continue;
+        }
+        match state.recover_tracker_instance(&id).await {
+            Ok(_) => retried += 1,
+            Err(e) => {
+                tracing::warn!("Network recovery: tracker retry failed for instance {}: {}", id, e);
+            }
+        }
+    }
+
+    retried
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Mutex, OnceLock};
+
+    fn env_lock() -> &'static Mutex<()> {
+        static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+        LOCK.get_or_init(|| Mutex::new(()))
+    }
+
+    #[test]
+    fn outage_tracker_fires_once_after_offline_then_online() {
+        let mut tracker = OutageTracker::default();
+
+        assert!(!tracker.observe(Connectivity::Online));
+        assert!(!tracker.observe(Connectivity::Offline));
+        assert!(!tracker.observe(Connectivity::Unknown));
+        assert!(tracker.observe(Connectivity::Online));
+        assert!(!tracker.observe(Connectivity::Online));
+    }
+
+    #[test]
+    fn config_defaults_to_enabled_without_probe_url() {
+        let guard = env_lock().lock();
+        assert!(guard.is_ok(), "failed to acquire env mutex");
+
+        let prev_enabled = std::env::var("NETWORK_RECOVERY").ok();
+        let prev_probe = std::env::var("NETWORK_RECOVERY_PROBE_URL").ok();
+
+        std::env::remove_var("NETWORK_RECOVERY");
+        std::env::remove_var("NETWORK_RECOVERY_PROBE_URL");
+
+        let config = NetworkRecoveryConfig::from_env();
+        assert!(config.enabled);
+        assert_eq!(config.probe_url, None);
+
+        match prev_enabled {
+            Some(value) => std::env::set_var("NETWORK_RECOVERY", value),
+            None => std::env::remove_var("NETWORK_RECOVERY"),
+        }
+        match prev_probe {
+            Some(value) => std::env::set_var("NETWORK_RECOVERY_PROBE_URL", value),
+            None => std::env::remove_var("NETWORK_RECOVERY_PROBE_URL"),
+        }
+    }
+}
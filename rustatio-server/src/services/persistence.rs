@@ -1,4 +1,7 @@
-use rustatio_core::{FakerConfig, FakerState, TorrentSummary};
+use super::cron::InstanceSchedule;
+use super::rate_cap::RateCap;
+use super::runtime_config::env_var;
+use rustatio_core::{FakerConfig, FakerState, StopReason, TorrentSummary};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
@@ -29,8 +32,18 @@ pub struct PersistedInstance {
     pub source: InstanceSource,
     #[serde(default)]
     pub tags: Vec<String>,
+    #[serde(default = "default_instance_enabled")]
+    pub enabled: bool,
     #[serde(default)]
     pub runtime: Option<PersistedRuntime>,
+    #[serde(default)]
+    pub schedule: Option<InstanceSchedule>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+const fn default_instance_enabled() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -65,6 +78,26 @@ pub struct PersistedRuntime {
     #[serde(default)]
     pub tracker_error: Option<String>,
     pub announce_count: u32,
+    #[serde(default)]
+    pub stop_reason: Option<StopReason>,
+    #[serde(default)]
+    pub estimated_bonus: Option<f64>,
+    #[serde(default)]
+    pub completed_already_sent: bool,
+    /// Latest graph points, up to the instance's `history_length`, so graphs survive restarts
+    #[serde(default)]
+    pub upload_rate_history: Vec<f64>,
+    #[serde(default)]
+    pub download_rate_history: Vec<f64>,
+    #[serde(default)]
+    pub ratio_history: Vec<f64>,
+    #[serde(default)]
+    pub history_timestamps: Vec<u64>,
+}
+
+/// The newest `len` points of a history series, as kept in the state file.
+pub fn history_tail<T: Copy>(history: &[T], len: usize) -> Vec<T> {
+    history[history.len().saturating_sub(len)..].to_vec()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -94,7 +127,7 @@ const fn default_watch_max_depth() -> u32 {
 }
 
 fn default_watch_auto_start() -> bool {
-    std::env::var("WATCH_AUTO_START").is_ok_and(|v| v.eq_ignore_ascii_case("true") || v == "1")
+    env_var("WATCH_AUTO_START").is_some_and(|v| v.eq_ignore_ascii_case("true") || v == "1")
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq, Eq)]
@@ -122,6 +155,12 @@ pub struct PersistedState {
     pub watch_settings: Option<WatchSettings>,
     #[serde(default)]
     pub custom_presets: Vec<CustomPreset>,
+    /// Bytes counted toward the global upload cap since its last reset
+    #[serde(default)]
+    pub upload_cap_used: u64,
+    /// Combined rate cap set through the API, overriding `MAX_TOTAL_*_KBPS`
+    #[serde(default)]
+    pub rate_cap: Option<RateCap>,
     pub version: u32,
 }
 
@@ -133,6 +172,8 @@ impl PersistedState {
             default_preset: None,
             watch_settings: None,
             custom_presets: Vec::new(),
+            upload_cap_used: 0,
+            rate_cap: None,
             version: 1,
         }
     }
@@ -140,50 +181,180 @@ impl PersistedState {
 
 pub struct Persistence {
     state_file: String,
+    torrents_dir: String,
+    keep_backup: bool,
+}
+
+/// What a read-back of the saved state file found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedStateCheck {
+    pub path: String,
+    pub bytes: u64,
+    pub instances: usize,
+}
+
+enum LoadError {
+    Missing,
+    Unreadable(String),
+    Corrupt(String),
 }
 
 impl Persistence {
     pub fn new(data_dir: &str) -> Self {
-        Self { state_file: format!("{data_dir}/state.json") }
+        let keep_backup = std::env::var("STATE_BACKUP").map_or(true, |v| {
+            matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")
+        });
+        Self {
+            state_file: format!("{data_dir}/state.json"),
+            torrents_dir: format!("{data_dir}/torrents"),
+            keep_backup,
+        }
     }
 
-    pub async fn load(&self) -> PersistedState {
-        let path = Path::new(&self.state_file);
+    fn torrent_file_path(&self, info_hash: &[u8; 20]) -> String {
+        format!("{}/{}.torrent", self.torrents_dir, hex::encode(info_hash))
+    }
 
-        if !path.exists() {
-            tracing::info!("No saved state found at {}, starting fresh", self.state_file);
-            return PersistedState::new();
-        }
+    /// Keep an instance's original `.torrent` next to the state file, so it can still
+    /// be exported after a restart.
+    pub async fn save_torrent_file(
+        &self,
+        info_hash: &[u8; 20],
+        bytes: &[u8],
+    ) -> Result<(), String> {
+        fs::create_dir_all(&self.torrents_dir)
+            .await
+            .map_err(|e| format!("Failed to create torrents directory: {e}"))?;
+        fs::write(self.torrent_file_path(info_hash), bytes)
+            .await
+            .map_err(|e| format!("Failed to write torrent file: {e}"))
+    }
+
+    pub async fn load_torrent_file(&self, info_hash: &[u8; 20]) -> Option<Vec<u8>> {
+        fs::read(self.torrent_file_path(info_hash)).await.ok()
+    }
 
-        match fs::File::open(path).await {
+    pub async fn remove_torrent_file(&self, info_hash: &[u8; 20]) {
+        let _ = fs::remove_file(self.torrent_file_path(info_hash)).await;
+    }
+
+    fn backup_file(&self) -> String {
+        format!("{}.bak", self.state_file)
+    }
+
+    async fn read_state(path: &str) -> Result<PersistedState, LoadError> {
+        let contents = match fs::File::open(path).await {
             Ok(mut file) => {
                 let mut contents = String::new();
-                if let Err(e) = file.read_to_string(&mut contents).await {
-                    tracing::error!("Failed to read state file: {}", e);
+                file.read_to_string(&mut contents)
+                    .await
+                    .map_err(|e| LoadError::Unreadable(e.to_string()))?;
+                contents
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Err(LoadError::Missing),
+            Err(e) => return Err(LoadError::Unreadable(e.to_string())),
+        };
+
+        serde_json::from_str(&contents).map_err(|e| LoadError::Corrupt(e.to_string()))
+    }
+
+    /// Load the saved state, falling back to `state.json.bak` when the primary file is
+    /// missing or unusable, and to a fresh state when the backup is unusable too.
+    pub async fn load(&self) -> PersistedState {
+        let backup = self.backup_file();
+
+        match Self::read_state(&self.state_file).await {
+            Ok(state) => {
+                tracing::info!("Loaded saved state from {}", self.state_file);
+                return state;
+            }
+            Err(LoadError::Missing) => {
+                if !self.keep_backup || !Path::new(&backup).exists() {
+                    tracing::info!("No saved state found at {}, starting fresh", self.state_file);
                     return PersistedState::new();
                 }
-
-                match serde_json::from_str(&contents) {
-                    Ok(state) => {
-                        tracing::info!("Loaded saved state from {}", self.state_file);
-                        state
-                    }
-                    Err(e) => {
-                        tracing::error!("Failed to parse state file: {}", e);
-                        let backup = format!("{}.corrupted", self.state_file);
-                        let _ = fs::rename(path, &backup).await;
-                        tracing::warn!("Backed up corrupted state to {}", backup);
-                        PersistedState::new()
-                    }
+                tracing::warn!("State file {} is missing, trying backup", self.state_file);
+            }
+            Err(LoadError::Unreadable(e)) => {
+                tracing::error!("Failed to read state file {}: {}", self.state_file, e);
+            }
+            Err(LoadError::Corrupt(e)) => {
+                tracing::error!("Failed to parse state file {}: {}", self.state_file, e);
+                let corrupted = format!("{}.corrupted", self.state_file);
+                if fs::rename(&self.state_file, &corrupted).await.is_ok() {
+                    tracing::warn!("Moved corrupted state to {}", corrupted);
                 }
             }
-            Err(e) => {
-                tracing::error!("Failed to open state file: {}", e);
+        }
+
+        if !self.keep_backup {
+            tracing::warn!("State backups are disabled, starting fresh");
+            return PersistedState::new();
+        }
+
+        match Self::read_state(&backup).await {
+            Ok(state) => {
+                tracing::warn!("Recovered saved state from backup {}", backup);
+                state
+            }
+            Err(LoadError::Missing) => {
+                tracing::warn!("No backup state found at {}, starting fresh", backup);
+                PersistedState::new()
+            }
+            Err(LoadError::Unreadable(e) | LoadError::Corrupt(e)) => {
+                tracing::error!("Backup state {} is also unusable ({}), starting fresh", backup, e);
                 PersistedState::new()
             }
         }
     }
 
+    /// Read the state file back and check it parses and holds `expected_instances`
+    /// instances, to confirm a save really reached the disk.
+    pub async fn verify(&self, expected_instances: usize) -> Result<SavedStateCheck, String> {
+        let path = &self.state_file;
+        let bytes = fs::metadata(path)
+            .await
+            .map_err(|e| format!("Cannot stat state file {path}: {e}"))?
+            .len();
+        let state = match Self::read_state(path).await {
+            Ok(state) => state,
+            Err(LoadError::Missing) => return Err(format!("State file {path} does not exist")),
+            Err(LoadError::Unreadable(e)) => {
+                return Err(format!("Cannot read state file {path} back: {e}"));
+            }
+            Err(LoadError::Corrupt(e)) => {
+                return Err(format!("State file {path} ({bytes} bytes) does not parse: {e}"));
+            }
+        };
+        let found = state.instances.len();
+        if found != expected_instances {
+            return Err(format!(
+                "State file {path} ({bytes} bytes) holds {found} instance(s), \
+                 expected {expected_instances}"
+            ));
+        }
+        Ok(SavedStateCheck { path: path.clone(), bytes, instances: expected_instances })
+    }
+
+    /// Flush the directory entry so the renames survive a power loss.
+    #[cfg(unix)]
+    async fn sync_parent_dir(path: &str) {
+        let Some(parent) = Path::new(path).parent() else {
+            return;
+        };
+        match fs::File::open(parent).await {
+            Ok(dir) => {
+                if let Err(e) = dir.sync_all().await {
+                    tracing::debug!("Failed to sync data directory: {}", e);
+                }
+            }
+            Err(e) => tracing::debug!("Failed to open data directory for sync: {}", e),
+        }
+    }
+
+    #[cfg(not(unix))]
+    async fn sync_parent_dir(_path: &str) {}
+
     pub async fn save(&self, state: &PersistedState) -> Result<(), String> {
         if let Some(parent) = Path::new(&self.state_file).parent() {
             if let Err(e) = fs::create_dir_all(parent).await {
@@ -204,22 +375,68 @@ impl Persistence {
 
         file.sync_all().await.map_err(|e| format!("Failed to sync state file: {e}"))?;
 
+        // Keep the last good state around so a corrupted write can be recovered on load.
+        if self.keep_backup && Path::new(&self.state_file).exists() {
+            if let Err(e) = fs::rename(&self.state_file, self.backup_file()).await {
+                tracing::warn!("Failed to rotate state backup: {}", e);
+            }
+        }
+
         fs::rename(&temp_file, &self.state_file)
             .await
             .map_err(|e| format!("Failed to rename state file: {e}"))?;
+        Self::sync_parent_dir(&self.state_file).await;
 
         tracing::debug!("State saved to {}", self.state_file);
         Ok(())
     }
 }
 
+/// Check at startup that `data_dir` exists (creating it if needed) and accepts new
+/// files, so a volume with the wrong owner is reported instead of every save failing.
+pub fn check_data_dir(data_dir: &str) -> Result<(), String> {
+    let dir = Path::new(data_dir);
+    let probe = dir.join(".rustatio-write-test");
+    let result = std::fs::create_dir_all(dir)
+        .and_then(|()| std::fs::write(&probe, b""))
+        .and_then(|()| std::fs::remove_file(&probe));
+    result.map_err(|e| {
+        let hint = ownership_hint(dir);
+        format!("DATA_DIR {data_dir} is not writable ({e}); state will not be saved. {hint}")
+    })
+}
+
+#[cfg(unix)]
+fn ownership_hint(dir: &Path) -> String {
+    use std::os::unix::fs::MetadataExt;
+
+    // /proc/self belongs to the user the process runs as
+    let process = std::fs::metadata("/proc/self").map_or_else(
+        |_| "an unknown user".to_string(),
+        |meta| format!("uid {} gid {}", meta.uid(), meta.gid()),
+    );
+    let owner = dir.ancestors().find_map(|path| std::fs::metadata(path).ok()).map_or_else(
+        || "an unknown owner".to_string(),
+        |meta| format!("uid {} gid {}", meta.uid(), meta.gid()),
+    );
+    format!(
+        "Rustatio runs as {process} and the directory (or its nearest existing parent) is owned \
+         by {owner}. Set PUID/PGID to the owner's IDs or chown the directory on the host."
+    )
+}
+
+#[cfg(not(unix))]
+fn ownership_hint(_dir: &Path) -> String {
+    "Check that the user running Rustatio can write to it.".to_string()
+}
+
 pub fn now_timestamp() -> u64 {
     std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::WatchSettings;
+    use super::{check_data_dir, PersistedState, Persistence, WatchSettings};
     use std::sync::{Mutex, OnceLock};
 
     fn env_lock() -> &'static Mutex<()> {
@@ -242,4 +459,103 @@ mod tests {
 
         std::env::remove_var("WATCH_AUTO_START");
     }
+
+    fn temp_persistence() -> (tempfile::TempDir, Persistence) {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let persistence = Persistence::new(&temp.path().to_string_lossy());
+        (temp, persistence)
+    }
+
+    fn state_with_version(version: u32) -> PersistedState {
+        PersistedState { version, ..PersistedState::new() }
+    }
+
+    #[tokio::test]
+    async fn save_rotates_previous_state_into_backup() {
+        let (_temp, persistence) = temp_persistence();
+
+        assert!(persistence.save(&state_with_version(1)).await.is_ok());
+        assert!(persistence.save(&state_with_version(2)).await.is_ok());
+
+        let backup = std::fs::read_to_string(persistence.backup_file());
+        assert!(backup.is_ok());
+        let backup = serde_json::from_str::<PersistedState>(&backup.unwrap_or_default());
+        assert_eq!(backup.map(|state| state.version).ok(), Some(1));
+        assert_eq!(persistence.load().await.version, 2);
+    }
+
+    #[tokio::test]
+    async fn verify_reads_the_saved_file_back() {
+        let (_temp, persistence) = temp_persistence();
+
+        assert!(persistence.verify(0).await.is_err());
+        assert!(persistence.save(&state_with_version(1)).await.is_ok());
+
+        let check = persistence.verify(0).await;
+        assert!(check.as_ref().is_ok_and(|check| check.bytes > 0 && check.instances == 0));
+        assert!(persistence.verify(2).await.is_err_and(|e| e.contains("expected 2")));
+
+        assert!(std::fs::write(&persistence.state_file, b"{ not json").is_ok());
+        assert!(persistence.verify(0).await.is_err_and(|e| e.contains("does not parse")));
+    }
+
+    #[tokio::test]
+    async fn load_falls_back_to_backup_when_primary_is_corrupt() {
+        let (_temp, persistence) = temp_persistence();
+
+        assert!(persistence.save(&state_with_version(1)).await.is_ok());
+        assert!(persistence.save(&state_with_version(2)).await.is_ok());
+        assert!(std::fs::write(&persistence.state_file, b"{ not json").is_ok());
+
+        assert_eq!(persistence.load().await.version, 1);
+        assert!(std::path::Path::new(&format!("{}.corrupted", persistence.state_file)).exists());
+    }
+
+    #[tokio::test]
+    async fn load_starts_fresh_when_primary_and_backup_are_corrupt() {
+        let (_temp, persistence) = temp_persistence();
+
+        assert!(std::fs::write(&persistence.state_file, b"garbage").is_ok());
+        assert!(std::fs::write(persistence.backup_file(), b"garbage").is_ok());
+
+        let state = persistence.load().await;
+        assert!(state.instances.is_empty());
+        assert_eq!(state.version, 1);
+    }
+
+    #[tokio::test]
+    async fn load_recovers_from_backup_when_primary_is_truncated() {
+        let (_temp, persistence) = temp_persistence();
+
+        assert!(persistence.save(&state_with_version(3)).await.is_ok());
+        assert!(persistence.save(&state_with_version(4)).await.is_ok());
+
+        // Simulate a crash halfway through writing the primary file
+        let contents = std::fs::read(&persistence.state_file).unwrap_or_default();
+        assert!(!contents.is_empty());
+        assert!(std::fs::write(&persistence.state_file, &contents[..contents.len() / 2]).is_ok());
+
+        assert_eq!(persistence.load().await.version, 3);
+    }
+
+    #[test]
+    fn data_dir_check_creates_the_dir_and_reports_unusable_ones() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+
+        let nested = temp.path().join("data");
+        assert!(check_data_dir(&nested.to_string_lossy()).is_ok());
+        assert!(nested.is_dir());
+        assert!(!nested.join(".rustatio-write-test").exists());
+
+        // A file where the directory should be fails even when running as root
+        let blocker = temp.path().join("file");
+        assert!(std::fs::write(&blocker, b"").is_ok());
+        let blocked = blocker.join("data");
+        let err = check_data_dir(&blocked.to_string_lossy()).err().unwrap_or_default();
+        assert!(err.contains("is not writable"), "{err}");
+    }
 }
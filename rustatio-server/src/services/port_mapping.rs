@@ -0,0 +1,203 @@
+use super::state::AppState;
+use rustatio_core::PortMapping;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const MAPPING_LIFETIME: Duration = Duration::from_hours(1);
+const RETRY_DELAY: Duration = Duration::from_mins(5);
+/// Bounded so a silent gateway cannot hold up shutdown
+const UNMAP_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// Keeps a NAT-PMP/UPnP mapping open on the router for the peer port instances announce.
+/// The announced port never changes; the mapping only makes it reachable.
+pub struct PortMappingService {
+    shutdown_tx: Option<mpsc::Sender<()>>,
+    task_handle: Option<tokio::task::JoinHandle<()>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PortMappingConfig {
+    pub enabled: bool,
+}
+
+impl PortMappingConfig {
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("ENABLE_PORT_MAPPING")
+            .is_ok_and(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"));
+        Self { enabled }
+    }
+}
+
+/// The port last handed to the gateway, the mapping it granted and when to ask again.
+#[derive(Debug, Default)]
+struct MappingTracker {
+    port: Option<u16>,
+    mapping: Option<PortMapping>,
+    next_attempt: Option<Instant>,
+}
+
+impl MappingTracker {
+    fn due(&self, port: u16, now: Instant) -> bool {
+        self.port != Some(port) || self.next_attempt.is_none_or(|next| now >= next)
+    }
+
+    fn record(&mut self, port: u16, retry_in: Duration, now: Instant) {
+        self.port = Some(port);
+        self.next_attempt = Some(now + retry_in);
+    }
+
+    /// The mapping to remove before mapping `port`, when it was granted for another port.
+    fn take_stale(&mut self, port: u16) -> Option<PortMapping> {
+        if self.mapping.as_ref().is_some_and(|mapping| mapping.internal_port != port) {
+            return self.mapping.take();
+        }
+        None
+    }
+}
+
+impl PortMappingService {
+    pub const fn new() -> Self {
+        Self { shutdown_tx: None, task_handle: None }
+    }
+
+    pub fn start(&mut self, state: AppState, config: PortMappingConfig) {
+        if !config.enabled || self.task_handle.is_some() {
+            return;
+        }
+
+        let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+        let handle = tokio::spawn(mapping_loop(state, shutdown_rx));
+        self.shutdown_tx = Some(shutdown_tx);
+        self.task_handle = Some(handle);
+
+        tracing::info!("Port mapping started (NAT-PMP, then UPnP)");
+    }
+
+    pub async fn shutdown(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(()).await;
+        }
+        if let Some(handle) = self.task_handle.take() {
+            let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
+        }
+        tracing::info!("Port mapping stopped");
+    }
+}
+
+async fn mapping_loop(state: AppState, mut shutdown_rx: mpsc::Receiver<()>) {
+    let mut tracker = MappingTracker::default();
+    let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = shutdown_rx.recv() => {
+                if let Some(mapping) = tracker.mapping.take() {
+                    remove_mapping(&mapping).await;
+                }
+                break;
+            }
+            _ = ticker.tick() => {
+                let Some(port) = state.peer_listener_status().await.desired_port else {
+                    continue;
+                };
+                let now = Instant::now();
+                if !tracker.due(port, now) {
+                    continue;
+                }
+                if let Some(stale) = tracker.take_stale(port) {
+                    remove_mapping(&stale).await;
+                }
+                match rustatio_core::map_port(port, MAPPING_LIFETIME).await {
+                    Ok(mapping) => {
+                        tracing::info!(
+                            "Port mapping: {} on {} maps external port {} to local port {} \
+                             for {}s",
+                            mapping.method,
+                            mapping.gateway,
+                            mapping.external_port,
+                            mapping.internal_port,
+                            mapping.lifetime.as_secs()
+                        );
+                        if mapping.external_port != port {
+                            tracing::warn!(
+                                "Port mapping: gateway picked external port {} but instances \
+                                 keep announcing port {}",
+                                mapping.external_port,
+                                port
+                            );
+                        }
+                        let renew_in = (mapping.lifetime / 2).max(CHECK_INTERVAL);
+                        tracker.mapping = Some(mapping);
+                        tracker.record(port, renew_in, now);
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            "Port mapping for port {} failed, announcing it unmapped: {}",
+                            port,
+                            e
+                        );
+                        tracker.record(port, RETRY_DELAY, now);
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn remove_mapping(mapping: &PortMapping) {
+    match tokio::time::timeout(UNMAP_TIMEOUT, rustatio_core::unmap_port(mapping)).await {
+        Ok(Ok(())) => tracing::info!(
+            "Port mapping: removed {} mapping of external port {} on {}",
+            mapping.method,
+            mapping.external_port,
+            mapping.gateway
+        ),
+        Ok(Err(e)) => tracing::warn!(
+            "Port mapping: failed to remove external port {}, it expires on its own: {}",
+            mapping.external_port,
+            e
+        ),
+        Err(_) => tracing::warn!(
+            "Port mapping: gateway did not confirm removing external port {}",
+            mapping.external_port
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracker_maps_new_ports_at_once_and_renews_when_due() {
+        let now = Instant::now();
+        let mut tracker = MappingTracker::default();
+
+        assert!(tracker.due(6881, now));
+
+        tracker.record(6881, Duration::from_mins(1), now);
+        assert!(!tracker.due(6881, now));
+        assert!(tracker.due(6882, now));
+        assert!(tracker.due(6881, now + Duration::from_mins(1)));
+    }
+
+    #[test]
+    fn tracker_hands_back_the_mapping_of_a_previous_port() {
+        let mut tracker = MappingTracker::default();
+        assert!(tracker.take_stale(6881).is_none());
+
+        tracker.mapping = Some(PortMapping {
+            method: rustatio_core::MappingMethod::NatPmp,
+            gateway: std::net::Ipv4Addr::new(192, 168, 1, 1).into(),
+            internal_port: 6881,
+            external_port: 40000,
+            lifetime: MAPPING_LIFETIME,
+        });
+        assert!(tracker.take_stale(6881).is_none());
+        assert_eq!(tracker.take_stale(6882).map(|mapping| mapping.external_port), Some(40000));
+        assert!(tracker.mapping.is_none());
+    }
+}
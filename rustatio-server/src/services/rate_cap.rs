@@ -0,0 +1,138 @@
+//! Server-wide ceiling on the combined upload/download rate of all instances.
+//!
+//! Every scheduler tick sums the current rates of running instances and derives
+//! one throttle factor per direction, applied to every instance, so the total
+//! stays under the cap while each instance keeps its share of the traffic.
+
+use super::runtime_config::env_var;
+use super::state::AppState;
+use rustatio_core::{FakerState, RatioFakerHandle};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RateCap {
+    /// Combined upload ceiling in KB/s
+    pub upload_kbps: Option<f64>,
+    /// Combined download ceiling in KB/s
+    pub download_kbps: Option<f64>,
+}
+
+impl RateCap {
+    pub fn from_env() -> Self {
+        let kbps = |name: &str| {
+            env_var(name)
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .filter(|cap| cap.is_finite() && *cap > 0.0)
+        };
+        Self {
+            upload_kbps: kbps("MAX_TOTAL_UPLOAD_KBPS"),
+            download_kbps: kbps("MAX_TOTAL_DOWNLOAD_KBPS"),
+        }
+    }
+}
+
+/// Throttle currently applied to every instance, with the rates it was derived from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateThrottle {
+    pub upload_factor: f64,
+    pub download_factor: f64,
+    /// Combined current upload rate of running instances in KB/s
+    pub total_upload_rate: f64,
+    /// Combined current download rate of running instances in KB/s
+    pub total_download_rate: f64,
+}
+
+impl Default for RateThrottle {
+    fn default() -> Self {
+        Self {
+            upload_factor: 1.0,
+            download_factor: 1.0,
+            total_upload_rate: 0.0,
+            total_download_rate: 0.0,
+        }
+    }
+}
+
+impl RateThrottle {
+    pub const fn is_active(&self) -> bool {
+        self.upload_factor < 1.0 || self.download_factor < 1.0
+    }
+}
+
+/// Factor keeping the combined rate under `cap`. `observed` was produced with
+/// `current_factor` applied, so the unthrottled demand is `observed / current_factor`.
+fn throttle_factor(cap: Option<f64>, observed: f64, current_factor: f64) -> f64 {
+    let Some(cap) = cap else {
+        return 1.0;
+    };
+    let demand = if current_factor > 0.0 { observed / current_factor } else { observed };
+    if demand <= cap {
+        1.0
+    } else {
+        (cap / demand).clamp(0.0, 1.0)
+    }
+}
+
+/// Recompute the throttle from the running instances and apply it to all of them.
+pub async fn enforce_rate_cap(state: &AppState) {
+    let cap = state.rate_cap().await;
+    let fakers: Vec<Arc<RatioFakerHandle>> = {
+        let guard = state.instances.read().await;
+        guard.values().map(|inst| Arc::clone(&inst.faker)).collect()
+    };
+
+    let (total_upload_rate, total_download_rate) = fakers
+        .iter()
+        .map(|faker| faker.stats_snapshot())
+        .filter(|stats| matches!(stats.state, FakerState::Running))
+        .fold((0.0, 0.0), |(up, down), stats| {
+            (up + stats.current_upload_rate, down + stats.current_download_rate)
+        });
+
+    let previous = state.rate_throttle();
+    let throttle = RateThrottle {
+        upload_factor: throttle_factor(cap.upload_kbps, total_upload_rate, previous.upload_factor),
+        download_factor: throttle_factor(
+            cap.download_kbps,
+            total_download_rate,
+            previous.download_factor,
+        ),
+        total_upload_rate,
+        total_download_rate,
+    };
+    state.set_rate_throttle(throttle);
+
+    if throttle.is_active() != previous.is_active() {
+        if throttle.is_active() {
+            tracing::info!(
+                "Rate cap active: throttling upload x{:.3}, download x{:.3}",
+                throttle.upload_factor,
+                throttle.download_factor
+            );
+        } else {
+            tracing::info!("Rate cap no longer limiting instances");
+        }
+    }
+
+    for faker in fakers {
+        faker.set_throttle(throttle.upload_factor, throttle.download_factor).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttle_factor_scales_demand_down_to_cap() {
+        assert_eq!(throttle_factor(None, 5000.0, 1.0), 1.0);
+        assert_eq!(throttle_factor(Some(1000.0), 800.0, 1.0), 1.0);
+        assert_eq!(throttle_factor(Some(1000.0), 4000.0, 1.0), 0.25);
+        // Observed 1000 at factor 0.25 means demand is still 4000
+        assert_eq!(throttle_factor(Some(1000.0), 1000.0, 0.25), 0.25);
+        // Demand dropped to 2000 (500 observed at 0.25)
+        assert_eq!(throttle_factor(Some(1000.0), 500.0, 0.25), 0.5);
+        assert_eq!(throttle_factor(Some(1000.0), 200.0, 0.25), 1.0);
+    }
+}
@@ -0,0 +1,236 @@
+//! Env-driven settings that can be re-read while the server is running.
+//!
+//! `POST /api/admin/reload-config` optionally loads `RELOAD_ENV_FILE` as an overlay
+//! over the process environment, re-applies the settings below and reports env vars
+//! that only take effect after a restart. Settings are read through [`env_var`] so
+//! they see the overlay; the process environment itself is never modified.
+
+use super::decorrelation::DecorrelationConfig;
+use super::rate_cap::RateCap;
+use super::scrape_cache::ScrapeCacheConfig;
+use std::collections::BTreeMap;
+use std::sync::{PoisonError, RwLock};
+
+const DEFAULT_STATS_PRECISION: u32 = 3;
+
+/// Values from the last loaded `RELOAD_ENV_FILE`, taking precedence over the process
+/// environment. An empty value unsets the variable.
+static ENV_OVERLAY: RwLock<BTreeMap<&'static str, String>> = RwLock::new(BTreeMap::new());
+
+/// Current value of an env-driven setting: the env file's value when it sets one,
+/// otherwise the process environment's.
+pub fn env_var(name: &str) -> Option<String> {
+    let overlay = ENV_OVERLAY.read().unwrap_or_else(PoisonError::into_inner);
+    match overlay.get(name) {
+        Some(value) if value.is_empty() => None,
+        Some(value) => Some(value.clone()),
+        None => std::env::var(name).ok(),
+    }
+}
+
+/// Env vars applied live on reload.
+pub const RELOADABLE_VARS: &[&str] = &[
+    "AUTH_TOKEN",
+    "AUTH_TOKENS",
+    "AUTH_TOKENS_FILE",
+    "WATCH_ENABLED",
+    "WATCH_DIR",
+    "WATCH_AUTO_START",
+    "WATCH_MAX_DEPTH",
+    "WATCH_INCLUDE",
+    "WATCH_EXCLUDE",
+    "WATCH_PROCESSED_DIR",
+    "WATCH_DELETE_AFTER_IMPORT",
+    "ANNOUNCE_DECORRELATION",
+    "ANNOUNCE_STAGGER_WINDOW_SECONDS",
+    "ANNOUNCE_RATE_SPREAD_PERCENT",
+    "MAX_INSTANCES_PER_IP",
+    "GLOBAL_MAX_TOTAL_UPLOADED_BYTES",
+    "STATS_PRECISION",
+    "VALIDATE_ON_CREATE",
+    "MAX_TOTAL_UPLOAD_KBPS",
+    "MAX_TOTAL_DOWNLOAD_KBPS",
+    "SCRAPE_CACHE_TTL_SECONDS",
+    "SCRAPE_CACHE_TRACKER_TTLS",
+];
+
+/// Env vars only read at startup.
+pub const RESTART_REQUIRED_VARS: &[&str] = &[
+    "PORT",
+    "AUTH_MAX_FAILURES",
+    "AUTH_FAILURE_WINDOW_SECONDS",
+    "AUTH_RATE_LIMIT_EXEMPT_LOCALHOST",
+    "TRUST_FORWARDED_FOR",
+    "DATA_DIR",
+    "RUST_LOG",
+    "STATE_BACKUP",
+    "RESTORE_RAMP_SECS",
+    "RESTORE_RAMP_DELAY_SECS",
+    "VPN_PORT_SYNC",
+    "VPN_PORT_SYNC_INTERVAL_SECONDS",
+    "VPN_IP_SYNC",
+    "VPN_KILLSWITCH",
+    "VPN_KILLSWITCH_INTERVAL_SECONDS",
+    "TRACKER_PROXY",
+    "DRY_RUN",
+    "NETWORK_RECOVERY",
+    "NETWORK_RECOVERY_INTERVAL_SECONDS",
+    "NETWORK_RECOVERY_PROBE_URL",
+    "WEBHOOK_URL",
+    "WEBHOOK_TEMPLATE",
+    "WEBHOOK_EVENTS",
+    "NOTIFY_FORMAT",
+    "ENABLE_PORT_MAPPING",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeSettings {
+    pub decorrelation: DecorrelationConfig,
+    pub global_upload_cap: Option<u64>,
+    /// Decimals for rate/ratio fields in API responses, `None` for raw values
+    pub stats_precision: Option<u32>,
+    /// Announce new torrents to their tracker before creating the instance
+    pub validate_on_create: bool,
+    /// Combined rate cap from `MAX_TOTAL_UPLOAD_KBPS` / `MAX_TOTAL_DOWNLOAD_KBPS`
+    pub rate_cap: RateCap,
+    /// Reuse of recent scrape results across instances
+    pub scrape_cache: ScrapeCacheConfig,
+}
+
+impl RuntimeSettings {
+    pub fn from_env() -> Self {
+        let global_upload_cap = env_var("GLOBAL_MAX_TOTAL_UPLOADED_BYTES")
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|cap| *cap > 0);
+
+        let stats_precision = match env_var("STATS_PRECISION") {
+            Some(v) if matches!(v.to_ascii_lowercase().as_str(), "off" | "none" | "raw") => None,
+            Some(v) => Some(v.trim().parse::<u32>().map_or(DEFAULT_STATS_PRECISION, |d| d.min(10))),
+            None => Some(DEFAULT_STATS_PRECISION),
+        };
+
+        let validate_on_create = env_var("VALIDATE_ON_CREATE").is_some_and(|v| {
+            matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")
+        });
+
+        Self {
+            decorrelation: DecorrelationConfig::from_env(),
+            global_upload_cap,
+            stats_precision,
+            validate_on_create,
+            rate_cap: RateCap::from_env(),
+            scrape_cache: ScrapeCacheConfig::from_env(),
+        }
+    }
+}
+
+/// Env vars a reload applied and env vars that changed but need a restart.
+#[derive(Debug, Default)]
+pub struct ReloadOutcome {
+    pub applied: Vec<&'static str>,
+    pub restart_required: Vec<&'static str>,
+}
+
+/// Current values of every reloadable and restart-only env var.
+pub type EnvSnapshot = BTreeMap<&'static str, Option<String>>;
+
+pub fn snapshot_env() -> EnvSnapshot {
+    RELOADABLE_VARS.iter().chain(RESTART_REQUIRED_VARS).map(|name| (*name, env_var(name))).collect()
+}
+
+/// Names whose value differs between two snapshots.
+pub fn changed_vars(before: &EnvSnapshot, after: &EnvSnapshot) -> Vec<&'static str> {
+    after
+        .iter()
+        .filter(|(name, value)| before.get(*name) != Some(*value))
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+/// Parse `KEY=VALUE` lines, skipping blanks, comments and unknown keys.
+fn parse_env_file(contents: &str) -> Vec<(&'static str, String)> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let name = RELOADABLE_VARS
+                .iter()
+                .chain(RESTART_REQUIRED_VARS)
+                .find(|name| **name == key.trim())?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            Some((*name, value.to_string()))
+        })
+        .collect()
+}
+
+/// Load `RELOAD_ENV_FILE` as the overlay read by [`env_var`], replacing the previous
+/// one. Returns the file path, or `None` when no env file is configured.
+pub fn load_env_file() -> Result<Option<String>, String> {
+    let Some(path) = std::env::var("RELOAD_ENV_FILE").ok().filter(|p| !p.trim().is_empty()) else {
+        return Ok(None);
+    };
+
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read env file {path}: {e}"))?;
+    set_env_overlay(parse_env_file(&contents).into_iter().collect());
+    Ok(Some(path))
+}
+
+fn set_env_overlay(overlay: BTreeMap<&'static str, String>) {
+    *ENV_OVERLAY.write().unwrap_or_else(PoisonError::into_inner) = overlay;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_file_keeps_known_keys_and_strips_quotes() {
+        let contents = "# comment\nexport AUTH_TOKEN=\"secret\"\nSTATS_PRECISION = 2\n\
+                        UNKNOWN=1\nPORT='9090'\n";
+        let parsed = parse_env_file(contents);
+
+        assert_eq!(
+            parsed,
+            vec![
+                ("AUTH_TOKEN", "secret".to_string()),
+                ("STATS_PRECISION", "2".to_string()),
+                ("PORT", "9090".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn env_overlay_takes_precedence_and_empty_values_unset() {
+        set_env_overlay(
+            [("WEBHOOK_TEMPLATE", "5".to_string()), ("NOTIFY_FORMAT", String::new())]
+                .into_iter()
+                .collect(),
+        );
+        assert_eq!(env_var("WEBHOOK_TEMPLATE").as_deref(), Some("5"));
+        assert_eq!(env_var("NOTIFY_FORMAT"), None);
+
+        set_env_overlay(BTreeMap::new());
+        assert_eq!(env_var("NOTIFY_FORMAT"), std::env::var("NOTIFY_FORMAT").ok());
+    }
+
+    #[test]
+    fn changed_vars_reports_differences_only() {
+        let before: EnvSnapshot =
+            [("PORT", Some("8080".to_string())), ("AUTH_TOKEN", None)].into_iter().collect();
+        let after: EnvSnapshot =
+            [("PORT", Some("8080".to_string())), ("AUTH_TOKEN", Some("token".to_string()))]
+                .into_iter()
+                .collect();
+
+        assert_eq!(changed_vars(&before, &after), vec!["AUTH_TOKEN"]);
+    }
+}
@@ -1,13 +1,21 @@
+use super::cron;
+use super::decorrelation;
+use super::events::{EventBroadcaster, InstanceEvent};
 use super::instance::FakerInstance;
 use super::lifecycle::InstanceLifecycle;
+use super::persistence::now_timestamp;
+use super::rate_cap;
+use super::scrape_batch;
 use super::state::AppState;
-use rustatio_core::logger::set_instance_context_str;
-use rustatio_core::{FakerState, RatioFakerHandle};
-use std::collections::HashMap;
+use rustatio_core::{FakerState, FakerStats, RatioFakerHandle, StopReason};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{mpsc, RwLock};
 
+/// Consecutive tracker retries after which an instance is reported as failing.
+const TRACKER_FAILING_ATTEMPTS: u32 = 3;
+
 pub struct Scheduler {
     shutdown_tx: Option<mpsc::Sender<()>>,
     task_handle: Option<tokio::task::JoinHandle<()>>,
@@ -53,8 +61,14 @@ async fn scheduler_loop(
     mut shutdown_rx: mpsc::Receiver<()>,
 ) {
     let update_interval = Duration::from_secs(5);
+    let stats_interval = Duration::from_secs(1);
     let save_interval = Duration::from_secs(30);
     let mut last_save = std::time::Instant::now();
+    let mut last_cron_minute: Option<i64> = None;
+    let mut multi_scrape_rejected = HashSet::new();
+
+    let mut update_ticker = ticker(update_interval);
+    let mut stats_ticker = ticker(stats_interval);
 
     tracing::info!("Scheduler loop started");
 
@@ -64,8 +78,21 @@ async fn scheduler_loop(
                 tracing::info!("Scheduler received shutdown signal");
                 break;
             }
-            () = tokio::time::sleep(update_interval) => {
+            _ = stats_ticker.tick() => {
+                state.publish_stats().await;
+            }
+            _ = update_ticker.tick() => {
+                scrape_batch::scrape_in_batches(&state, &mut multi_scrape_rejected).await;
                 let dirty = update_instances(&state, &instances).await;
+                run_cron_schedules(&state, &mut last_cron_minute).await;
+
+                state.enforce_global_upload_cap().await;
+                rate_cap::enforce_rate_cap(&state).await;
+
+                let decorrelation = state.decorrelation();
+                if decorrelation.enabled {
+                    decorrelation::stagger_announces(&state, &decorrelation).await;
+                }
 
                 if dirty {
                     if let Err(e) = state.save_state().await {
@@ -86,13 +113,38 @@ async fn scheduler_loop(
     tracing::info!("Scheduler loop stopped");
 }
 
+/// Run cron schedules for every minute since the last run, catching up on minutes
+/// a slow tick skipped (at most an hour back).
+async fn run_cron_schedules(state: &AppState, last_minute: &mut Option<i64>) {
+    let minute = i64::try_from(now_timestamp() / 60).unwrap_or_default();
+    if *last_minute == Some(minute) {
+        return;
+    }
+    let first = last_minute.map_or(minute, |last| (last + 1).max(minute - 59));
+    for unix_minute in first..=minute {
+        cron::run_schedules(state, unix_minute).await;
+    }
+    *last_minute = Some(minute);
+}
+
+/// Interval whose first tick is one `period` from now.
+fn ticker(period: Duration) -> tokio::time::Interval {
+    let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    interval
+}
+
 async fn update_instances(
     state: &AppState,
     instances: &Arc<RwLock<HashMap<String, FakerInstance>>>,
 ) -> bool {
     let items: Vec<(String, Arc<RatioFakerHandle>)> = {
         let guard = instances.read().await;
-        guard.iter().map(|(id, inst)| (id.clone(), Arc::clone(&inst.faker))).collect()
+        guard
+            .iter()
+            .filter(|(_, inst)| inst.enabled)
+            .map(|(id, inst)| (id.clone(), Arc::clone(&inst.faker)))
+            .collect()
     };
 
     let mut dirty = false;
@@ -108,19 +160,11 @@ async fn update_instances(
             continue;
         }
 
-        let label = {
-            let guard = instances.read().await;
-            guard
-                .get(&id)
-                .map(|instance| instance.summary.name.clone())
-                .filter(|name| !name.is_empty())
-                .unwrap_or_else(|| id.clone())
-        };
-        set_instance_context_str(Some(&label));
         let result = if should_retry {
             state.recover_tracker_instance(&id).await.map(|_| ())
         } else {
-            faker.update().await.map_err(|e| e.to_string())
+            let update = async { faker.update().await.map_err(|e| e.to_string()) };
+            state.in_log_context(&id, update).await
         };
         if let Err(e) = result {
             let action = if should_retry { "tracker recovery" } else { "update" };
@@ -129,6 +173,12 @@ async fn update_instances(
         }
 
         let after = faker.stats_snapshot();
+        if after.announce_count != before.announce_count {
+            state.invalidate_scrape_cache(&id).await;
+        }
+        for event in transition_events(&id, &before, &after) {
+            state.emit_instance_event(event);
+        }
         {
             let mut guard = instances.write().await;
             if let Some(instance) = guard.get_mut(&id) {
@@ -151,3 +201,26 @@ async fn update_instances(
 
     dirty
 }
+
+/// Events for a stop condition being reached or the tracker failing repeatedly
+/// during one update.
+fn transition_events(id: &str, before: &FakerStats, after: &FakerStats) -> Vec<InstanceEvent> {
+    let mut events = Vec::new();
+    if after.stop_condition_met && !before.stop_condition_met {
+        let condition = match &after.stop_reason {
+            Some(StopReason::StopConditionMet { which }) => which.clone(),
+            _ => "stop condition".to_string(),
+        };
+        events.push(InstanceEvent::StopConditionMet { id: id.to_string(), condition });
+    }
+    if before.tracker_retry_attempt < TRACKER_FAILING_ATTEMPTS
+        && after.tracker_retry_attempt >= TRACKER_FAILING_ATTEMPTS
+    {
+        events.push(InstanceEvent::TrackerFailing {
+            id: id.to_string(),
+            attempts: after.tracker_retry_attempt,
+            error: after.tracker_error.clone().unwrap_or_default(),
+        });
+    }
+    events
+}
@@ -0,0 +1,256 @@
+//! Multi-torrent scrapes (BEP 48) for instances sharing a tracker.
+//!
+//! Every scheduler tick, before instances update, the scrapes that are due are
+//! answered from the scrape cache when it holds a recent result, and otherwise
+//! grouped by tracker and sent as one request per tracker. Each instance gets its
+//! own entry back as if it had scraped alone, and instances of the same torrent share
+//! one answer. Trackers that refuse a multi-hash scrape get one request per torrent;
+//! torrents missing from an answer scrape in their own update.
+
+use super::state::AppState;
+use rustatio_core::protocol::{ScrapeResponse, TrackerClient, TrackerError};
+use rustatio_core::{RatioFakerHandle, ScrapeTarget};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Instances whose scrape can share one request: same tracker, same emulated client
+/// and same proxy, so the tracker sees exactly what each would have sent.
+type GroupKey = (String, String, Option<String>);
+
+struct DueScrape {
+    id: String,
+    faker: Arc<RatioFakerHandle>,
+    target: ScrapeTarget,
+}
+
+fn group_key(target: &ScrapeTarget) -> GroupKey {
+    let client = target.tracker_client.client_config();
+    (target.tracker_url.clone(), client.user_agent.clone(), client.proxy_url.clone())
+}
+
+/// Group due scrapes that can share a request.
+fn groups(due: Vec<DueScrape>) -> Vec<Vec<DueScrape>> {
+    let mut groups: HashMap<GroupKey, Vec<DueScrape>> = HashMap::new();
+    for scrape in due {
+        groups.entry(group_key(&scrape.target)).or_default().push(scrape);
+    }
+    groups.into_values().collect()
+}
+
+/// Info hashes of a group, each once, in the order instances were found.
+fn distinct_hashes(group: &[DueScrape]) -> Vec<[u8; 20]> {
+    let mut seen = HashSet::new();
+    group.iter().map(|s| s.target.info_hash).filter(|hash| seen.insert(*hash)).collect()
+}
+
+/// Errors meaning the tracker understood the request but won't serve it, as opposed
+/// to the tracker being unreachable.
+fn rejects_multi_hash(error: &TrackerError) -> bool {
+    match error {
+        TrackerError::TrackerFailure(_)
+        | TrackerError::InvalidResponse(_)
+        | TrackerError::BencodeError(_) => true,
+        TrackerError::HttpError(message) => message.starts_with("HTTP status"),
+        TrackerError::UrlError(_) | TrackerError::Ipv6Unreachable(_) => false,
+    }
+}
+
+/// Scrape every torrent of a group at once. `None` when the tracker failed or refused,
+/// in which case refusing trackers are added to `rejected`.
+async fn scrape_many(
+    tracker_client: &TrackerClient,
+    tracker_url: &str,
+    info_hashes: &[[u8; 20]],
+    rejected: &mut HashSet<String>,
+) -> Option<HashMap<[u8; 20], ScrapeResponse>> {
+    match tracker_client.scrape_many(tracker_url, info_hashes).await {
+        Ok(answers) if answers.len() > 1 => Some(answers),
+        Ok(_) => {
+            tracing::info!(
+                "Tracker {} ignored a multi-hash scrape, scraping its torrents one by one",
+                tracker_url
+            );
+            rejected.insert(tracker_url.to_string());
+            None
+        }
+        Err(e) if rejects_multi_hash(&e) => {
+            tracing::info!(
+                "Tracker {} rejected a multi-hash scrape, scraping its torrents one by one: {}",
+                tracker_url,
+                e
+            );
+            rejected.insert(tracker_url.to_string());
+            None
+        }
+        Err(e) => {
+            tracing::debug!("Multi-hash scrape of {} failed: {}", tracker_url, e);
+            None
+        }
+    }
+}
+
+/// Apply a scrape result to an instance as if its own update had scraped, dropping the
+/// cached result when the scrape made it announce.
+async fn apply(state: &AppState, scrape: &DueScrape, result: Result<ScrapeResponse, TrackerError>) {
+    let announces = scrape.faker.stats_snapshot().announce_count;
+    let apply = async {
+        scrape.faker.apply_scrape(result.map_err(Into::into)).await;
+        Ok(())
+    };
+    let _ = state.in_log_context(&scrape.id, apply).await;
+    if scrape.faker.stats_snapshot().announce_count != announces {
+        state.scrape_cache().invalidate(&scrape.target.tracker_url, &scrape.target.info_hash);
+    }
+}
+
+/// Answer the due scrapes of running instances from the scrape cache, or with one
+/// request per tracker. Trackers that reject a multi-hash scrape are added to
+/// `rejected` and get one request per torrent from then on.
+pub async fn scrape_in_batches(state: &AppState, rejected: &mut HashSet<String>) {
+    let fakers: Vec<(String, Arc<RatioFakerHandle>)> = {
+        let guard = state.instances.read().await;
+        guard
+            .iter()
+            .filter(|(_, inst)| inst.enabled)
+            .map(|(id, inst)| (id.clone(), Arc::clone(&inst.faker)))
+            .collect()
+    };
+
+    let cache_config = state.scrape_cache_config();
+    let cache = state.scrape_cache();
+    let now = Instant::now();
+    cache.prune(&cache_config, now);
+
+    let mut due = Vec::new();
+    for (id, faker) in fakers {
+        let Some(target) = faker.due_scrape().await else {
+            continue;
+        };
+        let ttl = cache_config.ttl_for(&target.tracker_url);
+        let scrape = DueScrape { id, faker, target };
+        match cache.get(&scrape.target.tracker_url, &scrape.target.info_hash, ttl, now) {
+            Some(response) => apply(state, &scrape, Ok(response)).await,
+            None => due.push(scrape),
+        }
+    }
+
+    for group in groups(due) {
+        let tracker_url = group[0].target.tracker_url.clone();
+        let tracker_client = Arc::clone(&group[0].target.tracker_client);
+        let cacheable = !cache_config.ttl_for(&tracker_url).is_zero();
+        let info_hashes = distinct_hashes(&group);
+
+        if info_hashes.len() > 1 && !rejected.contains(&tracker_url) {
+            let Some(answers) =
+                scrape_many(&tracker_client, &tracker_url, &info_hashes, rejected).await
+            else {
+                continue;
+            };
+            if cacheable {
+                for (info_hash, response) in &answers {
+                    cache.insert(&tracker_url, info_hash, response.clone(), now);
+                }
+            }
+            for scrape in &group {
+                if let Some(response) = answers.get(&scrape.target.info_hash) {
+                    apply(state, scrape, Ok(response.clone())).await;
+                }
+            }
+            continue;
+        }
+
+        for info_hash in info_hashes {
+            let mut sharing = group.iter().filter(|s| s.target.info_hash == info_hash);
+            let Some(first) = sharing.next() else {
+                continue;
+            };
+            let response = match tracker_client.scrape(&tracker_url, &info_hash).await {
+                Ok(response) => response,
+                Err(e) => {
+                    // Instances sharing the torrent retry on their own schedule
+                    apply(state, first, Err(e)).await;
+                    continue;
+                }
+            };
+            if cacheable {
+                cache.insert(&tracker_url, &info_hash, response.clone(), now);
+            }
+            apply(state, first, Ok(response.clone())).await;
+            for scrape in sharing {
+                apply(state, scrape, Ok(response.clone())).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustatio_core::protocol::TrackerClient;
+    use rustatio_core::{ClientConfig, ClientType, FakerConfig, RatioFaker, TorrentInfo};
+
+    fn due(id: &str, tracker_url: &str, client_type: ClientType) -> DueScrape {
+        let client_config = ClientConfig::get(client_type, None);
+        let tracker_client =
+            TrackerClient::new(client_config, None).unwrap_or_else(|e| panic!("{e}"));
+        let torrent = TorrentInfo::default();
+        let faker = RatioFaker::new(Arc::new(torrent), FakerConfig::default(), None)
+            .unwrap_or_else(|e| panic!("{e}"));
+        DueScrape {
+            id: id.to_string(),
+            faker: Arc::new(RatioFakerHandle::new(faker)),
+            target: ScrapeTarget {
+                tracker_client: Arc::new(tracker_client),
+                tracker_url: tracker_url.to_string(),
+                info_hash: [id.as_bytes()[0]; 20],
+            },
+        }
+    }
+
+    fn ids(groups: &[Vec<DueScrape>]) -> Vec<Vec<&str>> {
+        let mut ids: Vec<Vec<&str>> = groups
+            .iter()
+            .map(|group| {
+                let mut ids: Vec<&str> = group.iter().map(|s| s.id.as_str()).collect();
+                ids.sort_unstable();
+                ids
+            })
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn groups_split_by_tracker_and_client() {
+        let shared = "https://shared.test/announce";
+        let single = "https://single.test/announce";
+        let due = vec![
+            due("1", shared, ClientType::QBittorrent),
+            due("2", shared, ClientType::QBittorrent),
+            due("3", shared, ClientType::Transmission),
+            due("4", single, ClientType::QBittorrent),
+        ];
+
+        assert_eq!(ids(&groups(due)), vec![vec!["1", "2"], vec!["3"], vec!["4"]]);
+    }
+
+    #[test]
+    fn instances_of_one_torrent_share_its_hash() {
+        let url = "https://shared.test/announce";
+        let group = vec![
+            due("1", url, ClientType::QBittorrent),
+            due("2", url, ClientType::QBittorrent),
+            due("1", url, ClientType::QBittorrent),
+        ];
+
+        assert_eq!(distinct_hashes(&group), vec![[b'1'; 20], [b'2'; 20]]);
+    }
+
+    #[test]
+    fn only_tracker_answers_count_as_rejecting_multi_hash() {
+        assert!(rejects_multi_hash(&TrackerError::TrackerFailure("no".into())));
+        assert!(rejects_multi_hash(&TrackerError::HttpError("HTTP status: 400".into())));
+        assert!(!rejects_multi_hash(&TrackerError::HttpError("timed out".into())));
+    }
+}
@@ -0,0 +1,224 @@
+//! Short-lived cache of scrape results shared by instances on the same tracker.
+//!
+//! Grids often run several instances of one torrent against one tracker, and each
+//! would scrape it on its own schedule. The scheduler answers a due scrape from a
+//! recent result for the same tracker URL and info hash instead, and drops that
+//! result as soon as one of those instances announces, since the swarm it described
+//! has changed.
+
+use super::runtime_config::env_var;
+use rustatio_core::primary_tracker_host;
+use rustatio_core::protocol::ScrapeResponse;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+const DEFAULT_TTL_SECS: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScrapeCacheConfig {
+    /// How long a scrape result is reused, zero to disable the cache
+    pub ttl: Duration,
+    /// TTL overrides by tracker host
+    pub tracker_ttls: HashMap<String, Duration>,
+}
+
+impl Default for ScrapeCacheConfig {
+    fn default() -> Self {
+        Self { ttl: Duration::from_secs(DEFAULT_TTL_SECS), tracker_ttls: HashMap::new() }
+    }
+}
+
+impl ScrapeCacheConfig {
+    pub fn from_env() -> Self {
+        let ttl = env_var("SCRAPE_CACHE_TTL_SECONDS")
+            .and_then(|v| {
+                let secs = v.trim().parse::<u64>().ok();
+                if secs.is_none() {
+                    tracing::warn!("Ignoring invalid SCRAPE_CACHE_TTL_SECONDS value: {:?}", v);
+                }
+                secs
+            })
+            .unwrap_or(DEFAULT_TTL_SECS);
+        let tracker_ttls = env_var("SCRAPE_CACHE_TRACKER_TTLS")
+            .map(|v| parse_tracker_ttls(&v))
+            .unwrap_or_default();
+        Self { ttl: Duration::from_secs(ttl), tracker_ttls }
+    }
+
+    /// TTL for results from `tracker_url`, zero when they must not be cached.
+    pub fn ttl_for(&self, tracker_url: &str) -> Duration {
+        primary_tracker_host(tracker_url)
+            .and_then(|host| self.tracker_ttls.get(&host).copied())
+            .unwrap_or(self.ttl)
+    }
+}
+
+/// Parse `host=seconds` pairs separated by commas, skipping malformed ones with a warning.
+fn parse_tracker_ttls(value: &str) -> HashMap<String, Duration> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .filter_map(|pair| {
+            let parsed = pair.split_once('=').and_then(|(host, secs)| {
+                let host = primary_tracker_host(host.trim())?;
+                let secs = secs.trim().parse::<u64>().ok()?;
+                Some((host, Duration::from_secs(secs)))
+            });
+            if parsed.is_none() {
+                tracing::warn!("Ignoring invalid SCRAPE_CACHE_TRACKER_TTLS entry: {:?}", pair);
+            }
+            parsed
+        })
+        .collect()
+}
+
+/// Cache hit and miss counts since startup, for debugging.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ScrapeCacheStats {
+    /// Due scrapes answered from the cache
+    pub hits: u64,
+    /// Due scrapes that had to reach the tracker
+    pub misses: u64,
+    /// Results currently cached
+    pub entries: usize,
+}
+
+type CacheKey = (String, [u8; 20]);
+
+struct CachedScrape {
+    response: ScrapeResponse,
+    fetched_at: Instant,
+}
+
+#[derive(Default)]
+pub struct ScrapeCache {
+    entries: Mutex<HashMap<CacheKey, CachedScrape>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ScrapeCache {
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<CacheKey, CachedScrape>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// A result for `tracker_url` and `info_hash` fetched less than `ttl` before `now`.
+    /// Counts a hit or a miss unless `ttl` is zero.
+    pub fn get(
+        &self,
+        tracker_url: &str,
+        info_hash: &[u8; 20],
+        ttl: Duration,
+        now: Instant,
+    ) -> Option<ScrapeResponse> {
+        if ttl.is_zero() {
+            return None;
+        }
+        let cached = self
+            .entries()
+            .get(&(tracker_url.to_string(), *info_hash))
+            .filter(|cached| now.saturating_duration_since(cached.fetched_at) < ttl)
+            .map(|cached| cached.response.clone());
+        let counter = if cached.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        cached
+    }
+
+    pub fn insert(
+        &self,
+        tracker_url: &str,
+        info_hash: &[u8; 20],
+        response: ScrapeResponse,
+        now: Instant,
+    ) {
+        let cached = CachedScrape { response, fetched_at: now };
+        self.entries().insert((tracker_url.to_string(), *info_hash), cached);
+    }
+
+    /// Forget the result for a torrent on a tracker, e.g. after one of its instances
+    /// announced.
+    pub fn invalidate(&self, tracker_url: &str, info_hash: &[u8; 20]) {
+        self.entries().remove(&(tracker_url.to_string(), *info_hash));
+    }
+
+    /// Drop results older than their tracker's TTL.
+    pub fn prune(&self, config: &ScrapeCacheConfig, now: Instant) {
+        self.entries().retain(|(tracker_url, _), cached| {
+            now.saturating_duration_since(cached.fetched_at) < config.ttl_for(tracker_url)
+        });
+    }
+
+    pub fn stats(&self) -> ScrapeCacheStats {
+        ScrapeCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries().len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://tracker.test/announce?passkey=abc";
+
+    fn response(complete: i64) -> ScrapeResponse {
+        ScrapeResponse { complete, incomplete: 1, downloaded: 0, name: None }
+    }
+
+    #[test]
+    fn results_are_reused_until_ttl_or_invalidation() {
+        let cache = ScrapeCache::default();
+        let ttl = Duration::from_mins(1);
+        let start = Instant::now();
+        let hash = [1; 20];
+
+        assert!(cache.get(URL, &hash, ttl, start).is_none());
+        cache.insert(URL, &hash, response(4), start);
+
+        let hit = cache.get(URL, &hash, ttl, start + Duration::from_secs(30));
+        assert_eq!(hit.map(|r| r.complete), Some(4));
+        assert!(cache.get(URL, &[2; 20], ttl, start).is_none());
+        assert!(cache.get(URL, &hash, ttl, start + ttl).is_none());
+
+        cache.invalidate(URL, &hash);
+        assert!(cache.get(URL, &hash, ttl, start).is_none());
+        assert_eq!(cache.stats(), ScrapeCacheStats { hits: 1, misses: 4, entries: 0 });
+    }
+
+    #[test]
+    fn zero_ttl_disables_the_cache_without_counting() {
+        let cache = ScrapeCache::default();
+        let now = Instant::now();
+        cache.insert(URL, &[1; 20], response(4), now);
+
+        assert!(cache.get(URL, &[1; 20], Duration::ZERO, now).is_none());
+        assert_eq!(cache.stats().misses, 0);
+    }
+
+    #[test]
+    fn tracker_ttls_override_the_default_by_host() {
+        let config = ScrapeCacheConfig {
+            ttl: Duration::from_mins(1),
+            tracker_ttls: parse_tracker_ttls("Tracker.Test=300, other.test=0, broken, x=y"),
+        };
+
+        assert_eq!(config.tracker_ttls.len(), 2);
+        assert_eq!(config.ttl_for(URL), Duration::from_mins(5));
+        assert_eq!(config.ttl_for("udp://other.test:6969/announce"), Duration::ZERO);
+        assert_eq!(config.ttl_for("https://else.test/announce"), Duration::from_mins(1));
+
+        let cache = ScrapeCache::default();
+        let start = Instant::now();
+        cache.insert(URL, &[1; 20], response(1), start);
+        cache.insert("https://else.test/announce", &[1; 20], response(1), start);
+        cache.prune(&config, start + Duration::from_mins(2));
+        assert_eq!(cache.stats().entries, 1);
+    }
+}
@@ -1,37 +1,117 @@
-use super::events::{EventBroadcaster, InstanceEvent, LogEvent};
-use super::instance::{FakerInstance, InstanceInfo};
+use super::bundle::{BundledInstance, ConfigBundle, ImportMode, ImportReport, BUNDLE_VERSION};
+use super::cron::InstanceSchedule;
+use super::decorrelation::DecorrelationConfig;
+use super::events::{EventBroadcaster, InstanceEvent, InstanceStats, LogEvent, StatsEvent};
+use super::import_jobs::ImportJobs;
+use super::instance::{
+    distinct_tags, FakerInstance, InstanceInfo, InstanceQuery, InstanceTotals, TagCount, TagFilter,
+    TagTotals,
+};
+use super::instance_logs::InstanceLogs;
 use super::lifecycle::InstanceLifecycle;
 use super::persistence::{
-    now_timestamp, CustomPreset, DefaultPreset, InstanceSource, PersistedInstance,
-    PersistedRuntime, PersistedState, Persistence, WatchSettings,
+    history_tail, now_timestamp, CustomPreset, DefaultPreset, InstanceSource, PersistedInstance,
+    PersistedRuntime, PersistedState, Persistence, SavedStateCheck, WatchSettings,
+};
+use super::rate_cap::{RateCap, RateThrottle};
+use super::runtime_config::{
+    changed_vars, snapshot_env, EnvSnapshot, ReloadOutcome, RuntimeSettings, RESTART_REQUIRED_VARS,
 };
-use rustatio_core::logger::set_instance_context_str;
+use super::scrape_cache::{ScrapeCache, ScrapeCacheConfig};
+use super::tracker_check::{check_tracker, TrackerCheck};
+use super::upload_cap::UploadCapMeter;
+use super::vpn_killswitch::VpnKillswitchConfig;
+use rustatio_core::logger::{with_instance_context, InstanceLogContext};
+use rustatio_core::protocol::TrackerError;
 use rustatio_core::{
-    primary_tracker_host, FakerConfig, FakerState, FakerStats, InstanceSummary,
-    PeerListenerService, PeerListenerStatus, RatioFaker, RatioFakerHandle, TorrentInfo,
+    FakerConfig, FakerError, FakerState, FakerStats, InstanceSummary, LastAnnounce,
+    PeerListenerService, PeerListenerStatus, RatioFaker, RatioFakerHandle, StopReason, TorrentInfo,
     TorrentSummary,
 };
-use std::collections::{BTreeSet, HashMap};
-use std::sync::atomic::{AtomicU16, Ordering};
-use std::sync::Arc;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::sync::{Arc, PoisonError};
 use std::time::Duration;
 use tokio::sync::{broadcast, Mutex, RwLock};
 
 type PeerListenerHandle = Arc<Mutex<PeerListenerService>>;
 
+/// Delay between restored auto-starts: a fixed per-instance delay, or a total window
+/// spread evenly across all instances.
+fn restore_ramp_spacing(
+    delay_secs: Option<f64>,
+    window_secs: Option<f64>,
+    count: usize,
+) -> Duration {
+    let secs = match (delay_secs, window_secs) {
+        (Some(delay), _) => delay,
+        (None, Some(window)) if count > 1 => window / (count - 1) as f64,
+        _ => return Duration::ZERO,
+    };
+    Duration::try_from_secs_f64(secs).unwrap_or(Duration::ZERO)
+}
+
+const KILLSWITCH_REASON: &str = "vpn_killswitch";
+const MAX_LABEL_CHARS: usize = 200;
+/// Longest restore ramp delay or window accepted, one day
+const MAX_RESTORE_RAMP_SECS: f64 = 86_400.0;
+
+fn restore_ramp_from_env(count: usize) -> Duration {
+    let secs = |name: &str| {
+        let value = std::env::var(name).ok()?;
+        match value.trim().parse::<f64>() {
+            Ok(secs) if secs.is_finite() && secs > 0.0 => {
+                if secs > MAX_RESTORE_RAMP_SECS {
+                    tracing::warn!(
+                        "{} of {}s is too long, using {}s",
+                        name,
+                        secs,
+                        MAX_RESTORE_RAMP_SECS
+                    );
+                }
+                Some(secs.min(MAX_RESTORE_RAMP_SECS))
+            }
+            _ => {
+                tracing::warn!("Ignoring invalid {} value: {:?}", name, value);
+                None
+            }
+        }
+    };
+    restore_ramp_spacing(secs("RESTORE_RAMP_DELAY_SECS"), secs("RESTORE_RAMP_SECS"), count)
+}
+
 #[derive(Clone)]
 pub struct AppState {
     pub instances: Arc<RwLock<HashMap<String, FakerInstance>>>,
     pub log_sender: broadcast::Sender<LogEvent>,
+    pub instance_logs: Arc<InstanceLogs>,
     pub instance_sender: broadcast::Sender<InstanceEvent>,
+    pub stats_sender: broadcast::Sender<Arc<StatsEvent>>,
     persistence: Arc<Persistence>,
     default_config: Arc<RwLock<Option<FakerConfig>>>,
     default_preset: Arc<RwLock<Option<DefaultPreset>>>,
     watch_settings: Arc<RwLock<Option<WatchSettings>>>,
     custom_presets: Arc<RwLock<Vec<CustomPreset>>>,
     http_client: reqwest::Client,
+    /// `TRACKER_PROXY`, used by every instance without a proxy of its own
+    tracker_proxy: Option<String>,
     forwarded_port: Arc<AtomicU16>,
+    vpn_public_ip: Arc<std::sync::RwLock<Option<IpAddr>>>,
     server_vpn_port_sync: bool,
+    server_vpn_killswitch: bool,
+    /// `DRY_RUN`: no faker contacts a tracker, whatever its own config says
+    server_dry_run: bool,
+    /// Instances paused by the kill-switch; `Some` while it is engaged
+    killswitch_paused: Arc<std::sync::Mutex<Option<Vec<String>>>>,
+    runtime: Arc<std::sync::RwLock<RuntimeSettings>>,
+    reload_baseline: Arc<std::sync::Mutex<EnvSnapshot>>,
+    upload_cap: Arc<std::sync::Mutex<UploadCapMeter>>,
+    upload_cap_reached: Arc<AtomicBool>,
+    rate_cap_override: Arc<RwLock<Option<RateCap>>>,
+    rate_throttle: Arc<std::sync::RwLock<RateThrottle>>,
+    scrape_cache: Arc<ScrapeCache>,
+    import_jobs: ImportJobs,
     peer_listener: Arc<RwLock<Option<PeerListenerHandle>>>,
     peer_listener_status: Arc<RwLock<PeerListenerStatus>>,
 }
@@ -40,6 +120,7 @@ pub struct InstanceBuildContext {
     id: String,
     torrent: Arc<TorrentInfo>,
     summary: Arc<TorrentSummary>,
+    torrent_file: Option<Arc<[u8]>>,
     config: FakerConfig,
     source: InstanceSource,
 }
@@ -52,7 +133,21 @@ impl InstanceBuildContext {
         source: InstanceSource,
     ) -> Self {
         let summary = Arc::new(torrent.summary());
-        Self { id: id.to_string(), torrent: Arc::new(torrent), summary, config, source }
+        Self {
+            id: id.to_string(),
+            torrent: Arc::new(torrent),
+            summary,
+            torrent_file: None,
+            config,
+            source,
+        }
+    }
+
+    /// Keep the `.torrent` the instance was parsed from, for bundle exports.
+    #[must_use]
+    pub fn with_torrent_file(mut self, bytes: Vec<u8>) -> Self {
+        self.torrent_file = Some(bytes.into());
+        self
     }
 }
 
@@ -62,27 +157,72 @@ struct ExistingInstanceState {
     created_at: u64,
     source: InstanceSource,
     tags: Vec<String>,
+    enabled: bool,
+    schedule: Option<InstanceSchedule>,
+    label: Option<String>,
     completion_percent: Option<f64>,
+    torrent_file: Option<Arc<[u8]>>,
+}
+
+impl ExistingInstanceState {
+    fn fresh(source: InstanceSource) -> Self {
+        Self {
+            cumulative_uploaded: 0,
+            cumulative_downloaded: 0,
+            created_at: now_timestamp(),
+            source,
+            tags: Vec::new(),
+            enabled: true,
+            schedule: None,
+            label: None,
+            completion_percent: None,
+            torrent_file: None,
+        }
+    }
 }
 
 impl AppState {
     pub fn new(data_dir: &str) -> Self {
         let (log_sender, _) = broadcast::channel(256);
         let (instance_sender, _) = broadcast::channel(1024);
+        let (stats_sender, _) = broadcast::channel(16);
+        // `main` refuses to start with an invalid TRACKER_PROXY
+        let tracker_proxy = tracker_proxy_from_env().ok().flatten();
         Self {
             instances: Arc::new(RwLock::new(HashMap::new())),
             log_sender,
+            instance_logs: Arc::new(InstanceLogs::default()),
             instance_sender,
+            stats_sender,
             persistence: Arc::new(Persistence::new(data_dir)),
             default_config: Arc::new(RwLock::new(None)),
             default_preset: Arc::new(RwLock::new(None)),
             watch_settings: Arc::new(RwLock::new(None)),
             custom_presets: Arc::new(RwLock::new(Vec::new())),
-            http_client: reqwest::Client::new(),
+            http_client: tracker_http_client(tracker_proxy.as_deref()).unwrap_or_else(|e| {
+                // Instances still carry the proxy in their own config
+                tracing::warn!("Shared tracker client ignores TRACKER_PROXY: {}", e);
+                reqwest::Client::new()
+            }),
+            tracker_proxy,
             forwarded_port: Arc::new(AtomicU16::new(0)),
+            vpn_public_ip: Arc::new(std::sync::RwLock::new(None)),
             server_vpn_port_sync: std::env::var("VPN_PORT_SYNC").is_ok_and(|v| {
                 matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")
             }),
+            server_vpn_killswitch: VpnKillswitchConfig::from_env().enabled,
+            server_dry_run: std::env::var("DRY_RUN").is_ok_and(|v| {
+                matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")
+            }),
+            killswitch_paused: Arc::new(std::sync::Mutex::new(None)),
+            runtime: Arc::new(std::sync::RwLock::new(RuntimeSettings::from_env())),
+            reload_baseline: Arc::new(std::sync::Mutex::new(snapshot_env())),
+            upload_cap: Arc::new(std::sync::Mutex::new(UploadCapMeter::default())),
+            upload_cap_reached: Arc::new(AtomicBool::new(false)),
+            rate_cap_override: Arc::new(RwLock::new(None)),
+            rate_throttle: Arc::new(std::sync::RwLock::new(RateThrottle::default())),
+            scrape_cache: Arc::new(ScrapeCache::default()),
+            import_jobs: ImportJobs::default(),
             peer_listener: Arc::new(RwLock::new(None)),
             peer_listener_status: Arc::new(RwLock::new(PeerListenerStatus::default())),
         }
@@ -99,10 +239,376 @@ impl AppState {
         self.forwarded_port.store(port.unwrap_or(0), Ordering::Relaxed);
     }
 
+    pub fn current_vpn_ip(&self) -> Option<IpAddr> {
+        *self.vpn_public_ip.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn set_current_vpn_ip(&self, ip: Option<IpAddr>) {
+        *self.vpn_public_ip.write().unwrap_or_else(PoisonError::into_inner) = ip;
+    }
+
     pub const fn vpn_port_sync_enabled(&self) -> bool {
         self.server_vpn_port_sync
     }
 
+    pub const fn dry_run_enabled(&self) -> bool {
+        self.server_dry_run
+    }
+
+    /// The config a faker runs with: `DRY_RUN` and the detected VPN IP are applied here
+    /// rather than written into instance configs, so instances go back to their own
+    /// settings once either goes away.
+    fn faker_config(&self, mut config: FakerConfig) -> FakerConfig {
+        config.dry_run |= self.server_dry_run;
+        if config.auto_announce_vpn_ip {
+            if let Some(ip) = self.current_vpn_ip() {
+                set_announce_ip(&mut config, ip);
+            }
+        }
+        // Announces bypass the shared client unless the faker knows about the proxy
+        if config.proxy_url.as_deref().is_none_or(|url| url.trim().is_empty()) {
+            config.proxy_url.clone_from(&self.tracker_proxy);
+        }
+        config
+    }
+
+    pub const fn vpn_killswitch_enabled(&self) -> bool {
+        self.server_vpn_killswitch
+    }
+
+    pub fn killswitch_engaged(&self) -> bool {
+        self.killswitch_paused.lock().unwrap_or_else(PoisonError::into_inner).is_some()
+    }
+
+    /// Pause every active instance because the VPN went down and remember them for
+    /// `release_killswitch`. Hit-and-run protection is bypassed and no `stopped` is
+    /// announced: nothing may reach a tracker outside the tunnel. Starts and resumes
+    /// stay blocked until the VPN is back.
+    pub async fn engage_killswitch(&self) -> usize {
+        {
+            let mut engaged = self.killswitch_paused.lock().unwrap_or_else(PoisonError::into_inner);
+            if engaged.is_some() {
+                return 0;
+            }
+            *engaged = Some(Vec::new());
+        }
+
+        let active: Vec<(String, Arc<RatioFakerHandle>)> = {
+            let instances = self.instances.read().await;
+            instances
+                .iter()
+                .filter(|(_, instance)| {
+                    matches!(
+                        instance.faker.stats_snapshot().state,
+                        FakerState::Starting | FakerState::Running
+                    )
+                })
+                .map(|(id, instance)| (id.clone(), Arc::clone(&instance.faker)))
+                .collect()
+        };
+
+        let mut paused = Vec::with_capacity(active.len());
+        for (id, faker) in active {
+            // The tunnel is down, so a `stopped` announce would go out from the real IP
+            faker.pause_silently().await;
+            tracing::warn!("VPN kill-switch paused instance {}", id);
+            self.emit_instance_event(InstanceEvent::Paused {
+                id: id.clone(),
+                reason: KILLSWITCH_REASON.to_string(),
+            });
+            paused.push(id);
+        }
+
+        let count = paused.len();
+        *self.killswitch_paused.lock().unwrap_or_else(PoisonError::into_inner) = Some(paused);
+        if let Err(e) = self.save_state().await {
+            tracing::warn!("Failed to save state after VPN kill-switch: {}", e);
+        }
+        self.refresh_peer_listener_port().await;
+        count
+    }
+
+    /// Resume the instances the kill-switch paused, skipping any that were stopped or
+    /// deleted meanwhile. Returns the number of resumed instances.
+    pub async fn release_killswitch(&self) -> usize {
+        let paused = self.killswitch_paused.lock().unwrap_or_else(PoisonError::into_inner).take();
+        let Some(ids) = paused else {
+            return 0;
+        };
+
+        let mut resumed = 0;
+        for id in ids {
+            let still_paused = {
+                let instances = self.instances.read().await;
+                instances.get(&id).is_some_and(|instance| {
+                    matches!(instance.faker.stats_snapshot().state, FakerState::Paused)
+                })
+            };
+            if !still_paused {
+                continue;
+            }
+            match self.resume_instance(&id).await {
+                Ok(()) => {
+                    tracing::info!("VPN kill-switch resumed instance {}", id);
+                    self.emit_instance_event(InstanceEvent::Resumed {
+                        id,
+                        reason: KILLSWITCH_REASON.to_string(),
+                    });
+                    resumed += 1;
+                }
+                Err(e) => tracing::warn!("VPN kill-switch failed to resume instance {}: {}", id, e),
+            }
+        }
+        resumed
+    }
+
+    pub fn runtime_settings(&self) -> RuntimeSettings {
+        self.runtime.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    pub fn set_runtime_settings(&self, settings: RuntimeSettings) {
+        *self.runtime.write().unwrap_or_else(PoisonError::into_inner) = settings;
+    }
+
+    pub fn decorrelation(&self) -> DecorrelationConfig {
+        self.runtime_settings().decorrelation
+    }
+
+    pub fn global_upload_cap(&self) -> Option<u64> {
+        self.runtime_settings().global_upload_cap
+    }
+
+    pub fn stats_precision(&self) -> Option<u32> {
+        self.runtime_settings().stats_precision
+    }
+
+    pub fn validate_on_create(&self) -> bool {
+        self.runtime_settings().validate_on_create
+    }
+
+    /// Time-boxed reachability check of a torrent's primary tracker
+    pub async fn check_tracker(&self, torrent: &TorrentSummary) -> TrackerCheck {
+        if self.server_dry_run {
+            return TrackerCheck::skipped(&torrent.announce, "Dry run: tracker not contacted");
+        }
+        check_tracker(self.http_client.clone(), &torrent.announce, &torrent.info_hash).await
+    }
+
+    /// Re-read env-driven settings. Reloadable env vars that changed since the last
+    /// reload are applied; restart-only vars that changed since startup are reported.
+    pub async fn reload_runtime_settings(&self) -> ReloadOutcome {
+        let current = snapshot_env();
+        let mut outcome = ReloadOutcome::default();
+        {
+            let mut baseline = self.reload_baseline.lock().unwrap_or_else(PoisonError::into_inner);
+            for name in changed_vars(&baseline, &current) {
+                if RESTART_REQUIRED_VARS.contains(&name) {
+                    outcome.restart_required.push(name);
+                } else {
+                    baseline.insert(name, current.get(name).cloned().flatten());
+                    outcome.applied.push(name);
+                }
+            }
+        }
+
+        let settings = RuntimeSettings::from_env();
+        let global_upload_cap = settings.global_upload_cap;
+        self.set_runtime_settings(settings);
+
+        if self.upload_cap_reached() {
+            let below_cap = match global_upload_cap {
+                Some(cap) => self.upload_cap_usage().await < cap,
+                None => true,
+            };
+            if below_cap {
+                self.upload_cap_reached.store(false, Ordering::Relaxed);
+                tracing::info!("Global upload cap lifted by configuration reload");
+            }
+        }
+
+        outcome
+    }
+
+    /// Round floating-point stats fields for API output.
+    pub fn shape_stats(&self, mut stats: FakerStats) -> FakerStats {
+        if let Some(decimals) = self.stats_precision() {
+            stats.round_floats(decimals);
+        }
+        stats
+    }
+
+    /// Publish stats of active instances to stats stream subscribers, if any.
+    pub async fn publish_stats(&self) {
+        if self.stats_sender.receiver_count() == 0 {
+            return;
+        }
+
+        let instances = {
+            let instances = self.instances.read().await;
+            instances
+                .iter()
+                .map(|(id, instance)| (id.clone(), instance.faker.stats_snapshot()))
+                .filter(|(_, stats)| {
+                    matches!(
+                        stats.state,
+                        FakerState::Starting | FakerState::Running | FakerState::Paused
+                    )
+                })
+                .map(|(id, stats)| InstanceStats { id, stats: self.shape_stats(stats) })
+                .collect()
+        };
+
+        let event = StatsEvent { timestamp: now_timestamp(), instances };
+        let _ = self.stats_sender.send(Arc::new(event));
+    }
+
+    pub const fn import_jobs(&self) -> &ImportJobs {
+        &self.import_jobs
+    }
+
+    pub fn upload_cap_reached(&self) -> bool {
+        self.upload_cap_reached.load(Ordering::Relaxed)
+    }
+
+    /// Sum of cumulative uploaded bytes across all instances.
+    pub async fn total_uploaded(&self) -> u64 {
+        let instances = self.instances.read().await;
+        instances.values().map(|instance| instance.faker.stats_snapshot().uploaded).sum()
+    }
+
+    /// Uploaded bytes counted toward the global cap since its last reset.
+    pub async fn upload_cap_usage(&self) -> u64 {
+        let instances = self.instances.read().await;
+        self.tally_upload_cap(&instances)
+    }
+
+    fn tally_upload_cap(&self, instances: &HashMap<String, FakerInstance>) -> u64 {
+        let uploaded = instances
+            .iter()
+            .map(|(id, instance)| (id.as_str(), instance.faker.stats_snapshot().uploaded));
+        self.upload_cap.lock().unwrap_or_else(PoisonError::into_inner).tally(uploaded)
+    }
+
+    /// Count the upload cap for `id` from `instance` on, before it takes over that ID.
+    fn rebase_upload_cap(
+        &self,
+        instances: &HashMap<String, FakerInstance>,
+        id: &str,
+        instance: &FakerInstance,
+    ) {
+        self.tally_upload_cap(instances);
+        self.upload_cap
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .rebase(id, instance.faker.stats_snapshot().uploaded);
+    }
+
+    /// Why `id` must keep seeding under hit-and-run protection, if it must.
+    pub async fn hnr_hold(&self, id: &str) -> Option<String> {
+        let faker = Arc::clone(&self.instances.read().await.get(id)?.faker);
+        faker.hnr_block_reason().await
+    }
+
+    /// Pause all active instances once the global upload cap is reached. Starts and
+    /// resumes stay blocked until the cap is reset. Instances held for hit-and-run
+    /// protection keep seeding and are paused once released. Returns true when newly
+    /// reached.
+    pub async fn enforce_global_upload_cap(&self) -> bool {
+        let Some(cap) = self.global_upload_cap() else {
+            return false;
+        };
+        let usage = self.upload_cap_usage().await;
+        if usage < cap {
+            return false;
+        }
+
+        let newly_reached = !self.upload_cap_reached.swap(true, Ordering::Relaxed);
+        if newly_reached {
+            tracing::warn!(
+                "Global upload cap reached ({} / {} bytes), pausing all instances",
+                usage,
+                cap
+            );
+        }
+        let active: Vec<String> = {
+            let instances = self.instances.read().await;
+            instances
+                .iter()
+                .filter(|(_, instance)| {
+                    matches!(
+                        instance.faker.stats_snapshot().state,
+                        FakerState::Starting | FakerState::Running
+                    )
+                })
+                .map(|(id, _)| id.clone())
+                .collect()
+        };
+        for id in &active {
+            if let Some(reason) = self.hnr_hold(id).await {
+                if newly_reached {
+                    tracing::info!("Upload cap held instance {} for HnR: {}", id, reason);
+                }
+                continue;
+            }
+            if let Err(e) = self.pause_instance(id, false).await {
+                tracing::warn!("Failed to pause instance {} at upload cap: {}", id, e);
+            }
+        }
+        newly_reached
+    }
+
+    /// Start counting toward the global upload cap from zero again.
+    pub async fn reset_global_upload_cap(&self) -> Result<(), String> {
+        let used = self.upload_cap_usage().await;
+        self.upload_cap.lock().unwrap_or_else(PoisonError::into_inner).reset();
+        self.upload_cap_reached.store(false, Ordering::Relaxed);
+        tracing::info!("Global upload cap reset after {} bytes uploaded", used);
+        self.save_state().await
+    }
+
+    /// Combined rate cap: the one set through the API, else `MAX_TOTAL_*_KBPS`.
+    pub async fn rate_cap(&self) -> RateCap {
+        let override_cap = *self.rate_cap_override.read().await;
+        override_cap.unwrap_or_else(|| self.runtime_settings().rate_cap)
+    }
+
+    /// Override the env rate cap, or fall back to it again with `None`.
+    pub async fn set_rate_cap(&self, cap: Option<RateCap>) -> Result<(), String> {
+        *self.rate_cap_override.write().await = cap;
+        self.save_state().await
+    }
+
+    pub fn rate_throttle(&self) -> RateThrottle {
+        *self.rate_throttle.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn set_rate_throttle(&self, throttle: RateThrottle) {
+        *self.rate_throttle.write().unwrap_or_else(PoisonError::into_inner) = throttle;
+    }
+
+    pub fn scrape_cache(&self) -> &ScrapeCache {
+        &self.scrape_cache
+    }
+
+    pub fn scrape_cache_config(&self) -> ScrapeCacheConfig {
+        self.runtime_settings().scrape_cache
+    }
+
+    /// Forget the cached scrape of instance `id`'s torrent on its current tracker,
+    /// after the instance announced and changed the swarm it described.
+    pub async fn invalidate_scrape_cache(&self, id: &str) {
+        let key = {
+            let instances = self.instances.read().await;
+            instances.get(id).and_then(|instance| {
+                let tracker_url = instance.faker.stats_snapshot().active_tracker?;
+                Some((tracker_url, instance.torrent_info_hash))
+            })
+        };
+        if let Some((tracker_url, info_hash)) = key {
+            self.scrape_cache.invalidate(&tracker_url, &info_hash);
+        }
+    }
+
     pub async fn peer_listener_status(&self) -> PeerListenerStatus {
         self.peer_listener_status.read().await.clone()
     }
@@ -121,7 +627,7 @@ impl AppState {
         let mut manual_ports = BTreeSet::new();
         let mut has_synced_active = false;
 
-        for instance in instances.values() {
+        for instance in instances.values().filter(|instance| instance.config.accept_connections) {
             let state = instance.faker.stats_snapshot().state;
             if matches!(state, FakerState::Starting | FakerState::Running | FakerState::Paused) {
                 if instance.config.vpn_port_sync {
@@ -182,7 +688,9 @@ impl AppState {
         }
     }
 
-    fn apply_forwarded_port_to_config(&self, config: &mut FakerConfig) {
+    /// Settle the values derived from the config itself (`seed_only`) and from the VPN
+    fn prepare_config(&self, config: &mut FakerConfig) {
+        config.apply_seed_only();
         if config.vpn_port_sync {
             if let Some(port) = self.current_forwarded_port() {
                 config.port = port;
@@ -208,7 +716,7 @@ impl AppState {
             vpn_port_sync: self.server_vpn_port_sync,
             ..FakerConfig::default()
         });
-        self.apply_forwarded_port_to_config(&mut config);
+        self.prepare_config(&mut config);
         config
     }
 
@@ -281,6 +789,11 @@ impl AppState {
             tracing::info!("Restored watch settings from saved state");
         }
 
+        if saved.rate_cap.is_some() {
+            *self.rate_cap_override.write().await = saved.rate_cap;
+            tracing::info!("Restored rate cap from saved state");
+        }
+
         if !saved.custom_presets.is_empty() {
             *self.custom_presets.write().await = saved.custom_presets.clone();
             tracing::info!(
@@ -301,48 +814,8 @@ impl AppState {
                 persisted.state
             );
 
-            let mut faker_config = persisted.config.clone();
-            let runtime = persisted.runtime.as_ref();
-            faker_config.initial_uploaded =
-                runtime.map_or(persisted.cumulative_uploaded, |rt| rt.uploaded);
-            faker_config.initial_downloaded =
-                runtime.map_or(persisted.cumulative_downloaded, |rt| rt.downloaded);
-
-            let summary = Arc::new(persisted.torrent.clone());
-            let torrent = Arc::new(persisted.torrent.to_info());
-
-            match RatioFaker::new(
-                Arc::clone(&torrent),
-                faker_config,
-                Some(self.http_client.clone()),
-            ) {
-                Ok(faker) => {
-                    let restored_stats = runtime.map_or_else(
-                        || Self::default_runtime_stats(&persisted.config),
-                        |value| {
-                            Self::stats_from_runtime(
-                                value,
-                                persisted.state,
-                                persisted.config.post_stop_action,
-                            )
-                        },
-                    );
-
-                    let instance = FakerInstance {
-                        faker: Arc::new(RatioFakerHandle::new(faker)),
-                        torrent,
-                        summary,
-                        config: persisted.config.clone(),
-                        torrent_info_hash: persisted.torrent.info_hash,
-                        cumulative_uploaded: persisted.cumulative_uploaded,
-                        cumulative_downloaded: persisted.cumulative_downloaded,
-                        created_at: persisted.created_at,
-                        source: persisted.source,
-                        tags: persisted.tags.clone(),
-                    };
-
-                    instance.faker.restore_snapshot(restored_stats).await;
-
+            match self.restore_instance(persisted).await {
+                Ok(instance) => {
                     self.instances.write().await.insert(id.clone(), instance);
 
                     self.emit_instance_event(InstanceEvent::Created {
@@ -352,7 +825,9 @@ impl AppState {
                         auto_started: false,
                     });
 
-                    if matches!(persisted.state, FakerState::Starting | FakerState::Running) {
+                    if persisted.enabled
+                        && matches!(persisted.state, FakerState::Starting | FakerState::Running)
+                    {
                         auto_start_ids.push(id.clone());
                     }
 
@@ -368,19 +843,68 @@ impl AppState {
             tracing::info!("Restored {} instances from saved state", restored_count);
         }
 
+        // Restored totals are the starting point, not new uploads
+        *self.upload_cap.lock().unwrap_or_else(PoisonError::into_inner) =
+            UploadCapMeter::new(saved.upload_cap_used);
+        let upload_cap_used = self.upload_cap_usage().await;
+        if let Some(cap) = self.global_upload_cap() {
+            if upload_cap_used >= cap {
+                self.upload_cap_reached.store(true, Ordering::Relaxed);
+                tracing::warn!("Global upload cap already reached, not auto-starting instances");
+            }
+        }
+
         // Second pass: auto-start instances that were previously running
         if !auto_start_ids.is_empty() {
-            tracing::info!("Auto-starting {} instance(s)...", auto_start_ids.len());
-            for id in &auto_start_ids {
-                if let Err(e) = self.start_instance(id).await {
-                    tracing::warn!("Failed to auto-start instance {}: {}", id, e);
-                }
+            let spacing = restore_ramp_from_env(auto_start_ids.len());
+            if spacing.is_zero() {
+                tracing::info!("Auto-starting {} instance(s)...", auto_start_ids.len());
+                self.auto_start_restored(&auto_start_ids, spacing).await;
+            } else {
+                // Ramp in the background so the server comes up without waiting
+                tracing::info!(
+                    "Auto-starting {} instance(s), one every {:.1}s...",
+                    auto_start_ids.len(),
+                    spacing.as_secs_f64()
+                );
+                let state = self.clone();
+                tokio::spawn(async move {
+                    state.auto_start_restored(&auto_start_ids, spacing).await;
+                });
             }
         }
 
         Ok(restored_count)
     }
 
+    async fn auto_start_restored(&self, ids: &[String], spacing: Duration) {
+        for (idx, id) in ids.iter().enumerate() {
+            if idx > 0 && !spacing.is_zero() {
+                tokio::time::sleep(spacing).await;
+            }
+            if !self.awaits_auto_start(id).await {
+                tracing::info!("Not auto-starting instance {}: changed since the restore", id);
+                continue;
+            }
+            if let Err(e) = self.start_instance(id).await {
+                tracing::warn!("Failed to auto-start instance {}: {}", id, e);
+            }
+        }
+    }
+
+    /// Whether a restored instance still exists, is enabled and keeps the running
+    /// state it was saved with, i.e. nobody stopped, paused or deleted it meanwhile.
+    async fn awaits_auto_start(&self, id: &str) -> bool {
+        let instances = self.instances.read().await;
+        instances.get(id).is_some_and(|instance| {
+            instance.enabled
+                && matches!(
+                    instance.faker.stats_snapshot().state,
+                    FakerState::Starting | FakerState::Running
+                )
+        })
+    }
+
     pub async fn save_state(&self) -> Result<(), String> {
         let instances = self.instances.read().await;
 
@@ -395,35 +919,195 @@ impl AppState {
             default_preset,
             watch_settings,
             custom_presets,
+            upload_cap_used: self.tally_upload_cap(&instances),
+            rate_cap: *self.rate_cap_override.read().await,
             version: 1,
         };
 
         for (id, instance) in instances.iter() {
-            let stats = instance.faker.stats_snapshot();
-            let mut config = instance.config.clone();
-            config.completion_percent = stats.torrent_completion;
-
-            persisted.instances.insert(
-                id.clone(),
-                PersistedInstance {
-                    id: id.clone(),
-                    torrent: (*instance.summary).clone(),
-                    config,
-                    cumulative_uploaded: stats.uploaded,
-                    cumulative_downloaded: stats.downloaded,
-                    state: stats.state,
-                    created_at: instance.created_at,
-                    updated_at: now_timestamp(),
-                    source: instance.source,
-                    tags: instance.tags.clone(),
-                    runtime: Some(Self::runtime_from_stats(&stats)),
-                },
-            );
+            persisted.instances.insert(id.clone(), Self::persist_instance(id, instance));
         }
 
         self.persistence.save(&persisted).await
     }
 
+    /// Save the state and read the file back, for shutdown where no later save can make
+    /// up for a failed one.
+    pub async fn save_state_verified(&self) -> Result<SavedStateCheck, String> {
+        let expected = self.instances.read().await.len();
+        self.save_state().await?;
+        self.persistence.verify(expected).await
+    }
+
+    /// Every instance with the default config and custom presets, for `import_bundle`
+    /// on another server.
+    pub async fn export_bundle(&self) -> ConfigBundle {
+        let instances = self.instances.read().await;
+        let mut exported: Vec<BundledInstance> = instances
+            .iter()
+            .map(|(id, instance)| BundledInstance {
+                instance: Self::persist_instance(id, instance),
+                torrent_file: instance.torrent_file.as_deref().map(hex::encode),
+            })
+            .collect();
+        drop(instances);
+        exported.sort_by(|a, b| {
+            a.instance
+                .created_at
+                .cmp(&b.instance.created_at)
+                .then_with(|| a.instance.id.cmp(&b.instance.id))
+        });
+
+        ConfigBundle {
+            version: BUNDLE_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            exported_at: now_timestamp(),
+            instances: exported,
+            default_config: self.default_config.read().await.clone(),
+            custom_presets: self.custom_presets.read().await.clone(),
+        }
+    }
+
+    /// Restore a bundle written by `export_bundle`. Instances are matched to existing
+    /// ones by info hash; `mode` decides whether those are skipped or overwritten.
+    /// Instances that were running when exported are started again.
+    pub async fn import_bundle(
+        &self,
+        bundle: ConfigBundle,
+        mode: ImportMode,
+    ) -> Result<ImportReport, String> {
+        bundle.check_version()?;
+
+        let mut report = ImportReport::default();
+        let mut auto_start_ids = Vec::new();
+
+        for bundled in bundle.instances {
+            let name = bundled.display_name().to_string();
+            let (mut persisted, torrent_file) = match bundled.into_parts() {
+                Ok(parts) => parts,
+                Err(e) => {
+                    report.errors.push(format!("{name}: {e}"));
+                    continue;
+                }
+            };
+            let existing = self.find_instance_by_info_hash(&persisted.torrent.info_hash).await;
+            if existing.is_some() && mode == ImportMode::Merge {
+                report.skipped.push(name);
+                continue;
+            }
+
+            let id = match existing {
+                Some(id) => id,
+                None if persisted.id.is_empty() || self.instance_exists(&persisted.id).await => {
+                    self.next_instance_id()
+                }
+                None => persisted.id.clone(),
+            };
+            persisted.id.clone_from(&id);
+            // The exporting server's watch folder is not this one's
+            persisted.source = InstanceSource::Manual;
+
+            let mut instance = match self.restore_instance(&persisted).await {
+                Ok(instance) => instance,
+                Err(e) => {
+                    report.errors.push(format!("{name}: {e}"));
+                    continue;
+                }
+            };
+            if let Some(bytes) = torrent_file {
+                instance.torrent_file = Some(bytes.into());
+                self.save_torrent_file(&instance).await;
+            }
+
+            let previous = {
+                let mut instances = self.instances.write().await;
+                self.rebase_upload_cap(&instances, &id, &instance);
+                instances.insert(id.clone(), instance)
+            };
+            if let Some(previous) = previous {
+                let _ = previous.faker.stop().await;
+                self.emit_instance_event(InstanceEvent::Deleted { id: id.clone() });
+                report.replaced.push(id.clone());
+            } else {
+                report.imported.push(id.clone());
+            }
+            self.emit_instance_event(InstanceEvent::Created {
+                id: id.clone(),
+                torrent_name: persisted.torrent.name.clone(),
+                info_hash: hex::encode(persisted.torrent.info_hash),
+                auto_started: false,
+            });
+
+            if persisted.enabled
+                && matches!(persisted.state, FakerState::Starting | FakerState::Running)
+            {
+                auto_start_ids.push(id);
+            }
+        }
+
+        if let Some(config) = bundle.default_config {
+            let mut default_config = self.default_config.write().await;
+            if mode == ImportMode::Replace || default_config.is_none() {
+                *default_config = Some(config);
+            }
+        }
+
+        let mut presets = self.custom_presets.write().await;
+        for preset in bundle.custom_presets {
+            match presets.iter_mut().find(|item| item.id == preset.id) {
+                Some(existing) if mode == ImportMode::Replace => *existing = preset,
+                Some(_) => {}
+                None => presets.push(preset),
+            }
+        }
+        drop(presets);
+
+        self.save_state().await?;
+        self.refresh_peer_listener_port().await;
+
+        tracing::info!(
+            "Imported bundle: {} new, {} replaced, {} skipped, {} failed",
+            report.imported.len(),
+            report.replaced.len(),
+            report.skipped.len(),
+            report.errors.len()
+        );
+
+        if !auto_start_ids.is_empty() {
+            let spacing = restore_ramp_from_env(auto_start_ids.len());
+            let state = self.clone();
+            tokio::spawn(async move {
+                state.auto_start_restored(&auto_start_ids, spacing).await;
+            });
+        }
+
+        Ok(report)
+    }
+
+    fn persist_instance(id: &str, instance: &FakerInstance) -> PersistedInstance {
+        let stats = instance.faker.stats_snapshot();
+        let mut config = instance.config.clone();
+        config.completion_percent = stats.torrent_completion;
+        let runtime = Self::runtime_from_stats(&stats, config.history_length);
+
+        PersistedInstance {
+            id: id.to_string(),
+            torrent: (*instance.summary).clone(),
+            config,
+            cumulative_uploaded: stats.uploaded,
+            cumulative_downloaded: stats.downloaded,
+            state: stats.state,
+            created_at: instance.created_at,
+            updated_at: now_timestamp(),
+            source: instance.source,
+            tags: instance.tags.clone(),
+            enabled: instance.enabled,
+            runtime: Some(runtime),
+            schedule: instance.schedule.clone(),
+            label: instance.label.clone(),
+        }
+    }
+
     #[allow(clippy::unused_self)]
     pub fn next_instance_id(&self) -> String {
         nanoid::nanoid!(10)
@@ -441,7 +1125,7 @@ impl AppState {
         let mut instances = self.instances.write().await;
         let instance = instances.get_mut(id).ok_or("Instance not found")?;
         let mut config = config;
-        self.apply_forwarded_port_to_config(&mut config);
+        self.prepare_config(&mut config);
         let mut faker_config = config.clone();
         Self::apply_cumulative_totals(
             &mut faker_config,
@@ -451,7 +1135,7 @@ impl AppState {
 
         instance
             .faker
-            .update_config(faker_config, Some(self.http_client.clone()))
+            .update_config(self.faker_config(faker_config), Some(self.http_client.clone()))
             .await
             .map_err(|e| e.to_string())?;
         instance.config = config;
@@ -474,14 +1158,14 @@ impl AppState {
         let mut instances = self.instances.write().await;
         let instance = instances.get_mut(id).ok_or("Instance not found")?;
         let mut config = config;
-        self.apply_forwarded_port_to_config(&mut config);
-        instance.config = config.clone();
+        self.prepare_config(&mut config);
 
         instance
             .faker
-            .update_config(config, Some(self.http_client.clone()))
+            .update_config(self.faker_config(config.clone()), Some(self.http_client.clone()))
             .await
             .map_err(|e| format!("Failed to update faker config: {e}"))?;
+        instance.config = config;
         drop(instances);
 
         if let Err(e) = self.save_state().await {
@@ -493,10 +1177,25 @@ impl AppState {
         Ok(())
     }
 
-    pub async fn bulk_update_configs(
+    /// Apply a partial config onto the instance's current config. Keys left out of
+    /// `patch` keep their current values.
+    pub async fn patch_instance_config(
         &self,
-        entries: Vec<(String, FakerConfig)>,
-    ) -> (Vec<String>, Vec<(String, String)>) {
+        id: &str,
+        patch: serde_json::Value,
+    ) -> Result<(), String> {
+        let current = {
+            let instances = self.instances.read().await;
+            instances.get(id).ok_or("Instance not found")?.config.clone()
+        };
+        let config = merge_config_patch(&current, patch)?;
+        self.update_instance_config_only(id, config).await
+    }
+
+    pub async fn bulk_update_configs(
+        &self,
+        entries: Vec<(String, FakerConfig)>,
+    ) -> (Vec<String>, Vec<(String, String)>) {
         let mut succeeded = Vec::new();
         let mut failed = Vec::new();
         let mut instances = self.instances.write().await;
@@ -505,7 +1204,7 @@ impl AppState {
             match instances.get_mut(&id) {
                 Some(instance) => {
                     let mut config = config;
-                    self.apply_forwarded_port_to_config(&mut config);
+                    self.prepare_config(&mut config);
 
                     let mut faker_config = config.clone();
                     Self::apply_cumulative_totals(
@@ -516,7 +1215,10 @@ impl AppState {
 
                     let result = instance
                         .faker
-                        .update_config(faker_config, Some(self.http_client.clone()))
+                        .update_config(
+                            self.faker_config(faker_config),
+                            Some(self.http_client.clone()),
+                        )
                         .await;
                     match result {
                         Ok(()) => {
@@ -554,14 +1256,23 @@ impl AppState {
         config: FakerConfig,
     ) -> Result<(), String> {
         let mut config = config;
-        self.apply_forwarded_port_to_config(&mut config);
+        self.prepare_config(&mut config);
         let context = InstanceBuildContext::new(id, torrent, config, InstanceSource::Manual);
         self.create_instance_internal(context).await
     }
 
-    pub async fn create_idle_instance(&self, id: &str, torrent: TorrentInfo) -> Result<(), String> {
+    /// `torrent_file` is the `.torrent` the instance came from; magnets have none.
+    pub async fn create_idle_instance(
+        &self,
+        id: &str,
+        torrent: TorrentInfo,
+        torrent_file: Option<Vec<u8>>,
+    ) -> Result<(), String> {
         let config = self.get_effective_default_config().await;
-        let context = InstanceBuildContext::new(id, torrent, config, InstanceSource::Manual);
+        let mut context = InstanceBuildContext::new(id, torrent, config, InstanceSource::Manual);
+        if let Some(bytes) = torrent_file {
+            context = context.with_torrent_file(bytes);
+        }
         let torrent = Arc::clone(&context.torrent);
         self.create_instance_internal(context).await?;
 
@@ -577,19 +1288,17 @@ impl AppState {
 
     pub async fn create_instance_with_event(
         &self,
-        id: &str,
-        torrent: TorrentInfo,
-        config: FakerConfig,
+        context: InstanceBuildContext,
         auto_started: bool,
     ) -> Result<(), String> {
-        let mut config = config;
-        self.apply_forwarded_port_to_config(&mut config);
-        let context = InstanceBuildContext::new(id, torrent, config, InstanceSource::WatchFolder);
+        let mut context = context;
+        self.prepare_config(&mut context.config);
+        let id = context.id.clone();
         let torrent = Arc::clone(&context.torrent);
         self.create_instance_internal(context).await?;
 
         self.emit_instance_event(InstanceEvent::Created {
-            id: id.to_string(),
+            id,
             torrent_name: torrent.name.clone(),
             info_hash: hex::encode(torrent.info_hash),
             auto_started,
@@ -598,22 +1307,77 @@ impl AppState {
         Ok(())
     }
 
-    async fn create_instance_internal(&self, context: InstanceBuildContext) -> Result<(), String> {
-        set_instance_context_str(Some(context.summary.name.as_str()));
-
-        if let Some(existing_id) =
-            self.duplicate_instance_id(&context.id, &context.torrent.info_hash).await
-        {
-            return Err(format!(
-                "Duplicate torrent skipped: already imported as instance {existing_id}"
-            ));
-        }
+    /// Create `new_id` with the torrent, config and tags of `id` but fresh totals and a
+    /// new peer ID and key. Clones are always manual so they are not tied to a watched file.
+    pub async fn clone_instance(&self, id: &str, new_id: &str) -> Result<(), String> {
+        let (context, tags) = {
+            let instances = self.instances.read().await;
+            let source = instances.get(id).ok_or("Instance not found")?;
+            if instances.contains_key(new_id) {
+                return Err(format!("Instance {new_id} already exists"));
+            }
+            let mut config = source.config.clone();
+            Self::apply_cumulative_totals(&mut config, 0, 0);
+            // A pinned peer ID would make the clone announce as the same client
+            config.peer_id_override = None;
+            let context = InstanceBuildContext {
+                id: new_id.to_string(),
+                torrent: Arc::clone(&source.torrent),
+                summary: Arc::clone(&source.summary),
+                torrent_file: source.torrent_file.clone(),
+                config,
+                source: InstanceSource::Manual,
+            };
+            (context, source.tags.clone())
+        };
 
-        let id = context.id.clone();
-        let existing = self.collect_existing_instance_state(&context).await;
-        let faker_config = Self::build_faker_config(&context, &existing);
+        let torrent = Arc::clone(&context.torrent);
+        let faker_config = context.config.clone();
+        let existing =
+            ExistingInstanceState { tags, ..ExistingInstanceState::fresh(context.source) };
         let instance = self.build_instance(context, faker_config, existing)?;
-        self.insert_instance(id, instance).await
+        self.insert_instance(new_id.to_string(), instance).await?;
+        tracing::info!("Cloned instance {} into {}", id, new_id);
+
+        self.emit_instance_event(InstanceEvent::Created {
+            id: new_id.to_string(),
+            torrent_name: torrent.name.clone(),
+            info_hash: hex::encode(torrent.info_hash),
+            auto_started: false,
+        });
+        Ok(())
+    }
+
+    async fn create_instance_internal(&self, context: InstanceBuildContext) -> Result<(), String> {
+        let log_context = InstanceLogContext {
+            id: context.id.clone(),
+            label: context.summary.name.clone(),
+            info_hash: Some(hex::encode(context.torrent.info_hash)),
+        };
+        let create = async move {
+            if let Some(existing_id) =
+                self.duplicate_instance_id(&context.id, &context.torrent.info_hash).await
+            {
+                return Err(format!(
+                    "Duplicate torrent skipped: already imported as instance {existing_id}"
+                ));
+            }
+
+            let id = context.id.clone();
+            let existing = self.collect_existing_instance_state(&context).await;
+            let faker_config = Self::build_faker_config(&context, &existing);
+            let instance = self.build_instance(context, faker_config, existing)?;
+            if self.validate_on_create() {
+                instance.faker.validate_with_tracker().await.map_err(|e| match e {
+                    FakerError::TrackerError(TrackerError::TrackerFailure(reason)) => {
+                        format!("Tracker rejected torrent: {reason}")
+                    }
+                    other => other.to_string(),
+                })?;
+            }
+            self.insert_instance(id, instance).await
+        };
+        with_instance_context(log_context, create).await
     }
 
     pub async fn get_stats(&self, id: &str) -> Result<FakerStats, String> {
@@ -626,12 +1390,34 @@ impl AppState {
         Ok(stats)
     }
 
+    /// The instance's last announce, `None` unless its config sets `record_last_announce`
+    pub async fn get_last_announce(&self, id: &str) -> Result<Option<LastAnnounce>, String> {
+        let faker = {
+            let instances = self.instances.read().await;
+            let instance = instances.get(id).ok_or("Instance not found")?;
+            Arc::clone(&instance.faker)
+        };
+        Ok(faker.last_announce().await)
+    }
+
     pub async fn get_instance_torrent(&self, id: &str) -> Result<TorrentInfo, String> {
         let instances = self.instances.read().await;
         let instance = instances.get(id).ok_or("Instance not found")?;
         Ok((*instance.torrent).clone())
     }
 
+    /// Recent log lines of an instance, oldest first, at most `limit` when set.
+    pub async fn get_instance_logs(
+        &self,
+        id: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<LogEvent>, String> {
+        if !self.instance_exists(id).await {
+            return Err("Instance not found".to_string());
+        }
+        Ok(self.instance_logs.recent(id, limit))
+    }
+
     pub async fn get_instance_summary(&self, id: &str) -> Result<TorrentSummary, String> {
         let instances = self.instances.read().await;
         let instance = instances.get(id).ok_or("Instance not found")?;
@@ -659,7 +1445,8 @@ impl AppState {
             }
         }
 
-        let removed = self.instances.write().await.remove(id);
+        let removed = self.remove_instance_entry(id).await;
+        self.instance_logs.remove(id);
 
         if removed.is_some() {
             self.emit_instance_event(InstanceEvent::Deleted { id: id.to_string() });
@@ -674,12 +1461,15 @@ impl AppState {
         Ok(())
     }
 
-    pub async fn list_instances(&self) -> Vec<InstanceInfo> {
+    pub async fn list_instances(&self, query: &InstanceQuery) -> Vec<InstanceInfo> {
         let instances = self.instances.read().await;
         let mut result = Vec::new();
 
         for (id, instance) in instances.iter() {
             let stats = instance.faker.stats_snapshot();
+            if !query.matches(instance.enabled, &stats, &instance.tags) {
+                continue;
+            }
 
             result.push(InstanceInfo {
                 id: id.clone(),
@@ -689,10 +1479,75 @@ impl AppState {
                 created_at: instance.created_at,
                 source: instance.source,
                 tags: instance.tags.clone(),
+                enabled: instance.enabled,
+                schedule: instance.schedule.clone(),
+                label: instance.label.clone(),
             });
         }
 
-        result
+        drop(instances);
+
+        query.sort(&mut result);
+        query.paginate(result)
+    }
+
+    /// Number of instances matching the query's filters, before paging.
+    pub async fn count_instances(&self, query: &InstanceQuery) -> usize {
+        let instances = self.instances.read().await;
+        instances
+            .values()
+            .filter(|instance| {
+                query.matches(instance.enabled, &instance.faker.stats_snapshot(), &instance.tags)
+            })
+            .count()
+    }
+
+    /// Distinct tags in use with the number of instances carrying each, by tag name.
+    /// Tags differing only in case are counted together under the spelling that sorts first.
+    pub async fn tag_counts(&self) -> Vec<TagCount> {
+        let instances = self.instances.read().await;
+        let mut counts: BTreeMap<String, TagCount> = BTreeMap::new();
+        for instance in instances.values() {
+            for (key, spelling) in distinct_tags(&instance.tags) {
+                let entry = counts
+                    .entry(key)
+                    .or_insert_with(|| TagCount { tag: spelling.to_string(), count: 0 });
+                if spelling < entry.tag.as_str() {
+                    entry.tag = spelling.to_string();
+                }
+                entry.count += 1;
+            }
+        }
+        drop(instances);
+        counts.into_values().collect()
+    }
+
+    /// Totals across all instances and, when `by_tag` is set, per tag (grouped like
+    /// `tag_counts`, untagged instances only count towards the overall totals).
+    pub async fn instance_totals(&self, by_tag: bool) -> (InstanceTotals, Vec<TagTotals>) {
+        let instances = self.instances.read().await;
+        let mut totals = InstanceTotals::default();
+        let mut tags: BTreeMap<String, TagTotals> = BTreeMap::new();
+        for instance in instances.values() {
+            let stats = instance.faker.stats_snapshot();
+            let size = instance.summary.total_size;
+            totals.add(&stats, size);
+            if !by_tag {
+                continue;
+            }
+            for (key, spelling) in distinct_tags(&instance.tags) {
+                let entry = tags.entry(key).or_insert_with(|| TagTotals {
+                    tag: spelling.to_string(),
+                    totals: InstanceTotals::default(),
+                });
+                if spelling < entry.tag.as_str() {
+                    entry.tag = spelling.to_string();
+                }
+                entry.totals.add(&stats, size);
+            }
+        }
+        drop(instances);
+        (totals, tags.into_values().collect())
     }
 
     pub async fn apply_vpn_forwarded_port(&self, port: u16) -> Result<usize, String> {
@@ -725,7 +1580,7 @@ impl AppState {
             } else {
                 instance
                     .faker
-                    .update_config(faker_config, Some(self.http_client.clone()))
+                    .update_config(self.faker_config(faker_config), Some(self.http_client.clone()))
                     .await
                     .map_err(|e| format!("Failed to update synced port: {e}"))?;
             }
@@ -740,6 +1595,38 @@ impl AppState {
         Ok(updated)
     }
 
+    /// Announce the VPN's public IP from instances with `auto_announce_vpn_ip`. Like in
+    /// `faker_config`, only the running fakers get it; stored configs stay as they are.
+    pub async fn apply_vpn_public_ip(&self, ip: IpAddr) -> Result<usize, String> {
+        self.set_current_vpn_ip(Some(ip));
+
+        let instances = self.instances.read().await;
+        let mut updated = 0usize;
+
+        for instance in instances.values().filter(|instance| instance.config.auto_announce_vpn_ip) {
+            let state = instance.faker.stats_snapshot().state;
+            if matches!(state, FakerState::Starting | FakerState::Running | FakerState::Paused) {
+                instance.faker.set_runtime_announce_ip(ip).await;
+            } else {
+                let mut faker_config = instance.config.clone();
+                Self::apply_cumulative_totals(
+                    &mut faker_config,
+                    instance.cumulative_uploaded,
+                    instance.cumulative_downloaded,
+                );
+                instance
+                    .faker
+                    .update_config(self.faker_config(faker_config), Some(self.http_client.clone()))
+                    .await
+                    .map_err(|e| format!("Failed to update synced IP: {e}"))?;
+            }
+
+            updated += 1;
+        }
+
+        Ok(updated)
+    }
+
     pub async fn get_instance_info_for_delete(
         &self,
         id: &str,
@@ -748,14 +1635,14 @@ impl AppState {
         instances.get(id).map(|inst| (inst.source, inst.torrent_info_hash))
     }
 
+    /// Instance holding this torrent, preferring the watch-folder one over its clones.
     pub async fn find_instance_by_info_hash(&self, info_hash: &[u8; 20]) -> Option<String> {
         let instances = self.instances.read().await;
-        for (id, instance) in instances.iter() {
-            if &instance.torrent_info_hash == info_hash {
-                return Some(id.clone());
-            }
-        }
-        None
+        instances
+            .iter()
+            .filter(|(_, instance)| &instance.torrent_info_hash == info_hash)
+            .max_by_key(|(_, instance)| instance.source == InstanceSource::WatchFolder)
+            .map(|(id, _)| id.clone())
     }
 
     pub async fn duplicate_instance_id(&self, id: &str, info_hash: &[u8; 20]) -> Option<String> {
@@ -808,6 +1695,83 @@ impl AppState {
         Ok(())
     }
 
+    /// Enable or park an instance. Disabling stops any active session; config and
+    /// stats are kept, and the instance is skipped by the scheduler and auto-start.
+    pub async fn set_instance_enabled(&self, id: &str, enabled: bool) -> Result<(), String> {
+        let faker = {
+            let mut instances = self.instances.write().await;
+            let instance = instances.get_mut(id).ok_or("Instance not found")?;
+            if instance.enabled == enabled {
+                return Ok(());
+            }
+            instance.enabled = enabled;
+            Arc::clone(&instance.faker)
+        };
+
+        if !enabled {
+            let state = faker.stats_snapshot().state;
+            if matches!(state, FakerState::Starting | FakerState::Running | FakerState::Paused) {
+                if let Err(e) = faker.stop().await {
+                    tracing::warn!("Failed to stop instance {} while disabling: {}", id, e);
+                }
+            }
+        }
+
+        if let Err(e) = self.save_state().await {
+            tracing::warn!("Failed to save state after updating enabled flag: {}", e);
+        }
+
+        self.refresh_peer_listener_port().await;
+
+        Ok(())
+    }
+
+    /// Set the name an instance is shown under; `None` or a blank label clears it.
+    pub async fn set_instance_label(
+        &self,
+        id: &str,
+        label: Option<String>,
+    ) -> Result<Option<String>, String> {
+        let label = label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+        if label.as_ref().is_some_and(|l| l.chars().count() > MAX_LABEL_CHARS) {
+            return Err(format!("Label must be at most {MAX_LABEL_CHARS} characters"));
+        }
+
+        {
+            let mut instances = self.instances.write().await;
+            let instance = instances.get_mut(id).ok_or("Instance not found")?;
+            instance.label.clone_from(&label);
+        }
+
+        if let Err(e) = self.save_state().await {
+            tracing::warn!("Failed to save state after updating label: {}", e);
+        }
+        Ok(label)
+    }
+
+    /// Replace an instance's start/stop schedule; `None` or blank expressions clear it.
+    pub async fn set_instance_schedule(
+        &self,
+        id: &str,
+        schedule: Option<InstanceSchedule>,
+    ) -> Result<Option<InstanceSchedule>, String> {
+        let schedule = schedule.and_then(InstanceSchedule::normalized);
+        if let Some(schedule) = &schedule {
+            schedule.validate()?;
+        }
+
+        {
+            let mut instances = self.instances.write().await;
+            let instance = instances.get_mut(id).ok_or("Instance not found")?;
+            instance.schedule.clone_from(&schedule);
+        }
+
+        if let Err(e) = self.save_state().await {
+            tracing::warn!("Failed to save state after updating schedule: {}", e);
+        }
+        Ok(schedule)
+    }
+
     pub async fn grid_update_tags(
         &self,
         ids: &[String],
@@ -839,63 +1803,39 @@ impl AppState {
     }
 
     pub async fn list_instance_summaries(&self) -> Vec<InstanceSummary> {
+        self.list_instance_summaries_matching(&TagFilter::default()).await
+    }
+
+    pub async fn list_instance_summaries_matching(
+        &self,
+        filter: &TagFilter,
+    ) -> Vec<InstanceSummary> {
         let instances = self.instances.read().await;
         let mut result = Vec::with_capacity(instances.len());
 
         for (id, instance) in instances.iter() {
-            let stats = instance.faker.stats_snapshot();
-
-            let source = match instance.source {
-                InstanceSource::Manual => "manual",
-                InstanceSource::WatchFolder => "watch_folder",
-            };
-
-            let state = match stats.state {
-                FakerState::Paused => "paused",
-                _ if stats.is_idling => "idle",
-                FakerState::Idle => "idle",
-                FakerState::Starting => "starting",
-                FakerState::Running => "running",
-                FakerState::Stopping => "stopping",
-                FakerState::Stopped => "stopped",
-            };
-
-            result.push(InstanceSummary {
-                id: id.clone(),
-                name: instance.summary.name.clone(),
-                info_hash: hex::encode(instance.torrent_info_hash),
-                primary_tracker_host: primary_tracker_host(&instance.summary.announce),
-                state: state.to_string(),
-                is_tracker_invalid: stats.tracker_error.is_some(),
-                tracker_error: stats.tracker_error.clone(),
-                tracker_retry_attempt: stats.tracker_retry_attempt,
-                tracker_retry_at_ms: stats.tracker_retry_at_ms,
-                tags: instance.tags.clone(),
-                total_size: instance.summary.total_size,
-                uploaded: stats.uploaded,
-                downloaded: stats.downloaded,
-                ratio: stats.ratio,
-                current_upload_rate: stats.current_upload_rate,
-                current_download_rate: stats.current_download_rate,
-                seeders: stats.seeders,
-                leechers: stats.leechers,
-                left: stats.left,
-                torrent_completion: stats.torrent_completion,
-                source: source.to_string(),
-                created_at: instance.created_at,
-            });
+            if filter.matches(&instance.tags) {
+                result.push(instance.grid_summary(id));
+            }
         }
 
+        result.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
         result
     }
 
+    /// Current grid summary of one instance, or `None` if it no longer exists.
+    pub async fn find_instance_summary(&self, id: &str) -> Option<InstanceSummary> {
+        let instances = self.instances.read().await;
+        instances.get(id).map(|instance| instance.grid_summary(id))
+    }
+
     pub async fn create_instance_with_tags(
         &self,
         context: InstanceBuildContext,
         tags: Vec<String>,
     ) -> Result<(), String> {
         let mut context = context;
-        self.apply_forwarded_port_to_config(&mut context.config);
+        self.prepare_config(&mut context.config);
         let id = context.id.clone();
         self.create_instance_internal(context).await?;
 
@@ -924,19 +1864,16 @@ impl AppState {
                     created_at: existing.created_at,
                     source: existing.source,
                     tags: existing.tags.clone(),
+                    enabled: existing.enabled,
+                    schedule: existing.schedule.clone(),
+                    label: existing.label.clone(),
                     completion_percent: Some(stats.torrent_completion),
+                    torrent_file: existing.torrent_file.clone(),
                 };
             }
         }
 
-        ExistingInstanceState {
-            cumulative_uploaded: 0,
-            cumulative_downloaded: 0,
-            created_at: now_timestamp(),
-            source: context.source,
-            tags: Vec::new(),
-            completion_percent: None,
-        }
+        ExistingInstanceState::fresh(context.source)
     }
 
     fn build_faker_config(
@@ -964,7 +1901,7 @@ impl AppState {
         let torrent_info_hash = context.torrent.info_hash;
         let faker = RatioFaker::new(
             Arc::clone(&context.torrent),
-            faker_config,
+            self.faker_config(faker_config),
             Some(self.http_client.clone()),
         )
         .map_err(|e| e.to_string())?;
@@ -973,6 +1910,7 @@ impl AppState {
             faker: Arc::new(RatioFakerHandle::new(faker)),
             torrent: context.torrent,
             summary: context.summary,
+            torrent_file: context.torrent_file.or(existing.torrent_file),
             config: context.config,
             torrent_info_hash,
             cumulative_uploaded: existing.cumulative_uploaded,
@@ -980,11 +1918,48 @@ impl AppState {
             created_at: existing.created_at,
             source: existing.source,
             tags: existing.tags,
+            enabled: existing.enabled,
+            schedule: existing.schedule,
+            label: existing.label,
         })
     }
 
+    async fn save_torrent_file(&self, instance: &FakerInstance) {
+        let Some(bytes) = &instance.torrent_file else {
+            return;
+        };
+        if let Err(e) = self.persistence.save_torrent_file(&instance.torrent_info_hash, bytes).await
+        {
+            tracing::warn!("Failed to save torrent file of {}: {}", instance.display_name(), e);
+        }
+    }
+
+    /// Remove `id`, and its saved `.torrent` unless another instance, such as a clone,
+    /// still uses the file kept under the same info hash.
+    async fn remove_instance_entry(&self, id: &str) -> Option<FakerInstance> {
+        let (removed, shared) = {
+            let mut instances = self.instances.write().await;
+            let removed = instances.remove(id);
+            let shared = removed.as_ref().is_some_and(|removed| {
+                instances
+                    .values()
+                    .any(|instance| instance.torrent_info_hash == removed.torrent_info_hash)
+            });
+            (removed, shared)
+        };
+        if let Some(removed) = removed.as_ref().filter(|_| !shared) {
+            self.persistence.remove_torrent_file(&removed.torrent_info_hash).await;
+        }
+        removed
+    }
+
     async fn insert_instance(&self, id: String, instance: FakerInstance) -> Result<(), String> {
-        self.instances.write().await.insert(id, instance);
+        self.save_torrent_file(&instance).await;
+        {
+            let mut instances = self.instances.write().await;
+            self.rebase_upload_cap(&instances, &id, &instance);
+            instances.insert(id, instance);
+        }
 
         if let Err(e) = self.save_state().await {
             tracing::warn!("Failed to save state after creating instance: {}", e);
@@ -1006,7 +1981,8 @@ impl AppState {
             }
         }
 
-        let removed = self.instances.write().await.remove(&id);
+        let removed = self.remove_instance_entry(&id).await;
+        self.instance_logs.remove(&id);
 
         if removed.is_some() {
             tracing::info!("Deleted instance {} (torrent file removed from watch folder)", id);
@@ -1032,7 +2008,7 @@ impl AppState {
                 stats.state,
                 FakerState::Starting | FakerState::Running | FakerState::Paused
             ) {
-                if let Err(e) = instance.faker.stop().await {
+                if let Err(e) = instance.faker.stop_with_reason(StopReason::Shutdown).await {
                     tracing::warn!("Failed to stop instance {}: {}", id, e);
                 }
             }
@@ -1045,11 +2021,60 @@ impl AppState {
 }
 
 impl AppState {
+    /// Rebuild an instance from its persisted form, with its last stats restored.
+    async fn restore_instance(
+        &self,
+        persisted: &PersistedInstance,
+    ) -> Result<FakerInstance, String> {
+        let mut faker_config = persisted.config.clone();
+        let runtime = persisted.runtime.as_ref();
+        faker_config.initial_uploaded =
+            runtime.map_or(persisted.cumulative_uploaded, |rt| rt.uploaded);
+        faker_config.initial_downloaded =
+            runtime.map_or(persisted.cumulative_downloaded, |rt| rt.downloaded);
+
+        let summary = Arc::new(persisted.torrent.clone());
+        let torrent = Arc::new(persisted.torrent.to_info());
+        let torrent_file = self.persistence.load_torrent_file(&persisted.torrent.info_hash).await;
+
+        let faker = RatioFaker::new(
+            Arc::clone(&torrent),
+            self.faker_config(faker_config),
+            Some(self.http_client.clone()),
+        )
+        .map_err(|e| e.to_string())?;
+        let restored_stats = runtime.map_or_else(
+            || Self::default_runtime_stats(&persisted.config),
+            |value| Self::stats_from_runtime(value, persisted.state, &persisted.config),
+        );
+
+        let instance = FakerInstance {
+            faker: Arc::new(RatioFakerHandle::new(faker)),
+            torrent,
+            summary,
+            torrent_file: torrent_file.map(Into::into),
+            config: persisted.config.clone(),
+            torrent_info_hash: persisted.torrent.info_hash,
+            cumulative_uploaded: persisted.cumulative_uploaded,
+            cumulative_downloaded: persisted.cumulative_downloaded,
+            created_at: persisted.created_at,
+            source: persisted.source,
+            tags: persisted.tags.clone(),
+            enabled: persisted.enabled,
+            schedule: persisted.schedule.clone(),
+            label: persisted.label.clone(),
+        };
+
+        instance.faker.restore_snapshot(restored_stats).await;
+        Ok(instance)
+    }
+
     fn default_runtime_stats(config: &FakerConfig) -> FakerStats {
         rustatio_core::RatioFaker::stats_from_config(config)
     }
 
-    fn runtime_from_stats(stats: &FakerStats) -> PersistedRuntime {
+    /// Runtime stats for the state file, with the newest `history_length` graph points.
+    fn runtime_from_stats(stats: &FakerStats, history_length: usize) -> PersistedRuntime {
         PersistedRuntime {
             uploaded: stats.uploaded,
             downloaded: stats.downloaded,
@@ -1082,14 +2107,22 @@ impl AppState {
             idling_reason: stats.idling_reason.clone(),
             tracker_error: stats.tracker_error.clone(),
             announce_count: stats.announce_count,
+            stop_reason: stats.stop_reason.clone(),
+            estimated_bonus: stats.estimated_bonus,
+            completed_already_sent: stats.completed_already_sent,
+            upload_rate_history: history_tail(&stats.upload_rate_history, history_length),
+            download_rate_history: history_tail(&stats.download_rate_history, history_length),
+            ratio_history: history_tail(&stats.ratio_history, history_length),
+            history_timestamps: history_tail(&stats.history_timestamps, history_length),
         }
     }
 
     fn stats_from_runtime(
         runtime: &PersistedRuntime,
         state: FakerState,
-        post_stop_action: rustatio_core::PostStopAction,
+        config: &FakerConfig,
     ) -> FakerStats {
+        let history_length = config.history_length;
         FakerStats {
             uploaded: runtime.uploaded,
             downloaded: runtime.downloaded,
@@ -1104,6 +2137,8 @@ impl AppState {
             tracker_error: runtime.tracker_error.clone(),
             tracker_retry_attempt: 0,
             tracker_retry_at_ms: None,
+            tracker_failure_reason: None,
+            tracker_warning: None,
             session_uploaded: runtime.session_uploaded,
             session_downloaded: runtime.session_downloaded,
             session_ratio: runtime.session_ratio,
@@ -1121,15 +2156,20 @@ impl AppState {
             eta_uploaded: runtime.eta_uploaded_secs.map(Duration::from_secs),
             eta_seed_time: runtime.eta_seed_time_secs.map(Duration::from_secs),
             eta_download_completion: runtime.eta_download_completion_secs.map(Duration::from_secs),
-            upload_rate_history: Vec::new(),
-            download_rate_history: Vec::new(),
-            ratio_history: Vec::new(),
-            history_timestamps: Vec::new(),
+            upload_rate_history: history_tail(&runtime.upload_rate_history, history_length),
+            download_rate_history: history_tail(&runtime.download_rate_history, history_length),
+            ratio_history: history_tail(&runtime.ratio_history, history_length),
+            history_timestamps: history_tail(&runtime.history_timestamps, history_length),
             last_announce: None,
             next_announce: None,
             announce_count: runtime.announce_count,
             stop_condition_met: runtime.stop_condition_met,
-            post_stop_action,
+            post_stop_action: config.post_stop_action,
+            stop_reason: runtime.stop_reason.clone(),
+            estimated_bonus: runtime.estimated_bonus,
+            hnr_protected: false,
+            active_tracker: None,
+            completed_already_sent: runtime.completed_already_sent,
         }
     }
 }
@@ -1143,15 +2183,73 @@ impl EventBroadcaster for AppState {
         self.instance_sender.subscribe()
     }
 
+    fn subscribe_stats(&self) -> broadcast::Receiver<Arc<StatsEvent>> {
+        self.stats_sender.subscribe()
+    }
+
     fn emit_instance_event(&self, event: InstanceEvent) {
         let _ = self.instance_sender.send(event);
     }
 }
 
+/// `TRACKER_PROXY`, or `None` when it is unset. Fails when it is not a usable proxy
+/// URL, which `main` treats as fatal rather than announcing from the real IP.
+pub fn tracker_proxy_from_env() -> Result<Option<String>, String> {
+    let Some(proxy_url) =
+        std::env::var("TRACKER_PROXY").ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+    else {
+        return Ok(None);
+    };
+    tracker_http_client(Some(&proxy_url)).map_err(|e| format!("Invalid TRACKER_PROXY: {e}"))?;
+    Ok(Some(proxy_url))
+}
+
+/// Shared HTTP client for tracker requests, routed through `TRACKER_PROXY` when set.
+fn tracker_http_client(proxy_url: Option<&str>) -> Result<reqwest::Client, reqwest::Error> {
+    let Some(proxy_url) = proxy_url else {
+        return Ok(reqwest::Client::new());
+    };
+    reqwest::Client::builder().proxy(reqwest::Proxy::all(proxy_url)?).build()
+}
+
+/// Set the IPv4 `announce_ip` or the `announce_ipv6` matching `ip`.
+const fn set_announce_ip(config: &mut FakerConfig, ip: IpAddr) {
+    match ip {
+        IpAddr::V4(_) => config.announce_ip = Some(ip),
+        IpAddr::V6(v6) => config.announce_ipv6 = Some(v6),
+    }
+}
+
+/// Overwrite the top-level keys of `current` with those in `patch`.
+fn merge_config_patch(
+    current: &FakerConfig,
+    patch: serde_json::Value,
+) -> Result<FakerConfig, String> {
+    let serde_json::Value::Object(patch) = patch else {
+        return Err("Config patch must be a JSON object".to_string());
+    };
+    let mut merged =
+        serde_json::to_value(current).map_err(|e| format!("Failed to read current config: {e}"))?;
+    let Some(fields) = merged.as_object_mut() else {
+        return Err("Current config is not an object".to_string());
+    };
+    for (key, value) in patch {
+        if !fields.contains_key(&key) {
+            return Err(format!("Unknown config field: {key}"));
+        }
+        fields.insert(key, value);
+    }
+    serde_json::from_value(merged).map_err(|e| format!("Invalid config: {e}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rustatio_core::{FakerConfig, FakerState, PostStopAction, PresetSettings, TorrentInfo};
+    use crate::services::instance::{InstanceSortKey, SortOrder, TagMatch};
+    use rustatio_core::{
+        FakerConfig, FakerState, PostStopAction, PresetSettings, TorrentInfo,
+        DEFAULT_COMPLETION_PERCENT,
+    };
 
     fn torrent() -> TorrentInfo {
         torrent_with_hash(7)
@@ -1160,6 +2258,7 @@ mod tests {
     fn torrent_with_hash(byte: u8) -> TorrentInfo {
         TorrentInfo {
             info_hash: [byte; 20],
+            info_hash_v2: None,
             announce: "https://tracker.test/announce".to_string(),
             announce_list: None,
             name: "sample".to_string(),
@@ -1170,11 +2269,21 @@ mod tests {
             comment: None,
             created_by: None,
             is_single_file: true,
+            is_private: false,
             file_count: 1,
             files: Vec::new(),
+            file_lengths: Vec::new(),
         }
     }
 
+    /// Instances start from zero totals, so seed them through a runtime snapshot.
+    async fn set_uploaded(state: &AppState, id: &str, uploaded: u64) {
+        let faker = Arc::clone(&state.instances.read().await[id].faker);
+        let mut stats = faker.stats_snapshot();
+        stats.uploaded = uploaded;
+        faker.restore_snapshot(stats).await;
+    }
+
     async fn set_instance_state(state: &AppState, id: &str, faker_state: FakerState) {
         let instances = state.instances.read().await;
         let instance = instances.get(id);
@@ -1206,7 +2315,7 @@ mod tests {
         assert!(applied.is_ok());
         assert_eq!(applied.unwrap_or_default(), 1);
 
-        let instances = state.list_instances().await;
+        let instances = state.list_instances(&InstanceQuery::default()).await;
         let synced_inst = instances.iter().find(|inst| inst.id == "synced");
         assert!(synced_inst.is_some());
         assert_eq!(synced_inst.map(|inst| inst.config.port), Some(51413));
@@ -1217,60 +2326,136 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn effective_default_config_uses_forwarded_port_when_sync_enabled() {
+    async fn find_instance_summary_reflects_the_current_state() {
         let temp = tempfile::tempdir();
         assert!(temp.is_ok());
         let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
         let state = AppState::new(&temp.path().to_string_lossy());
 
-        let config = FakerConfig { vpn_port_sync: true, port: 6881, ..FakerConfig::default() };
+        let created = state.create_instance("one", torrent(), FakerConfig::default()).await;
+        assert!(created.is_ok());
+        let summary = state.find_instance_summary("one").await;
+        assert_eq!(summary.map(|summary| summary.state), Some("stopped".to_string()));
 
-        let saved = state.set_default_config(Some(config)).await;
-        assert!(saved.is_ok());
-        state.set_current_forwarded_port(Some(45123));
+        assert!(state.set_instance_enabled("one", false).await.is_ok());
+        let summary = state.find_instance_summary("one").await;
+        assert_eq!(summary.map(|summary| summary.state), Some("disabled".to_string()));
 
-        let effective = state.get_effective_default_config().await;
-        assert!(effective.vpn_port_sync);
-        assert_eq!(effective.port, 45123);
+        assert!(state.delete_instance("one", true).await.is_ok());
+        assert!(state.find_instance_summary("one").await.is_none());
     }
 
     #[tokio::test]
-    async fn update_instance_config_uses_forwarded_port_when_sync_enabled() {
+    async fn apply_vpn_public_ip_sets_announce_ip_of_opted_in_instances() {
         let temp = tempfile::tempdir();
         assert!(temp.is_ok());
         let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
         let state = AppState::new(&temp.path().to_string_lossy());
 
-        let config = FakerConfig { vpn_port_sync: true, port: 6881, ..FakerConfig::default() };
-
-        let created = state.create_instance("synced", torrent(), config).await;
-        assert!(created.is_ok());
+        let auto = FakerConfig { auto_announce_vpn_ip: true, ..FakerConfig::default() };
+        assert!(state.create_instance("auto", torrent(), auto.clone()).await.is_ok());
+        let manual = torrent_with_hash(8);
+        assert!(state.create_instance("manual", manual, FakerConfig::default()).await.is_ok());
 
-        state.set_current_forwarded_port(Some(51413));
+        let vpn_ip: IpAddr = [10, 8, 0, 2].into();
+        let applied = state.apply_vpn_public_ip(vpn_ip).await;
+        assert_eq!(applied.ok(), Some(1));
 
-        let updated = state
-            .update_instance_config(
-                "synced",
-                FakerConfig { vpn_port_sync: true, port: 6881, ..FakerConfig::default() },
-            )
-            .await;
-        assert!(updated.is_ok());
+        let announced = |id: &'static str| {
+            let state = state.clone();
+            async move {
+                let faker = Arc::clone(&state.instances.read().await[id].faker);
+                faker.effective_announce_ip().await
+            }
+        };
+        assert_eq!(announced("auto").await, Some(vpn_ip));
+        assert_eq!(announced("manual").await, None);
 
-        let instances = state.list_instances().await;
-        let synced_inst = instances.iter().find(|inst| inst.id == "synced");
-        assert!(synced_inst.is_some());
-        assert_eq!(synced_inst.map(|inst| inst.config.port), Some(51413));
+        // The stored config keeps its own IP, which applies again without the flag
+        let instances = state.list_instances(&InstanceQuery::default()).await;
+        assert!(instances.iter().all(|inst| inst.config.announce_ip.is_none()));
+        let manual_ip: IpAddr = [192, 0, 2, 7].into();
+        let own_ip =
+            FakerConfig { auto_announce_vpn_ip: false, announce_ip: Some(manual_ip), ..auto };
+        assert!(state.update_instance_config("auto", own_ip).await.is_ok());
+        assert_eq!(announced("auto").await, Some(manual_ip));
     }
 
     #[tokio::test]
-    async fn update_instance_config_only_persists_vpn_sync_flag() {
+    async fn effective_default_config_uses_forwarded_port_when_sync_enabled() {
         let temp = tempfile::tempdir();
         assert!(temp.is_ok());
         let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
         let state = AppState::new(&temp.path().to_string_lossy());
 
-        let created = state.create_instance("synced", torrent(), FakerConfig::default()).await;
-        assert!(created.is_ok());
+        let config = FakerConfig { vpn_port_sync: true, port: 6881, ..FakerConfig::default() };
+
+        let saved = state.set_default_config(Some(config)).await;
+        assert!(saved.is_ok());
+        state.set_current_forwarded_port(Some(45123));
+
+        let effective = state.get_effective_default_config().await;
+        assert!(effective.vpn_port_sync);
+        assert_eq!(effective.port, 45123);
+    }
+
+    #[tokio::test]
+    async fn default_completion_is_the_same_on_every_creation_path() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+
+        let from_default = FakerConfig::default().completion_percent;
+        let from_preset = FakerConfig::from(PresetSettings::default()).completion_percent;
+        assert_eq!(from_default, DEFAULT_COMPLETION_PERCENT);
+        assert_eq!(from_preset, DEFAULT_COMPLETION_PERCENT);
+
+        assert!(state.create_idle_instance("idle", torrent(), None).await.is_ok());
+        let instances = state.list_instances(&InstanceQuery::default()).await;
+        let idle = instances.iter().find(|inst| inst.id == "idle");
+        assert_eq!(idle.map(|inst| inst.config.completion_percent), Some(from_default));
+        let stats = state.get_stats("idle").await;
+        assert_eq!(stats.ok().map(|s| s.torrent_completion), Some(from_default));
+    }
+
+    #[tokio::test]
+    async fn update_instance_config_uses_forwarded_port_when_sync_enabled() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+
+        let config = FakerConfig { vpn_port_sync: true, port: 6881, ..FakerConfig::default() };
+
+        let created = state.create_instance("synced", torrent(), config).await;
+        assert!(created.is_ok());
+
+        state.set_current_forwarded_port(Some(51413));
+
+        let updated = state
+            .update_instance_config(
+                "synced",
+                FakerConfig { vpn_port_sync: true, port: 6881, ..FakerConfig::default() },
+            )
+            .await;
+        assert!(updated.is_ok());
+
+        let instances = state.list_instances(&InstanceQuery::default()).await;
+        let synced_inst = instances.iter().find(|inst| inst.id == "synced");
+        assert!(synced_inst.is_some());
+        assert_eq!(synced_inst.map(|inst| inst.config.port), Some(51413));
+    }
+
+    #[tokio::test]
+    async fn update_instance_config_only_persists_vpn_sync_flag() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+
+        let created = state.create_instance("synced", torrent(), FakerConfig::default()).await;
+        assert!(created.is_ok());
 
         state.set_current_forwarded_port(Some(51413));
 
@@ -1312,7 +2497,7 @@ mod tests {
         assert!(applied.is_ok());
         assert_eq!(applied.unwrap_or_default(), 1);
 
-        let instances = state.list_instances().await;
+        let instances = state.list_instances(&InstanceQuery::default()).await;
         let synced_inst = instances.iter().find(|inst| inst.id == "synced");
         assert!(synced_inst.is_some());
         assert_eq!(synced_inst.map(|inst| inst.config.port), Some(51413));
@@ -1355,6 +2540,26 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn desired_peer_port_skips_instances_refusing_connections() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+
+        let closed =
+            FakerConfig { accept_connections: false, port: 50000, ..FakerConfig::default() };
+        assert!(state.create_instance("closed", torrent(), closed).await.is_ok());
+        let open = FakerConfig { port: 50001, ..FakerConfig::default() };
+        assert!(state.create_instance("open", torrent_with_hash(8), open).await.is_ok());
+
+        set_instance_state(&state, "closed", FakerState::Running).await;
+        assert_eq!(state.desired_peer_port_from_instances().await, Ok(None));
+
+        set_instance_state(&state, "open", FakerState::Running).await;
+        assert_eq!(state.desired_peer_port_from_instances().await, Ok(Some(50001)));
+    }
+
     #[tokio::test]
     async fn save_and_load_restores_paused_runtime_state() {
         let temp = tempfile::tempdir();
@@ -1399,7 +2604,7 @@ mod tests {
         let restored_count = loaded.unwrap_or(0);
         assert_eq!(restored_count, 1);
 
-        let instances = restored.list_instances().await;
+        let instances = restored.list_instances(&InstanceQuery::default()).await;
         assert_eq!(instances.len(), 1);
         let stats = &instances[0].stats;
         assert!(matches!(stats.state, FakerState::Paused));
@@ -1411,6 +2616,677 @@ mod tests {
         assert_eq!(stats.seed_time_progress, 50.0);
     }
 
+    #[tokio::test]
+    async fn save_and_load_keeps_partial_completion_exact() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let path = temp.path().to_string_lossy().to_string();
+        let state = AppState::new(&path);
+
+        let total_size = 7_340_033u64;
+        let torrent = TorrentInfo { total_size, ..torrent() };
+        let config = FakerConfig { completion_percent: 37.0, ..FakerConfig::default() };
+        let created = state.create_instance("partial", torrent, config).await;
+        assert!(created.is_ok());
+
+        let left_before = state.list_instances(&InstanceQuery::default()).await[0].stats.left;
+        assert_eq!(left_before, total_size - (total_size as f64 * 0.37).round() as u64);
+
+        let saved = state.save_state().await;
+        assert!(saved.is_ok());
+
+        let restored = AppState::new(&path);
+        let loaded = restored.load_saved_state().await;
+        assert!(loaded.is_ok());
+
+        let instances = restored.list_instances(&InstanceQuery::default()).await;
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances[0].stats.left, left_before);
+
+        // Re-applying the persisted config (as the UI does before starting) must not drift.
+        let persisted_config = instances[0].config.clone();
+        let updated = restored.update_instance_config_only("partial", persisted_config).await;
+        assert!(updated.is_ok());
+        let restored_instances = restored.list_instances(&InstanceQuery::default()).await;
+        assert_eq!(restored_instances[0].stats.left, left_before);
+    }
+
+    #[tokio::test]
+    async fn list_instances_orders_by_created_at_then_id() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+
+        for (idx, id) in ["c", "b", "a"].into_iter().enumerate() {
+            let torrent = torrent_with_hash(idx as u8 + 1);
+            let created = state.create_instance(id, torrent, FakerConfig::default()).await;
+            assert!(created.is_ok());
+        }
+        {
+            let mut instances = state.instances.write().await;
+            for (id, created_at) in [("c", 100), ("b", 200), ("a", 200)] {
+                if let Some(instance) = instances.get_mut(id) {
+                    instance.created_at = created_at;
+                }
+            }
+        }
+
+        let ids: Vec<String> = state
+            .list_instances(&InstanceQuery::default())
+            .await
+            .into_iter()
+            .map(|i| i.id)
+            .collect();
+        assert_eq!(ids, vec!["c", "a", "b"]);
+
+        let summary_ids: Vec<String> =
+            state.list_instance_summaries().await.into_iter().map(|s| s.id).collect();
+        assert_eq!(summary_ids, ids);
+    }
+
+    #[tokio::test]
+    async fn disabled_instance_persists_and_reports_disabled() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let path = temp.path().to_string_lossy().to_string();
+        let state = AppState::new(&path);
+
+        let created = state.create_instance("parked", torrent(), FakerConfig::default()).await;
+        assert!(created.is_ok());
+        assert!(state.set_instance_enabled("parked", false).await.is_ok());
+        assert!(state.start_instance("parked").await.is_err());
+
+        let restored = AppState::new(&path);
+        assert!(restored.load_saved_state().await.is_ok());
+
+        let instances = restored.list_instances(&InstanceQuery::default()).await;
+        assert_eq!(instances.len(), 1);
+        assert!(!instances[0].enabled);
+        assert_eq!(restored.list_instance_summaries().await[0].state, "disabled");
+    }
+
+    #[test]
+    fn restore_ramp_prefers_fixed_delay_then_spreads_window() {
+        assert_eq!(restore_ramp_spacing(Some(2.0), Some(60.0), 5), Duration::from_secs(2));
+        assert_eq!(restore_ramp_spacing(None, Some(60.0), 5), Duration::from_secs(15));
+        assert_eq!(restore_ramp_spacing(None, Some(60.0), 1), Duration::ZERO);
+        assert_eq!(restore_ramp_spacing(None, None, 5), Duration::ZERO);
+        assert_eq!(restore_ramp_spacing(Some(1e300), None, 5), Duration::ZERO);
+        assert_eq!(restore_ramp_spacing(None, Some(1e300), 5), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn restore_ramp_skips_instances_changed_meanwhile() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+        assert!(state.create_instance("stopped", torrent(), FakerConfig::default()).await.is_ok());
+        set_instance_state(&state, "stopped", FakerState::Stopped).await;
+
+        let ids = ["stopped".to_string(), "deleted".to_string()];
+        state.auto_start_restored(&ids, Duration::ZERO).await;
+
+        let faker = Arc::clone(&state.instances.read().await["stopped"].faker);
+        assert!(matches!(faker.stats_snapshot().state, FakerState::Stopped));
+        assert!(!state.instance_exists("deleted").await);
+    }
+
+    #[tokio::test]
+    async fn global_upload_cap_blocks_starts_until_reset() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let path = temp.path().to_string_lossy().to_string();
+        let state = AppState::new(&path);
+        state.set_runtime_settings(RuntimeSettings {
+            global_upload_cap: Some(1024),
+            ..state.runtime_settings()
+        });
+
+        assert!(state.create_instance("capped", torrent(), FakerConfig::default()).await.is_ok());
+        set_uploaded(&state, "capped", 4096).await;
+        assert_eq!(state.total_uploaded().await, 4096);
+
+        assert!(state.enforce_global_upload_cap().await);
+        assert!(state.upload_cap_reached());
+        assert!(!state.enforce_global_upload_cap().await);
+        assert!(state.start_instance("capped").await.is_err());
+
+        assert!(state.reset_global_upload_cap().await.is_ok());
+        assert!(!state.upload_cap_reached());
+        assert_eq!(state.upload_cap_usage().await, 0);
+        assert_eq!(state.persistence.load().await.upload_cap_used, 0);
+    }
+
+    #[tokio::test]
+    async fn upload_cap_usage_survives_deletes_and_restarts() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let path = temp.path().to_string_lossy().to_string();
+        let state = AppState::new(&path);
+        state.set_runtime_settings(RuntimeSettings {
+            global_upload_cap: Some(10_000),
+            ..state.runtime_settings()
+        });
+
+        for (id, byte) in [("a", 1), ("b", 2)] {
+            let torrent = torrent_with_hash(byte);
+            assert!(state.create_instance(id, torrent, FakerConfig::default()).await.is_ok());
+        }
+        set_uploaded(&state, "a", 3000).await;
+        set_uploaded(&state, "b", 2000).await;
+        assert_eq!(state.upload_cap_usage().await, 5000);
+
+        assert!(state.delete_instance("a", true).await.is_ok());
+        assert_eq!(state.upload_cap_usage().await, 5000);
+
+        set_uploaded(&state, "b", 2500).await;
+        assert!(state.save_state().await.is_ok());
+        let restored = AppState::new(&path);
+        assert!(restored.load_saved_state().await.is_ok());
+        assert_eq!(restored.upload_cap_usage().await, 5500);
+    }
+
+    #[tokio::test]
+    async fn upload_cap_holds_instances_under_hnr_protection() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+        state.set_runtime_settings(RuntimeSettings {
+            global_upload_cap: Some(1024),
+            ..state.runtime_settings()
+        });
+
+        let config = FakerConfig { hnr_min_seed_time: Some(3600), ..FakerConfig::default() };
+        assert!(state.create_instance("protected", torrent(), config).await.is_ok());
+        set_instance_state(&state, "protected", FakerState::Running).await;
+        set_uploaded(&state, "protected", 4096).await;
+
+        assert!(state.pause_instance("protected", false).await.is_err());
+        assert!(state.stop_instance("protected", false).await.is_err());
+
+        assert!(state.enforce_global_upload_cap().await);
+        let faker = Arc::clone(&state.instances.read().await["protected"].faker);
+        assert!(matches!(faker.stats_snapshot().state, FakerState::Running));
+        assert!(state.hnr_hold("protected").await.is_some());
+
+        // Paused on a later tick once the requirement is met
+        let mut stats = faker.stats_snapshot();
+        stats.elapsed_time = Duration::from_hours(2);
+        faker.restore_snapshot(stats).await;
+        assert!(!state.enforce_global_upload_cap().await);
+        assert!(matches!(faker.stats_snapshot().state, FakerState::Paused));
+    }
+
+    #[tokio::test]
+    async fn scheduled_stop_is_refused_under_hnr_protection() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+
+        let config = FakerConfig { hnr_min_seed_time: Some(3600), ..FakerConfig::default() };
+        assert!(state.create_instance("protected", torrent(), config).await.is_ok());
+        let schedule =
+            InstanceSchedule { stop_cron: Some("* * * * *".to_string()), ..Default::default() };
+        assert!(state.set_instance_schedule("protected", Some(schedule)).await.is_ok());
+        set_instance_state(&state, "protected", FakerState::Running).await;
+
+        crate::services::cron::run_schedules(&state, 0).await;
+        let faker = Arc::clone(&state.instances.read().await["protected"].faker);
+        assert!(matches!(faker.stats_snapshot().state, FakerState::Running));
+    }
+
+    #[tokio::test]
+    async fn rate_history_survives_a_restart_up_to_history_length() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let path = temp.path().to_string_lossy().to_string();
+        let state = AppState::new(&path);
+        let config = FakerConfig { history_length: 70, ..FakerConfig::default() };
+        assert!(state.create_instance("graph", torrent(), config).await.is_ok());
+
+        let faker = Arc::clone(&state.instances.read().await["graph"].faker);
+        let mut stats = faker.stats_snapshot();
+        stats.upload_rate_history = (0..80).map(f64::from).collect();
+        stats.ratio_history = vec![0.5, 0.75];
+        stats.history_timestamps = (0..80).collect();
+        faker.restore_snapshot(stats).await;
+        assert!(state.save_state().await.is_ok());
+
+        let restored = AppState::new(&path);
+        assert!(restored.load_saved_state().await.is_ok());
+        let instances = restored.list_instances(&InstanceQuery::default()).await;
+        let stats = &instances[0].stats;
+        assert_eq!(stats.upload_rate_history.len(), 70);
+        assert_eq!(stats.upload_rate_history.first(), Some(&10.0));
+        assert_eq!(stats.history_timestamps.last(), Some(&79));
+        assert_eq!(stats.ratio_history, vec![0.5, 0.75]);
+    }
+
+    #[tokio::test]
+    async fn stats_events_keep_history_until_stripped() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+        assert!(state.create_instance("graph", torrent(), FakerConfig::default()).await.is_ok());
+
+        let faker = Arc::clone(&state.instances.read().await["graph"].faker);
+        let mut stats = faker.stats_snapshot();
+        stats.state = FakerState::Running;
+        stats.upload_rate_history = vec![1.0, 2.0];
+        stats.history_timestamps = vec![1, 2];
+        faker.restore_snapshot(stats).await;
+
+        let mut rx = state.subscribe_stats();
+        state.publish_stats().await;
+        let event = rx.recv().await.unwrap_or_else(|_| panic!("no stats event"));
+        assert_eq!(event.instances[0].stats.upload_rate_history, vec![1.0, 2.0]);
+
+        let stripped = event.without_history();
+        assert!(stripped.instances[0].stats.upload_rate_history.is_empty());
+        assert!(stripped.instances[0].stats.history_timestamps.is_empty());
+        assert!(matches!(stripped.instances[0].stats.state, FakerState::Running));
+    }
+
+    #[tokio::test]
+    async fn list_instances_filters_sorts_and_pages() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+
+        for (id, byte, uploaded) in [("a", 1, 300), ("b", 2, 100), ("c", 3, 200)] {
+            let torrent = torrent_with_hash(byte);
+            assert!(state.create_instance(id, torrent, FakerConfig::default()).await.is_ok());
+            set_uploaded(&state, id, uploaded).await;
+        }
+        assert!(state.update_instance_tags("a", vec!["Movies".to_string()]).await.is_ok());
+        assert!(state.update_instance_tags("c", vec!["movies".to_string()]).await.is_ok());
+        assert!(state.set_instance_enabled("b", false).await.is_ok());
+
+        let ids = |instances: Vec<InstanceInfo>| -> Vec<String> {
+            instances.into_iter().map(|instance| instance.id).collect()
+        };
+        let by_upload = InstanceQuery {
+            sort: InstanceSortKey::Uploaded,
+            order: SortOrder::Desc,
+            ..InstanceQuery::default()
+        };
+        assert_eq!(ids(state.list_instances(&by_upload).await), vec!["a", "c", "b"]);
+
+        let paged = InstanceQuery { limit: Some(1), offset: 1, ..by_upload.clone() };
+        assert_eq!(ids(state.list_instances(&paged).await), vec!["c"]);
+        assert_eq!(state.count_instances(&paged).await, 3);
+
+        let tagged = InstanceQuery { tag: Some("MOVIES".to_string()), ..by_upload.clone() };
+        assert_eq!(ids(state.list_instances(&tagged).await), vec!["a", "c"]);
+
+        let disabled = InstanceQuery { state: Some("disabled".to_string()), ..Default::default() };
+        assert_eq!(ids(state.list_instances(&disabled).await), vec!["b"]);
+
+        assert!(state.update_instance_tags("c", vec!["movies".into(), "4k".into()]).await.is_ok());
+        let any = InstanceQuery { tags: Some("4K, tv".to_string()), ..by_upload.clone() };
+        assert_eq!(ids(state.list_instances(&any).await), vec!["c"]);
+        let all = InstanceQuery {
+            tags: Some("movies,4k".to_string()),
+            tag_match: TagMatch::All,
+            ..by_upload
+        };
+        assert_eq!(ids(state.list_instances(&all).await), vec!["c"]);
+        let filter = TagFilter { tags: Some("movies".to_string()), tag_match: TagMatch::All };
+        assert_eq!(state.list_instance_summaries_matching(&filter).await.len(), 2);
+
+        let counts = state.tag_counts().await;
+        let expected = vec![
+            TagCount { tag: "4k".to_string(), count: 1 },
+            TagCount { tag: "Movies".to_string(), count: 2 },
+        ];
+        assert_eq!(counts, expected);
+    }
+
+    #[tokio::test]
+    async fn instance_logs_are_served_until_the_instance_is_deleted() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+
+        assert!(state.get_instance_logs("a", None).await.is_err());
+        assert!(state.create_instance("a", torrent(), FakerConfig::default()).await.is_ok());
+        state.instance_logs.push("a", LogEvent::new("info", "announced".to_string()));
+
+        let lines = state.get_instance_logs("a", Some(10)).await.unwrap_or_default();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].message, "announced");
+
+        assert!(state.delete_instance("a", true).await.is_ok());
+        assert!(state.instance_logs.recent("a", None).is_empty());
+    }
+
+    #[tokio::test]
+    async fn instance_totals_sum_every_instance_and_group_by_tag() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+
+        for (id, byte, uploaded) in [("a", 1, 2048), ("b", 2, 1024), ("c", 3, 0)] {
+            let torrent = torrent_with_hash(byte);
+            assert!(state.create_instance(id, torrent, FakerConfig::default()).await.is_ok());
+            set_uploaded(&state, id, uploaded).await;
+        }
+        set_instance_state(&state, "a", FakerState::Running).await;
+        set_instance_state(&state, "b", FakerState::Paused).await;
+        let tags = vec!["Movies".to_string(), "movies".to_string()];
+        assert!(state.update_instance_tags("a", tags).await.is_ok());
+        assert!(state.update_instance_tags("b", vec!["movies".into()]).await.is_ok());
+
+        let (totals, by_tag) = state.instance_totals(false).await;
+        assert!(by_tag.is_empty());
+        assert_eq!(totals.instances, 3);
+        assert_eq!((totals.running, totals.paused, totals.stopped, totals.idle), (1, 1, 1, 0));
+        assert_eq!(totals.uploaded, 3072);
+        assert_eq!(totals.ratio, 1.0);
+
+        let (_, by_tag) = state.instance_totals(true).await;
+        assert_eq!(by_tag.len(), 1);
+        assert_eq!(by_tag[0].tag, "Movies");
+        assert_eq!(by_tag[0].totals.instances, 2);
+        assert_eq!(by_tag[0].totals.uploaded, 3072);
+        assert_eq!(by_tag[0].totals.ratio, 1.5);
+    }
+
+    #[tokio::test]
+    async fn bundles_restore_instances_and_merge_or_replace_duplicates() {
+        let temp = tempfile::tempdir().unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let source = AppState::new(&temp.path().join("source").to_string_lossy());
+        for (id, byte) in [("a", 1), ("b", 2)] {
+            let torrent = torrent_with_hash(byte);
+            assert!(source.create_instance(id, torrent, FakerConfig::default()).await.is_ok());
+        }
+        set_uploaded(&source, "a", 4096).await;
+        assert!(source.update_instance_tags("a", vec!["movies".to_string()]).await.is_ok());
+        let bundle = source.export_bundle().await;
+        assert_eq!(bundle.instances.len(), 2);
+
+        let target = AppState::new(&temp.path().join("target").to_string_lossy());
+        for (id, byte) in [("b", 2), ("x", 3)] {
+            let torrent = torrent_with_hash(byte);
+            assert!(target.create_instance(id, torrent, FakerConfig::default()).await.is_ok());
+        }
+        let merged = target.import_bundle(bundle.clone(), ImportMode::Merge).await;
+        let merged = merged.unwrap_or_else(|e| panic!("merge failed: {e}"));
+        assert_eq!(merged.imported, vec!["a"]);
+        assert_eq!(merged.skipped.len(), 1);
+        assert_eq!(target.get_stats("a").await.map(|stats| stats.uploaded), Ok(4096));
+        assert_eq!(target.instances.read().await["a"].tags, vec!["movies"]);
+
+        let replaced = target.import_bundle(bundle.clone(), ImportMode::Replace).await;
+        let replaced = replaced.unwrap_or_else(|e| panic!("replace failed: {e}"));
+        assert_eq!(replaced.replaced, vec!["a", "b"]);
+        assert_eq!(target.instances.read().await.len(), 3);
+
+        let newer = ConfigBundle { version: BUNDLE_VERSION + 1, ..bundle };
+        assert!(target.import_bundle(newer, ImportMode::Merge).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn server_proxy_routes_announces_and_scrapes() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Answers like a tracker behind an HTTP proxy and reports each request line
+        let listener =
+            tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap_or_else(|e| panic!("{e}"));
+        let addr = listener.local_addr().unwrap_or_else(|e| panic!("{e}"));
+        let (lines_tx, mut lines_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request).into_owned();
+                let line = request.lines().next().unwrap_or_default().to_string();
+                let body = if line.contains("/scrape?") {
+                    let mut body = b"d5:filesd20:".to_vec();
+                    body.extend_from_slice(&[7u8; 20]);
+                    body.extend_from_slice(b"d8:completei1e10:downloadedi0e10:incompletei0eeee");
+                    body
+                } else {
+                    b"d8:intervali1800ee".to_vec()
+                };
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = socket.write_all(head.as_bytes()).await;
+                let _ = socket.write_all(&body).await;
+                let _ = lines_tx.send(line);
+            }
+        });
+
+        let temp = tempfile::tempdir().unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState {
+            tracker_proxy: Some(format!("http://{addr}")),
+            ..AppState::new(&temp.path().to_string_lossy())
+        };
+        let torrent =
+            TorrentInfo { announce: "http://tracker.invalid/announce".to_string(), ..torrent() };
+        assert!(state.create_instance("p", torrent, FakerConfig::default()).await.is_ok());
+        let faker = Arc::clone(&state.instances.read().await["p"].faker);
+
+        assert!(faker.validate_with_tracker().await.is_ok());
+        assert!(faker.scrape().await.is_ok());
+
+        let mut lines = Vec::new();
+        while let Ok(line) = lines_rx.try_recv() {
+            lines.push(line);
+        }
+        assert!(
+            lines.iter().all(|line| line.starts_with("GET http://tracker.invalid/")),
+            "{lines:?}"
+        );
+        assert!(lines.iter().any(|line| line.contains("/announce?")), "{lines:?}");
+        assert!(lines.iter().any(|line| line.contains("/scrape?")), "{lines:?}");
+    }
+
+    #[tokio::test]
+    async fn bundles_carry_the_original_torrent_file() {
+        let bytes: &[u8] = b"d8:announce28:http://tracker.test/announce4:infod6:lengthi256e\
+            4:name6:sample12:piece lengthi256e6:pieces20:aaaaaaaaaaaaaaaaaaaaee";
+        let summary = TorrentSummary::from_bytes(bytes)
+            .unwrap_or_else(|e| panic!("sample torrent should parse: {e}"));
+        let temp = tempfile::tempdir().unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let source = AppState::new(&temp.path().join("source").to_string_lossy());
+        let created = source.create_idle_instance("a", summary.to_info(), Some(bytes.to_vec()));
+        assert!(created.await.is_ok());
+        let saved = source.persistence.load_torrent_file(&summary.info_hash).await;
+        assert_eq!(saved.as_deref(), Some(bytes));
+
+        let bundle = source.export_bundle().await;
+        assert_eq!(bundle.instances[0].torrent_file, Some(hex::encode(bytes)));
+
+        let target = AppState::new(&temp.path().join("target").to_string_lossy());
+        let mut tampered = bundle.clone();
+        tampered.instances[0].torrent_file = Some(hex::encode(b"d4:infod4:name1:xee"));
+        let report = target.import_bundle(tampered, ImportMode::Merge).await;
+        assert_eq!(report.map(|r| r.errors.len()), Ok(1));
+
+        let report = target.import_bundle(bundle, ImportMode::Merge).await;
+        assert_eq!(report.map(|r| r.imported), Ok(vec!["a".to_string()]));
+        let imported = target.instances.read().await["a"].torrent_file.clone();
+        assert_eq!(imported.as_deref(), Some(bytes));
+
+        assert!(target.delete_instance("a", false).await.is_ok());
+        assert!(target.persistence.load_torrent_file(&summary.info_hash).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn clones_keep_the_torrent_file_when_the_original_is_deleted() {
+        let bytes: &[u8] = b"d8:announce28:http://tracker.test/announce4:infod6:lengthi256e\
+            4:name6:sample12:piece lengthi256e6:pieces20:aaaaaaaaaaaaaaaaaaaaee";
+        let summary = TorrentSummary::from_bytes(bytes)
+            .unwrap_or_else(|e| panic!("sample torrent should parse: {e}"));
+        let temp = tempfile::tempdir().unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let path = temp.path().to_string_lossy().to_string();
+        let state = AppState::new(&path);
+        let created = state.create_idle_instance("a", summary.to_info(), Some(bytes.to_vec()));
+        assert!(created.await.is_ok());
+        assert!(state.clone_instance("a", "b").await.is_ok());
+        assert!(state.delete_instance("a", false).await.is_ok());
+
+        let restored = AppState::new(&path);
+        assert!(restored.load_saved_state().await.is_ok());
+        let bundle = restored.export_bundle().await;
+        assert_eq!(bundle.instances.len(), 1);
+        assert_eq!(bundle.instances[0].torrent_file, Some(hex::encode(bytes)));
+
+        assert!(restored.delete_instance("b", false).await.is_ok());
+        assert!(restored.persistence.load_torrent_file(&summary.info_hash).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn clone_gets_fresh_totals_new_peer_id_and_manual_source() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+        let mut events = state.subscribe_instance_events();
+
+        let pinned = FakerConfig {
+            peer_id_override: Some("-qB5000-pinnedpeerid".to_string()),
+            ..FakerConfig::default()
+        };
+        assert!(state.create_instance("watched", torrent(), pinned).await.is_ok());
+        set_uploaded(&state, "watched", 4096).await;
+        assert!(state.update_instance_source("watched", InstanceSource::WatchFolder).await.is_ok());
+
+        assert!(state.clone_instance("watched", "copy").await.is_ok());
+        assert!(state.clone_instance("missing", "other").await.is_err());
+        assert!(state.clone_instance("watched", "copy").await.is_err());
+        assert!(matches!(
+            events.try_recv(),
+            Ok(InstanceEvent::Created { ref id, .. }) if id == "copy"
+        ));
+
+        let instances = state.instances.read().await;
+        let (source, copy) = (&instances["watched"], &instances["copy"]);
+        assert_eq!(copy.source, InstanceSource::Manual);
+        assert_eq!(copy.torrent_info_hash, source.torrent_info_hash);
+        assert_eq!(copy.faker.stats_snapshot().uploaded, 0);
+        assert_eq!(source.faker.stats_snapshot().uploaded, 4096);
+        assert_eq!(source.faker.peer_id().await, "-qB5000-pinnedpeerid");
+        assert_ne!(copy.faker.peer_id().await, source.faker.peer_id().await);
+        assert_eq!(copy.config.peer_id_override, None);
+        drop(instances);
+
+        let info_hash = torrent().info_hash;
+        assert_eq!(state.find_instance_by_info_hash(&info_hash).await.as_deref(), Some("watched"));
+    }
+
+    #[tokio::test]
+    async fn instance_label_replaces_name_in_summaries_and_is_restored() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let path = temp.path().to_string_lossy().to_string();
+        let state = AppState::new(&path);
+        assert!(state.create_instance("labelled", torrent(), FakerConfig::default()).await.is_ok());
+
+        assert!(state.set_instance_label("labelled", Some("x".repeat(201))).await.is_err());
+        let stored = state.set_instance_label("labelled", Some(" Linux ISO ".to_string())).await;
+        assert_eq!(stored, Ok(Some("Linux ISO".to_string())));
+
+        let summaries = state.list_instance_summaries().await;
+        assert_eq!(summaries[0].name, "Linux ISO");
+        assert_eq!(summaries[0].label.as_deref(), Some("Linux ISO"));
+
+        let restored = AppState::new(&path);
+        assert!(restored.load_saved_state().await.is_ok());
+        let instances = restored.list_instances(&InstanceQuery::default()).await;
+        assert_eq!(instances[0].label.as_deref(), Some("Linux ISO"));
+
+        assert_eq!(restored.set_instance_label("labelled", Some("  ".to_string())).await, Ok(None));
+        assert_ne!(restored.list_instance_summaries().await[0].name, "Linux ISO");
+    }
+
+    #[tokio::test]
+    async fn instance_schedule_is_validated_and_restored() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let path = temp.path().to_string_lossy().to_string();
+        let state = AppState::new(&path);
+        assert!(state.create_instance("night", torrent(), FakerConfig::default()).await.is_ok());
+
+        let invalid =
+            InstanceSchedule { start_cron: Some("0 25 * * *".to_string()), ..Default::default() };
+        assert!(state.set_instance_schedule("night", Some(invalid)).await.is_err());
+
+        let schedule = InstanceSchedule {
+            start_cron: Some(" 0 22 * * * ".to_string()),
+            stop_cron: Some("0 6 * * 1-5".to_string()),
+            utc_offset_minutes: 60,
+        };
+        let stored = state.set_instance_schedule("night", Some(schedule)).await;
+        let stored = stored.unwrap_or_else(|e| panic!("schedule should be accepted: {e}"));
+        assert_eq!(stored.as_ref().and_then(|s| s.start_cron.as_deref()), Some("0 22 * * *"));
+
+        let restored = AppState::new(&path);
+        assert!(restored.load_saved_state().await.is_ok());
+        assert_eq!(restored.list_instances(&InstanceQuery::default()).await[0].schedule, stored);
+    }
+
+    #[tokio::test]
+    async fn killswitch_pauses_active_instances_and_resumes_only_those() {
+        let temp = tempfile::tempdir();
+        assert!(temp.is_ok());
+        let temp = temp.unwrap_or_else(|_| panic!("failed to create tempdir"));
+        let state = AppState::new(&temp.path().to_string_lossy());
+        let mut events = state.subscribe_instance_events();
+
+        assert!(state.create_instance("active", torrent(), FakerConfig::default()).await.is_ok());
+        let idle = torrent_with_hash(8);
+        assert!(state.create_instance("idle", idle, FakerConfig::default()).await.is_ok());
+        let faker_of = |id: &'static str| {
+            let state = state.clone();
+            async move {
+                let instances = state.instances.read().await;
+                Arc::clone(&instances.get(id).unwrap_or_else(|| panic!("missing {id}")).faker)
+            }
+        };
+        assert!(faker_of("active").await.resume().await.is_ok());
+
+        assert_eq!(state.engage_killswitch().await, 1);
+        assert!(state.killswitch_engaged());
+        assert!(matches!(faker_of("active").await.stats_snapshot().state, FakerState::Paused));
+        assert!(matches!(
+            events.try_recv(),
+            Ok(InstanceEvent::Paused { ref id, .. }) if id == "active"
+        ));
+        assert!(state.start_instance("idle").await.is_err());
+        assert_eq!(state.engage_killswitch().await, 0);
+
+        assert_eq!(state.release_killswitch().await, 1);
+        assert!(!state.killswitch_engaged());
+        assert!(matches!(faker_of("active").await.stats_snapshot().state, FakerState::Running));
+        assert!(matches!(faker_of("idle").await.stats_snapshot().state, FakerState::Stopped));
+        assert_eq!(state.release_killswitch().await, 0);
+    }
+
     #[tokio::test]
     async fn custom_and_default_presets_persist_to_state_file() {
         let temp = tempfile::tempdir();
@@ -1496,6 +3372,7 @@ mod tests {
                 let mut stats = instance.faker.stats_snapshot();
                 stats.state = FakerState::Stopped;
                 stats.tracker_error = Some("Torrent not found on tracker".to_string());
+                stats.completed_already_sent = true;
                 stats.current_upload_rate = 0.0;
                 stats.current_download_rate = 0.0;
                 instance.faker.restore_snapshot(stats).await;
@@ -1509,11 +3386,12 @@ mod tests {
         let loaded = restored.load_saved_state().await;
         assert!(loaded.is_ok());
 
-        let instances = restored.list_instances().await;
+        let instances = restored.list_instances(&InstanceQuery::default()).await;
         assert_eq!(instances.len(), 1);
         let stats = &instances[0].stats;
         assert!(matches!(stats.state, FakerState::Stopped));
         assert_eq!(stats.tracker_error.as_deref(), Some("Torrent not found on tracker"));
+        assert!(stats.completed_already_sent);
     }
 
     #[tokio::test]
@@ -1547,4 +3425,20 @@ mod tests {
         assert_eq!(saved.config.port, updated.port);
         assert_eq!(saved.config.stop_at_ratio, updated.stop_at_ratio);
     }
+
+    #[test]
+    fn config_patch_only_overwrites_given_fields() {
+        let current = FakerConfig { port: 51413, scrape_interval: 60, ..FakerConfig::default() };
+
+        let merged = merge_config_patch(&current, serde_json::json!({ "scrape_interval": 30 }))
+            .unwrap_or_else(|e| panic!("patch should apply: {e}"));
+        assert_eq!(merged.scrape_interval, 30);
+        assert_eq!(merged.port, 51413);
+
+        let unknown = merge_config_patch(&current, serde_json::json!({ "scrape_intervall": 30 }));
+        assert!(unknown.is_err_and(|e| e.contains("scrape_intervall")));
+
+        let invalid = merge_config_patch(&current, serde_json::json!({ "port": "not a port" }));
+        assert!(invalid.is_err_and(|e| e.starts_with("Invalid config")));
+    }
 }
@@ -0,0 +1,245 @@
+//! Quick tracker reachability check run when a torrent is loaded.
+//!
+//! A single scrape tells whether the tracker answers and supports scrape; trackers
+//! whose announce URL has no scrape counterpart (BEP 48) get a plain GET instead.
+//! The check is capped at a couple of seconds so a dead tracker never blocks loading.
+
+use rustatio_core::protocol::{TrackerClient, TrackerError};
+use rustatio_core::{ClientConfig, ClientType};
+use serde::Serialize;
+use std::time::Duration;
+use utoipa::ToSchema;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Query keys private trackers commonly carry the passkey in
+const PASSKEY_QUERY_KEYS: &[&str] = &["passkey", "pk", "authkey", "torrent_pass", "apikey"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct TrackerCheck {
+    /// Tracker URL that was checked
+    pub url: String,
+    /// Whether the tracker answered; `null` when it could not be checked
+    pub reachable: Option<bool>,
+    /// Whether the tracker answers scrape requests; `null` when unknown
+    pub scrape_supported: Option<bool>,
+    /// The URL carries a passkey, as private trackers require
+    pub requires_passkey: bool,
+    /// Why the check failed or what looked wrong
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl TrackerCheck {
+    fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            reachable: None,
+            scrape_supported: None,
+            requires_passkey: has_passkey(url),
+            error: None,
+        }
+    }
+
+    /// A check that was not run, with the reason why.
+    pub fn skipped(url: &str, reason: &str) -> Self {
+        Self { error: Some(reason.to_string()), ..Self::new(url) }
+    }
+}
+
+/// Check `tracker_url` for the torrent with `info_hash`, giving up after `CHECK_TIMEOUT`.
+pub async fn check_tracker(
+    http: reqwest::Client,
+    tracker_url: &str,
+    info_hash: &[u8; 20],
+) -> TrackerCheck {
+    let mut check = TrackerCheck::new(tracker_url);
+    if !(tracker_url.starts_with("http://") || tracker_url.starts_with("https://")) {
+        check.error = Some("Only HTTP(S) trackers can be checked".to_string());
+        return check;
+    }
+
+    let outcome = if supports_scrape_url(tracker_url) {
+        let config = ClientConfig::get(ClientType::QBittorrent, None);
+        let client = match TrackerClient::new(config, Some(http)) {
+            Ok(client) => client,
+            Err(e) => {
+                check.error = Some(e.to_string());
+                return check;
+            }
+        };
+        tokio::time::timeout(CHECK_TIMEOUT, client.scrape(tracker_url, info_hash))
+            .await
+            .map(|result| classify_scrape(result.map(|_| ())))
+    } else {
+        tokio::time::timeout(CHECK_TIMEOUT, http.get(tracker_url).send()).await.map(|result| {
+            match result {
+                Ok(_) => (Some(true), Some(false), None),
+                Err(e) => (Some(false), None, Some(e.to_string())),
+            }
+        })
+    };
+
+    let (reachable, scrape_supported, error) = outcome.unwrap_or_else(|_| {
+        (Some(false), None, Some(format!("No answer within {}s", CHECK_TIMEOUT.as_secs())))
+    });
+    check.reachable = reachable;
+    check.scrape_supported = scrape_supported;
+    check.error = error;
+    check
+}
+
+/// Map a scrape outcome to (reachable, `scrape_supported`, error).
+fn classify_scrape(
+    result: Result<(), TrackerError>,
+) -> (Option<bool>, Option<bool>, Option<String>) {
+    match result {
+        Ok(()) => (Some(true), Some(true), None),
+        Err(TrackerError::InvalidResponse(msg)) if msg.contains("not found in scrape") => {
+            (Some(true), Some(true), Some("Tracker does not know this torrent".to_string()))
+        }
+        // An HTTP status or an unparsable body still means the tracker answered
+        Err(TrackerError::HttpError(msg)) if msg.starts_with("HTTP status") => {
+            (Some(true), Some(false), Some(msg))
+        }
+        Err(
+            e @ (TrackerError::InvalidResponse(_)
+            | TrackerError::BencodeError(_)
+            | TrackerError::TrackerFailure(_)),
+        ) => (Some(true), Some(false), Some(e.to_string())),
+        Err(e) => (Some(false), None, Some(e.to_string())),
+    }
+}
+
+/// BEP 48: only announce URLs whose last path segment starts with `announce` have a
+/// scrape URL.
+fn supports_scrape_url(tracker_url: &str) -> bool {
+    reqwest::Url::parse(tracker_url).is_ok_and(|url| {
+        url.path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .is_some_and(|last| last.starts_with("announce"))
+    })
+}
+
+/// Whether the URL carries a passkey, either as a known query key or as a long
+/// alphanumeric path segment (`/<passkey>/announce`).
+fn has_passkey(tracker_url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(tracker_url) else {
+        return false;
+    };
+    let in_query = url
+        .query_pairs()
+        .any(|(key, value)| !value.is_empty() && PASSKEY_QUERY_KEYS.contains(&key.as_ref()));
+    let in_path = url.path_segments().is_some_and(|mut segments| segments.any(is_passkey_segment));
+    in_query || in_path
+}
+
+fn is_passkey_segment(segment: &str) -> bool {
+    segment.len() >= 16 && segment.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// The URL with passkeys and the announce `key` replaced by `REDACTED`, safe to show.
+/// The rest is kept byte for byte, encoded info hash included.
+pub fn redact_tracker_url(tracker_url: &str) -> String {
+    let (base, query) = tracker_url.split_once('?').unwrap_or((tracker_url, ""));
+    let base: Vec<&str> = base
+        .split('/')
+        .map(|segment| if is_passkey_segment(segment) { "REDACTED" } else { segment })
+        .collect();
+    let base = base.join("/");
+    if query.is_empty() {
+        return base;
+    }
+    let query: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if key == "key" || PASSKEY_QUERY_KEYS.contains(&key) => {
+                format!("{key}=REDACTED")
+            }
+            _ => pair.to_string(),
+        })
+        .collect();
+    format!("{base}?{}", query.join("&"))
+}
+
+/// `text` with every `http(s)://` URL in it passed through [`redact_tracker_url`], for
+/// error messages that may quote the request URL.
+pub fn redact_urls_in_text(text: &str) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = ["http://", "https://"].iter().filter_map(|s| rest.find(s)).min() {
+        redacted.push_str(&rest[..start]);
+        let url = &rest[start..];
+        let end = url
+            .find(|c: char| c.is_whitespace() || matches!(c, ')' | '"' | '\''))
+            .unwrap_or(url.len());
+        redacted.push_str(&redact_tracker_url(&url[..end]));
+        rest = &url[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passkeys_are_detected_in_query_and_path() {
+        assert!(has_passkey("https://tracker.test/announce.php?passkey=abc123"));
+        assert!(has_passkey("https://tracker.test/0123456789abcdef0123/announce"));
+        assert!(!has_passkey("https://tracker.test/announce"));
+        assert!(!has_passkey("udp://tracker.test:1337"));
+    }
+
+    #[test]
+    fn redaction_hides_passkeys_and_the_announce_key() {
+        assert_eq!(
+            redact_tracker_url(
+                "https://tracker.test/announce.php?passkey=abc&info_hash=%AB%CD&key=1F2E"
+            ),
+            "https://tracker.test/announce.php?passkey=REDACTED&info_hash=%AB%CD&key=REDACTED"
+        );
+        assert_eq!(
+            redact_tracker_url("https://tracker.test/0123456789abcdef0123/announce?left=0"),
+            "https://tracker.test/REDACTED/announce?left=0"
+        );
+        assert_eq!(redact_tracker_url("udp://tracker.test:1337"), "udp://tracker.test:1337");
+    }
+
+    #[test]
+    fn redaction_covers_urls_quoted_in_error_messages() {
+        assert_eq!(
+            redact_urls_in_text(
+                "error sending request for url (https://tracker.test/announce?passkey=abc&left=0)"
+            ),
+            "error sending request for url (https://tracker.test/announce?passkey=REDACTED&left=0)"
+        );
+        assert_eq!(
+            redact_urls_in_text("moved to http://a.test/0123456789abcdef0123/announce then failed"),
+            "moved to http://a.test/REDACTED/announce then failed"
+        );
+        assert_eq!(redact_urls_in_text("HTTP status: 502"), "HTTP status: 502");
+    }
+
+    #[test]
+    fn scrape_urls_follow_bep_48() {
+        assert!(supports_scrape_url("https://tracker.test/announce"));
+        assert!(supports_scrape_url("https://tracker.test/pk/announce.php?x=1"));
+        assert!(!supports_scrape_url("https://tracker.test/a"));
+    }
+
+    #[test]
+    fn scrape_errors_separate_answering_from_unreachable_trackers() {
+        let status = Err(TrackerError::HttpError("HTTP status: 404 Not Found".into()));
+        assert_eq!(classify_scrape(status).0, Some(true));
+
+        let unknown =
+            Err(TrackerError::InvalidResponse("Torrent not found in scrape response".into()));
+        assert_eq!(classify_scrape(unknown).1, Some(true));
+
+        let refused = Err(TrackerError::HttpError("connection refused".into()));
+        let expected = (Some(false), None, Some("HTTP error: connection refused".to_string()));
+        assert_eq!(classify_scrape(refused), expected);
+    }
+}
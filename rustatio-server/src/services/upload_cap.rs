@@ -0,0 +1,69 @@
+//! Metering for the server-wide cap on total uploaded bytes.
+//!
+//! Usage only grows: every tally adds what each instance uploaded since it was
+//! last seen, so deleting or replacing instances never gives bytes back. Only a
+//! reset of the cap brings it back to zero.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Default)]
+pub struct UploadCapMeter {
+    used: u64,
+    /// Cumulative uploaded bytes of each instance at the last tally
+    seen: HashMap<String, u64>,
+}
+
+impl UploadCapMeter {
+    pub fn new(used: u64) -> Self {
+        Self { used, seen: HashMap::new() }
+    }
+
+    /// Add what each instance uploaded since the last tally and return the total used.
+    /// Instances seen for the first time count from their current total; instances
+    /// no longer listed are forgotten.
+    pub fn tally<'a>(&mut self, uploaded: impl IntoIterator<Item = (&'a str, u64)>) -> u64 {
+        let mut seen = HashMap::with_capacity(self.seen.len());
+        for (id, uploaded) in uploaded {
+            let last = self.seen.get(id).copied().unwrap_or(uploaded);
+            self.used = self.used.saturating_add(uploaded.saturating_sub(last));
+            seen.insert(id.to_string(), uploaded);
+        }
+        self.seen = seen;
+        self.used
+    }
+
+    /// Count `id` from `uploaded` on, e.g. when a new instance takes over its ID.
+    pub fn rebase(&mut self, id: &str, uploaded: u64) {
+        self.seen.insert(id.to_string(), uploaded);
+    }
+
+    pub const fn reset(&mut self) {
+        self.used = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_only_grows_until_reset() {
+        let mut meter = UploadCapMeter::new(100);
+        assert_eq!(meter.tally([("a", 1000), ("b", 50)]), 100);
+        assert_eq!(meter.tally([("a", 1500), ("b", 80)]), 630);
+
+        // Removing an instance keeps what it uploaded
+        assert_eq!(meter.tally([("b", 100)]), 650);
+
+        // A replacement with a higher total only counts what it uploads afterwards
+        meter.rebase("b", 10_000);
+        assert_eq!(meter.tally([("b", 10_200)]), 850);
+
+        // A lower total (stats reset) is a new starting point, not negative usage
+        assert_eq!(meter.tally([("b", 0)]), 850);
+        assert_eq!(meter.tally([("b", 25)]), 875);
+
+        meter.reset();
+        assert_eq!(meter.tally([("b", 40)]), 15);
+    }
+}
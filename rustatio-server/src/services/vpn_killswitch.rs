@@ -0,0 +1,170 @@
+//! VPN kill-switch: pauses active instances while Gluetun reports the VPN down
+//! and resumes exactly those instances once the tunnel is running again.
+//!
+//! An unreachable control server is not treated as a drop, so a restart of the
+//! Gluetun API alone does not pause anything.
+
+use super::state::AppState;
+use crate::services::GluetunAuth;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+const DEFAULT_INTERVAL_SECS: u64 = 5;
+
+pub struct VpnKillswitch {
+    shutdown_tx: Option<mpsc::Sender<()>>,
+    task_handle: Option<tokio::task::JoinHandle<()>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VpnKillswitchConfig {
+    pub enabled: bool,
+    pub interval: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VpnStatus {
+    Running,
+    Down,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transition {
+    Dropped,
+    Restored,
+}
+
+#[derive(Debug, Default)]
+struct VpnWatch {
+    running: Option<bool>,
+}
+
+impl VpnWatch {
+    /// Returns the transition this status causes, if any. A VPN that is already
+    /// down at the first probe counts as a drop.
+    const fn observe(&mut self, status: VpnStatus) -> Option<Transition> {
+        let running = match status {
+            VpnStatus::Running => true,
+            VpnStatus::Down => false,
+            VpnStatus::Unknown => return None,
+        };
+        match (self.running.replace(running), running) {
+            (None | Some(true), false) => Some(Transition::Dropped),
+            (Some(false), true) => Some(Transition::Restored),
+            _ => None,
+        }
+    }
+}
+
+impl VpnKillswitchConfig {
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("VPN_KILLSWITCH")
+            .is_ok_and(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"));
+
+        let interval = std::env::var("VPN_KILLSWITCH_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_INTERVAL_SECS);
+
+        Self { enabled, interval: Duration::from_secs(interval) }
+    }
+}
+
+impl VpnKillswitch {
+    pub const fn new() -> Self {
+        Self { shutdown_tx: None, task_handle: None }
+    }
+
+    pub fn start(&mut self, state: AppState, config: VpnKillswitchConfig) {
+        if !config.enabled || self.task_handle.is_some() {
+            return;
+        }
+
+        let interval = config.interval;
+        let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+        let handle = tokio::spawn(killswitch_loop(state, interval, shutdown_rx));
+        self.shutdown_tx = Some(shutdown_tx);
+        self.task_handle = Some(handle);
+
+        tracing::info!("VPN kill-switch started (interval={}s)", interval.as_secs());
+    }
+
+    pub async fn shutdown(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(()).await;
+        }
+        if let Some(handle) = self.task_handle.take() {
+            let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
+        }
+        tracing::info!("VPN kill-switch stopped");
+    }
+}
+
+async fn killswitch_loop(state: AppState, interval: Duration, mut shutdown_rx: mpsc::Receiver<()>) {
+    let auth = GluetunAuth::from_env();
+    let client = match reqwest::Client::builder().timeout(Duration::from_secs(2)).build() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!("VPN kill-switch failed to build HTTP client: {}", err);
+            return;
+        }
+    };
+
+    let mut watch = VpnWatch::default();
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = shutdown_rx.recv() => break,
+            _ = ticker.tick() => {
+                match watch.observe(probe(&client, &auth).await) {
+                    Some(Transition::Dropped) => {
+                        let paused = state.engage_killswitch().await;
+                        tracing::warn!("VPN is down, kill-switch paused {} instance(s)", paused);
+                    }
+                    Some(Transition::Restored) => {
+                        let resumed = state.release_killswitch().await;
+                        tracing::info!("VPN is back, kill-switch resumed {} instance(s)", resumed);
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+}
+
+async fn probe(client: &reqwest::Client, auth: &GluetunAuth) -> VpnStatus {
+    match auth.vpn_running(client).await {
+        Some(true) => VpnStatus::Running,
+        Some(false) => VpnStatus::Down,
+        None => VpnStatus::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watch_reports_drops_and_restores_once() {
+        let mut watch = VpnWatch::default();
+
+        assert_eq!(watch.observe(VpnStatus::Running), None);
+        assert_eq!(watch.observe(VpnStatus::Down), Some(Transition::Dropped));
+        assert_eq!(watch.observe(VpnStatus::Unknown), None);
+        assert_eq!(watch.observe(VpnStatus::Down), None);
+        assert_eq!(watch.observe(VpnStatus::Running), Some(Transition::Restored));
+        assert_eq!(watch.observe(VpnStatus::Running), None);
+    }
+
+    #[test]
+    fn watch_treats_a_down_vpn_at_startup_as_a_drop() {
+        let mut watch = VpnWatch::default();
+
+        assert_eq!(watch.observe(VpnStatus::Unknown), None);
+        assert_eq!(watch.observe(VpnStatus::Down), Some(Transition::Dropped));
+    }
+}
@@ -1,6 +1,7 @@
 use super::state::AppState;
 use crate::services::GluetunAuth;
 use serde::Deserialize;
+use std::net::IpAddr;
 use std::time::Duration;
 use tokio::sync::mpsc;
 
@@ -14,6 +15,8 @@ pub struct VpnPortSync {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct VpnPortSyncConfig {
     pub enabled: bool,
+    /// Also track the VPN's public IP for instances with `auto_announce_vpn_ip`
+    pub ip_sync: bool,
     pub interval: Duration,
 }
 
@@ -22,10 +25,20 @@ struct GluetunPortForward {
     port: u16,
 }
 
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+struct GluetunPublicIp {
+    public_ip: String,
+}
+
 impl VpnPortSyncConfig {
     pub fn from_env() -> Self {
-        let enabled = std::env::var("VPN_PORT_SYNC")
-            .is_ok_and(|v| matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"));
+        let flag = |name: &str| {
+            std::env::var(name).is_ok_and(|v| {
+                matches!(v.to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on")
+            })
+        };
+        let enabled = flag("VPN_PORT_SYNC");
+        let ip_sync = flag("VPN_IP_SYNC");
 
         let interval = std::env::var("VPN_PORT_SYNC_INTERVAL_SECONDS")
             .ok()
@@ -33,7 +46,7 @@ impl VpnPortSyncConfig {
             .filter(|secs| *secs > 0)
             .unwrap_or(DEFAULT_INTERVAL_SECS);
 
-        Self { enabled, interval: Duration::from_secs(interval) }
+        Self { enabled, ip_sync, interval: Duration::from_secs(interval) }
     }
 }
 
@@ -43,7 +56,7 @@ impl VpnPortSync {
     }
 
     pub fn start(&mut self, state: AppState, config: VpnPortSyncConfig) {
-        if !config.enabled || self.task_handle.is_some() {
+        if !(config.enabled || config.ip_sync) || self.task_handle.is_some() {
             return;
         }
 
@@ -52,7 +65,12 @@ impl VpnPortSync {
         self.shutdown_tx = Some(shutdown_tx);
         self.task_handle = Some(handle);
 
-        tracing::info!("VPN port sync started (interval={}s)", config.interval.as_secs());
+        tracing::info!(
+            "VPN port sync started (ports={}, ip={}, interval={}s)",
+            config.enabled,
+            config.ip_sync,
+            config.interval.as_secs()
+        );
     }
 
     pub async fn shutdown(&mut self) {
@@ -87,8 +105,15 @@ async fn sync_loop(
         tokio::select! {
             _ = shutdown_rx.recv() => break,
             _ = ticker.tick() => {
-                if let Err(err) = sync_once(&state, &client, &auth).await {
-                    tracing::debug!("VPN port sync skipped update: {}", err);
+                if config.enabled {
+                    if let Err(err) = sync_once(&state, &client, &auth).await {
+                        tracing::debug!("VPN port sync skipped update: {}", err);
+                    }
+                }
+                if config.ip_sync {
+                    if let Err(err) = sync_public_ip(&state, &client, &auth).await {
+                        tracing::debug!("VPN IP sync skipped update: {}", err);
+                    }
                 }
             }
         }
@@ -119,6 +144,45 @@ async fn sync_once(
     Ok(())
 }
 
+async fn sync_public_ip(
+    state: &AppState,
+    client: &reqwest::Client,
+    auth: &GluetunAuth,
+) -> Result<(), String> {
+    let ip = fetch_public_ip(client, auth).await?;
+    if state.current_vpn_ip() == Some(ip) {
+        return Ok(());
+    }
+
+    let updated = state.apply_vpn_public_ip(ip).await?;
+    tracing::info!("Detected VPN public IP {} and updated {} instance(s)", ip, updated);
+
+    Ok(())
+}
+
+async fn fetch_public_ip(client: &reqwest::Client, auth: &GluetunAuth) -> Result<IpAddr, String> {
+    let response = auth
+        .get(client, "/v1/publicip/ip")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query Gluetun public IP endpoint: {e}"))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Gluetun public IP endpoint returned HTTP {}", response.status()));
+    }
+
+    let payload = response
+        .json::<GluetunPublicIp>()
+        .await
+        .map_err(|e| format!("Failed to parse Gluetun public IP response: {e}"))?;
+
+    payload
+        .public_ip
+        .trim()
+        .parse()
+        .map_err(|_| format!("Gluetun has no public IP yet (got '{}')", payload.public_ip))
+}
+
 async fn fetch_forwarded_port(client: &reqwest::Client, auth: &GluetunAuth) -> Result<u16, String> {
     let response = auth
         .get(client, "/v1/portforward")
@@ -161,12 +225,15 @@ mod tests {
     fn config_defaults_to_disabled() {
         let prev_enabled = std::env::var("VPN_PORT_SYNC").ok();
         let prev_interval = std::env::var("VPN_PORT_SYNC_INTERVAL_SECONDS").ok();
+        let prev_ip_sync = std::env::var("VPN_IP_SYNC").ok();
 
         std::env::remove_var("VPN_PORT_SYNC");
         std::env::remove_var("VPN_PORT_SYNC_INTERVAL_SECONDS");
+        std::env::remove_var("VPN_IP_SYNC");
 
         let config = VpnPortSyncConfig::from_env();
         assert!(!config.enabled);
+        assert!(!config.ip_sync);
         assert_eq!(config.interval, Duration::from_secs(DEFAULT_INTERVAL_SECS));
 
         match prev_enabled {
@@ -177,5 +244,8 @@ mod tests {
             Some(value) => std::env::set_var("VPN_PORT_SYNC_INTERVAL_SECONDS", value),
             None => std::env::remove_var("VPN_PORT_SYNC_INTERVAL_SECONDS"),
         }
+        if let Some(value) = prev_ip_sync {
+            std::env::set_var("VPN_IP_SYNC", value);
+        }
     }
 }
@@ -1,9 +1,11 @@
+use crate::services::instance::InstanceQuery;
 use crate::services::lifecycle::InstanceLifecycle;
 use crate::services::persistence::InstanceSource;
-use crate::services::state::AppState;
+use crate::services::runtime_config::env_var;
+use crate::services::state::{AppState, InstanceBuildContext};
 use rustatio_watch::{
-    EngineConfig, InstanceSource as WatchSource, InstanceState, NewInstance, WatchEngine,
-    WatchService as EngineWatchService,
+    AfterImport, EngineConfig, InstanceSource as WatchSource, InstanceState, NewInstance,
+    WatchEngine, WatchService as EngineWatchService,
 };
 use serde::Serialize;
 use std::path::PathBuf;
@@ -11,20 +13,34 @@ use std::sync::Arc;
 use utoipa::ToSchema;
 
 fn env_bool(name: &str, default: bool) -> bool {
-    std::env::var(name).map_or(default, |v| v.eq_ignore_ascii_case("true") || v == "1")
+    env_var(name).map_or(default, |v| v.eq_ignore_ascii_case("true") || v == "1")
+}
+
+/// Comma-separated glob patterns from `name`.
+fn env_globs(name: &str) -> Vec<String> {
+    env_var(name)
+        .map(|v| {
+            v.split(',').map(str::trim).filter(|p| !p.is_empty()).map(str::to_string).collect()
+        })
+        .unwrap_or_default()
 }
 
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct WatchStatus {
     pub enabled: bool,
     pub watch_dir: String,
+    /// Whether imported torrents start right away (`WATCH_AUTO_START`)
     pub auto_start: bool,
+    /// Whether imports use the config saved with `PUT /config/default` rather than the
+    /// built-in defaults (a folder `preset.json` still takes precedence)
+    pub uses_default_config: bool,
     pub file_count: usize,
     pub loaded_count: usize,
 }
 
 #[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct WatchedFile {
+    /// Path relative to the watch dir, e.g. `tracker-a/file.torrent`
     pub filename: String,
     pub path: String,
     pub status: WatchedFileStatus,
@@ -47,6 +63,7 @@ impl From<rustatio_watch::WatchStatus> for WatchStatus {
             enabled: status.enabled,
             watch_dir: status.watch_dir,
             auto_start: status.auto_start,
+            uses_default_config: status.uses_default_config,
             file_count: status.file_count,
             loaded_count: status.loaded_count,
         }
@@ -82,6 +99,13 @@ pub struct WatchConfig {
     pub auto_start: bool,
     pub enabled: bool,
     pub max_depth: u32,
+    /// `WATCH_INCLUDE` globs, matched against paths relative to the watch dir
+    pub include: Vec<String>,
+    /// `WATCH_EXCLUDE` globs
+    pub exclude: Vec<String>,
+    /// What happens to a torrent file once imported (`WATCH_PROCESSED_DIR`,
+    /// `WATCH_DELETE_AFTER_IMPORT`)
+    pub after_import: AfterImport,
 }
 
 #[derive(Debug, Clone)]
@@ -92,15 +116,13 @@ pub enum WatchDisabledReason {
 
 impl WatchConfig {
     pub fn from_env() -> (Self, Option<WatchDisabledReason>) {
-        let watch_dir = std::env::var("WATCH_DIR").unwrap_or_else(|_| "/torrents".to_string());
+        let watch_dir = env_var("WATCH_DIR").unwrap_or_else(|| "/torrents".to_string());
         let watch_path = PathBuf::from(&watch_dir);
 
         let auto_start = env_bool("WATCH_AUTO_START", false);
 
-        let max_depth = std::env::var("WATCH_MAX_DEPTH")
-            .ok()
-            .and_then(|value| value.parse::<u32>().ok())
-            .unwrap_or(1);
+        let max_depth =
+            env_var("WATCH_MAX_DEPTH").and_then(|value| value.parse::<u32>().ok()).unwrap_or(1);
 
         let auto_detect = || {
             if watch_path.exists() && watch_path.is_dir() {
@@ -110,23 +132,53 @@ impl WatchConfig {
             }
         };
 
-        let (enabled, disabled_reason) = std::env::var("WATCH_ENABLED").map_or_else(
-            |_| auto_detect(),
-            |val| {
-                let val_lower = val.to_lowercase();
-                if val_lower == "false" || val == "0" {
-                    (false, Some(WatchDisabledReason::ExplicitlyDisabled))
-                } else if val_lower == "true" || val == "1" {
-                    (true, None)
-                } else if val.is_empty() {
-                    auto_detect()
-                } else {
-                    (true, None)
-                }
+        let (enabled, disabled_reason) = env_var("WATCH_ENABLED").map_or_else(auto_detect, |val| {
+            let val_lower = val.to_lowercase();
+            if val_lower == "false" || val == "0" {
+                (false, Some(WatchDisabledReason::ExplicitlyDisabled))
+            } else if val_lower == "true" || val == "1" {
+                (true, None)
+            } else if val.is_empty() {
+                auto_detect()
+            } else {
+                (true, None)
+            }
+        });
+
+        let include = env_globs("WATCH_INCLUDE");
+        let exclude = env_globs("WATCH_EXCLUDE");
+
+        let processed_dir = env_var("WATCH_PROCESSED_DIR")
+            .map(|dir| dir.trim().to_string())
+            .filter(|dir| !dir.is_empty());
+        let after_import = if env_bool("WATCH_DELETE_AFTER_IMPORT", false) {
+            AfterImport::Delete
+        } else {
+            processed_dir.map_or(AfterImport::Keep, |dir| AfterImport::Move(PathBuf::from(dir)))
+        };
+
+        (
+            Self {
+                watch_dir: watch_path,
+                auto_start,
+                enabled,
+                max_depth,
+                include,
+                exclude,
+                after_import,
             },
-        );
+            disabled_reason,
+        )
+    }
 
-        (Self { watch_dir: watch_path, auto_start, enabled, max_depth }, disabled_reason)
+    /// Env config with the persisted watch settings (depth, auto-start) applied on top.
+    pub async fn resolve(state: &AppState) -> (Self, Option<WatchDisabledReason>) {
+        let (mut config, disabled_reason) = Self::from_env();
+        if let Some(settings) = state.get_watch_settings_optional().await {
+            config.max_depth = settings.max_depth;
+            config.auto_start = settings.auto_start;
+        }
+        (config, disabled_reason)
     }
 }
 
@@ -143,7 +195,7 @@ impl ServerWatchEngine {
 #[async_trait::async_trait]
 impl WatchEngine for ServerWatchEngine {
     async fn list_instances(&self) -> Vec<InstanceState> {
-        let instances = self.state.list_instances().await;
+        let instances = self.state.list_instances(&InstanceQuery::default()).await;
         instances
             .into_iter()
             .map(|inst| InstanceState {
@@ -171,9 +223,13 @@ impl WatchEngine for ServerWatchEngine {
     async fn create_instance(&self, instance: NewInstance) -> Result<(), String> {
         self.state
             .create_instance_with_event(
-                &instance.id,
-                instance.info,
-                instance.config,
+                InstanceBuildContext::new(
+                    &instance.id,
+                    instance.info,
+                    instance.config,
+                    InstanceSource::WatchFolder,
+                )
+                .with_torrent_file(instance.torrent_file),
                 instance.auto_start,
             )
             .await
@@ -225,6 +281,9 @@ impl WatchServiceWrapper {
                 auto_start: config.auto_start,
                 enabled: config.enabled,
                 max_depth: config.max_depth,
+                include: config.include,
+                exclude: config.exclude,
+                after_import: config.after_import,
             },
             engine,
         );
@@ -238,6 +297,9 @@ impl WatchServiceWrapper {
             auto_start: config.auto_start,
             enabled: config.enabled,
             max_depth: config.max_depth,
+            include: config.include,
+            exclude: config.exclude,
+            after_import: config.after_import,
         }
     }
 
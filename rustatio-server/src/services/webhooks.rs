@@ -0,0 +1,441 @@
+//! Outbound webhooks for instance lifecycle events.
+//!
+//! Subscribes to `InstanceEvent`s and posts each one to `WEBHOOK_URL`. The body is
+//! shaped by `NOTIFY_FORMAT`: the JSON payload as-is (`raw`), a Discord or Slack
+//! message, or a plain-text ntfy message with title and priority headers.
+//! `WEBHOOK_TEMPLATE` replaces the body, with `{{event}}`, `{{id}}`, `{{name}}`,
+//! `{{message}}`, `{{text}}`, `{{ratio}}`, `{{uploaded}}` and `{{downloaded}}`
+//! substituted as JSON-escaped values.
+
+use super::events::{EventBroadcaster, InstanceEvent};
+use super::persistence::now_timestamp;
+use super::state::AppState;
+use rustatio_core::format::format_bytes;
+use rustatio_core::FakerStats;
+use serde::Serialize;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct WebhookService {
+    config: WebhookConfig,
+    shutdown_tx: Option<mpsc::Sender<()>>,
+    task_handle: Option<tokio::task::JoinHandle<()>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NotifyFormat {
+    #[default]
+    Raw,
+    Discord,
+    Ntfy,
+    Slack,
+}
+
+impl NotifyFormat {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "raw" | "json" => Some(Self::Raw),
+            "discord" => Some(Self::Discord),
+            "ntfy" => Some(Self::Ntfy),
+            "slack" => Some(Self::Slack),
+            _ => None,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Raw => "raw",
+            Self::Discord => "discord",
+            Self::Ntfy => "ntfy",
+            Self::Slack => "slack",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WebhookConfig {
+    pub url: Option<String>,
+    pub template: Option<String>,
+    pub format: NotifyFormat,
+    /// Event types to send; empty sends every event
+    pub events: Vec<String>,
+}
+
+impl WebhookConfig {
+    pub fn from_env() -> Self {
+        let non_empty = |name: &str| {
+            std::env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+        };
+        let events = non_empty("WEBHOOK_EVENTS")
+            .map(|v| {
+                v.split(',')
+                    .map(|event| event.trim().to_ascii_lowercase())
+                    .filter(|event| !event.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let format = non_empty("NOTIFY_FORMAT").map_or(NotifyFormat::Raw, |v| {
+            NotifyFormat::parse(&v).unwrap_or_else(|| {
+                tracing::warn!("Unknown NOTIFY_FORMAT '{}', using raw", v);
+                NotifyFormat::Raw
+            })
+        });
+
+        Self {
+            url: non_empty("WEBHOOK_URL"),
+            template: non_empty("WEBHOOK_TEMPLATE"),
+            format,
+            events,
+        }
+    }
+
+    fn wants(&self, event_type: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|event| event == event_type)
+    }
+
+    /// Request body and its content type for `payload`.
+    fn body(&self, payload: &WebhookPayload) -> (String, &'static str) {
+        let content_type = match self.format {
+            NotifyFormat::Ntfy => "text/plain; charset=utf-8",
+            _ => "application/json",
+        };
+        if let Some(template) = &self.template {
+            return (payload.render(template), content_type);
+        }
+        let body = match self.format {
+            NotifyFormat::Raw => serde_json::to_string(payload).unwrap_or_default(),
+            NotifyFormat::Discord => serde_json::json!({ "content": payload.text() }).to_string(),
+            NotifyFormat::Slack => serde_json::json!({ "text": payload.text() }).to_string(),
+            NotifyFormat::Ntfy => payload.text(),
+        };
+        (body, content_type)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct WebhookPayload {
+    event: &'static str,
+    id: String,
+    torrent_name: Option<String>,
+    message: String,
+    timestamp: u64,
+    stats: Option<FakerStats>,
+}
+
+impl WebhookPayload {
+    /// One human-readable line, with sizes formatted like the CLI.
+    fn text(&self) -> String {
+        let name = self.torrent_name.as_deref().unwrap_or(&self.id);
+        self.stats.as_ref().map_or_else(
+            || format!("{name}: {}", self.message),
+            |stats| {
+                format!(
+                    "{name}: {} (ratio {:.3}, uploaded {}, downloaded {})",
+                    self.message,
+                    stats.ratio,
+                    format_bytes(stats.uploaded),
+                    format_bytes(stats.downloaded)
+                )
+            },
+        )
+    }
+
+    fn title(&self) -> String {
+        format!("Rustatio: {}", self.event.replace('_', " "))
+    }
+
+    /// ntfy priority: failures stand out, routine lifecycle changes stay quiet.
+    fn priority(&self) -> &'static str {
+        match self.event {
+            "tracker_failing" => "high",
+            "created" | "deleted" => "low",
+            _ => "default",
+        }
+    }
+
+    fn render(&self, template: &str) -> String {
+        let json = |value: &str| {
+            let quoted = serde_json::to_string(value).unwrap_or_default();
+            let inner = quoted.strip_prefix('"').and_then(|q| q.strip_suffix('"'));
+            inner.unwrap_or_default().to_string()
+        };
+        let stat =
+            |field: fn(&FakerStats) -> String| self.stats.as_ref().map(field).unwrap_or_default();
+        template
+            .replace("{{event}}", self.event)
+            .replace("{{id}}", &json(&self.id))
+            .replace("{{name}}", &json(self.torrent_name.as_deref().unwrap_or(&self.id)))
+            .replace("{{message}}", &json(&self.message))
+            .replace("{{text}}", &json(&self.text()))
+            .replace("{{ratio}}", &stat(|stats| format!("{:.3}", stats.ratio)))
+            .replace("{{uploaded}}", &stat(|stats| stats.uploaded.to_string()))
+            .replace("{{downloaded}}", &stat(|stats| stats.downloaded.to_string()))
+    }
+}
+
+/// Event type name and a one-line description of what happened.
+fn describe(event: &InstanceEvent) -> (&'static str, &str, String) {
+    match event {
+        InstanceEvent::Created { id, torrent_name, .. } => {
+            ("created", id, format!("Instance created for {torrent_name}"))
+        }
+        InstanceEvent::Deleted { id } => ("deleted", id, "Instance deleted".to_string()),
+        InstanceEvent::Paused { id, reason } => {
+            ("paused", id, format!("Instance paused ({reason})"))
+        }
+        InstanceEvent::Resumed { id, reason } => {
+            ("resumed", id, format!("Instance resumed ({reason})"))
+        }
+        InstanceEvent::StopConditionMet { id, condition } => {
+            ("stop_condition_met", id, format!("Stop condition met: {condition}"))
+        }
+        InstanceEvent::TrackerFailing { id, attempts, error } => {
+            ("tracker_failing", id, format!("Tracker failed {attempts} times in a row: {error}"))
+        }
+    }
+}
+
+impl WebhookService {
+    pub const fn new() -> Self {
+        Self {
+            config: WebhookConfig {
+                url: None,
+                template: None,
+                format: NotifyFormat::Raw,
+                events: Vec::new(),
+            },
+            shutdown_tx: None,
+            task_handle: None,
+        }
+    }
+
+    pub const fn config(&self) -> &WebhookConfig {
+        &self.config
+    }
+
+    pub fn start(&mut self, state: AppState, config: WebhookConfig) {
+        if self.task_handle.is_some() {
+            return;
+        }
+        self.config = config.clone();
+        let Some(url) = config.url.clone() else {
+            return;
+        };
+
+        let format = config.format;
+        let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+        let events = state.subscribe_instance_events();
+        let handle = tokio::spawn(webhook_loop(state, config, events, shutdown_rx));
+        self.shutdown_tx = Some(shutdown_tx);
+        self.task_handle = Some(handle);
+
+        tracing::info!(
+            "Webhook notifications enabled ({}, format={})",
+            redact_url(&url),
+            format.as_str()
+        );
+    }
+
+    pub async fn shutdown(&mut self) {
+        if let Some(tx) = self.shutdown_tx.take() {
+            let _ = tx.send(()).await;
+        }
+        if let Some(handle) = self.task_handle.take() {
+            let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
+        }
+        tracing::info!("Webhook notifications stopped");
+    }
+}
+
+/// Scheme and host only, since webhook URLs usually embed a secret token.
+fn redact_url(url: &str) -> String {
+    reqwest::Url::parse(url).map_or_else(
+        |_| "invalid URL".to_string(),
+        |parsed| format!("{}://{}", parsed.scheme(), parsed.host_str().unwrap_or_default()),
+    )
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))
+}
+
+async fn webhook_loop(
+    state: AppState,
+    config: WebhookConfig,
+    mut events: broadcast::Receiver<InstanceEvent>,
+    mut shutdown_rx: mpsc::Receiver<()>,
+) {
+    let client = match http_client() {
+        Ok(client) => client,
+        Err(err) => {
+            tracing::error!("Webhooks: {}", err);
+            return;
+        }
+    };
+
+    loop {
+        let event = tokio::select! {
+            _ = shutdown_rx.recv() => break,
+            event = events.recv() => event,
+        };
+        match event {
+            Ok(event) => {
+                let (event_type, id, message) = describe(&event);
+                if !config.wants(event_type) {
+                    continue;
+                }
+                let payload = build_payload(&state, event_type, id, message).await;
+                match deliver(&client, &config, &payload).await {
+                    Ok(()) => tracing::debug!("Webhook sent for {} event of {}", event_type, id),
+                    Err(e) => {
+                        tracing::warn!("Webhook for {} event of {} failed: {}", event_type, id, e);
+                    }
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                tracing::warn!("Webhooks fell behind, dropped {} event(s)", skipped);
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+async fn build_payload(
+    state: &AppState,
+    event: &'static str,
+    id: &str,
+    message: String,
+) -> WebhookPayload {
+    let (torrent_name, stats) = {
+        let instances = state.instances.read().await;
+        instances.get(id).map_or((None, None), |instance| {
+            let mut stats = instance.faker.stats_snapshot();
+            stats.upload_rate_history = Vec::new();
+            stats.download_rate_history = Vec::new();
+            stats.ratio_history = Vec::new();
+            stats.history_timestamps = Vec::new();
+            (Some(instance.display_name().to_string()), Some(state.shape_stats(stats)))
+        })
+    };
+    WebhookPayload {
+        event,
+        id: id.to_string(),
+        torrent_name,
+        message,
+        timestamp: now_timestamp(),
+        stats,
+    }
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    config: &WebhookConfig,
+    payload: &WebhookPayload,
+) -> Result<(), String> {
+    let url = config.url.as_deref().ok_or("WEBHOOK_URL is not set")?;
+    let (body, content_type) = config.body(payload);
+    let mut request =
+        client.post(url).header(reqwest::header::CONTENT_TYPE, content_type).body(body);
+    if config.format == NotifyFormat::Ntfy {
+        request = request.header("Title", payload.title()).header("Priority", payload.priority());
+    }
+
+    request
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map(|_| ())
+        .map_err(|e| e.without_url().to_string())
+}
+
+/// Send a sample notification, ignoring the `WEBHOOK_EVENTS` filter.
+pub async fn send_test(config: &WebhookConfig) -> Result<(), String> {
+    let payload = WebhookPayload {
+        event: "test",
+        id: "test".to_string(),
+        torrent_name: Some("Rustatio".to_string()),
+        message: "Test notification, webhooks are working".to_string(),
+        timestamp: now_timestamp(),
+        stats: None,
+    };
+    deliver(&http_client()?, config, &payload).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> WebhookPayload {
+        WebhookPayload {
+            event: "stop_condition_met",
+            id: "abc".to_string(),
+            torrent_name: Some("Some \"quoted\" name".to_string()),
+            message: "Stop condition met: ratio".to_string(),
+            timestamp: 0,
+            stats: None,
+        }
+    }
+
+    fn config(format: NotifyFormat) -> WebhookConfig {
+        WebhookConfig { format, ..WebhookConfig::default() }
+    }
+
+    #[test]
+    fn template_placeholders_are_json_escaped() {
+        let rendered = payload().render(r#"{"content": "{{name}}: {{message}} ({{ratio}})"}"#);
+
+        assert_eq!(
+            rendered,
+            r#"{"content": "Some \"quoted\" name: Stop condition met: ratio ()"}"#
+        );
+        assert!(serde_json::from_str::<serde_json::Value>(&rendered).is_ok());
+    }
+
+    #[test]
+    fn event_filter_defaults_to_everything() {
+        let mut config = WebhookConfig::default();
+        assert!(config.wants("created"));
+
+        config.events = vec!["stop_condition_met".to_string()];
+        assert!(config.wants("stop_condition_met"));
+        assert!(!config.wants("created"));
+    }
+
+    #[test]
+    fn bodies_are_shaped_per_service() {
+        let text = "Some \"quoted\" name: Stop condition met: ratio";
+
+        let (discord, _) = config(NotifyFormat::Discord).body(&payload());
+        assert_eq!(discord, serde_json::json!({ "content": text }).to_string());
+
+        let (slack, _) = config(NotifyFormat::Slack).body(&payload());
+        assert_eq!(slack, serde_json::json!({ "text": text }).to_string());
+
+        let (ntfy, content_type) = config(NotifyFormat::Ntfy).body(&payload());
+        assert_eq!(ntfy, text);
+        assert!(content_type.starts_with("text/plain"));
+
+        let (raw, _) = config(NotifyFormat::Raw).body(&payload());
+        let raw: serde_json::Value =
+            serde_json::from_str(&raw).unwrap_or_else(|e| panic!("raw body should be JSON: {e}"));
+        assert_eq!(raw["event"], "stop_condition_met");
+    }
+
+    #[test]
+    fn notify_format_parses_known_names() {
+        assert_eq!(NotifyFormat::parse(" Discord "), Some(NotifyFormat::Discord));
+        assert_eq!(NotifyFormat::parse("ntfy"), Some(NotifyFormat::Ntfy));
+        assert_eq!(NotifyFormat::parse("teams"), None);
+    }
+
+    #[test]
+    fn redact_url_keeps_only_scheme_and_host() {
+        assert_eq!(redact_url("https://discord.com/api/webhooks/1/secret"), "https://discord.com");
+    }
+}
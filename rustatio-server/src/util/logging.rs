@@ -1,4 +1,5 @@
-use crate::services::LogEvent;
+use crate::services::{InstanceLogs, LogEvent};
+use rustatio_core::logger::current_instance_context;
 use std::sync::Arc;
 use tokio::sync::broadcast;
 use tracing::field::{Field, Visit};
@@ -8,11 +9,12 @@ use tracing_subscriber::Layer;
 
 pub struct BroadcastLayer {
     sender: Arc<broadcast::Sender<LogEvent>>,
+    instance_logs: Arc<InstanceLogs>,
 }
 
 impl BroadcastLayer {
-    pub fn new(sender: broadcast::Sender<LogEvent>) -> Self {
-        Self { sender: Arc::new(sender) }
+    pub fn new(sender: broadcast::Sender<LogEvent>, instance_logs: Arc<InstanceLogs>) -> Self {
+        Self { sender: Arc::new(sender), instance_logs }
     }
 }
 
@@ -73,7 +75,17 @@ impl<S: Subscriber> Layer<S> for BroadcastLayer {
             Level::TRACE => "trace",
         };
 
+        let mut event = LogEvent::new(level, visitor.message);
+
+        // Events are delivered synchronously inside the task that logged them, so
+        // its instance context tells which instance the line belongs to
+        if let Some(context) = current_instance_context() {
+            let id = context.id.clone();
+            event = event.for_instance(context);
+            self.instance_logs.push(&id, event.clone());
+        }
+
         // Send to broadcast channel (ignore errors - no subscribers is fine)
-        let _ = self.sender.send(LogEvent::new(level, visitor.message));
+        let _ = self.sender.send(event);
     }
 }
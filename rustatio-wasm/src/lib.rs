@@ -109,6 +109,19 @@ pub fn load_torrent(file_bytes: &[u8]) -> Result<JsValue, JsValue> {
     to_js(&torrent)
 }
 
+#[wasm_bindgen]
+pub fn load_magnet(uri: &str, declared_size: Option<u64>) -> Result<JsValue, JsValue> {
+    let torrent = TorrentInfo::from_magnet(uri, declared_size).map_err(|e| {
+        let error_msg = format!("Failed to load magnet link: {e}");
+        rustatio_core::log_error!("{}", error_msg);
+        JsValue::from_str(&error_msg)
+    })?;
+
+    rustatio_core::log_info!("Magnet loaded: {} ({} bytes)", torrent.name, torrent.total_size);
+
+    to_js(&torrent)
+}
+
 #[wasm_bindgen]
 pub fn load_instance_torrent(id: u32, file_bytes: &[u8]) -> Result<JsValue, JsValue> {
     rustatio_core::logger::set_instance_context(Some(id));
@@ -306,6 +319,10 @@ pub async fn get_stats(id: u32) -> Result<JsValue, JsValue> {
 pub async fn stop_faker(id: u32) -> Result<(), JsValue> {
     rustatio_core::logger::set_instance_context(Some(id));
     with_instance(id, |mut instance| async move {
+        if let Some(reason) = instance.faker.hnr_block_reason() {
+            return (instance, Err(JsValue::from_str(&reason)));
+        }
+
         let final_stats = instance.faker.get_stats();
 
         let result = instance.faker.stop().await.map_err(|e| JsValue::from_str(&e.to_string()));
@@ -328,7 +345,11 @@ pub async fn stop_faker(id: u32) -> Result<(), JsValue> {
 pub async fn pause_faker(id: u32) -> Result<(), JsValue> {
     rustatio_core::logger::set_instance_context(Some(id));
     with_instance(id, |mut instance| async move {
-        let result = instance.faker.pause().map_err(|e| JsValue::from_str(&e.to_string()));
+        if let Some(reason) = instance.faker.hnr_block_reason() {
+            return (instance, Err(JsValue::from_str(&reason)));
+        }
+
+        let result = instance.faker.pause().await.map_err(|e| JsValue::from_str(&e.to_string()));
         (instance, result)
     })
     .await
@@ -338,7 +359,7 @@ pub async fn pause_faker(id: u32) -> Result<(), JsValue> {
 pub async fn resume_faker(id: u32) -> Result<(), JsValue> {
     rustatio_core::logger::set_instance_context(Some(id));
     with_instance(id, |mut instance| async move {
-        let result = instance.faker.resume().map_err(|e| JsValue::from_str(&e.to_string()));
+        let result = instance.faker.resume().await.map_err(|e| JsValue::from_str(&e.to_string()));
         (instance, result)
     })
     .await
@@ -423,6 +444,7 @@ pub async fn grid_import(torrent_files: JsValue, config_json: JsValue) -> Result
 
     let settings: GridImportSettings = serde_wasm_bindgen::from_value(config_json)
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    settings.base_config.validate().map_err(|e| JsValue::from_str(&e.to_string()))?;
 
     let mut imported: Vec<serde_json::Value> = Vec::new();
     let mut duplicates: Vec<String> = Vec::new();
@@ -578,7 +600,7 @@ pub async fn grid_stop(ids_json: JsValue) -> Result<JsValue, JsValue> {
 }
 
 #[wasm_bindgen]
-pub fn grid_pause(ids_json: JsValue) -> Result<JsValue, JsValue> {
+pub async fn grid_pause(ids_json: JsValue) -> Result<JsValue, JsValue> {
     let ids: Vec<u32> =
         serde_wasm_bindgen::from_value(ids_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
 
@@ -588,7 +610,7 @@ pub fn grid_pause(ids_json: JsValue) -> Result<JsValue, JsValue> {
     for id in ids {
         match take_instance(id) {
             Ok(mut instance) => {
-                match instance.faker.pause() {
+                match instance.faker.pause().await {
                     Ok(()) => succeeded.push(id.to_string()),
                     Err(e) => failed
                         .push(serde_json::json!({ "id": id.to_string(), "error": e.to_string() })),
@@ -606,7 +628,7 @@ pub fn grid_pause(ids_json: JsValue) -> Result<JsValue, JsValue> {
 }
 
 #[wasm_bindgen]
-pub fn grid_resume(ids_json: JsValue) -> Result<JsValue, JsValue> {
+pub async fn grid_resume(ids_json: JsValue) -> Result<JsValue, JsValue> {
     let ids: Vec<u32> =
         serde_wasm_bindgen::from_value(ids_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
 
@@ -616,7 +638,7 @@ pub fn grid_resume(ids_json: JsValue) -> Result<JsValue, JsValue> {
     for id in ids {
         match take_instance(id) {
             Ok(mut instance) => {
-                match instance.faker.resume() {
+                match instance.faker.resume().await {
                     Ok(()) => succeeded.push(id.to_string()),
                     Err(e) => failed
                         .push(serde_json::json!({ "id": id.to_string(), "error": e.to_string() })),
@@ -662,6 +684,7 @@ pub fn grid_update_config(ids_json: JsValue, config_json: JsValue) -> Result<JsV
         serde_wasm_bindgen::from_value(ids_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
     let preset: PresetSettings = serde_wasm_bindgen::from_value(config_json)
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    preset.validate().map_err(|e| JsValue::from_str(&e.to_string()))?;
     let faker_config: FakerConfig = preset.into();
 
     let mut succeeded: Vec<String> = Vec::new();
@@ -770,6 +793,7 @@ pub fn list_summaries() -> Result<JsValue, JsValue> {
         summaries.push(InstanceSummary {
             id: id.to_string(),
             name: instance.torrent.name.clone(),
+            label: None,
             info_hash: info_hash_hex,
             primary_tracker_host: primary_tracker_host(&instance.torrent.announce),
             state: match stats.state {
@@ -781,6 +805,7 @@ pub fn list_summaries() -> Result<JsValue, JsValue> {
             tracker_error: stats.tracker_error.clone(),
             tracker_retry_attempt: stats.tracker_retry_attempt,
             tracker_retry_at_ms: stats.tracker_retry_at_ms,
+            stop_reason: stats.stop_reason.clone(),
             tags: instance.tags.clone(),
             total_size: instance.torrent.total_size,
             uploaded: stats.uploaded,
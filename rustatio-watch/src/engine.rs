@@ -1,6 +1,7 @@
-use crate::paths::relative_watch_path;
-use crate::scan::{is_torrent_file, scan_torrent_paths};
-use crate::types::{EngineConfig, WatchStatus, WatchedFile, WatchedFileStatus};
+use crate::paths::{relative_watch_path, PathFilter};
+use crate::preset::folder_preset;
+use crate::scan::{is_torrent_file, passes_filter, scan_torrent_paths};
+use crate::types::{AfterImport, EngineConfig, WatchStatus, WatchedFile, WatchedFileStatus};
 use async_trait::async_trait;
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use rustatio_core::TorrentSummary;
@@ -28,6 +29,8 @@ pub struct InstanceState {
 pub struct NewInstance {
     pub id: String,
     pub info: rustatio_core::TorrentInfo,
+    /// The watched `.torrent` file's contents
+    pub torrent_file: Vec<u8>,
     pub config: rustatio_core::FakerConfig,
     pub auto_start: bool,
 }
@@ -50,6 +53,7 @@ pub trait WatchEngine: Send + Sync {
 
 pub struct WatchService<E: WatchEngine> {
     config: EngineConfig,
+    filter: PathFilter,
     engine: Arc<E>,
     loaded_hashes: Arc<RwLock<HashSet<[u8; 20]>>>,
     path_to_hash: Arc<RwLock<HashMap<PathBuf, [u8; 20]>>>,
@@ -80,6 +84,18 @@ struct HashDetachCtx<'a, E: WatchEngine> {
 }
 
 impl<E: WatchEngine> HashDetachCtx<'_, E> {
+    /// Delete the instance loaded from `path` after the file was removed, if the file
+    /// is still tracked.
+    async fn detach_removed(&self, watch_dir: &Path, path: &Path) {
+        let Ok(relative) = relative_watch_path(watch_dir, path) else {
+            return;
+        };
+        let info_hash = self.path_to_hash.read().await.get(&relative).copied();
+        if let Some(hash) = info_hash {
+            self.detach(Some(&relative), &hash, true).await;
+        }
+    }
+
     async fn detach(
         &self,
         relative: Option<&PathBuf>,
@@ -111,8 +127,13 @@ impl<E: WatchEngine + 'static> WatchService<E> {
     pub fn new(config: EngineConfig, engine: Arc<E>) -> Self {
         let auto_start = config.auto_start;
         let max_depth = config.max_depth;
+        let mut filter = PathFilter::new(&config.include, &config.exclude);
+        if let AfterImport::Move(processed_dir) = &config.after_import {
+            filter = filter.skipping_dir(&config.watch_dir, processed_dir);
+        }
         Self {
             config,
+            filter,
             engine,
             loaded_hashes: Arc::new(RwLock::new(HashSet::new())),
             path_to_hash: Arc::new(RwLock::new(HashMap::new())),
@@ -183,8 +204,10 @@ impl<E: WatchEngine + 'static> WatchService<E> {
 
         let runner = WatchRunner {
             watch_dir: self.config.watch_dir.clone(),
+            after_import: self.config.after_import.clone(),
             auto_start: Arc::clone(&self.auto_start),
             max_depth: Arc::clone(&self.max_depth),
+            filter: self.filter.clone(),
             engine: Arc::clone(&self.engine),
             loaded_hashes: Arc::clone(&self.loaded_hashes),
             path_to_hash: Arc::clone(&self.path_to_hash),
@@ -213,8 +236,12 @@ impl<E: WatchEngine + 'static> WatchService<E> {
         }
     }
 
+    fn scan_paths(&self) -> Result<Vec<PathBuf>, std::io::Error> {
+        scan_torrent_paths(&self.config.watch_dir, self.config.max_depth, &self.filter)
+    }
+
     async fn scan_directory(&self) {
-        let entries = match scan_torrent_paths(&self.config.watch_dir, self.config.max_depth) {
+        let entries = match self.scan_paths() {
             Ok(entries) => entries,
             Err(e) => {
                 tracing::warn!("Failed to scan watch directory: {}", e);
@@ -228,6 +255,7 @@ impl<E: WatchEngine + 'static> WatchService<E> {
             loaded_hashes: &self.loaded_hashes,
             path_to_hash: &self.path_to_hash,
             watch_dir: &self.config.watch_dir,
+            after_import: &self.config.after_import,
         };
 
         for path in entries {
@@ -239,13 +267,13 @@ impl<E: WatchEngine + 'static> WatchService<E> {
 
     pub async fn get_status(&self) -> WatchStatus {
         let loaded_count = self.loaded_hashes.read().await.len();
-        let file_count = scan_torrent_paths(&self.config.watch_dir, self.config.max_depth)
-            .map_or(0, |entries| entries.len());
+        let file_count = self.scan_paths().map_or(0, |entries| entries.len());
 
         WatchStatus {
             enabled: self.config.enabled,
             watch_dir: self.config.watch_dir.to_string_lossy().to_string(),
             auto_start: self.auto_start.load(Ordering::Relaxed),
+            uses_default_config: self.engine.default_config().await.is_some(),
             file_count,
             loaded_count,
         }
@@ -255,7 +283,7 @@ impl<E: WatchEngine + 'static> WatchService<E> {
         let mut files = Vec::new();
         let loaded_hashes = self.loaded_hashes.read().await;
 
-        let Ok(entries) = scan_torrent_paths(&self.config.watch_dir, self.config.max_depth) else {
+        let Ok(entries) = self.scan_paths() else {
             return files;
         };
 
@@ -323,6 +351,7 @@ impl<E: WatchEngine + 'static> WatchService<E> {
             loaded_hashes: &self.loaded_hashes,
             path_to_hash: &self.path_to_hash,
             watch_dir: &self.config.watch_dir,
+            after_import: &self.config.after_import,
         };
 
         process_torrent_file(&canonical_file, &context).await?;
@@ -336,8 +365,8 @@ impl<E: WatchEngine + 'static> WatchService<E> {
             return Err("Watch directory does not exist".to_string());
         }
 
-        let entries = scan_torrent_paths(&self.config.watch_dir, self.config.max_depth)
-            .map_err(|e| format!("Failed to read watch directory: {e}"))?;
+        let entries =
+            self.scan_paths().map_err(|e| format!("Failed to read watch directory: {e}"))?;
 
         let context = WatchContext {
             auto_start: &self.auto_start,
@@ -345,6 +374,7 @@ impl<E: WatchEngine + 'static> WatchService<E> {
             loaded_hashes: &self.loaded_hashes,
             path_to_hash: &self.path_to_hash,
             watch_dir: &self.config.watch_dir,
+            after_import: &self.config.after_import,
         };
         let detach = HashDetachCtx {
             engine: &self.engine,
@@ -420,6 +450,7 @@ struct WatchContext<'a, E: WatchEngine> {
     loaded_hashes: &'a Arc<RwLock<HashSet<[u8; 20]>>>,
     path_to_hash: &'a Arc<RwLock<HashMap<PathBuf, [u8; 20]>>>,
     watch_dir: &'a Path,
+    after_import: &'a AfterImport,
 }
 
 async fn process_torrent_file<E: WatchEngine>(
@@ -434,45 +465,47 @@ async fn process_torrent_file<E: WatchEngine>(
 
     let info_hash = torrent.info_hash;
 
-    {
-        let hashes = context.loaded_hashes.read().await;
-        if hashes.contains(&info_hash) {
-            if context.engine.find_instance_by_info_hash(&info_hash).await.is_some() {
-                if let Err(e) = context
-                    .engine
-                    .update_instance_source_by_info_hash(&info_hash, InstanceSource::WatchFolder)
-                    .await
-                {
-                    tracing::warn!("Failed to update instance source: {}", e);
-                }
-            }
-
-            let canonical_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
-            if let Ok(relative) = relative_watch_path(context.watch_dir, &canonical_path) {
-                context.path_to_hash.write().await.insert(relative, info_hash);
-            }
-
-            tracing::warn!(
-                "Skipping duplicate torrent '{}' (info_hash: {})",
-                torrent.name,
-                hex::encode(info_hash)
-            );
-            return Ok(());
+    // A loaded hash whose instance was deleted since (e.g. after it became manual) is
+    // stale and must not keep the torrent from being imported again
+    let duplicate = context.loaded_hashes.read().await.contains(&info_hash)
+        && context.engine.find_instance_by_info_hash(&info_hash).await.is_some();
+    if duplicate {
+        if let Err(e) = context
+            .engine
+            .update_instance_source_by_info_hash(&info_hash, InstanceSource::WatchFolder)
+            .await
+        {
+            tracing::warn!("Failed to update instance source: {}", e);
         }
+
+        tracing::warn!(
+            "Skipping duplicate torrent '{}' (info_hash: {})",
+            torrent.name,
+            hex::encode(info_hash)
+        );
+        // Moved or deleted like any import, so later scans don't pick it up again
+        return settle_imported_file(path, info_hash, context).await;
     }
 
     let new_id = context.engine.next_instance_id();
-    let config =
+    let mut config =
         context.engine.default_config().await.unwrap_or_else(rustatio_core::FakerConfig::default);
-    let instance = NewInstance { id: new_id.clone(), info: torrent.to_info(), config, auto_start };
+    if let Some((settings, preset)) = folder_preset(context.watch_dir, path) {
+        tracing::info!("Applying {} to '{}'", preset, torrent.name);
+        settings.apply_to(&mut config);
+    }
+    let instance = NewInstance {
+        id: new_id.clone(),
+        info: torrent.to_info(),
+        torrent_file: data,
+        config,
+        auto_start,
+    };
 
     context.engine.create_instance(instance).await?;
 
     context.loaded_hashes.write().await.insert(info_hash);
-
-    let canonical_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
-    let relative = relative_watch_path(context.watch_dir, &canonical_path)?;
-    context.path_to_hash.write().await.insert(relative, info_hash);
+    settle_imported_file(path, info_hash, context).await?;
 
     tracing::info!("Loaded torrent '{}' from watch folder as instance", torrent.name);
 
@@ -485,10 +518,96 @@ async fn process_torrent_file<E: WatchEngine>(
     Ok(())
 }
 
+/// Apply `after_import` to a file whose torrent is loaded. A file moved or deleted
+/// is not tracked, so its removal event does not delete the instance; one left in
+/// place, on purpose or because cleanup failed, is. Once no tracked file is left for
+/// the instance it becomes manual, so it can be deleted like one.
+async fn settle_imported_file<E: WatchEngine>(
+    path: &Path,
+    info_hash: [u8; 20],
+    context: &WatchContext<'_, E>,
+) -> Result<(), String> {
+    let canonical_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let relative = relative_watch_path(context.watch_dir, &canonical_path)?;
+    if let Err(e) = finish_import(&canonical_path, &relative, context.after_import) {
+        tracing::warn!("Failed to clean up imported torrent {:?}: {}", canonical_path, e);
+        context.path_to_hash.write().await.insert(relative, info_hash);
+    } else if *context.after_import == AfterImport::Keep {
+        context.path_to_hash.write().await.insert(relative, info_hash);
+    } else if !context.path_to_hash.read().await.values().any(|hash| *hash == info_hash) {
+        if let Err(e) = context
+            .engine
+            .update_instance_source_by_info_hash(&info_hash, InstanceSource::Manual)
+            .await
+        {
+            tracing::warn!("Failed to update instance source: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Move or delete an imported torrent file as configured.
+fn finish_import(path: &Path, relative: &Path, after_import: &AfterImport) -> Result<(), String> {
+    match after_import {
+        AfterImport::Keep => Ok(()),
+        AfterImport::Delete => {
+            std::fs::remove_file(path).map_err(|e| format!("Failed to delete: {e}"))?;
+            tracing::info!("Deleted imported torrent file {:?}", relative);
+            Ok(())
+        }
+        AfterImport::Move(processed_dir) => {
+            let target = free_target_path(&processed_dir.join(relative));
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+            }
+            move_file(path, &target)?;
+            tracing::info!("Moved imported torrent file to {}", target.display());
+            Ok(())
+        }
+    }
+}
+
+/// `target`, or `name-1.torrent`, `name-2.torrent`, ... when it is already taken.
+fn free_target_path(target: &Path) -> PathBuf {
+    if !target.exists() {
+        return target.to_path_buf();
+    }
+    let stem = target.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let mut n = 1u64;
+    loop {
+        let candidate = target.with_file_name(format!("{stem}-{n}.torrent"));
+        if !candidate.exists() {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+/// Rename, which is atomic within one filesystem. Across filesystems, copy to a
+/// temporary name next to the target and rename that, so the target never appears
+/// half-written.
+fn move_file(from: &Path, to: &Path) -> Result<(), String> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_then_remove(from, to)
+}
+
+/// The cross-filesystem half of [`move_file`].
+fn copy_then_remove(from: &Path, to: &Path) -> Result<(), String> {
+    let partial = to.with_extension("torrent.part");
+    std::fs::copy(from, &partial).map_err(|e| format!("Failed to copy: {e}"))?;
+    std::fs::rename(&partial, to).map_err(|e| format!("Failed to move: {e}"))?;
+    std::fs::remove_file(from).map_err(|e| format!("Failed to remove original: {e}"))
+}
+
 pub struct WatchRunner<E: WatchEngine> {
     watch_dir: PathBuf,
+    after_import: AfterImport,
     auto_start: Arc<AtomicBool>,
     max_depth: Arc<AtomicU32>,
+    filter: PathFilter,
     engine: Arc<E>,
     loaded_hashes: Arc<RwLock<HashSet<[u8; 20]>>>,
     path_to_hash: Arc<RwLock<HashMap<PathBuf, [u8; 20]>>>,
@@ -524,6 +643,7 @@ impl<E: WatchEngine> WatchRunner<E> {
                             let max_depth = self.max_depth.load(Ordering::Relaxed);
                             if is_torrent_file(&path)
                                 && crate::paths::is_within_depth(&self.watch_dir, &path, max_depth, false)
+                                && passes_filter(&self.watch_dir, &path, &self.filter)
                             {
                                 tokio::time::sleep(std::time::Duration::from_millis(500)).await;
 
@@ -533,6 +653,7 @@ impl<E: WatchEngine> WatchRunner<E> {
                                     loaded_hashes: &self.loaded_hashes,
                                     path_to_hash: &self.path_to_hash,
                                     watch_dir: &self.watch_dir,
+                                    after_import: &self.after_import,
                                 };
 
                                 if let Err(e) = process_torrent_file(&path, &context).await {
@@ -548,20 +669,7 @@ impl<E: WatchEngine> WatchRunner<E> {
                         };
 
                         for path in event.paths {
-                            let (info_hash, matched_path) = {
-                                let mapping = self.path_to_hash.read().await;
-                                let relative = relative_watch_path(&self.watch_dir, &path).ok();
-
-                                relative.map_or((None, None), |relative| {
-                                    mapping.get(&relative).map_or((None, None), |&hash| {
-                                        (Some(hash), Some(relative.clone()))
-                                    })
-                                })
-                            };
-
-                            if let Some(hash) = info_hash {
-                                detach.detach(matched_path.as_ref(), &hash, true).await;
-                            }
+                            detach.detach_removed(&self.watch_dir, &path).await;
                         }
                     }
                 }
@@ -581,14 +689,12 @@ mod tests {
     struct MockEngine {
         instances: Arc<RwLock<Vec<InstanceState>>>,
         started: Arc<RwLock<Vec<String>>>,
+        deleted: Arc<RwLock<Vec<[u8; 20]>>>,
     }
 
     impl MockEngine {
         fn with_instances(instances: Vec<InstanceState>) -> Self {
-            Self {
-                instances: Arc::new(RwLock::new(instances)),
-                started: Arc::new(RwLock::new(Vec::new())),
-            }
+            Self { instances: Arc::new(RwLock::new(instances)), ..Self::default() }
         }
 
         async fn started_ids(&self) -> Vec<String> {
@@ -602,7 +708,14 @@ mod tests {
             self.instances.read().await.clone()
         }
 
-        async fn create_instance(&self, _instance: NewInstance) -> Result<(), String> {
+        async fn create_instance(&self, instance: NewInstance) -> Result<(), String> {
+            self.instances.write().await.push(InstanceState {
+                id: instance.id,
+                info_hash: instance.info.info_hash,
+                source: InstanceSource::WatchFolder,
+                state: "stopped".to_string(),
+                name: instance.info.name,
+            });
             Ok(())
         }
 
@@ -611,7 +724,8 @@ mod tests {
             Ok(())
         }
 
-        async fn delete_instance_by_info_hash(&self, _info_hash: &[u8; 20]) -> Result<(), String> {
+        async fn delete_instance_by_info_hash(&self, info_hash: &[u8; 20]) -> Result<(), String> {
+            self.deleted.write().await.push(*info_hash);
             Ok(())
         }
 
@@ -647,6 +761,195 @@ mod tests {
         }
     }
 
+    /// A minimal single-file torrent; different names give different info hashes.
+    fn torrent_bytes(name: &str) -> Vec<u8> {
+        let announce = "http://tracker.test/announce";
+        format!(
+            "d8:announce{}:{announce}4:infod6:lengthi1024e4:name{}:{name}\
+             12:piece lengthi1024e6:pieces20:{}ee",
+            announce.len(),
+            name.len(),
+            "p".repeat(20)
+        )
+        .into_bytes()
+    }
+
+    struct Imports {
+        engine: Arc<MockEngine>,
+        auto_start: Arc<AtomicBool>,
+        loaded_hashes: Arc<RwLock<HashSet<[u8; 20]>>>,
+        path_to_hash: Arc<RwLock<HashMap<PathBuf, [u8; 20]>>>,
+        watch_dir: PathBuf,
+        after_import: AfterImport,
+    }
+
+    impl Imports {
+        fn new(watch_dir: &Path, after_import: AfterImport) -> Self {
+            Self {
+                engine: Arc::new(MockEngine::default()),
+                auto_start: Arc::new(AtomicBool::new(false)),
+                loaded_hashes: Arc::default(),
+                path_to_hash: Arc::default(),
+                watch_dir: watch_dir.to_path_buf(),
+                after_import,
+            }
+        }
+
+        async fn process(&self, path: &Path) -> Result<(), String> {
+            let context = WatchContext {
+                auto_start: &self.auto_start,
+                engine: &self.engine,
+                loaded_hashes: &self.loaded_hashes,
+                path_to_hash: &self.path_to_hash,
+                watch_dir: &self.watch_dir,
+                after_import: &self.after_import,
+            };
+            process_torrent_file(path, &context).await
+        }
+
+        async fn file_removed(&self, path: &Path) {
+            let detach = HashDetachCtx {
+                engine: &self.engine,
+                loaded_hashes: &self.loaded_hashes,
+                path_to_hash: &self.path_to_hash,
+            };
+            detach.detach_removed(&self.watch_dir, path).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn moved_import_takes_a_free_name_and_outlives_its_file(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let watch = TempDir::new()?;
+        let processed = TempDir::new()?;
+        let watch_dir = watch.path().canonicalize()?;
+        std::fs::create_dir_all(watch_dir.join("sub"))?;
+        std::fs::create_dir_all(processed.path().join("sub"))?;
+        let source = watch_dir.join("sub").join("a.torrent");
+        std::fs::write(&source, torrent_bytes("a"))?;
+        std::fs::write(processed.path().join("sub").join("a.torrent"), b"earlier")?;
+
+        let imports = Imports::new(&watch_dir, AfterImport::Move(processed.path().to_path_buf()));
+        imports.process(&source).await?;
+
+        assert!(!source.exists());
+        assert_eq!(std::fs::read(processed.path().join("sub").join("a.torrent"))?, b"earlier");
+        assert_eq!(
+            std::fs::read(processed.path().join("sub").join("a-1.torrent"))?,
+            torrent_bytes("a")
+        );
+
+        // The move's removal event must not take the instance with it
+        imports.file_removed(&source).await;
+        assert!(imports.engine.deleted.read().await.is_empty());
+        assert_eq!(imports.engine.list_instances().await.len(), 1);
+        assert_eq!(imports.loaded_hashes.read().await.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn deleted_imports_and_duplicates_leave_the_folder(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let watch = TempDir::new()?;
+        let watch_dir = watch.path().canonicalize()?;
+        let first = watch_dir.join("a.torrent");
+        let copy = watch_dir.join("a-copy.torrent");
+        std::fs::write(&first, torrent_bytes("a"))?;
+
+        let imports = Imports::new(&watch_dir, AfterImport::Delete);
+        imports.process(&first).await?;
+        assert!(!first.exists());
+
+        // A second file with a loaded info hash is cleaned up too, not re-scanned forever
+        std::fs::write(&copy, torrent_bytes("a"))?;
+        imports.process(&copy).await?;
+        assert!(!copy.exists());
+        assert_eq!(imports.engine.list_instances().await.len(), 1);
+        assert!(imports.path_to_hash.read().await.is_empty());
+
+        imports.file_removed(&first).await;
+        assert!(imports.engine.deleted.read().await.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn moved_or_deleted_imports_become_manual() -> Result<(), Box<dyn std::error::Error>> {
+        let processed = TempDir::new()?;
+        for after_import in [AfterImport::Delete, AfterImport::Move(processed.path().into())] {
+            let watch = TempDir::new()?;
+            let watch_dir = watch.path().canonicalize()?;
+            let path = watch_dir.join("a.torrent");
+            std::fs::write(&path, torrent_bytes("a"))?;
+
+            let imports = Imports::new(&watch_dir, after_import);
+            imports.process(&path).await?;
+            let instances = imports.engine.list_instances().await;
+            assert!(matches!(instances[0].source, InstanceSource::Manual));
+
+            // Once the manual instance is deleted, the same torrent imports again
+            imports.engine.instances.write().await.clear();
+            std::fs::write(&path, torrent_bytes("a"))?;
+            imports.process(&path).await?;
+            assert_eq!(imports.engine.list_instances().await.len(), 1);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn cleaned_up_duplicate_of_a_kept_file_stays_watched(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let watch = TempDir::new()?;
+        let watch_dir = watch.path().canonicalize()?;
+        let kept = watch_dir.join("a.torrent");
+        let copy = watch_dir.join("a-copy.torrent");
+        std::fs::write(&kept, torrent_bytes("a"))?;
+
+        let mut imports = Imports::new(&watch_dir, AfterImport::Keep);
+        imports.process(&kept).await?;
+        imports.after_import = AfterImport::Delete;
+        std::fs::write(&copy, torrent_bytes("a"))?;
+        imports.process(&copy).await?;
+
+        assert!(!copy.exists());
+        let instances = imports.engine.list_instances().await;
+        assert!(matches!(instances[0].source, InstanceSource::WatchFolder));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn kept_import_is_deleted_with_its_file() -> Result<(), Box<dyn std::error::Error>> {
+        let watch = TempDir::new()?;
+        let watch_dir = watch.path().canonicalize()?;
+        let path = watch_dir.join("a.torrent");
+        std::fs::write(&path, torrent_bytes("a"))?;
+
+        let imports = Imports::new(&watch_dir, AfterImport::Keep);
+        imports.process(&path).await?;
+        assert!(path.exists());
+
+        std::fs::remove_file(&path)?;
+        imports.file_removed(&path).await;
+        assert_eq!(imports.engine.deleted.read().await.len(), 1);
+        assert!(imports.loaded_hashes.read().await.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn cross_device_move_copies_then_removes() -> Result<(), Box<dyn std::error::Error>> {
+        let from_dir = TempDir::new()?;
+        let to_dir = TempDir::new()?;
+        let from = from_dir.path().join("a.torrent");
+        let to = to_dir.path().join("a.torrent");
+        std::fs::write(&from, b"torrent")?;
+
+        copy_then_remove(&from, &to)?;
+
+        assert_eq!(std::fs::read(&to)?, b"torrent");
+        assert!(!from.exists());
+        assert!(!to.with_extension("torrent.part").exists());
+        Ok(())
+    }
+
     fn watch_instance(id: &str, state: &str) -> InstanceState {
         InstanceState {
             id: id.to_string(),
@@ -667,6 +970,9 @@ mod tests {
             auto_start: true,
             enabled: true,
             max_depth: 1,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            after_import: AfterImport::Keep,
         };
 
         let service = WatchService::new(config, Arc::new(engine.clone()));
@@ -687,6 +993,9 @@ mod tests {
             auto_start: true,
             enabled: true,
             max_depth: 1,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            after_import: AfterImport::Keep,
         };
 
         let service = WatchService::new(config, Arc::new(engine.clone()));
@@ -707,6 +1016,9 @@ mod tests {
             auto_start: false,
             enabled: true,
             max_depth: 1,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            after_import: AfterImport::Keep,
         };
 
         let service = WatchService::new(config, Arc::new(engine.clone()));
@@ -1,7 +1,8 @@
 mod engine;
 mod paths;
+mod preset;
 mod scan;
 mod types;
 
 pub use engine::{InstanceSource, InstanceState, NewInstance, WatchEngine, WatchService};
-pub use types::{EngineConfig, WatchStatus, WatchedFile, WatchedFileStatus};
+pub use types::{AfterImport, EngineConfig, WatchStatus, WatchedFile, WatchedFileStatus};
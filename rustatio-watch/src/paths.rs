@@ -1,5 +1,78 @@
+use glob::{MatchOptions, Pattern};
 use std::path::{Component, Path, PathBuf};
 
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Include/exclude globs over paths relative to the watch dir. A pattern containing
+/// `/` is matched against the whole relative path (`tracker-a/**`), any other against
+/// the file name only (`*.torrent`).
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    /// Folder inside the watch dir that is never scanned, such as the processed dir
+    skipped_dir: Option<PathBuf>,
+}
+
+impl PathFilter {
+    /// Compile the patterns, skipping (and logging) invalid ones.
+    pub fn new(include: &[String], exclude: &[String]) -> Self {
+        Self {
+            include: compile_patterns(include),
+            exclude: compile_patterns(exclude),
+            skipped_dir: None,
+        }
+    }
+
+    /// Also reject everything under `dir` when it lies inside `watch_dir`.
+    pub fn skipping_dir(mut self, watch_dir: &Path, dir: &Path) -> Self {
+        let absolute =
+            |path: &Path| std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        self.skipped_dir = absolute(dir)
+            .strip_prefix(absolute(watch_dir))
+            .ok()
+            .filter(|relative| relative.components().next().is_some())
+            .map(Path::to_path_buf);
+        self
+    }
+
+    pub fn matches(&self, relative: &Path) -> bool {
+        if self.skipped_dir.as_ref().is_some_and(|dir| relative.starts_with(dir)) {
+            return false;
+        }
+        let file_name = relative.file_name().map(Path::new);
+        let matches_any = |patterns: &[Pattern]| {
+            patterns.iter().any(|pattern| {
+                if pattern.as_str().contains('/') {
+                    pattern.matches_path_with(relative, MATCH_OPTIONS)
+                } else {
+                    file_name.is_some_and(|name| pattern.matches_path_with(name, MATCH_OPTIONS))
+                }
+            })
+        };
+        (self.include.is_empty() || matches_any(&self.include)) && !matches_any(&self.exclude)
+    }
+}
+
+fn compile_patterns(patterns: &[String]) -> Vec<Pattern> {
+    patterns
+        .iter()
+        .map(|pattern| pattern.trim())
+        .filter(|pattern| !pattern.is_empty())
+        .filter_map(|pattern| match Pattern::new(pattern) {
+            Ok(compiled) => Some(compiled),
+            Err(e) => {
+                tracing::warn!("Ignoring invalid watch glob '{}': {}", pattern, e);
+                None
+            }
+        })
+        .collect()
+}
+
 fn depth_for_path(path: &Path, is_dir: bool) -> u32 {
     let count =
         path.components().filter(|component| matches!(component, Component::Normal(_))).count()
@@ -54,4 +127,29 @@ mod tests {
         let path = PathBuf::from("tracker");
         assert_eq!(depth_for_path(&path, true), 1);
     }
+
+    #[test]
+    fn test_path_filter_include_and_exclude() {
+        let filter = PathFilter::new(
+            &["tracker-a/**".to_string(), "keep-*.torrent".to_string()],
+            &["*.tmp.torrent".to_string(), "tracker-a/old/**".to_string()],
+        );
+        assert!(filter.matches(Path::new("tracker-a/one.torrent")));
+        assert!(filter.matches(Path::new("tracker-a/nested/two.torrent")));
+        assert!(filter.matches(Path::new("tracker-b/keep-me.torrent")));
+        assert!(!filter.matches(Path::new("tracker-b/other.torrent")));
+        assert!(!filter.matches(Path::new("tracker-a/one.tmp.torrent")));
+        assert!(!filter.matches(Path::new("tracker-a/old/three.torrent")));
+
+        let everything = PathFilter::new(&[], &["[".to_string()]);
+        assert!(everything.matches(Path::new("any/where.torrent")));
+
+        let skipping = PathFilter::default()
+            .skipping_dir(Path::new("/torrents"), Path::new("/torrents/processed"));
+        assert!(!skipping.matches(Path::new("processed/tracker-a/one.torrent")));
+        assert!(skipping.matches(Path::new("tracker-a/one.torrent")));
+        let outside =
+            PathFilter::default().skipping_dir(Path::new("/torrents"), Path::new("/done"));
+        assert!(outside.matches(Path::new("processed/one.torrent")));
+    }
 }
@@ -0,0 +1,86 @@
+use rustatio_core::PresetSettings;
+use std::path::Path;
+
+/// Preset file a watch subfolder can carry to configure the torrents dropped in it.
+pub const PRESET_FILE_NAME: &str = "preset.json";
+
+/// Settings from the `preset.json` nearest to `torrent_path`, looking in its folder and
+/// then each parent up to and including `watch_dir`. They go over the default config,
+/// so a preset only needs the settings it changes. `None` when there is none or the
+/// nearest one cannot be read, so the default config applies unchanged.
+pub fn folder_preset(watch_dir: &Path, torrent_path: &Path) -> Option<(PresetSettings, String)> {
+    let root = watch_dir.canonicalize().unwrap_or_else(|_| watch_dir.to_path_buf());
+    let torrent_path = torrent_path.canonicalize().unwrap_or_else(|_| torrent_path.to_path_buf());
+
+    let preset_path = torrent_path
+        .ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(&root))
+        .map(|dir| dir.join(PRESET_FILE_NAME))
+        .find(|candidate| candidate.is_file())?;
+
+    let parsed =
+        std::fs::read_to_string(&preset_path).map_err(|e| e.to_string()).and_then(|contents| {
+            let settings =
+                serde_json::from_str::<PresetSettings>(&contents).map_err(|e| e.to_string())?;
+            settings.validate().map_err(|e| e.to_string())?;
+            Ok(settings)
+        });
+    match parsed {
+        Ok(settings) => {
+            let label = preset_path.strip_prefix(&root).unwrap_or(&preset_path);
+            Some((settings, label.to_string_lossy().to_string()))
+        }
+        Err(e) => {
+            tracing::warn!("Ignoring unreadable watch preset {}: {}", preset_path.display(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn nearest_preset_wins() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let root = dir.path();
+        let fast = root.join("fast");
+        let nested = fast.join("nested");
+        fs::create_dir_all(&nested)?;
+        fs::create_dir_all(root.join("plain"))?;
+
+        fs::write(fast.join(PRESET_FILE_NAME), r#"{"uploadRate": 1024.0}"#)?;
+        fs::write(nested.join("a.torrent"), b"test")?;
+        fs::write(root.join("plain").join("b.torrent"), b"test")?;
+
+        let found = folder_preset(root, &nested.join("a.torrent"));
+        let (settings, label) = found.ok_or("preset not found")?;
+        assert_eq!(label, "fast/preset.json");
+
+        // Settings the preset leaves out keep the user's default config
+        let mut config = rustatio_core::FakerConfig {
+            download_rate: 7.0,
+            announce_jitter_secs: 0,
+            ..rustatio_core::FakerConfig::default()
+        };
+        settings.apply_to(&mut config);
+        assert!((config.upload_rate - 1024.0).abs() < f64::EPSILON);
+        assert!((config.download_rate - 7.0).abs() < f64::EPSILON);
+        assert_eq!(config.announce_jitter_secs, 0);
+
+        assert!(folder_preset(root, &root.join("plain").join("b.torrent")).is_none());
+
+        fs::write(nested.join(PRESET_FILE_NAME), "not json")?;
+        assert!(folder_preset(root, &nested.join("a.torrent")).is_none());
+
+        let bad_stop_time = r#"{"stopAtDatetimeEnabled": true, "stopAtDatetime": "soon"}"#;
+        fs::write(nested.join(PRESET_FILE_NAME), bad_stop_time)?;
+        assert!(folder_preset(root, &nested.join("a.torrent")).is_none());
+
+        Ok(())
+    }
+}
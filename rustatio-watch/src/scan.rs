@@ -1,13 +1,19 @@
-use crate::paths::is_within_depth;
+use crate::paths::{is_within_depth, relative_watch_path, PathFilter};
 use std::path::{Path, PathBuf};
 
 pub fn is_torrent_file(path: &Path) -> bool {
     path.is_file() && path.extension().is_some_and(|ext| ext == "torrent")
 }
 
+/// Whether `path` passes the include/exclude globs, matched relative to `watch_dir`.
+pub fn passes_filter(watch_dir: &Path, path: &Path, filter: &PathFilter) -> bool {
+    relative_watch_path(watch_dir, path).is_ok_and(|relative| filter.matches(&relative))
+}
+
 pub fn scan_torrent_paths(
     watch_dir: &Path,
     max_depth: u32,
+    filter: &PathFilter,
 ) -> Result<Vec<PathBuf>, std::io::Error> {
     let mut results = Vec::new();
     if !watch_dir.exists() {
@@ -38,7 +44,10 @@ pub fn scan_torrent_paths(
                 continue;
             }
 
-            if is_torrent_file(&path) && is_within_depth(&root, &path, max_depth, false) {
+            if is_torrent_file(&path)
+                && is_within_depth(&root, &path, max_depth, false)
+                && passes_filter(&root, &path, filter)
+            {
                 results.push(path);
             }
         }
@@ -75,21 +84,40 @@ mod tests {
         let nested_file = nested_dir.join("deep.torrent");
         write_torrent(&nested_file)?;
 
-        let depth0 = scan_torrent_paths(root, 0)?;
+        let depth0 = scan_torrent_paths(root, 0, &PathFilter::default())?;
         assert!(depth0.iter().any(|p| p.ends_with("root.torrent")));
         assert!(depth0.iter().any(|p| p.ends_with("one.torrent")));
         assert!(depth0.iter().any(|p| p.ends_with("deep.torrent")));
 
-        let depth1 = scan_torrent_paths(root, 1)?;
+        let depth1 = scan_torrent_paths(root, 1, &PathFilter::default())?;
         assert!(depth1.iter().any(|p| p.ends_with("root.torrent")));
         assert!(depth1.iter().any(|p| p.ends_with("one.torrent")));
         assert!(!depth1.iter().any(|p| p.ends_with("deep.torrent")));
 
-        let depth2 = scan_torrent_paths(root, 2)?;
+        let depth2 = scan_torrent_paths(root, 2, &PathFilter::default())?;
         assert!(depth2.iter().any(|p| p.ends_with("root.torrent")));
         assert!(depth2.iter().any(|p| p.ends_with("one.torrent")));
         assert!(depth2.iter().any(|p| p.ends_with("deep.torrent")));
 
         Ok(())
     }
+
+    #[test]
+    fn test_scan_torrent_paths_applies_globs() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempdir()?;
+        let root = dir.path();
+
+        for sub in ["tracker-a", "tracker-b"] {
+            fs::create_dir_all(root.join(sub))?;
+            write_torrent(&root.join(sub).join("one.torrent"))?;
+            write_torrent(&root.join(sub).join("skip.torrent"))?;
+        }
+
+        let filter = PathFilter::new(&["tracker-a/**".to_string()], &["skip*".to_string()]);
+        let found = scan_torrent_paths(root, 0, &filter)?;
+        assert_eq!(found.len(), 1);
+        assert!(found[0].ends_with("tracker-a/one.torrent"));
+
+        Ok(())
+    }
 }
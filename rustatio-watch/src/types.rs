@@ -7,11 +7,31 @@ pub struct EngineConfig {
     pub auto_start: bool,
     pub enabled: bool,
     pub max_depth: u32,
+    /// Glob patterns a torrent's path relative to the watch dir must match (any of them);
+    /// empty accepts every torrent
+    pub include: Vec<String>,
+    /// Glob patterns excluding torrents, checked after `include`
+    pub exclude: Vec<String>,
+    /// What happens to a torrent file once it has been imported
+    pub after_import: AfterImport,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AfterImport {
+    /// Leave the file in place; deleting it later deletes the instance
+    #[default]
+    Keep,
+    /// Move the file into this directory, keeping its subfolder
+    Move(PathBuf),
+    /// Delete the file
+    Delete,
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct WatchedFile {
+    /// Path relative to the watch dir, e.g. `tracker-a/file.torrent`
     pub filename: String,
+    /// Absolute path on disk
     pub path: String,
     pub status: WatchedFileStatus,
     pub info_hash: Option<String>,
@@ -31,7 +51,11 @@ pub enum WatchedFileStatus {
 pub struct WatchStatus {
     pub enabled: bool,
     pub watch_dir: String,
+    /// Whether imported torrents start right away
     pub auto_start: bool,
+    /// Whether imports use the saved default config rather than the built-in defaults
+    /// (a folder `preset.json` still takes precedence)
+    pub uses_default_config: bool,
     pub file_count: usize,
     pub loaded_count: usize,
 }
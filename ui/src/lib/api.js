@@ -332,6 +332,13 @@ export function emitLog(level, message) {
   }
 }
 
+// Warn when the server's load-time tracker check got no usable answer
+function warnUnreachableTracker(tracker, prefix) {
+  if (tracker?.reachable === false) {
+    emitLog('warn', `${prefix}Tracker unreachable: ${tracker.error || tracker.url}`);
+  }
+}
+
 // Server API helper with logging and authentication
 async function serverFetch(endpoint, options = {}, logMessage = null) {
   const url = `${serverBaseUrl}/api${endpoint}`;
@@ -435,6 +442,7 @@ const serverApi = {
       'info',
       `Torrent loaded: ${data.data.torrent.name} (${formatBytes(data.data.torrent.total_size)})`
     );
+    warnUnreachableTracker(data.data.tracker, '');
     return data.data.torrent;
   },
   // Load torrent for a specific instance (creates idle instance on server)
@@ -476,6 +484,7 @@ const serverApi = {
       'info',
       `[Instance ${id}] Torrent loaded: ${data.data.torrent.name} (${formatBytes(data.data.torrent.total_size)})`
     );
+    warnUnreachableTracker(data.data.tracker, `[Instance ${id}] `);
     return data.data.torrent;
   },
   startFaker: async (id, torrent, config) => {
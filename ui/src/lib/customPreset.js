@@ -56,12 +56,19 @@ export function buildCustomPreset(instance, { name, description = '', id, now =
       completionPercent: instance.completionPercent,
       randomizeRates: instance.randomizeRates,
       randomRangePercent: instance.randomRangePercent,
+      randomizeMode: instance.randomizeMode ?? 'uniform',
       updateIntervalSeconds: instance.updateIntervalSeconds,
       scrapeInterval: instance.scrapeInterval,
+      scrapeEnabled: instance.scrapeEnabled ?? true,
+      announceOnPause: instance.announceOnPause ?? false,
+      announceOnResume: instance.announceOnResume ?? true,
+      seedOnly: instance.seedOnly ?? false,
+      dryRun: instance.dryRun ?? false,
       progressiveRatesEnabled: instance.progressiveRatesEnabled,
       targetUploadRate: instance.targetUploadRate,
       targetDownloadRate: instance.targetDownloadRate,
       progressiveDurationHours: instance.progressiveDurationHours,
+      progressiveCurve: instance.progressiveCurve ?? 'linear',
       stopAtRatioEnabled: instance.stopAtRatioEnabled,
       stopAtRatio: instance.stopAtRatio,
       randomizeRatio: instance.randomizeRatio,
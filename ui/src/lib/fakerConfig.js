@@ -23,9 +23,10 @@ export function buildFakerConfig(instance, clientVersions = {}, opts = {}) {
     initial_uploaded: parseInt(instance.initialUploaded ?? 0) * 1024 * 1024,
     initial_downloaded: initialDownloaded,
     completion_percent: completionPercent,
-    num_want: 50,
+    num_want: null,
     randomize_rates: instance.randomizeRates ?? true,
     random_range_percent: parseFloat(instance.randomRangePercent ?? 20),
+    randomize_mode: instance.randomizeMode || 'uniform',
     randomize_ratio: instance.randomizeRatio ?? false,
     random_ratio_range_percent: parseFloat(instance.randomRatioRangePercent ?? 10),
     stop_at_ratio: instance.stopAtRatioEnabled ? parseFloat(instance.stopAtRatio ?? 2.0) : null,
@@ -52,6 +53,12 @@ export function buildFakerConfig(instance, clientVersions = {}, opts = {}) {
       ? parseFloat(instance.targetDownloadRate ?? 200)
       : null,
     progressive_duration: parseFloat(instance.progressiveDurationHours ?? 1) * 3600,
+    progressive_curve: instance.progressiveCurve || 'linear',
     scrape_interval: parseInt(instance.scrapeInterval ?? 60),
+    scrape_enabled: instance.scrapeEnabled ?? true,
+    announce_on_pause: instance.announceOnPause ?? false,
+    announce_on_resume: instance.announceOnResume ?? true,
+    seed_only: instance.seedOnly ?? false,
+    dry_run: instance.dryRun ?? false,
   };
 }
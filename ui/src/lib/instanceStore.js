@@ -69,6 +69,7 @@ function createDefaultInstance(id, defaults = {}) {
     randomizeRates: defaults.randomizeRates !== undefined ? defaults.randomizeRates : true,
     randomRangePercent:
       defaults.randomRangePercent !== undefined ? defaults.randomRangePercent : 20,
+    randomizeMode: defaults.randomizeMode !== undefined ? defaults.randomizeMode : 'uniform',
     updateIntervalSeconds:
       defaults.updateIntervalSeconds !== undefined ? defaults.updateIntervalSeconds : 5,
 
@@ -110,6 +111,11 @@ function createDefaultInstance(id, defaults = {}) {
 
     // Scrape interval
     scrapeInterval: defaults.scrapeInterval !== undefined ? defaults.scrapeInterval : 60,
+    scrapeEnabled: defaults.scrapeEnabled !== undefined ? defaults.scrapeEnabled : true,
+    announceOnPause: defaults.announceOnPause !== undefined ? defaults.announceOnPause : false,
+    announceOnResume: defaults.announceOnResume !== undefined ? defaults.announceOnResume : true,
+    seedOnly: defaults.seedOnly !== undefined ? defaults.seedOnly : false,
+    dryRun: defaults.dryRun !== undefined ? defaults.dryRun : false,
 
     // Progressive rates
     progressiveRatesEnabled:
@@ -119,6 +125,8 @@ function createDefaultInstance(id, defaults = {}) {
       defaults.targetDownloadRate !== undefined ? defaults.targetDownloadRate : 200,
     progressiveDurationHours:
       defaults.progressiveDurationHours !== undefined ? defaults.progressiveDurationHours : 1,
+    progressiveCurve:
+      defaults.progressiveCurve !== undefined ? defaults.progressiveCurve : 'linear',
 
     // Status
     statusMessage: 'Select a torrent file to begin',
@@ -231,8 +239,14 @@ function loadSessionFromStorage(config = null) {
         cumulativeDownloaded: bytesToMB(inst.cumulative_downloaded),
         randomizeRates: inst.randomize_rates,
         randomRangePercent: inst.random_range_percent,
+        randomizeMode: inst.randomize_mode || 'uniform',
         updateIntervalSeconds: inst.update_interval_seconds,
         scrapeInterval: inst.scrape_interval ?? 60,
+        scrapeEnabled: inst.scrape_enabled ?? true,
+        announceOnPause: inst.announce_on_pause ?? false,
+        announceOnResume: inst.announce_on_resume ?? true,
+        seedOnly: inst.seed_only ?? false,
+        dryRun: inst.dry_run ?? false,
         stopAtRatioEnabled: inst.stop_at_ratio_enabled,
         stopAtRatio: inst.stop_at_ratio,
         randomizeRatio: inst.randomize_ratio || false,
@@ -251,6 +265,7 @@ function loadSessionFromStorage(config = null) {
         targetUploadRate: inst.target_upload_rate,
         targetDownloadRate: inst.target_download_rate,
         progressiveDurationHours: inst.progressive_duration_hours,
+        progressiveCurve: inst.progressive_curve || 'linear',
       })),
       activeInstanceId: isTauri
         ? sessionData.active_instance_real_id
@@ -309,6 +324,7 @@ function buildInstanceDefaultsFromServer(serverInst) {
     cumulativeDownloaded: bytesToMB(serverInst.stats.downloaded),
     randomizeRates: config.randomize_rates,
     randomRangePercent: config.random_range_percent,
+    randomizeMode: config.randomize_mode || 'uniform',
     stopAtRatioEnabled: config.stop_at_ratio !== null,
     stopAtRatio: config.stop_at_ratio || 2.0,
     randomizeRatio: config.randomize_ratio || false,
@@ -327,7 +343,13 @@ function buildInstanceDefaultsFromServer(serverInst) {
     targetUploadRate: config.target_upload_rate || 100,
     targetDownloadRate: config.target_download_rate || 200,
     progressiveDurationHours: (config.progressive_duration || 3600) / 3600,
+    progressiveCurve: config.progressive_curve || 'linear',
     scrapeInterval: config.scrape_interval || 60,
+    scrapeEnabled: config.scrape_enabled ?? true,
+    announceOnPause: config.announce_on_pause ?? false,
+    announceOnResume: config.announce_on_resume ?? true,
+    seedOnly: config.seed_only ?? false,
+    dryRun: config.dry_run ?? false,
   };
 }
 
@@ -872,10 +894,12 @@ export const instanceActions = {
               initialDownloaded: serverDefaults.initialDownloaded,
               randomizeRates: serverDefaults.randomizeRates,
               randomRangePercent: serverDefaults.randomRangePercent,
+              randomizeMode: serverDefaults.randomizeMode,
               progressiveRatesEnabled: serverDefaults.progressiveRatesEnabled,
               targetUploadRate: serverDefaults.targetUploadRate,
               targetDownloadRate: serverDefaults.targetDownloadRate,
               progressiveDurationHours: serverDefaults.progressiveDurationHours,
+              progressiveCurve: serverDefaults.progressiveCurve,
               stopAtRatioEnabled: serverDefaults.stopAtRatioEnabled,
               stopAtRatio: serverDefaults.stopAtRatio,
               randomizeRatio: serverDefaults.randomizeRatio,
@@ -891,6 +915,11 @@ export const instanceActions = {
               idleWhenNoSeeders: serverDefaults.idleWhenNoSeeders,
               postStopAction: serverDefaults.postStopAction,
               scrapeInterval: serverDefaults.scrapeInterval,
+              scrapeEnabled: serverDefaults.scrapeEnabled,
+              announceOnPause: serverDefaults.announceOnPause,
+              announceOnResume: serverDefaults.announceOnResume,
+              seedOnly: serverDefaults.seedOnly,
+              dryRun: serverDefaults.dryRun,
             });
             if (
               gridSummary?.source &&
@@ -103,8 +103,14 @@ export function serializeSessionInstances(instances) {
     cumulative_downloaded: parseInt(inst.cumulativeDownloaded) * 1024 * 1024,
     randomize_rates: inst.randomizeRates,
     random_range_percent: parseFloat(inst.randomRangePercent),
+    randomize_mode: inst.randomizeMode,
     update_interval_seconds: parseInt(inst.updateIntervalSeconds),
     scrape_interval: parseInt(inst.scrapeInterval) || 60,
+    scrape_enabled: inst.scrapeEnabled ?? true,
+    announce_on_pause: inst.announceOnPause ?? false,
+    announce_on_resume: inst.announceOnResume ?? true,
+    seed_only: inst.seedOnly ?? false,
+    dry_run: inst.dryRun ?? false,
     stop_at_ratio_enabled: inst.stopAtRatioEnabled,
     stop_at_ratio: parseFloat(inst.stopAtRatio),
     randomize_ratio: inst.randomizeRatio,
@@ -123,6 +129,7 @@ export function serializeSessionInstances(instances) {
     target_upload_rate: parseFloat(inst.targetUploadRate),
     target_download_rate: parseFloat(inst.targetDownloadRate),
     progressive_duration_hours: parseFloat(inst.progressiveDurationHours),
+    progressive_curve: inst.progressiveCurve,
   }));
 }
 
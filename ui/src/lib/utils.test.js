@@ -184,8 +184,14 @@ test('serializeSessionInstances preserves backup snapshot shape for desktop/web
       cumulativeDownloaded: '50',
       randomizeRates: true,
       randomRangePercent: '10',
+      randomizeMode: 'smoothed',
       updateIntervalSeconds: '7',
       scrapeInterval: '90',
+      scrapeEnabled: false,
+      announceOnPause: true,
+      announceOnResume: false,
+      seedOnly: false,
+      dryRun: true,
       stopAtRatioEnabled: true,
       stopAtRatio: '2.5',
       randomizeRatio: true,
@@ -204,6 +210,7 @@ test('serializeSessionInstances preserves backup snapshot shape for desktop/web
       targetUploadRate: '500',
       targetDownloadRate: '800',
       progressiveDurationHours: '6',
+      progressiveCurve: 'ease_out',
     },
   ]);
 
@@ -225,8 +232,14 @@ test('serializeSessionInstances preserves backup snapshot shape for desktop/web
       cumulative_downloaded: 52428800,
       randomize_rates: true,
       random_range_percent: 10,
+      randomize_mode: 'smoothed',
       update_interval_seconds: 7,
       scrape_interval: 90,
+      scrape_enabled: false,
+      announce_on_pause: true,
+      announce_on_resume: false,
+      seed_only: false,
+      dry_run: true,
       stop_at_ratio_enabled: true,
       stop_at_ratio: 2.5,
       randomize_ratio: true,
@@ -245,6 +258,7 @@ test('serializeSessionInstances preserves backup snapshot shape for desktop/web
       target_upload_rate: 500,
       target_download_rate: 800,
       progressive_duration_hours: 6,
+      progressive_curve: 'ease_out',
     },
   ]);
 });